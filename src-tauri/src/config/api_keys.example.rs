@@ -1,5 +0,0 @@
-// API Keys configuration - EXAMPLE FILE
-// Copy this file to api_keys.rs and add your actual API key
-// This file is committed to the repository as an example
-
-pub const RESEND_API_KEY: &str = "your_resend_api_key_here"; 
\ No newline at end of file
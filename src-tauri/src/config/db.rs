@@ -1,78 +1,279 @@
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::env;
 use std::path::PathBuf;
-use rusqlite::Connection;
+use std::thread;
+use std::time::{Duration, Instant};
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{Connection, Transaction};
+use crate::error::{AppError, AppResult};
 use crate::repository::sqlite_person_repository::SqlitePersonRepository;
+use crate::repository::sqlite_membership_repository::SqliteMembershipRepository;
+use crate::repository::sqlite_check_in_repository::SqliteCheckInRepository;
+use crate::repository::sqlite_body_measurement_repository::SqliteBodyMeasurementRepository;
 use crate::repository::sqlite_exercise_repository::SqliteExerciseRepository;
+use crate::repository::sqlite_taxonomy_repository::SqliteTaxonomyRepository;
 use crate::repository::sqlite_workout_entry_repository::SqliteWorkoutEntryRepository;
 use crate::repository::sqlite_routine_repository::SqliteRoutineRepository;
+use crate::repository::sqlite_program_repository::SqliteProgramRepository;
+use crate::repository::sqlite_assignment_repository::SqliteAssignmentRepository;
+use crate::repository::sqlite_progression_repository::SqliteProgressionRepository;
+use crate::repository::sqlite_report_repository::SqliteReportRepository;
 use crate::services::person_service::PersonService;
+use crate::services::membership_service::MembershipService;
+use crate::services::check_in_service::CheckInService;
+use crate::services::body_measurement_service::BodyMeasurementService;
 use crate::services::exercise_service::ExerciseService;
 use crate::services::workout_entry_service::WorkoutEntryService;
 use crate::services::routine_service::RoutineService;
+use crate::services::program_service::ProgramService;
+use crate::services::assignment_service::AssignmentService;
+use crate::services::progression_service::ProgressionService;
+use crate::services::analytics_service::AnalyticsService;
+use crate::services::report_service::ReportService;
+use crate::services::backup_service::BackupService;
+use crate::services::settings_service::SettingsService;
+use crate::services::updater_service::UpdaterService;
 
-pub fn setup_services() -> (PersonService, ExerciseService, WorkoutEntryService, RoutineService) {
+pub type PooledConnection = r2d2::PooledConnection<SqliteConnectionManager>;
+
+const POOL_MAX_SIZE: u32 = 8;
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+const STATEMENT_CACHE_CAPACITY: usize = 64;
+// How long a database swap waits for borrowed connections to come back
+const SWAP_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Shared SQLite connection pool injected into every `Sqlite*Repository`.
+/// Clones share the same underlying pool, which `swap_database` can replace
+/// while the app is running (e.g. when a backup is restored).
+#[derive(Clone)]
+pub struct DbPool {
+    inner: Arc<RwLock<Option<Pool<SqliteConnectionManager>>>>,
+}
+
+impl DbPool {
+    pub fn get(&self) -> AppResult<PooledConnection> {
+        let guard = self.inner.read().map_err(|_| AppError::database_unavailable("Database pool lock poisoned"))?;
+        match guard.as_ref() {
+            Some(pool) => Ok(pool.get()?),
+            None => Err(AppError::database_unavailable("Database is being replaced")),
+        }
+    }
+
+    /// Closes every connection, runs `swap` on the files of `db_path` and reopens
+    /// the pool on it, applying pending migrations. New requests wait meanwhile.
+    /// The pool is reopened even if `swap` fails so the app keeps working.
+    pub fn swap_database<F>(&self, db_path: &str, swap: F) -> Result<(), String>
+    where
+        F: FnOnce() -> Result<(), String>,
+    {
+        let mut guard = self.inner.write().map_err(|_| "Database pool lock poisoned".to_string())?;
+
+        if let Some(pool) = guard.as_ref() {
+            let started = Instant::now();
+            loop {
+                let state = pool.state();
+                if state.connections == state.idle_connections {
+                    break;
+                }
+                if started.elapsed() > SWAP_DRAIN_TIMEOUT {
+                    return Err("Database is busy, try again in a moment".to_string());
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+        }
+        // Dropping the last handle closes the connections and checkpoints the WAL
+        *guard = None;
+
+        let swapped = swap();
+
+        let pool = build_pool(db_path)?;
+        *guard = Some(pool);
+        drop(guard);
+
+        run_database_migrations(self)?;
+        swapped
+    }
+}
+
+/// Everything `setup_services` builds for the app state
+pub struct Services {
+    pub person_service: PersonService,
+    pub membership_service: MembershipService,
+    pub check_in_service: CheckInService,
+    pub body_measurement_service: BodyMeasurementService,
+    pub exercise_service: ExerciseService,
+    pub workout_entry_service: WorkoutEntryService,
+    pub routine_service: RoutineService,
+    pub program_service: ProgramService,
+    pub assignment_service: AssignmentService,
+    pub progression_service: ProgressionService,
+    pub analytics_service: AnalyticsService,
+    pub report_service: ReportService,
+    pub backup_service: BackupService,
+    pub settings_service: Arc<SettingsService>,
+    pub updater_service: UpdaterService,
+}
+
+/// Creates the connection pool. Pragmas and the prepared-statement cache are
+/// configured once per physical connection instead of on every query.
+pub fn create_connection_pool(db_path: &str) -> Result<DbPool, String> {
+    Ok(DbPool { inner: Arc::new(RwLock::new(Some(build_pool(db_path)?))) })
+}
+
+/// Migrated pool on a throwaway file; keep the directory alive for as long as the pool
+#[cfg(test)]
+pub fn test_pool() -> (tempfile::TempDir, DbPool) {
+    let dir = tempfile::tempdir().expect("temporary directory");
+    let path = dir.path().join("test.db");
+    let pool = create_connection_pool(path.to_str().expect("utf-8 path")).expect("test pool");
+    run_database_migrations(&pool).expect("migrations");
+    (dir, pool)
+}
+
+fn build_pool(db_path: &str) -> Result<Pool<SqliteConnectionManager>, String> {
+    let manager = SqliteConnectionManager::file(db_path).with_init(|conn| {
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA synchronous = NORMAL;
+             PRAGMA foreign_keys = ON;",
+        )?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+        Ok(())
+    });
+
+    Pool::builder()
+        .max_size(POOL_MAX_SIZE)
+        .build(manager)
+        .map_err(|e| format!("Failed to create database connection pool: {}", e))
+}
+
+pub fn setup_services() -> Result<Services, String> {
     let db_path = get_database_path();
+    let data_dir = db_path.parent().unwrap_or(&db_path).to_path_buf();
+    let settings_service = Arc::new(SettingsService::new(&data_dir));
+    let updater_service = UpdaterService::new(settings_service.clone(), data_dir.join("updates"));
     let db_path_str = match db_path.to_str() {
         Some(path) => path,
         None => {
             eprintln!("Warning: Failed to convert database path to string");
-            return (
-                PersonService::new(Arc::new(SqlitePersonRepository::new_dummy())),
-                ExerciseService::new(Arc::new(SqliteExerciseRepository::new_dummy())),
-                WorkoutEntryService::new(Arc::new(SqliteWorkoutEntryRepository::new_dummy())),
-                RoutineService::new(Arc::new(SqliteRoutineRepository::new_dummy())),
-            );
+            return Ok(Services {
+                person_service: PersonService::new(Arc::new(SqlitePersonRepository::new_dummy())),
+                membership_service: MembershipService::new(
+                    Arc::new(SqliteMembershipRepository::new_dummy()),
+                    Arc::new(SqlitePersonRepository::new_dummy()),
+                ),
+                check_in_service: CheckInService::new(
+                    Arc::new(SqliteCheckInRepository::new_dummy()),
+                    Arc::new(SqlitePersonRepository::new_dummy()),
+                ),
+                body_measurement_service: BodyMeasurementService::new(
+                    Arc::new(SqliteBodyMeasurementRepository::new_dummy()),
+                    Arc::new(SqlitePersonRepository::new_dummy()),
+                ),
+                exercise_service: ExerciseService::new(
+                    Arc::new(SqliteExerciseRepository::new_dummy()),
+                    Arc::new(SqliteTaxonomyRepository::new_dummy()),
+                ),
+                workout_entry_service: WorkoutEntryService::new(
+                    Arc::new(SqliteWorkoutEntryRepository::new_dummy()),
+                    Arc::new(SqliteExerciseRepository::new_dummy()),
+                ),
+                routine_service: RoutineService::new(Arc::new(SqliteRoutineRepository::new_dummy())),
+                program_service: ProgramService::new(
+                    Arc::new(SqliteProgramRepository::new_dummy()),
+                    Arc::new(SqliteRoutineRepository::new_dummy()),
+                ),
+                assignment_service: AssignmentService::new(
+                    Arc::new(SqliteAssignmentRepository::new_dummy()),
+                    Arc::new(SqlitePersonRepository::new_dummy()),
+                    Arc::new(SqliteRoutineRepository::new_dummy()),
+                    ProgramService::new(
+                        Arc::new(SqliteProgramRepository::new_dummy()),
+                        Arc::new(SqliteRoutineRepository::new_dummy()),
+                    ),
+                    WorkoutEntryService::new(
+                        Arc::new(SqliteWorkoutEntryRepository::new_dummy()),
+                        Arc::new(SqliteExerciseRepository::new_dummy()),
+                    ),
+                ),
+                progression_service: ProgressionService::new(
+                    Arc::new(SqliteProgressionRepository::new_dummy()),
+                    Arc::new(SqliteRoutineRepository::new_dummy()),
+                    Arc::new(SqliteWorkoutEntryRepository::new_dummy()),
+                ),
+                analytics_service: AnalyticsService::new(Arc::new(SqliteWorkoutEntryRepository::new_dummy())),
+                report_service: ReportService::new(Arc::new(SqliteReportRepository::new_dummy())),
+                backup_service: BackupService::new(None, settings_service.clone()),
+                settings_service,
+                updater_service,
+            });
         }
     };
 
-    // Run database migrations if needed
-    if let Err(e) = run_database_migrations(db_path_str) {
-        eprintln!("Warning: Database migration failed: {}", e);
-        // Continue anyway, the app should still work
-    }
+    let pool = create_connection_pool(db_path_str)?;
 
-    // Create repositories with error handling
-    let person_repository = match SqlitePersonRepository::new_safe(db_path_str) {
-        Ok(repo) => Arc::new(repo),
-        Err(e) => {
-            eprintln!("Warning: Failed to create person repository: {}", e);
-            // Create a dummy repository that returns empty results
-            Arc::new(SqlitePersonRepository::new_dummy())
-        }
-    };
+    // The migration runner owns the schema; never start against a database we could not migrate
+    run_database_migrations(&pool)?;
 
-    let exercise_repository = match SqliteExerciseRepository::new_safe(db_path_str) {
-        Ok(repo) => Arc::new(repo),
-        Err(e) => {
-            eprintln!("Warning: Failed to create exercise repository: {}", e);
-            Arc::new(SqliteExerciseRepository::new_dummy())
-        }
-    };
-
-    let workout_entry_repository = match SqliteWorkoutEntryRepository::new_safe(db_path_str) {
-        Ok(repo) => Arc::new(repo),
-        Err(e) => {
-            eprintln!("Warning: Failed to create workout entry repository: {}", e);
-            Arc::new(SqliteWorkoutEntryRepository::new_dummy())
-        }
-    };
-
-    let routine_repository = match SqliteRoutineRepository::new_safe(db_path_str) {
-        Ok(repo) => Arc::new(repo),
-        Err(e) => {
-            eprintln!("Warning: Failed to create routine repository: {}", e);
-            Arc::new(SqliteRoutineRepository::new_dummy())
-        }
-    };
+    // Create repositories sharing the same pool
+    let person_repository = Arc::new(SqlitePersonRepository::new(pool.clone()));
+    let membership_repository = Arc::new(SqliteMembershipRepository::new(pool.clone()));
+    let check_in_repository = Arc::new(SqliteCheckInRepository::new(pool.clone()));
+    let body_measurement_repository = Arc::new(SqliteBodyMeasurementRepository::new(pool.clone()));
+    let exercise_repository = Arc::new(SqliteExerciseRepository::new(pool.clone()));
+    let taxonomy_repository = Arc::new(SqliteTaxonomyRepository::new(pool.clone()));
+    let workout_entry_repository = Arc::new(SqliteWorkoutEntryRepository::new(pool.clone()));
+    let routine_repository = Arc::new(SqliteRoutineRepository::new(pool.clone()));
+    let program_repository = Arc::new(SqliteProgramRepository::new(pool.clone()));
+    let assignment_repository = Arc::new(SqliteAssignmentRepository::new(pool.clone()));
+    let progression_repository = Arc::new(SqliteProgressionRepository::new(pool.clone()));
+    let report_repository = Arc::new(SqliteReportRepository::new(pool.clone()));
 
     // Create services
-    let person_service = PersonService::new(person_repository);
-    let exercise_service = ExerciseService::new(exercise_repository);
-    let workout_entry_service = WorkoutEntryService::new(workout_entry_repository);
-    let routine_service = RoutineService::new(routine_repository);
+    let person_service = PersonService::new(person_repository.clone());
+    let membership_service = MembershipService::new(membership_repository, person_repository.clone());
+    let check_in_service = CheckInService::new(check_in_repository, person_repository.clone());
+    let body_measurement_service = BodyMeasurementService::new(body_measurement_repository, person_repository.clone());
+    let workout_entry_service = WorkoutEntryService::new(workout_entry_repository.clone(), exercise_repository.clone());
+    // Scheduling writes sessions through its own WorkoutEntryService so they get the same validation
+    let assignment_service = AssignmentService::new(
+        assignment_repository,
+        person_repository,
+        routine_repository.clone(),
+        ProgramService::new(program_repository.clone(), routine_repository.clone()),
+        WorkoutEntryService::new(workout_entry_repository.clone(), exercise_repository.clone()),
+    );
+    let progression_service = ProgressionService::new(
+        progression_repository,
+        routine_repository.clone(),
+        workout_entry_repository.clone(),
+    );
+    let exercise_service = ExerciseService::new(exercise_repository, taxonomy_repository);
+    let routine_service = RoutineService::new(routine_repository.clone());
+    let program_service = ProgramService::new(program_repository, routine_repository);
+    let analytics_service = AnalyticsService::new(workout_entry_repository);
+    let report_service = ReportService::new(report_repository);
+    let backup_service = BackupService::new(Some(pool), settings_service.clone());
 
-    (person_service, exercise_service, workout_entry_service, routine_service)
+    Ok(Services {
+        person_service,
+        membership_service,
+        check_in_service,
+        body_measurement_service,
+        exercise_service,
+        workout_entry_service,
+        routine_service,
+        program_service,
+        assignment_service,
+        progression_service,
+        analytics_service,
+        report_service,
+        backup_service,
+        settings_service,
+        updater_service,
+    })
 }
 
 pub fn get_database_path() -> PathBuf {
@@ -154,54 +355,895 @@ fn get_app_data_directory() -> PathBuf {
     }
 }
 
-fn run_database_migrations(db_path: &str) -> Result<(), rusqlite::Error> {
-    let conn = Connection::open(db_path)?;
+/// A single, ordered schema change. Each migration runs inside its own
+/// transaction and is recorded in the `migrations` table once applied.
+struct Migration {
+    version: u32,
+    name: &'static str,
+    up: fn(&Transaction) -> rusqlite::Result<()>,
+}
+
+/// Full schema history, oldest first. Never edit or reorder an entry that has
+/// shipped; append a new migration instead.
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "create_core_tables", up: create_core_tables },
+    Migration { version: 2, name: "add_logical_deletion", up: add_logical_deletion },
+    Migration { version: 3, name: "workout_entries_date_column", up: migrate_workout_entries_date_column },
+    Migration { version: 4, name: "add_ordering_and_groups", up: add_ordering_and_groups },
+    Migration { version: 5, name: "create_workout_sets", up: create_workout_sets },
+    Migration { version: 6, name: "add_intensity_fields", up: add_intensity_fields },
+    Migration { version: 7, name: "add_measurement_types", up: add_measurement_types },
+    Migration { version: 8, name: "create_exercise_taxonomy", up: create_exercise_taxonomy },
+    Migration { version: 9, name: "create_memberships", up: create_memberships },
+    Migration { version: 10, name: "create_check_ins", up: create_check_ins },
+    Migration { version: 11, name: "add_person_profile", up: add_person_profile },
+    Migration { version: 12, name: "create_body_measurements", up: create_body_measurements },
+    Migration { version: 13, name: "create_programs", up: create_programs },
+    Migration { version: 14, name: "create_routine_assignments", up: create_routine_assignments },
+    Migration { version: 15, name: "create_progression_rules", up: create_progression_rules },
+    Migration { version: 16, name: "link_workout_entries_to_routines", up: link_workout_entries_to_routines },
+    Migration { version: 17, name: "create_routine_revisions", up: create_routine_revisions },
+    Migration { version: 18, name: "add_workout_entry_status", up: add_workout_entry_status },
+    Migration { version: 19, name: "store_workout_entry_prescriptions", up: store_workout_entry_prescriptions },
+];
 
-    // Create migrations table if it doesn't exist
-    if let Err(e) = conn.execute(
+/// Latest schema version known by this binary.
+pub fn latest_schema_version() -> u32 {
+    MIGRATIONS.iter().map(|m| m.version).max().unwrap_or(0)
+}
+
+pub fn run_database_migrations(pool: &DbPool) -> Result<(), String> {
+    let mut conn = pool.get()
+        .map_err(|e| format!("Failed to get database connection: {}", e))?;
+    run_migrations(&mut conn)
+}
+
+/// Brings the schema on `conn` up to date. Works on any connection so fixture
+/// databases from older app versions can be migrated directly.
+pub fn run_migrations(conn: &mut Connection) -> Result<(), String> {
+    conn.execute(
         "CREATE TABLE IF NOT EXISTS migrations (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             version TEXT NOT NULL UNIQUE,
             applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
         )",
         [],
-    ) {
-        eprintln!("Failed to create migrations table: {}", e);
-        return Err(e);
+    ).map_err(|e| format!("Failed to create migrations table: {}", e))?;
+
+    let applied = applied_versions(conn)
+        .map_err(|e| format!("Failed to read applied migrations: {}", e))?;
+
+    // Refuse to touch a database written by a newer version of the app
+    let latest = latest_schema_version();
+    if let Some(newest) = applied.iter().max() {
+        if *newest > latest {
+            return Err(format!(
+                "Database schema version {} is newer than this app supports ({}). Please update the application.",
+                newest, latest
+            ));
+        }
     }
 
-    // No migrations needed for initial version
-    // Add future migrations here when needed:
-    // let migrations = vec![
-    //     ("001_add_new_column", "ALTER TABLE table_name ADD COLUMN new_column TEXT"),
-    // ];
-    
-    // Uncomment the following code when you need to add migrations:
-    // for (version, sql) in migrations {
-    //     // Check if migration already applied
-    //     let applied = conn.query_row(
-    //         "SELECT COUNT(*) FROM migrations WHERE version = ?",
-    //         [version],
-    //         |row| row.get::<_, i32>(0),
-    //     ).unwrap_or(0) > 0;
-    //
-    //     if !applied {
-    //         // Apply migration
-    //         if let Err(e) = conn.execute(sql, []) {
-    //             eprintln!("Failed to apply migration {}: {}", version, e);
-    //             continue;
-    //         }
-    //
-    //         // Record migration as applied
-    //         if let Err(e) = conn.execute(
-    //             "INSERT INTO migrations (version) VALUES (?)",
-    //             [version],
-    //         ) {
-    //             eprintln!("Failed to record migration {}: {}", version, e);
-    //         } else {
-    //             println!("Applied migration: {}", version);
-    //         }
-    //     }
-    // }
+    // Table rebuilds must not trip over foreign keys (bundled SQLite enables them by default)
+    conn.execute_batch("PRAGMA foreign_keys = OFF")
+        .map_err(|e| format!("Failed to disable foreign keys: {}", e))?;
+    let result = apply_pending_migrations(conn, &applied);
+    conn.execute_batch("PRAGMA foreign_keys = ON")
+        .map_err(|e| format!("Failed to re-enable foreign keys: {}", e))?;
+    result
+}
+
+fn apply_pending_migrations(conn: &mut Connection, applied: &[u32]) -> Result<(), String> {
+    for migration in MIGRATIONS {
+        if applied.contains(&migration.version) {
+            continue;
+        }
+
+        let label = migration_label(migration);
+        let tx = conn.transaction()
+            .map_err(|e| format!("Failed to start migration {}: {}", label, e))?;
+
+        (migration.up)(&tx).map_err(|e| format!("Failed to apply migration {}: {}", label, e))?;
+
+        tx.execute("INSERT INTO migrations (version) VALUES (?1)", [&label])
+            .map_err(|e| format!("Failed to record migration {}: {}", label, e))?;
+
+        tx.commit().map_err(|e| format!("Failed to commit migration {}: {}", label, e))?;
+        println!("Applied migration: {}", label);
+    }
+
+    Ok(())
+}
+
+fn migration_label(migration: &Migration) -> String {
+    format!("{:03}_{}", migration.version, migration.name)
+}
+
+// Versions are stored as "NNN_name"; anything without a numeric prefix is ignored
+fn applied_versions(conn: &Connection) -> rusqlite::Result<Vec<u32>> {
+    let mut stmt = conn.prepare("SELECT version FROM migrations")?;
+    let versions = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(versions
+        .iter()
+        .filter_map(|v| v.split('_').next().and_then(|n| n.parse::<u32>().ok()))
+        .collect())
+}
+
+fn table_sql(conn: &Connection, table: &str) -> rusqlite::Result<Option<String>> {
+    let mut stmt = conn.prepare("SELECT sql FROM sqlite_master WHERE type='table' AND name = ?1")?;
+    let mut rows = stmt.query([table])?;
+    match rows.next()? {
+        Some(row) => Ok(Some(row.get(0)?)),
+        None => Ok(None),
+    }
+}
+
+fn column_exists(conn: &Connection, table: &str, column: &str) -> rusqlite::Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))? // Column name is at index 1
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(columns.iter().any(|c| c == column))
+}
+
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> rusqlite::Result<()> {
+    if !column_exists(conn, table, column)? {
+        println!("Adding {} column to {} table...", column, table);
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
+    Ok(())
+}
+
+// 001: current shape of every table. Existing tables from older app versions
+// are left alone here and upgraded by the migrations that follow.
+fn create_core_tables(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS people (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            last_name TEXT NOT NULL,
+            phone TEXT NOT NULL,
+            deleted_at DATETIME NULL,
+            is_active BOOLEAN DEFAULT 1
+        );
+
+        CREATE TABLE IF NOT EXISTS exercise (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            code TEXT NOT NULL UNIQUE,
+            deleted_at DATETIME NULL,
+            is_active BOOLEAN DEFAULT 1
+        );
+
+        CREATE TABLE IF NOT EXISTS workout_entries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            person_id INTEGER NOT NULL,
+            exercise_id INTEGER NOT NULL,
+            date DATE NOT NULL,
+            sets INTEGER,
+            reps INTEGER,
+            weight REAL,
+            notes TEXT,
+            order_index INTEGER DEFAULT 0,
+            group_number INTEGER DEFAULT 1,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (person_id) REFERENCES people (id) ON DELETE CASCADE,
+            FOREIGN KEY (exercise_id) REFERENCES exercise (id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS routines (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            code TEXT NOT NULL UNIQUE,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            deleted_at DATETIME,
+            is_active INTEGER DEFAULT 1
+        );
+
+        CREATE TABLE IF NOT EXISTS routine_exercises (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            routine_id INTEGER NOT NULL,
+            exercise_id INTEGER NOT NULL,
+            order_index INTEGER NOT NULL DEFAULT 0,
+            sets INTEGER,
+            reps INTEGER,
+            weight REAL,
+            notes TEXT,
+            group_number INTEGER DEFAULT 1,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (routine_id) REFERENCES routines (id) ON DELETE CASCADE,
+            FOREIGN KEY (exercise_id) REFERENCES exercise (id) ON DELETE CASCADE,
+            UNIQUE(routine_id, exercise_id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_routine_exercises_routine_id
+            ON routine_exercises (routine_id);
+
+        CREATE INDEX IF NOT EXISTS idx_routine_exercises_order
+            ON routine_exercises (routine_id, order_index);",
+    )
+}
+
+// 002: soft delete columns that older versions created tables without
+fn add_logical_deletion(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "people", "deleted_at", "DATETIME NULL")?;
+    add_column_if_missing(tx, "people", "is_active", "BOOLEAN DEFAULT 1")?;
+    add_column_if_missing(tx, "exercise", "deleted_at", "DATETIME NULL")?;
+    add_column_if_missing(tx, "exercise", "is_active", "BOOLEAN DEFAULT 1")?;
+    add_column_if_missing(tx, "routines", "deleted_at", "DATETIME")?;
+    add_column_if_missing(tx, "routines", "is_active", "INTEGER DEFAULT 1")?;
+    Ok(())
+}
+
+// 003: the first releases stored workout dates as TEXT; rebuild the table with a DATE column
+fn migrate_workout_entries_date_column(tx: &Transaction) -> rusqlite::Result<()> {
+    let needs_rebuild = table_sql(tx, "workout_entries")?
+        .map(|sql| sql.contains("date TEXT"))
+        .unwrap_or(false);
+
+    if needs_rebuild {
+        println!("Migrating workout_entries table from TEXT to DATE...");
+
+        // Keep ordering data when the old table already had it
+        let order_index = if column_exists(tx, "workout_entries", "order_index")? { "order_index" } else { "0" };
+        let group_number = if column_exists(tx, "workout_entries", "group_number")? { "group_number" } else { "1" };
+
+        tx.execute(
+            "CREATE TABLE workout_entries_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                person_id INTEGER NOT NULL,
+                exercise_id INTEGER NOT NULL,
+                date DATE NOT NULL,
+                sets INTEGER,
+                reps INTEGER,
+                weight REAL,
+                notes TEXT,
+                order_index INTEGER DEFAULT 0,
+                group_number INTEGER DEFAULT 1,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (person_id) REFERENCES people (id) ON DELETE CASCADE,
+                FOREIGN KEY (exercise_id) REFERENCES exercise (id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // Copy data, ensuring the date format is correct
+        tx.execute(
+            &format!(
+                "INSERT INTO workout_entries_new (id, person_id, exercise_id, date, sets, reps, weight, notes, order_index, group_number, created_at, updated_at)
+                 SELECT id, person_id, exercise_id,
+                        CASE
+                            WHEN length(date) = 10 AND date LIKE '____-__-__' THEN date
+                            ELSE date('now')
+                        END as date,
+                        sets, reps, weight, notes, {} as order_index, {} as group_number, created_at, updated_at
+                 FROM workout_entries",
+                order_index, group_number
+            ),
+            [],
+        )?;
+
+        tx.execute("DROP TABLE workout_entries", [])?;
+        tx.execute("ALTER TABLE workout_entries_new RENAME TO workout_entries", [])?;
+    }
+
+    // Dropping the old table also drops its index, so (re)create it here
+    tx.execute(
+        "CREATE INDEX IF NOT EXISTS idx_workout_entries_person_date
+         ON workout_entries (person_id, date)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+// 004: exercise ordering and grouping inside workouts and routines
+fn add_ordering_and_groups(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "workout_entries", "order_index", "INTEGER DEFAULT 0")?;
+    add_column_if_missing(tx, "workout_entries", "group_number", "INTEGER DEFAULT 1")?;
+    add_column_if_missing(tx, "routine_exercises", "group_number", "INTEGER DEFAULT 1")?;
+    Ok(())
+}
+
+// 005: per-set detail (reps, weight, completion, set type) for each workout entry
+fn create_workout_sets(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS workout_sets (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            workout_entry_id INTEGER NOT NULL,
+            order_index INTEGER NOT NULL DEFAULT 0,
+            reps INTEGER,
+            weight REAL,
+            completed INTEGER NOT NULL DEFAULT 0,
+            set_type TEXT NOT NULL DEFAULT 'working'
+                CHECK (set_type IN ('warm_up', 'working', 'drop', 'failure')),
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (workout_entry_id) REFERENCES workout_entries (id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_workout_sets_entry
+            ON workout_sets (workout_entry_id, order_index);",
+    )
+}
+
+// 006: structured intensity prescription (RPE, RIR, tempo, rest) for workouts and routines
+fn add_intensity_fields(tx: &Transaction) -> rusqlite::Result<()> {
+    for table in ["workout_entries", "routine_exercises"] {
+        add_column_if_missing(tx, table, "rpe", "REAL")?;
+        add_column_if_missing(tx, table, "rir", "INTEGER")?;
+        add_column_if_missing(tx, table, "tempo", "TEXT")?;
+        add_column_if_missing(tx, table, "rest_seconds", "INTEGER")?;
+    }
+    Ok(())
+}
+
+// 007: time/distance based exercises and the matching fields on workouts and routines
+fn add_measurement_types(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(
+        tx,
+        "exercise",
+        "measurement_type",
+        "TEXT NOT NULL DEFAULT 'reps_weight'
+            CHECK (measurement_type IN ('reps_weight', 'time', 'distance', 'distance_time', 'bodyweight_reps'))",
+    )?;
+    for table in ["workout_entries", "routine_exercises"] {
+        add_column_if_missing(tx, table, "duration_seconds", "INTEGER")?;
+        add_column_if_missing(tx, table, "distance_meters", "REAL")?;
+    }
+    Ok(())
+}
+
+// 008: muscle groups, equipment and movement patterns, linked many-to-many to exercises
+fn create_exercise_taxonomy(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS muscle_groups (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS equipment (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS movement_patterns (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS exercise_muscle_groups (
+            exercise_id INTEGER NOT NULL,
+            muscle_group_id INTEGER NOT NULL,
+            is_primary INTEGER NOT NULL DEFAULT 1,
+            PRIMARY KEY (exercise_id, muscle_group_id),
+            FOREIGN KEY (exercise_id) REFERENCES exercise (id) ON DELETE CASCADE,
+            FOREIGN KEY (muscle_group_id) REFERENCES muscle_groups (id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS exercise_equipment (
+            exercise_id INTEGER NOT NULL,
+            equipment_id INTEGER NOT NULL,
+            PRIMARY KEY (exercise_id, equipment_id),
+            FOREIGN KEY (exercise_id) REFERENCES exercise (id) ON DELETE CASCADE,
+            FOREIGN KEY (equipment_id) REFERENCES equipment (id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS exercise_movement_patterns (
+            exercise_id INTEGER NOT NULL,
+            movement_pattern_id INTEGER NOT NULL,
+            PRIMARY KEY (exercise_id, movement_pattern_id),
+            FOREIGN KEY (exercise_id) REFERENCES exercise (id) ON DELETE CASCADE,
+            FOREIGN KEY (movement_pattern_id) REFERENCES movement_patterns (id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_exercise_muscle_groups_group
+            ON exercise_muscle_groups (muscle_group_id, is_primary);
+        CREATE INDEX IF NOT EXISTS idx_exercise_equipment_equipment
+            ON exercise_equipment (equipment_id);
+        CREATE INDEX IF NOT EXISTS idx_exercise_movement_patterns_pattern
+            ON exercise_movement_patterns (movement_pattern_id);",
+    )
+}
+
+// 009: membership plans, the subscriptions people buy and the payments made for them
+fn create_memberships(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS membership_plans (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            price REAL NOT NULL CHECK (price >= 0),
+            duration_days INTEGER NOT NULL CHECK (duration_days > 0),
+            is_active INTEGER NOT NULL DEFAULT 1,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS subscriptions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            person_id INTEGER NOT NULL,
+            plan_id INTEGER NOT NULL,
+            start_date DATE NOT NULL,
+            end_date DATE NOT NULL,
+            price REAL NOT NULL CHECK (price >= 0),
+            notes TEXT,
+            cancelled_at DATETIME,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (person_id) REFERENCES people (id) ON DELETE CASCADE,
+            FOREIGN KEY (plan_id) REFERENCES membership_plans (id)
+        );
+
+        CREATE TABLE IF NOT EXISTS payments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            subscription_id INTEGER NOT NULL,
+            amount REAL NOT NULL CHECK (amount > 0),
+            paid_on DATE NOT NULL,
+            method TEXT NOT NULL DEFAULT 'cash'
+                CHECK (method IN ('cash', 'card', 'transfer', 'other')),
+            notes TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (subscription_id) REFERENCES subscriptions (id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_subscriptions_person
+            ON subscriptions (person_id, start_date);
+        CREATE INDEX IF NOT EXISTS idx_subscriptions_end_date
+            ON subscriptions (end_date);
+        CREATE INDEX IF NOT EXISTS idx_payments_subscription
+            ON payments (subscription_id);",
+    )
+}
+
+// 010: gym visits, with local check-in and check-out times
+fn create_check_ins(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS check_ins (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            person_id INTEGER NOT NULL,
+            checked_in_at DATETIME NOT NULL,
+            checked_out_at DATETIME,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (person_id) REFERENCES people (id) ON DELETE CASCADE,
+            CHECK (checked_out_at IS NULL OR checked_out_at >= checked_in_at)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_check_ins_checked_in_at
+            ON check_ins (checked_in_at);
+        CREATE INDEX IF NOT EXISTS idx_check_ins_person
+            ON check_ins (person_id, checked_in_at);",
+    )
+}
+
+// 011: optional client profile (birth date, sex, contact, goals, medical notes, emergency contact)
+fn add_person_profile(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "people", "birth_date", "DATE")?;
+    add_column_if_missing(tx, "people", "sex", "TEXT CHECK (sex IS NULL OR sex IN ('female', 'male', 'other'))")?;
+    add_column_if_missing(tx, "people", "email", "TEXT")?;
+    add_column_if_missing(tx, "people", "height_cm", "REAL")?;
+    add_column_if_missing(tx, "people", "training_goal", "TEXT")?;
+    add_column_if_missing(tx, "people", "medical_notes", "TEXT")?;
+    add_column_if_missing(tx, "people", "emergency_contact_name", "TEXT")?;
+    add_column_if_missing(tx, "people", "emergency_contact_phone", "TEXT")?;
     Ok(())
 }
+
+// 012: body measurement sessions, with one row per recorded metric
+fn create_body_measurements(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS body_measurements (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            person_id INTEGER NOT NULL,
+            date DATE NOT NULL,
+            notes TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (person_id) REFERENCES people (id) ON DELETE CASCADE,
+            UNIQUE (person_id, date)
+        );
+
+        CREATE TABLE IF NOT EXISTS body_measurement_values (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            measurement_id INTEGER NOT NULL,
+            metric TEXT NOT NULL,
+            value REAL NOT NULL,
+            FOREIGN KEY (measurement_id) REFERENCES body_measurements (id) ON DELETE CASCADE,
+            UNIQUE (measurement_id, metric)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_body_measurement_values_metric
+            ON body_measurement_values (metric, measurement_id);",
+    )
+}
+
+// 013: multi-week programs; each day points to a routine or lists its own exercises
+fn create_programs(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS programs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            code TEXT NOT NULL UNIQUE,
+            description TEXT,
+            weeks INTEGER NOT NULL CHECK (weeks > 0),
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            deleted_at DATETIME,
+            is_active INTEGER DEFAULT 1
+        );
+
+        CREATE TABLE IF NOT EXISTS program_days (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            program_id INTEGER NOT NULL,
+            week INTEGER NOT NULL,
+            day_index INTEGER NOT NULL,
+            label TEXT,
+            routine_id INTEGER,
+            notes TEXT,
+            FOREIGN KEY (program_id) REFERENCES programs (id) ON DELETE CASCADE,
+            FOREIGN KEY (routine_id) REFERENCES routines (id) ON DELETE SET NULL,
+            UNIQUE (program_id, week, day_index)
+        );
+
+        CREATE TABLE IF NOT EXISTS program_day_exercises (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            program_day_id INTEGER NOT NULL,
+            exercise_id INTEGER NOT NULL,
+            order_index INTEGER NOT NULL DEFAULT 0,
+            sets INTEGER,
+            reps INTEGER,
+            weight REAL,
+            notes TEXT,
+            group_number INTEGER DEFAULT 1,
+            rpe REAL,
+            rir INTEGER,
+            tempo TEXT,
+            rest_seconds INTEGER,
+            duration_seconds INTEGER,
+            distance_meters REAL,
+            FOREIGN KEY (program_day_id) REFERENCES program_days (id) ON DELETE CASCADE,
+            FOREIGN KEY (exercise_id) REFERENCES exercise (id) ON DELETE CASCADE,
+            UNIQUE (program_day_id, exercise_id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_program_days_routine
+            ON program_days (routine_id);",
+    )
+}
+
+// 014: routines and programs assigned to people on given weekdays
+fn create_routine_assignments(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS routine_assignments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            person_id INTEGER NOT NULL,
+            routine_id INTEGER,
+            program_id INTEGER,
+            start_date TEXT NOT NULL,
+            end_date TEXT,
+            weekdays TEXT NOT NULL,
+            notes TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (person_id) REFERENCES people (id) ON DELETE CASCADE,
+            FOREIGN KEY (routine_id) REFERENCES routines (id) ON DELETE CASCADE,
+            FOREIGN KEY (program_id) REFERENCES programs (id) ON DELETE CASCADE,
+            CHECK ((routine_id IS NULL) <> (program_id IS NULL))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_routine_assignments_person
+            ON routine_assignments (person_id, start_date);",
+    )
+}
+
+// 015: progression rules, keyed by routine and exercise so they survive
+// replacing the routine's exercise list
+fn create_progression_rules(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS progression_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            routine_id INTEGER NOT NULL,
+            exercise_id INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            increment_kg REAL,
+            min_reps INTEGER,
+            max_reps INTEGER,
+            percentage REAL,
+            deload_after_failures INTEGER,
+            deload_percentage REAL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (routine_id) REFERENCES routines (id) ON DELETE CASCADE,
+            FOREIGN KEY (exercise_id) REFERENCES exercise (id) ON DELETE CASCADE,
+            UNIQUE (routine_id, exercise_id)
+        );",
+    )
+}
+
+// 016: the routine exercise a workout entry was planned from. Replacing a routine's
+// exercise list recreates its rows, so the routine itself is kept as well.
+fn link_workout_entries_to_routines(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "workout_entries", "routine_id", "INTEGER REFERENCES routines (id) ON DELETE SET NULL")?;
+    add_column_if_missing(
+        tx,
+        "workout_entries",
+        "routine_exercise_id",
+        "INTEGER REFERENCES routine_exercises (id) ON DELETE SET NULL",
+    )?;
+    tx.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_workout_entries_routine
+            ON workout_entries (routine_id);
+
+        CREATE INDEX IF NOT EXISTS idx_workout_entries_routine_exercise
+            ON workout_entries (routine_exercise_id);",
+    )
+}
+
+// 017: immutable snapshots of a routine and its exercises, one per change.
+// Existing routines start with their current state as revision 1.
+fn create_routine_revisions(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS routine_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            routine_id INTEGER NOT NULL,
+            revision INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            code TEXT NOT NULL,
+            comment TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (routine_id) REFERENCES routines (id) ON DELETE CASCADE,
+            UNIQUE (routine_id, revision)
+        );
+
+        CREATE TABLE IF NOT EXISTS routine_revision_exercises (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            revision_id INTEGER NOT NULL,
+            exercise_id INTEGER NOT NULL,
+            order_index INTEGER NOT NULL DEFAULT 0,
+            sets INTEGER,
+            reps INTEGER,
+            weight REAL,
+            notes TEXT,
+            group_number INTEGER,
+            rpe REAL,
+            rir INTEGER,
+            tempo TEXT,
+            rest_seconds INTEGER,
+            duration_seconds INTEGER,
+            distance_meters REAL,
+            FOREIGN KEY (revision_id) REFERENCES routine_revisions (id) ON DELETE CASCADE,
+            FOREIGN KEY (exercise_id) REFERENCES exercise (id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_routine_revision_exercises_revision
+            ON routine_revision_exercises (revision_id);
+
+        INSERT INTO routine_revisions (routine_id, revision, name, code)
+        SELECT id, 1, name, code FROM routines;
+
+        INSERT INTO routine_revision_exercises (revision_id, exercise_id, order_index, sets, reps, weight, notes, group_number,
+                                                rpe, rir, tempo, rest_seconds, duration_seconds, distance_meters)
+        SELECT rr.id, re.exercise_id, re.order_index, re.sets, re.reps, re.weight, re.notes, re.group_number,
+               re.rpe, re.rir, re.tempo, re.rest_seconds, re.duration_seconds, re.distance_meters
+        FROM routine_revisions rr
+        JOIN routine_exercises re ON re.routine_id = rr.routine_id
+        WHERE rr.revision = 1;",
+    )
+}
+
+// 018: scheduled sessions are stored as planned entries until they are logged.
+// Existing entries were all logged by hand, future-dated ones included, and stay performed.
+fn add_workout_entry_status(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(
+        tx,
+        "workout_entries",
+        "status",
+        "TEXT NOT NULL DEFAULT 'performed' CHECK (status IN ('planned', 'performed'))",
+    )?;
+    tx.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_workout_entries_person_status
+            ON workout_entries (person_id, status);",
+    )
+}
+
+// 019: the routine's sets × reps × weight as they were when the entry was planned,
+// so later routine edits don't change past adherence. Existing entries take the
+// routine revision in effect on their date, or the first one.
+fn store_workout_entry_prescriptions(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "workout_entries", "prescribed_sets", "INTEGER")?;
+    add_column_if_missing(tx, "workout_entries", "prescribed_reps", "INTEGER")?;
+    add_column_if_missing(tx, "workout_entries", "prescribed_weight", "REAL")?;
+    tx.execute_batch(
+        "UPDATE workout_entries
+         SET (prescribed_sets, prescribed_reps, prescribed_weight) = (
+             SELECT rre.sets, rre.reps, rre.weight
+             FROM routine_revision_exercises rre
+             WHERE rre.exercise_id = workout_entries.exercise_id
+               AND rre.revision_id = COALESCE(
+                   (SELECT MAX(id) FROM routine_revisions
+                    WHERE routine_id = workout_entries.routine_id AND date(created_at) <= date(workout_entries.date)),
+                   (SELECT MIN(id) FROM routine_revisions WHERE routine_id = workout_entries.routine_id)
+               )
+         )
+         WHERE routine_id IS NOT NULL;",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Schema of the first releases: TEXT dates, no soft delete, no ordering or groups
+    const FIRST_RELEASE_SCHEMA: &str = "
+        CREATE TABLE people (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            last_name TEXT NOT NULL,
+            phone TEXT NOT NULL
+        );
+        CREATE TABLE exercise (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            code TEXT NOT NULL UNIQUE
+        );
+        CREATE TABLE workout_entries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            person_id INTEGER NOT NULL,
+            exercise_id INTEGER NOT NULL,
+            date TEXT NOT NULL,
+            sets INTEGER,
+            reps INTEGER,
+            weight REAL,
+            notes TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE TABLE routines (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            code TEXT NOT NULL UNIQUE,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE TABLE routine_exercises (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            routine_id INTEGER NOT NULL,
+            exercise_id INTEGER NOT NULL,
+            order_index INTEGER NOT NULL DEFAULT 0,
+            sets INTEGER,
+            reps INTEGER,
+            weight REAL,
+            notes TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(routine_id, exercise_id)
+        );
+
+        INSERT INTO people (name, last_name, phone) VALUES ('Ana', 'Ruiz', '600000001');
+        INSERT INTO exercise (name, code) VALUES ('Squat', 'SQ');
+        INSERT INTO routines (name, code) VALUES ('Legs', 'LEGS');
+        INSERT INTO routine_exercises (routine_id, exercise_id, sets, reps, weight) VALUES (1, 1, 3, 5, 100.0);";
+
+    fn applied(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn.prepare("SELECT version FROM migrations ORDER BY version").unwrap();
+        let versions = stmt.query_map([], |row| row.get(0)).unwrap();
+        versions.collect::<Result<_, _>>().unwrap()
+    }
+
+    fn count(conn: &Connection, sql: &str) -> i32 {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn first_release_database_is_migrated_with_its_data() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(FIRST_RELEASE_SCHEMA).unwrap();
+        conn.execute_batch(
+            "INSERT INTO workout_entries (person_id, exercise_id, date, sets, reps, weight, notes)
+             VALUES (1, 1, '2024-01-02', 3, 5, 100.0, 'felt good'), (1, 1, 'yesterday', 1, 1, 20.0, NULL),
+                    (1, 1, '2999-01-01', 3, 5, 105.0, NULL);",
+        )
+        .unwrap();
+
+        run_migrations(&mut conn).unwrap();
+
+        let versions = applied(&conn);
+        assert_eq!(versions.len(), MIGRATIONS.len());
+        assert_eq!(versions.first().map(String::as_str), Some("001_create_core_tables"));
+        assert_eq!(versions.last().unwrap(), &format!("{:03}_{}", latest_schema_version(), MIGRATIONS.last().unwrap().name));
+
+        let sql = table_sql(&conn, "workout_entries").unwrap().unwrap();
+        assert!(sql.contains("date DATE") && !sql.contains("date TEXT"));
+        let (date, sets, reps, weight, notes, order_index, group_number, status): (String, i32, i32, f64, String, i32, i32, String) = conn
+            .query_row(
+                "SELECT date, sets, reps, weight, notes, order_index, group_number, status FROM workout_entries WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?, row.get(7)?)),
+            )
+            .unwrap();
+        assert_eq!((date.as_str(), sets, reps, weight, notes.as_str()), ("2024-01-02", 3, 5, 100.0, "felt good"));
+        assert_eq!((order_index, group_number, status.as_str()), (0, 1, "performed"));
+        // Malformed dates are replaced rather than dropped; future ones were logged all the same
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM workout_entries WHERE length(date) = 10"), 3);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM workout_entries WHERE status = 'planned'"), 0);
+
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM people WHERE deleted_at IS NULL AND is_active = 1"), 1);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM exercise WHERE deleted_at IS NULL AND is_active = 1"), 1);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM routines WHERE deleted_at IS NULL AND is_active = 1"), 1);
+        assert_eq!(count(&conn, "SELECT group_number FROM routine_exercises"), 1);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM routine_revision_exercises"), 1);
+
+        // Nothing left to do the second time
+        run_migrations(&mut conn).unwrap();
+        assert_eq!(applied(&conn).len(), MIGRATIONS.len());
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM workout_entries"), 3);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM routine_revisions"), 1);
+    }
+
+    #[test]
+    fn text_date_table_keeps_its_ordering() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(FIRST_RELEASE_SCHEMA).unwrap();
+        conn.execute_batch(
+            "ALTER TABLE workout_entries ADD COLUMN order_index INTEGER DEFAULT 0;
+             ALTER TABLE workout_entries ADD COLUMN group_number INTEGER DEFAULT 1;
+             INSERT INTO workout_entries (person_id, exercise_id, date, sets, order_index, group_number)
+             VALUES (1, 1, '2024-01-02', 3, 4, 2);",
+        )
+        .unwrap();
+
+        run_migrations(&mut conn).unwrap();
+
+        let (order_index, group_number): (i32, i32) = conn
+            .query_row("SELECT order_index, group_number FROM workout_entries", [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap();
+        assert_eq!((order_index, group_number), (4, 2));
+    }
+
+    #[test]
+    fn existing_entries_take_the_revision_in_effect_on_their_date() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO people (name, last_name, phone) VALUES ('Ana', 'Ruiz', '600000001');
+             INSERT INTO exercise (name, code) VALUES ('Squat', 'SQ');
+             INSERT INTO routines (name, code) VALUES ('Legs', 'LEGS');
+             INSERT INTO routine_revisions (routine_id, revision, name, code, created_at)
+             VALUES (1, 1, 'Legs', 'LEGS', '2024-01-01 09:00:00'), (1, 2, 'Legs', 'LEGS', '2024-03-01 09:00:00');
+             INSERT INTO routine_revision_exercises (revision_id, exercise_id, sets, reps, weight)
+             VALUES (1, 1, 3, 5, 100.0), (2, 1, 5, 5, 120.0);
+             INSERT INTO workout_entries (person_id, exercise_id, date, routine_id)
+             VALUES (1, 1, '2023-12-01', 1), (1, 1, '2024-02-01', 1), (1, 1, '2024-03-01', 1);
+             DELETE FROM migrations WHERE version = '019_store_workout_entry_prescriptions';",
+        )
+        .unwrap();
+
+        run_migrations(&mut conn).unwrap();
+
+        let mut stmt = conn.prepare("SELECT prescribed_sets, prescribed_weight FROM workout_entries ORDER BY date").unwrap();
+        let prescriptions: Vec<(i32, f64)> =
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).unwrap().collect::<Result<_, _>>().unwrap();
+        // Entries older than the first revision take that one
+        assert_eq!(prescriptions, [(3, 100.0), (3, 100.0), (5, 120.0)]);
+    }
+
+    #[test]
+    fn newer_database_is_rejected() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+        conn.execute("INSERT INTO migrations (version) VALUES ('999_future')", []).unwrap();
+
+        let error = run_migrations(&mut conn).unwrap_err();
+        assert!(error.contains("newer than this app supports"), "{}", error);
+        assert_eq!(applied(&conn).len(), MIGRATIONS.len() + 1);
+    }
+}
@@ -1,2 +1 @@
-pub mod db;
-pub mod api_keys;
\ No newline at end of file
+pub mod db;
\ No newline at end of file
@@ -0,0 +1,234 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+
+pub type AppResult<T> = Result<T, AppError>;
+
+/// Error shared by repositories, services and Tauri commands.
+///
+/// It reaches the frontend as `{ code, field, message }`, where `code` is one of the
+/// stable values returned by [`AppError::code`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    NotFound { message: String },
+    Validation { field: Option<String>, message: String },
+    Duplicate { field: Option<String>, message: String },
+    DatabaseUnavailable { message: String },
+    Database { message: String },
+}
+
+impl AppError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        AppError::NotFound { message: message.into() }
+    }
+
+    pub fn validation(field: &str, message: impl Into<String>) -> Self {
+        AppError::Validation { field: Some(field.to_string()), message: message.into() }
+    }
+
+    pub fn duplicate(field: &str, message: impl Into<String>) -> Self {
+        AppError::Duplicate { field: Some(field.to_string()), message: message.into() }
+    }
+
+    pub fn database_unavailable(message: impl Into<String>) -> Self {
+        AppError::DatabaseUnavailable { message: message.into() }
+    }
+
+    pub fn database(message: impl Into<String>) -> Self {
+        AppError::Database { message: message.into() }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound { .. } => "NOT_FOUND",
+            AppError::Validation { .. } => "VALIDATION_FAILED",
+            AppError::Duplicate { .. } => "DUPLICATE",
+            AppError::DatabaseUnavailable { .. } => "DATABASE_UNAVAILABLE",
+            AppError::Database { .. } => "DATABASE_ERROR",
+        }
+    }
+
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            AppError::Validation { field, .. } | AppError::Duplicate { field, .. } => field.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::NotFound { message }
+            | AppError::Validation { message, .. }
+            | AppError::Duplicate { message, .. }
+            | AppError::DatabaseUnavailable { message }
+            | AppError::Database { message } => message,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("field", &self.field())?;
+        state.serialize_field("message", self.message())?;
+        state.end()
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(error: rusqlite::Error) -> Self {
+        use rusqlite::ffi;
+
+        match &error {
+            rusqlite::Error::QueryReturnedNoRows => AppError::not_found("Record not found"),
+            rusqlite::Error::SqliteFailure(failure, message) => match failure.extended_code {
+                ffi::SQLITE_CONSTRAINT_UNIQUE | ffi::SQLITE_CONSTRAINT_PRIMARYKEY => AppError::Duplicate {
+                    field: message.as_deref().and_then(constraint_column),
+                    message: "A record with the same value already exists".to_string(),
+                },
+                ffi::SQLITE_CONSTRAINT_FOREIGNKEY => AppError::Validation {
+                    field: None,
+                    message: "Referenced record does not exist".to_string(),
+                },
+                _ => match failure.code {
+                    rusqlite::ErrorCode::DatabaseBusy
+                    | rusqlite::ErrorCode::DatabaseLocked
+                    | rusqlite::ErrorCode::CannotOpen
+                    | rusqlite::ErrorCode::NotADatabase
+                    | rusqlite::ErrorCode::ReadOnly => AppError::database_unavailable(error.to_string()),
+                    _ => AppError::database(error.to_string()),
+                },
+            },
+            _ => AppError::database(error.to_string()),
+        }
+    }
+}
+
+impl From<r2d2::Error> for AppError {
+    fn from(error: r2d2::Error) -> Self {
+        AppError::database_unavailable(format!("Failed to get database connection: {}", error))
+    }
+}
+
+// "UNIQUE constraint failed: exercise.code" -> "code"
+fn constraint_column(message: &str) -> Option<String> {
+    message
+        .rsplit(": ")
+        .next()
+        .and_then(|columns| columns.split(", ").next())
+        .and_then(|column| column.split('.').nth(1))
+        .map(|column| column.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::{ffi, params, Connection};
+
+    fn connection() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "PRAGMA foreign_keys = ON;
+             CREATE TABLE exercise (id INTEGER PRIMARY KEY, code TEXT NOT NULL UNIQUE);
+             CREATE TABLE routine_exercises (
+                 routine_id INTEGER NOT NULL,
+                 exercise_id INTEGER NOT NULL REFERENCES exercise(id),
+                 UNIQUE (routine_id, exercise_id)
+             );
+             INSERT INTO exercise (id, code) VALUES (1, 'SQ');",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn unique_violation_maps_to_duplicate_with_column() {
+        let conn = connection();
+        let error: AppError = conn
+            .execute("INSERT INTO exercise (id, code) VALUES (2, 'SQ')", [])
+            .unwrap_err()
+            .into();
+
+        assert_eq!(error.code(), "DUPLICATE");
+        assert_eq!(error.field(), Some("code"));
+    }
+
+    #[test]
+    fn primary_key_violation_maps_to_duplicate() {
+        let conn = connection();
+        let error: AppError = conn
+            .execute("INSERT INTO exercise (id, code) VALUES (1, 'BP')", [])
+            .unwrap_err()
+            .into();
+
+        assert_eq!(error.code(), "DUPLICATE");
+        assert_eq!(error.field(), Some("id"));
+    }
+
+    #[test]
+    fn foreign_key_violation_maps_to_validation() {
+        let conn = connection();
+        let error: AppError = conn
+            .execute("INSERT INTO routine_exercises (routine_id, exercise_id) VALUES (1, ?1)", params![99])
+            .unwrap_err()
+            .into();
+
+        assert_eq!(error.code(), "VALIDATION_FAILED");
+        assert_eq!(error.field(), None);
+    }
+
+    #[test]
+    fn missing_row_maps_to_not_found() {
+        let conn = connection();
+        let error: AppError = conn
+            .query_row("SELECT code FROM exercise WHERE id = 99", [], |row| row.get::<_, String>(0))
+            .unwrap_err()
+            .into();
+
+        assert_eq!(error.code(), "NOT_FOUND");
+    }
+
+    #[test]
+    fn busy_and_locked_map_to_unavailable() {
+        for code in [ffi::SQLITE_BUSY, ffi::SQLITE_LOCKED, ffi::SQLITE_READONLY] {
+            let error: AppError = rusqlite::Error::SqliteFailure(ffi::Error::new(code), None).into();
+            assert_eq!(error.code(), "DATABASE_UNAVAILABLE");
+        }
+    }
+
+    #[test]
+    fn other_failures_map_to_database_error() {
+        let conn = connection();
+        let error: AppError = conn.execute("INSERT INTO missing_table VALUES (1)", []).unwrap_err().into();
+
+        assert_eq!(error.code(), "DATABASE_ERROR");
+    }
+
+    #[test]
+    fn constraint_column_reads_the_first_column() {
+        assert_eq!(constraint_column("UNIQUE constraint failed: exercise.code").as_deref(), Some("code"));
+        assert_eq!(
+            constraint_column("UNIQUE constraint failed: routine_exercises.routine_id, routine_exercises.exercise_id")
+                .as_deref(),
+            Some("routine_id")
+        );
+        assert_eq!(constraint_column("constraint failed"), None);
+    }
+
+    #[test]
+    fn serializes_code_field_and_message() {
+        let value = serde_json::to_value(AppError::duplicate("code", "Ya existe")).unwrap();
+        assert_eq!(value, serde_json::json!({ "code": "DUPLICATE", "field": "code", "message": "Ya existe" }));
+
+        let value = serde_json::to_value(AppError::not_found("Record not found")).unwrap();
+        assert_eq!(value["field"], serde_json::Value::Null);
+    }
+}
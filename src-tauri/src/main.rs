@@ -4,141 +4,355 @@ mod models;
 mod repository;
 mod services;
 mod config;
+mod error;
 
-use models::person::Person;
+use models::person::{DuplicateCandidate, Person, PersonMergeSummary};
+use models::membership::{MembershipPlan, Payment, Subscription, SubscriptionWithDetails};
+use models::check_in::{CheckIn, DailyOccupancy, HourlyOccupancy, VisitStreaks};
+use models::body_measurement::{BodyMeasurement, BodyMetric, MeasurementPoint};
 use models::exercise::Exercise;
+use models::taxonomy::{ExerciseFilter, ExerciseTaxonomy, ExerciseTaxonomyUpdate, TaxonomyItem, TaxonomyKind};
 use models::workout_entry::WorkoutEntry;
+use models::workout_set::WorkoutSet;
+use models::personal_record::{ExerciseStats, OneRepMaxFormula, PersonalRecord};
+use models::report::{AdherenceReport, ExerciseSetsPoint, FrequencyPoint, IntensityPoint, ReportBucket, TonnagePoint};
 use models::routine::Routine;
 use models::routine_exercise::RoutineExercise;
+use models::routine_revision::{RoutineRevision, RoutineRevisionDiff, RoutineRevisionWithExercises};
+use models::program::{Program, ProgramDay, ProgramWithDays};
+use models::assignment::{Assignment, AssignmentWithDetails, PlannedSession, ScheduleSummary};
+use models::progression::{ProgressionProposal, ProgressionRule};
+use models::settings::{AppSettings, SettingsView};
 use services::person_service::PersonService;
+use services::membership_service::MembershipService;
+use services::check_in_service::CheckInService;
+use services::body_measurement_service::BodyMeasurementService;
 use services::exercise_service::ExerciseService;
 use services::workout_entry_service::WorkoutEntryService;
 use services::routine_service::RoutineService;
-use services::backup_service::BackupService;
-use services::updater_service::UpdaterService;
-use tauri::{State, Manager};
+use services::program_service::ProgramService;
+use services::assignment_service::AssignmentService;
+use services::progression_service::ProgressionService;
+use services::analytics_service::AnalyticsService;
+use services::report_service::ReportService;
+use services::backup_service::{BackupInfo, BackupService};
+use services::settings_service::SettingsService;
+use services::updater_service::{UpdateInfo, UpdaterService};
+use tauri::{State, Manager, Emitter};
+use std::sync::Arc;
 use config::db::setup_services;
+use error::AppError;
 
 // Person commands
 #[tauri::command]
-fn create_person(service: State<'_, PersonService>, person: Person) -> Result<(), String> {
+fn create_person(service: State<'_, PersonService>, person: Person) -> Result<(), AppError> {
     service.create_person(person)
 }
 
 #[tauri::command]
-fn get_persons(service: State<'_, PersonService>) -> Vec<Person> {
+fn get_persons(service: State<'_, PersonService>) -> Result<Vec<Person>, AppError> {
     service.list_people()
 }
 
 #[tauri::command]
-fn get_persons_paginated(service: State<'_, PersonService>, page: i32, page_size: i32) -> Vec<Person> {
+fn get_persons_paginated(service: State<'_, PersonService>, page: i32, page_size: i32) -> Result<Vec<Person>, AppError> {
     service.list_people_paginated(page, page_size)
 }
 
 #[tauri::command]
-fn get_persons_paginated_response(service: State<'_, PersonService>, page: i32, page_size: i32) -> models::person::PaginatedPersonResponse {
+fn get_persons_paginated_response(service: State<'_, PersonService>, page: i32, page_size: i32) -> Result<models::person::PaginatedPersonResponse, AppError> {
     service.list_people_paginated_response(page, page_size)
 }
 
 #[tauri::command]
-fn search_persons(service: State<'_, PersonService>, query: String) -> Vec<Person> {
+fn search_persons(service: State<'_, PersonService>, query: String) -> Result<Vec<Person>, AppError> {
     service.search_people(&query)
 }
 
 #[tauri::command]
-fn search_persons_paginated(service: State<'_, PersonService>, query: String, page: i32, page_size: i32) -> Vec<Person> {
+fn search_persons_paginated(service: State<'_, PersonService>, query: String, page: i32, page_size: i32) -> Result<Vec<Person>, AppError> {
     service.search_people_paginated(&query, page, page_size)
 }
 
 #[tauri::command]
-fn search_persons_paginated_response(service: State<'_, PersonService>, query: String, page: i32, page_size: i32) -> models::person::PaginatedPersonResponse {
+fn search_persons_paginated_response(service: State<'_, PersonService>, query: String, page: i32, page_size: i32) -> Result<models::person::PaginatedPersonResponse, AppError> {
     service.search_people_paginated_response(&query, page, page_size)
 }
 
 #[tauri::command]
-fn delete_person(service: State<'_, PersonService>, id: i32) -> Result<(), String> {
+fn delete_person(service: State<'_, PersonService>, id: i32) -> Result<(), AppError> {
     service.delete_person(id)
 }
 
 #[tauri::command]
-fn restore_person(service: State<'_, PersonService>, id: i32) -> Result<(), String> {
+fn restore_person(service: State<'_, PersonService>, id: i32) -> Result<(), AppError> {
     service.restore_person(id)
 }
 
 #[tauri::command]
-fn get_deleted_people(service: State<'_, PersonService>) -> Vec<Person> {
+fn get_deleted_people(service: State<'_, PersonService>) -> Result<Vec<Person>, AppError> {
     service.list_deleted_people()
 }
 
 #[tauri::command]
-fn count_deleted_people(service: State<'_, PersonService>) -> i32 {
+fn count_deleted_people(service: State<'_, PersonService>) -> Result<i32, AppError> {
     service.count_deleted_people()
 }
 
 #[tauri::command]
-fn update_person(service: State<'_, PersonService>, person: Person) -> Result<(), String> {
+fn update_person(service: State<'_, PersonService>, person: Person) -> Result<(), AppError> {
     service.update_person(person)
 }
 
+#[tauri::command]
+fn find_people_by_phone(service: State<'_, PersonService>, phone: String) -> Result<Vec<Person>, AppError> {
+    service.find_people_by_phone(&phone)
+}
+
+#[tauri::command]
+fn find_duplicate_people(service: State<'_, PersonService>) -> Result<Vec<DuplicateCandidate>, AppError> {
+    service.find_duplicate_people()
+}
+
+#[tauri::command]
+fn merge_people(service: State<'_, PersonService>, survivor_id: i32, duplicate_id: i32) -> Result<PersonMergeSummary, AppError> {
+    service.merge_people(survivor_id, duplicate_id)
+}
+
+// Membership commands
+#[tauri::command]
+fn list_membership_plans(service: State<'_, MembershipService>, include_inactive: bool) -> Result<Vec<MembershipPlan>, AppError> {
+    service.list_plans(include_inactive)
+}
+
+#[tauri::command]
+fn create_membership_plan(service: State<'_, MembershipService>, plan: MembershipPlan) -> Result<i32, AppError> {
+    service.create_plan(plan)
+}
+
+#[tauri::command]
+fn update_membership_plan(service: State<'_, MembershipService>, plan: MembershipPlan) -> Result<(), AppError> {
+    service.update_plan(plan)
+}
+
+#[tauri::command]
+fn create_subscription(service: State<'_, MembershipService>, subscription: Subscription) -> Result<i32, AppError> {
+    service.create_subscription(subscription)
+}
+
+#[tauri::command]
+fn get_person_subscriptions(service: State<'_, MembershipService>, person_id: i32) -> Result<Vec<SubscriptionWithDetails>, AppError> {
+    service.get_person_subscriptions(person_id)
+}
+
+#[tauri::command]
+fn cancel_subscription(service: State<'_, MembershipService>, id: i32) -> Result<(), AppError> {
+    service.cancel_subscription(id)
+}
+
+#[tauri::command]
+fn record_payment(service: State<'_, MembershipService>, payment: Payment) -> Result<i32, AppError> {
+    service.record_payment(payment)
+}
+
+#[tauri::command]
+fn get_subscription_payments(service: State<'_, MembershipService>, subscription_id: i32) -> Result<Vec<Payment>, AppError> {
+    service.get_subscription_payments(subscription_id)
+}
+
+#[tauri::command]
+fn delete_payment(service: State<'_, MembershipService>, id: i32) -> Result<(), AppError> {
+    service.delete_payment(id)
+}
+
+#[tauri::command]
+fn get_expiring_memberships(service: State<'_, MembershipService>, days: i32) -> Result<Vec<SubscriptionWithDetails>, AppError> {
+    service.get_expiring_memberships(days)
+}
+
+#[tauri::command]
+fn get_overdue_clients(service: State<'_, MembershipService>) -> Result<Vec<SubscriptionWithDetails>, AppError> {
+    service.get_overdue_clients()
+}
+
+// Check-in commands
+#[tauri::command]
+fn check_in(service: State<'_, CheckInService>, person_id: i32) -> Result<CheckIn, AppError> {
+    service.check_in(person_id)
+}
+
+#[tauri::command]
+fn check_in_by_phone(service: State<'_, CheckInService>, phone: String) -> Result<CheckIn, AppError> {
+    service.check_in_by_phone(&phone)
+}
+
+#[tauri::command]
+fn check_out(service: State<'_, CheckInService>, person_id: i32) -> Result<CheckIn, AppError> {
+    service.check_out(person_id)
+}
+
+#[tauri::command]
+fn get_open_check_ins(service: State<'_, CheckInService>) -> Result<Vec<CheckIn>, AppError> {
+    service.get_open_check_ins()
+}
+
+#[tauri::command]
+fn get_person_check_ins(service: State<'_, CheckInService>, person_id: i32, start_date: String, end_date: String) -> Result<Vec<CheckIn>, AppError> {
+    service.get_person_check_ins(person_id, &start_date, &end_date)
+}
+
+#[tauri::command]
+fn delete_check_in(service: State<'_, CheckInService>, id: i32) -> Result<(), AppError> {
+    service.delete_check_in(id)
+}
+
+#[tauri::command]
+fn get_daily_occupancy(service: State<'_, CheckInService>, start_date: String, end_date: String) -> Result<Vec<DailyOccupancy>, AppError> {
+    service.get_daily_occupancy(&start_date, &end_date)
+}
+
+#[tauri::command]
+fn get_hourly_occupancy(service: State<'_, CheckInService>, start_date: String, end_date: String) -> Result<Vec<HourlyOccupancy>, AppError> {
+    service.get_hourly_occupancy(&start_date, &end_date)
+}
+
+#[tauri::command]
+fn get_visit_streaks(service: State<'_, CheckInService>, person_id: i32) -> Result<VisitStreaks, AppError> {
+    service.get_visit_streaks(person_id)
+}
+
+// Body measurement commands
+#[tauri::command]
+fn create_body_measurement(service: State<'_, BodyMeasurementService>, measurement: BodyMeasurement) -> Result<i32, AppError> {
+    service.create_measurement(measurement)
+}
+
+#[tauri::command]
+fn update_body_measurement(service: State<'_, BodyMeasurementService>, measurement: BodyMeasurement) -> Result<(), AppError> {
+    service.update_measurement(measurement)
+}
+
+#[tauri::command]
+fn delete_body_measurement(service: State<'_, BodyMeasurementService>, id: i32) -> Result<(), AppError> {
+    service.delete_measurement(id)
+}
+
+#[tauri::command]
+fn get_body_measurements(service: State<'_, BodyMeasurementService>, person_id: i32, start_date: String, end_date: String) -> Result<Vec<BodyMeasurement>, AppError> {
+    service.get_person_measurements(person_id, &start_date, &end_date)
+}
+
+#[tauri::command]
+fn get_body_measurement_series(
+    service: State<'_, BodyMeasurementService>,
+    person_id: i32,
+    metric: BodyMetric,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<MeasurementPoint>, AppError> {
+    service.get_measurement_series(person_id, metric, &start_date, &end_date)
+}
+
 // Exercise commands
 #[tauri::command]
-fn create_exercise(service: State<'_, ExerciseService>, exercise: Exercise) -> Result<(), String> {
+fn create_exercise(service: State<'_, ExerciseService>, exercise: Exercise) -> Result<(), AppError> {
     service.create_exercise(exercise)
 }
 
 #[tauri::command]
-fn get_exercises(service: State<'_, ExerciseService>) -> Vec<Exercise> {
+fn get_exercises(service: State<'_, ExerciseService>) -> Result<Vec<Exercise>, AppError> {
     service.list_exercises()
 }
 
 #[tauri::command]
-fn get_exercises_paginated(service: State<'_, ExerciseService>, page: i32, page_size: i32) -> models::exercise::PaginatedExerciseResponse {
+fn get_exercises_paginated(service: State<'_, ExerciseService>, page: i32, page_size: i32) -> Result<models::exercise::PaginatedExerciseResponse, AppError> {
     service.list_exercises_paginated(page, page_size)
 }
 
 #[tauri::command]
-fn delete_exercise(service: State<'_, ExerciseService>, id: i32) -> Result<(), String> {
+fn delete_exercise(service: State<'_, ExerciseService>, id: i32) -> Result<(), AppError> {
     service.delete_exercise(id)
 }
 
 #[tauri::command]
-fn restore_exercise(service: State<'_, ExerciseService>, id: i32) -> Result<(), String> {
+fn restore_exercise(service: State<'_, ExerciseService>, id: i32) -> Result<(), AppError> {
     service.restore_exercise(id)
 }
 
 #[tauri::command]
-fn get_deleted_exercises(service: State<'_, ExerciseService>) -> Vec<Exercise> {
+fn get_deleted_exercises(service: State<'_, ExerciseService>) -> Result<Vec<Exercise>, AppError> {
     service.list_deleted_exercises()
 }
 
 #[tauri::command]
-fn count_deleted_exercises(service: State<'_, ExerciseService>) -> i32 {
+fn count_deleted_exercises(service: State<'_, ExerciseService>) -> Result<i32, AppError> {
     service.count_deleted_exercises()
 }
 
 #[tauri::command]
-fn update_exercise(service: State<'_, ExerciseService>, exercise: Exercise) -> Result<(), String> {
+fn update_exercise(service: State<'_, ExerciseService>, exercise: Exercise) -> Result<(), AppError> {
     service.update_exercise(exercise)
 }
 
 #[tauri::command]
-fn search_exercises_paginated(service: State<'_, ExerciseService>, query: String, page: i32, page_size: i32) -> models::exercise::PaginatedExerciseResponse {
-    service.search_exercises_paginated(&query, page, page_size)
+fn search_exercises_paginated(
+    service: State<'_, ExerciseService>,
+    query: String,
+    page: i32,
+    page_size: i32,
+    filter: Option<ExerciseFilter>,
+) -> Result<models::exercise::PaginatedExerciseResponse, AppError> {
+    service.search_exercises_paginated(&query, &filter.unwrap_or_default(), page, page_size)
+}
+
+// Exercise taxonomy commands
+#[tauri::command]
+fn list_taxonomy(service: State<'_, ExerciseService>, kind: TaxonomyKind) -> Result<Vec<TaxonomyItem>, AppError> {
+    service.list_taxonomy(kind)
+}
+
+#[tauri::command]
+fn create_taxonomy_item(service: State<'_, ExerciseService>, kind: TaxonomyKind, name: String) -> Result<i32, AppError> {
+    service.create_taxonomy_item(kind, &name)
+}
+
+#[tauri::command]
+fn rename_taxonomy_item(service: State<'_, ExerciseService>, kind: TaxonomyKind, id: i32, name: String) -> Result<(), AppError> {
+    service.rename_taxonomy_item(kind, id, &name)
+}
+
+#[tauri::command]
+fn delete_taxonomy_item(service: State<'_, ExerciseService>, kind: TaxonomyKind, id: i32) -> Result<(), AppError> {
+    service.delete_taxonomy_item(kind, id)
+}
+
+#[tauri::command]
+fn get_exercise_taxonomy(service: State<'_, ExerciseService>, exercise_id: i32) -> Result<ExerciseTaxonomy, AppError> {
+    service.get_exercise_taxonomy(exercise_id)
+}
+
+#[tauri::command]
+fn set_exercise_taxonomy(service: State<'_, ExerciseService>, exercise_id: i32, taxonomy: ExerciseTaxonomyUpdate) -> Result<(), AppError> {
+    service.set_exercise_taxonomy(exercise_id, taxonomy)
 }
 
 // Workout Entry commands
 #[tauri::command]
-fn create_workout_entry(service: State<'_, WorkoutEntryService>, workout_entry: WorkoutEntry) -> Result<(), String> {
+fn create_workout_entry(service: State<'_, WorkoutEntryService>, workout_entry: WorkoutEntry) -> Result<(), AppError> {
     service.create_workout_entry(workout_entry)
 }
 
 #[tauri::command]
-fn create_workout_session(service: State<'_, WorkoutEntryService>, workout_entries: Vec<WorkoutEntry>) -> Result<(), String> {
-    service.create_workout_session(workout_entries)
+fn create_workout_session(
+    service: State<'_, WorkoutEntryService>,
+    workout_entries: Vec<WorkoutEntry>,
+    formula: Option<OneRepMaxFormula>,
+) -> Result<Vec<PersonalRecord>, AppError> {
+    service.create_workout_session(workout_entries, formula.unwrap_or_default())
 }
 
 #[tauri::command]
-fn create_batch(service: State<'_, WorkoutEntryService>, workout_entries: Vec<WorkoutEntry>) -> Result<(), String> {
+fn create_batch(service: State<'_, WorkoutEntryService>, workout_entries: Vec<WorkoutEntry>) -> Result<(), AppError> {
     service.create_batch(workout_entries)
 }
 
@@ -148,113 +362,231 @@ fn get_workout_entries_by_person_and_date_range(
     person_id: i32, 
     start_date: String, 
     end_date: String
-) -> Vec<models::workout_entry::WorkoutEntryWithDetails> {
+) -> Result<Vec<models::workout_entry::WorkoutEntryWithDetails>, AppError> {
     service.get_workout_entries_by_person_and_date_range(person_id, &start_date, &end_date)
 }
 
 #[tauri::command]
-fn get_workout_entries_by_person(service: State<'_, WorkoutEntryService>, person_id: i32) -> Vec<models::workout_entry::WorkoutEntryWithDetails> {
+fn get_workout_entries_by_person(service: State<'_, WorkoutEntryService>, person_id: i32) -> Result<Vec<models::workout_entry::WorkoutEntryWithDetails>, AppError> {
     service.get_workout_entries_by_person(person_id)
 }
 
 #[tauri::command]
-fn update_workout_entry(service: State<'_, WorkoutEntryService>, workout_entry: WorkoutEntry) -> Result<(), String> {
+fn update_workout_entry(service: State<'_, WorkoutEntryService>, workout_entry: WorkoutEntry) -> Result<(), AppError> {
     service.update_workout_entry(workout_entry)
 }
 
 #[tauri::command]
-fn delete_workout_entry(service: State<'_, WorkoutEntryService>, id: i32) -> Result<(), String> {
+fn delete_workout_entry(service: State<'_, WorkoutEntryService>, id: i32) -> Result<(), AppError> {
     service.delete_workout_entry(id)
 }
 
 #[tauri::command]
-fn get_all_workout_entries(service: State<'_, WorkoutEntryService>) -> Vec<models::workout_entry::WorkoutEntryWithDetails> {
+fn get_all_workout_entries(service: State<'_, WorkoutEntryService>) -> Result<Vec<models::workout_entry::WorkoutEntryWithDetails>, AppError> {
     service.list_all_workout_entries()
 }
 
 #[tauri::command]
-fn replace_workout_session(service: State<'_, WorkoutEntryService>, person_id: i32, date: String, workout_entries: Vec<WorkoutEntry>) -> Result<(), String> {
-    service.replace_workout_session(person_id, &date, workout_entries)
+fn replace_workout_session(
+    service: State<'_, WorkoutEntryService>,
+    person_id: i32,
+    date: String,
+    workout_entries: Vec<WorkoutEntry>,
+    formula: Option<OneRepMaxFormula>,
+) -> Result<Vec<PersonalRecord>, AppError> {
+    service.replace_workout_session(person_id, &date, workout_entries, formula.unwrap_or_default())
+}
+
+#[tauri::command]
+fn log_planned_session(
+    service: State<'_, WorkoutEntryService>,
+    person_id: i32,
+    date: String,
+    formula: Option<OneRepMaxFormula>,
+) -> Result<Vec<PersonalRecord>, AppError> {
+    service.log_planned_session(person_id, &date, formula.unwrap_or_default())
 }
 
 #[tauri::command]
-fn replace_workout_session_granular(service: State<'_, WorkoutEntryService>, ids_to_delete: Vec<i32>, workout_entries_to_insert: Vec<WorkoutEntry>) -> Result<(), String> {
+fn replace_workout_session_granular(service: State<'_, WorkoutEntryService>, ids_to_delete: Vec<i32>, workout_entries_to_insert: Vec<WorkoutEntry>) -> Result<(), AppError> {
     service.replace_workout_session_granular(ids_to_delete, workout_entries_to_insert)
 }
 
 #[tauri::command]
-fn update_exercise_order(service: State<'_, WorkoutEntryService>, exercise_orders: Vec<(i32, i32)>) -> Result<(), String> {
+fn update_exercise_order(service: State<'_, WorkoutEntryService>, exercise_orders: Vec<(i32, i32)>) -> Result<(), AppError> {
     service.update_exercise_order(exercise_orders)
 }
 
 #[tauri::command]
-fn renumber_workout_groups(service: State<'_, WorkoutEntryService>, person_id: i32, date: String) -> Result<(), String> {
+fn renumber_workout_groups(service: State<'_, WorkoutEntryService>, person_id: i32, date: String) -> Result<(), AppError> {
     service.renumber_groups(person_id, &date)
 }
 
+// Workout set commands
+#[tauri::command]
+fn get_workout_sets(service: State<'_, WorkoutEntryService>, workout_entry_id: i32) -> Result<Vec<WorkoutSet>, AppError> {
+    service.get_workout_sets(workout_entry_id)
+}
+
+#[tauri::command]
+fn create_workout_set(service: State<'_, WorkoutEntryService>, workout_set: WorkoutSet) -> Result<i32, AppError> {
+    service.create_workout_set(workout_set)
+}
+
+#[tauri::command]
+fn update_workout_set(service: State<'_, WorkoutEntryService>, workout_set: WorkoutSet) -> Result<(), AppError> {
+    service.update_workout_set(workout_set)
+}
+
+#[tauri::command]
+fn delete_workout_set(service: State<'_, WorkoutEntryService>, id: i32) -> Result<(), AppError> {
+    service.delete_workout_set(id)
+}
+
+#[tauri::command]
+fn reorder_workout_sets(service: State<'_, WorkoutEntryService>, set_orders: Vec<(i32, i32)>) -> Result<(), AppError> {
+    service.reorder_workout_sets(set_orders)
+}
+
+// Analytics commands
+#[tauri::command]
+fn get_exercise_stats(
+    service: State<'_, AnalyticsService>,
+    person_id: i32,
+    exercise_id: i32,
+    formula: Option<OneRepMaxFormula>,
+) -> Result<ExerciseStats, AppError> {
+    service.get_exercise_stats(person_id, exercise_id, formula.unwrap_or_default())
+}
+
+#[tauri::command]
+fn get_personal_record_history(
+    service: State<'_, AnalyticsService>,
+    person_id: i32,
+    exercise_id: Option<i32>,
+    formula: Option<OneRepMaxFormula>,
+) -> Result<Vec<PersonalRecord>, AppError> {
+    service.get_personal_record_history(person_id, exercise_id, formula.unwrap_or_default())
+}
+
+// Report commands
+#[tauri::command]
+fn get_tonnage_report(
+    service: State<'_, ReportService>,
+    person_id: i32,
+    start_date: String,
+    end_date: String,
+    bucket: Option<ReportBucket>,
+) -> Result<Vec<TonnagePoint>, AppError> {
+    service.get_tonnage_report(person_id, &start_date, &end_date, bucket.unwrap_or_default())
+}
+
+#[tauri::command]
+fn get_frequency_report(
+    service: State<'_, ReportService>,
+    person_id: i32,
+    start_date: String,
+    end_date: String,
+    bucket: Option<ReportBucket>,
+) -> Result<Vec<FrequencyPoint>, AppError> {
+    service.get_frequency_report(person_id, &start_date, &end_date, bucket.unwrap_or_default())
+}
+
+#[tauri::command]
+fn get_sets_per_exercise_report(
+    service: State<'_, ReportService>,
+    person_id: i32,
+    start_date: String,
+    end_date: String,
+    bucket: Option<ReportBucket>,
+) -> Result<Vec<ExerciseSetsPoint>, AppError> {
+    service.get_sets_per_exercise_report(person_id, &start_date, &end_date, bucket.unwrap_or_default())
+}
+
+#[tauri::command]
+fn get_intensity_report(
+    service: State<'_, ReportService>,
+    person_id: i32,
+    start_date: String,
+    end_date: String,
+    bucket: Option<ReportBucket>,
+) -> Result<Vec<IntensityPoint>, AppError> {
+    service.get_intensity_report(person_id, &start_date, &end_date, bucket.unwrap_or_default())
+}
+
+#[tauri::command]
+fn get_adherence_report(
+    service: State<'_, ReportService>,
+    person_id: i32,
+    start_date: String,
+    end_date: String,
+) -> Result<AdherenceReport, AppError> {
+    service.get_adherence_report(person_id, &start_date, &end_date)
+}
+
 // Routine commands
 #[tauri::command]
-fn create_routine(service: State<'_, RoutineService>, name: String, code: String) -> Result<i32, String> {
+fn create_routine(service: State<'_, RoutineService>, name: String, code: String) -> Result<i32, AppError> {
     service.create_routine(name, code)
 }
 
 #[tauri::command]
-fn get_routine_by_id(service: State<'_, RoutineService>, id: i32) -> Option<Routine> {
+fn get_routine_by_id(service: State<'_, RoutineService>, id: i32) -> Result<Option<Routine>, AppError> {
     service.get_routine_by_id(id)
 }
 
 #[tauri::command]
-fn get_routine_with_exercises(service: State<'_, RoutineService>, id: i32) -> Option<models::routine::RoutineWithExercises> {
+fn get_routine_with_exercises(service: State<'_, RoutineService>, id: i32) -> Result<Option<models::routine::RoutineWithExercises>, AppError> {
     service.get_routine_with_exercises(id)
 }
 
 #[tauri::command]
-fn update_routine(service: State<'_, RoutineService>, id: i32, name: String, code: String) -> Result<(), String> {
+fn update_routine(service: State<'_, RoutineService>, id: i32, name: String, code: String) -> Result<(), AppError> {
     service.update_routine(id, name, code)
 }
 
 #[tauri::command]
-fn delete_routine(service: State<'_, RoutineService>, id: i32) -> Result<(), String> {
+fn delete_routine(service: State<'_, RoutineService>, id: i32) -> Result<(), AppError> {
     service.delete_routine(id)
 }
 
 #[tauri::command]
-fn restore_routine(service: State<'_, RoutineService>, id: i32) -> Result<(), String> {
+fn restore_routine(service: State<'_, RoutineService>, id: i32) -> Result<(), AppError> {
     service.restore_routine(id)
 }
 
 #[tauri::command]
-fn list_routines(service: State<'_, RoutineService>) -> Vec<Routine> {
+fn list_routines(service: State<'_, RoutineService>) -> Result<Vec<Routine>, AppError> {
     service.list_routines()
 }
 
 #[tauri::command]
-fn get_deleted_routines(service: State<'_, RoutineService>) -> Vec<Routine> {
+fn get_deleted_routines(service: State<'_, RoutineService>) -> Result<Vec<Routine>, AppError> {
     service.list_deleted_routines()
 }
 
 #[tauri::command]
-fn count_deleted_routines(service: State<'_, RoutineService>) -> i32 {
+fn count_deleted_routines(service: State<'_, RoutineService>) -> Result<i32, AppError> {
     service.count_deleted_routines()
 }
 
 #[tauri::command]
-fn renumber_routine_groups(service: State<'_, RoutineService>, routine_id: i32) -> Result<(), String> {
+fn renumber_routine_groups(service: State<'_, RoutineService>, routine_id: i32) -> Result<(), AppError> {
     service.renumber_routine_groups(routine_id)
 }
 
 #[tauri::command]
-fn list_routines_paginated(service: State<'_, RoutineService>, page: i32, page_size: i32) -> Vec<Routine> {
+fn list_routines_paginated(service: State<'_, RoutineService>, page: i32, page_size: i32) -> Result<Vec<Routine>, AppError> {
     service.list_routines_paginated(page, page_size)
 }
 
 #[tauri::command]
-fn search_routines(service: State<'_, RoutineService>, query: String) -> Vec<Routine> {
+fn search_routines(service: State<'_, RoutineService>, query: String) -> Result<Vec<Routine>, AppError> {
     service.search_routines(query)
 }
 
 #[tauri::command]
-fn search_routines_paginated(service: State<'_, RoutineService>, query: String, page: i32, page_size: i32) -> Vec<Routine> {
+fn search_routines_paginated(service: State<'_, RoutineService>, query: String, page: i32, page_size: i32) -> Result<Vec<Routine>, AppError> {
     service.search_routines_paginated(query, page, page_size)
 }
 
@@ -269,8 +601,14 @@ fn add_exercise_to_routine(
     weight: Option<f64>,
     notes: Option<String>,
     group_number: Option<i32>,
-) -> Result<(), String> {
-    service.add_exercise_to_routine(routine_id, exercise_id, order_index, sets, reps, weight, notes, group_number)
+    rpe: Option<f64>,
+    rir: Option<i32>,
+    tempo: Option<String>,
+    rest_seconds: Option<i32>,
+    duration_seconds: Option<i32>,
+    distance_meters: Option<f64>,
+) -> Result<(), AppError> {
+    service.add_exercise_to_routine(routine_id, exercise_id, order_index, sets, reps, weight, notes, group_number, rpe, rir, tempo, rest_seconds, duration_seconds, distance_meters)
 }
 
 #[tauri::command]
@@ -285,28 +623,39 @@ fn update_routine_exercise(
     weight: Option<f64>,
     notes: Option<String>,
     group_number: Option<i32>,
-) -> Result<(), String> {
-    service.update_routine_exercise(id, routine_id, exercise_id, order_index, sets, reps, weight, notes, group_number)
+    rpe: Option<f64>,
+    rir: Option<i32>,
+    tempo: Option<String>,
+    rest_seconds: Option<i32>,
+    duration_seconds: Option<i32>,
+    distance_meters: Option<f64>,
+) -> Result<(), AppError> {
+    service.update_routine_exercise(id, routine_id, exercise_id, order_index, sets, reps, weight, notes, group_number, rpe, rir, tempo, rest_seconds, duration_seconds, distance_meters)
 }
 
 #[tauri::command]
-fn remove_exercise_from_routine(service: State<'_, RoutineService>, routine_id: i32, exercise_id: i32) -> Result<(), String> {
+fn remove_exercise_from_routine(service: State<'_, RoutineService>, routine_id: i32, exercise_id: i32) -> Result<(), AppError> {
     service.remove_exercise_from_routine(routine_id, exercise_id)
 }
 
 #[tauri::command]
-fn get_routine_exercises(service: State<'_, RoutineService>, routine_id: i32) -> Vec<models::routine_exercise::RoutineExerciseWithDetails> {
+fn get_routine_exercises(service: State<'_, RoutineService>, routine_id: i32) -> Result<Vec<models::routine_exercise::RoutineExerciseWithDetails>, AppError> {
     service.get_routine_exercises(routine_id)
 }
 
 #[tauri::command]
-fn reorder_routine_exercises(service: State<'_, RoutineService>, routine_id: i32, exercise_orders: Vec<(i32, i32)>) -> Result<(), String> {
+fn reorder_routine_exercises(service: State<'_, RoutineService>, routine_id: i32, exercise_orders: Vec<(i32, i32)>) -> Result<(), AppError> {
     service.reorder_routine_exercises(routine_id, exercise_orders)
 }
 
 #[tauri::command]
-fn replace_routine_exercises(service: State<'_, RoutineService>, routine_id: i32, exercises: Vec<RoutineExercise>) -> Result<(), String> {
-    service.replace_routine_exercises(routine_id, exercises)
+fn replace_routine_exercises(
+    service: State<'_, RoutineService>,
+    routine_id: i32,
+    exercises: Vec<RoutineExercise>,
+    comment: Option<String>,
+) -> Result<(), AppError> {
+    service.replace_routine_exercises(routine_id, exercises, comment)
 }
 
 #[tauri::command]
@@ -314,30 +663,221 @@ fn create_routine_from_workout(
     service: State<'_, RoutineService>,
     name: String,
     code: String,
-    workout_exercises: Vec<(i32, Option<i32>, Option<i32>, Option<f64>, Option<String>, Option<i32>)>,
-) -> Result<i32, String> {
+    workout_exercises: Vec<WorkoutEntry>,
+) -> Result<i32, AppError> {
     service.create_routine_from_workout(name, code, workout_exercises)
 }
 
+#[tauri::command]
+fn list_routine_revisions(service: State<'_, RoutineService>, routine_id: i32) -> Result<Vec<RoutineRevision>, AppError> {
+    service.list_routine_revisions(routine_id)
+}
+
+#[tauri::command]
+fn get_routine_revision(
+    service: State<'_, RoutineService>,
+    routine_id: i32,
+    revision: i32,
+) -> Result<Option<RoutineRevisionWithExercises>, AppError> {
+    service.get_routine_revision(routine_id, revision)
+}
+
+#[tauri::command]
+fn diff_routine_revisions(
+    service: State<'_, RoutineService>,
+    routine_id: i32,
+    from_revision: i32,
+    to_revision: i32,
+) -> Result<RoutineRevisionDiff, AppError> {
+    service.diff_routine_revisions(routine_id, from_revision, to_revision)
+}
+
+#[tauri::command]
+fn restore_routine_revision(
+    service: State<'_, RoutineService>,
+    routine_id: i32,
+    revision: i32,
+    comment: Option<String>,
+) -> Result<i32, AppError> {
+    service.restore_routine_revision(routine_id, revision, comment)
+}
+
+// Program commands
+#[tauri::command]
+fn create_program(service: State<'_, ProgramService>, program: Program, days: Vec<ProgramDay>) -> Result<i32, AppError> {
+    service.create_program(program, days)
+}
+
+#[tauri::command]
+fn update_program(service: State<'_, ProgramService>, program: Program, days: Vec<ProgramDay>) -> Result<(), AppError> {
+    service.update_program(program, days)
+}
+
+#[tauri::command]
+fn clone_program(service: State<'_, ProgramService>, id: i32, name: String, code: String) -> Result<i32, AppError> {
+    service.clone_program(id, name, code)
+}
+
+#[tauri::command]
+fn get_program(service: State<'_, ProgramService>, id: i32) -> Result<Option<ProgramWithDays>, AppError> {
+    service.get_program(id)
+}
+
+#[tauri::command]
+fn list_programs(service: State<'_, ProgramService>) -> Result<Vec<Program>, AppError> {
+    service.list_programs()
+}
+
+#[tauri::command]
+fn delete_program(service: State<'_, ProgramService>, id: i32) -> Result<(), AppError> {
+    service.delete_program(id)
+}
+
+#[tauri::command]
+fn restore_program(service: State<'_, ProgramService>, id: i32) -> Result<(), AppError> {
+    service.restore_program(id)
+}
+
+// Assignment commands
+#[tauri::command]
+fn create_assignment(service: State<'_, AssignmentService>, assignment: Assignment) -> Result<i32, AppError> {
+    service.create_assignment(assignment)
+}
+
+#[tauri::command]
+fn update_assignment(service: State<'_, AssignmentService>, assignment: Assignment) -> Result<(), AppError> {
+    service.update_assignment(assignment)
+}
+
+#[tauri::command]
+fn delete_assignment(service: State<'_, AssignmentService>, id: i32) -> Result<(), AppError> {
+    service.delete_assignment(id)
+}
+
+#[tauri::command]
+fn get_person_assignments(service: State<'_, AssignmentService>, person_id: i32) -> Result<Vec<AssignmentWithDetails>, AppError> {
+    service.get_person_assignments(person_id)
+}
+
+#[tauri::command]
+fn get_planned_sessions(
+    service: State<'_, AssignmentService>,
+    person_id: i32,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<PlannedSession>, AppError> {
+    service.get_planned_sessions(person_id, &start_date, &end_date)
+}
+
+#[tauri::command]
+fn get_due_sessions(service: State<'_, AssignmentService>, person_id: i32) -> Result<Vec<PlannedSession>, AppError> {
+    service.get_due_sessions(person_id)
+}
+
+#[tauri::command]
+fn schedule_assignment(
+    service: State<'_, AssignmentService>,
+    id: i32,
+    start_date: String,
+    end_date: String,
+) -> Result<ScheduleSummary, AppError> {
+    service.schedule_assignment(id, &start_date, &end_date)
+}
+
+// Progression commands
+#[tauri::command]
+fn set_progression_rule(service: State<'_, ProgressionService>, rule: ProgressionRule) -> Result<i32, AppError> {
+    service.set_progression_rule(rule)
+}
+
+#[tauri::command]
+fn delete_progression_rule(service: State<'_, ProgressionService>, routine_id: i32, exercise_id: i32) -> Result<(), AppError> {
+    service.delete_progression_rule(routine_id, exercise_id)
+}
+
+#[tauri::command]
+fn get_progression_rules(service: State<'_, ProgressionService>, routine_id: i32) -> Result<Vec<ProgressionRule>, AppError> {
+    service.get_progression_rules(routine_id)
+}
+
+#[tauri::command]
+fn propose_next_session(
+    service: State<'_, ProgressionService>,
+    person_id: i32,
+    routine_id: i32,
+    formula: Option<OneRepMaxFormula>,
+) -> Result<Vec<ProgressionProposal>, AppError> {
+    service.propose_next_session(person_id, routine_id, formula.unwrap_or_default())
+}
+
 // Backup commands
 #[tauri::command]
 async fn execute_backup(backup_service: tauri::State<'_, BackupService>) -> Result<(), String> {
     backup_service.execute_backup().await
 }
 
+#[tauri::command]
+fn create_local_backup(backup_service: State<'_, BackupService>) -> Result<BackupInfo, String> {
+    backup_service.create_local_backup()
+}
+
+#[tauri::command]
+fn list_backups(backup_service: State<'_, BackupService>) -> Result<Vec<BackupInfo>, String> {
+    backup_service.list_backups()
+}
+
+#[tauri::command]
+fn restore_backup(
+    app: tauri::AppHandle,
+    backup_service: State<'_, BackupService>,
+    file_name: String,
+    passphrase: Option<String>,
+) -> Result<(), String> {
+    backup_service.restore_backup(&file_name, passphrase.as_deref())?;
+    // Every cached view is stale once the database has been replaced
+    app.emit("backup-restored", &file_name)
+        .map_err(|e| format!("Failed to emit event: {}", e))
+}
+
+// Settings commands. Secrets are accepted but never sent back to the webview.
+#[tauri::command]
+fn get_settings(settings_service: State<'_, Arc<SettingsService>>) -> SettingsView {
+    SettingsView::from(&settings_service.get())
+}
+
+#[tauri::command]
+fn update_settings(
+    app: tauri::AppHandle,
+    settings_service: State<'_, Arc<SettingsService>>,
+    settings: AppSettings,
+) -> Result<SettingsView, String> {
+    let settings = SettingsView::from(&settings_service.update(settings)?);
+    app.emit("settings-changed", &settings)
+        .map_err(|e| format!("Failed to emit event: {}", e))?;
+    Ok(settings)
+}
+
 // Updater commands
 #[tauri::command]
-async fn check_for_updates(updater_service: tauri::State<'_, UpdaterService>) -> Result<Option<serde_json::Value>, String> {
-    match updater_service.check_for_updates().await {
-        Ok(Some(update_info)) => Ok(Some(serde_json::to_value(update_info).map_err(|e| e.to_string())?)),
-        Ok(None) => Ok(None),
-        Err(e) => Err(e),
+async fn check_for_updates(app: tauri::AppHandle, updater_service: State<'_, UpdaterService>) -> Result<Option<UpdateInfo>, String> {
+    let update_info = updater_service.check_for_updates().await?;
+    if let Some(update_info) = &update_info {
+        app.emit("update-available", update_info)
+            .map_err(|e| format!("Failed to emit event: {}", e))?;
     }
+    Ok(update_info)
 }
 
 #[tauri::command]
-async fn download_update(updater_service: tauri::State<'_, UpdaterService>, download_url: String) -> Result<(), String> {
-    updater_service.download_update(download_url).await
+async fn download_update(app: tauri::AppHandle, updater_service: State<'_, UpdaterService>, download_url: String) -> Result<String, String> {
+    // Only verified installers reach the disk
+    let installer_path = updater_service.download_update(&download_url).await?;
+    let installer_path = installer_path.to_string_lossy().to_string();
+    let _ = webbrowser::open(&installer_path);
+
+    app.emit("update-downloaded", &installer_path)
+        .map_err(|e| format!("Failed to emit event: {}", e))?;
+    Ok(installer_path)
 }
 
 fn main() {
@@ -346,17 +886,24 @@ fn main() {
     
     tauri::Builder::default()
         .setup(|app| {
-            // Initialize services safely without panic catching
-            let (person_service, exercise_service, workout_entry_service, routine_service) = setup_services();
-            let backup_service = BackupService::new();
-            let updater_service = UpdaterService::new(app.handle().clone());
+            // Initialize services; fails startup if the database cannot be migrated
+            let services = setup_services()?;
             
-            app.manage(person_service);
-            app.manage(exercise_service);
-            app.manage(workout_entry_service);
-            app.manage(routine_service);
-            app.manage(backup_service);
-            app.manage(updater_service);
+            app.manage(services.person_service);
+            app.manage(services.membership_service);
+            app.manage(services.check_in_service);
+            app.manage(services.body_measurement_service);
+            app.manage(services.exercise_service);
+            app.manage(services.workout_entry_service);
+            app.manage(services.routine_service);
+            app.manage(services.program_service);
+            app.manage(services.assignment_service);
+            app.manage(services.progression_service);
+            app.manage(services.analytics_service);
+            app.manage(services.report_service);
+            app.manage(services.backup_service);
+            app.manage(services.settings_service);
+            app.manage(services.updater_service);
             
             Ok(())
         })
@@ -374,6 +921,37 @@ fn main() {
             get_deleted_people,
             count_deleted_people,
             update_person,
+            find_people_by_phone,
+            find_duplicate_people,
+            merge_people,
+            // Membership commands
+            list_membership_plans,
+            create_membership_plan,
+            update_membership_plan,
+            create_subscription,
+            get_person_subscriptions,
+            cancel_subscription,
+            record_payment,
+            get_subscription_payments,
+            delete_payment,
+            get_expiring_memberships,
+            get_overdue_clients,
+            // Check-in commands
+            check_in,
+            check_in_by_phone,
+            check_out,
+            get_open_check_ins,
+            get_person_check_ins,
+            delete_check_in,
+            get_daily_occupancy,
+            get_hourly_occupancy,
+            get_visit_streaks,
+            // Body measurement commands
+            create_body_measurement,
+            update_body_measurement,
+            delete_body_measurement,
+            get_body_measurements,
+            get_body_measurement_series,
             // Exercise commands
             create_exercise,
             get_exercises,
@@ -384,6 +962,13 @@ fn main() {
             count_deleted_exercises,
             update_exercise,
             search_exercises_paginated,
+            // Exercise taxonomy commands
+            list_taxonomy,
+            create_taxonomy_item,
+            rename_taxonomy_item,
+            delete_taxonomy_item,
+            get_exercise_taxonomy,
+            set_exercise_taxonomy,
             // Workout entry commands
             create_workout_entry,
             create_workout_session,
@@ -395,8 +980,24 @@ fn main() {
             get_all_workout_entries,
             replace_workout_session,
             replace_workout_session_granular,
+            log_planned_session,
             update_exercise_order,
             renumber_workout_groups,
+            // Workout set commands
+            get_workout_sets,
+            create_workout_set,
+            update_workout_set,
+            delete_workout_set,
+            reorder_workout_sets,
+            // Analytics commands
+            get_exercise_stats,
+            get_personal_record_history,
+            // Report commands
+            get_tonnage_report,
+            get_frequency_report,
+            get_sets_per_exercise_report,
+            get_intensity_report,
+            get_adherence_report,
             // Routine commands
             create_routine,
             get_routine_by_id,
@@ -418,8 +1019,39 @@ fn main() {
             reorder_routine_exercises,
             replace_routine_exercises,
             create_routine_from_workout,
+            list_routine_revisions,
+            get_routine_revision,
+            diff_routine_revisions,
+            restore_routine_revision,
+            // Program commands
+            create_program,
+            update_program,
+            clone_program,
+            get_program,
+            list_programs,
+            delete_program,
+            restore_program,
+            // Assignment commands
+            create_assignment,
+            update_assignment,
+            delete_assignment,
+            get_person_assignments,
+            get_planned_sessions,
+            get_due_sessions,
+            schedule_assignment,
+            // Progression commands
+            set_progression_rule,
+            delete_progression_rule,
+            get_progression_rules,
+            propose_next_session,
             // Backup commands
             execute_backup,
+            create_local_backup,
+            list_backups,
+            restore_backup,
+            // Settings commands
+            get_settings,
+            update_settings,
             // Updater commands
             check_for_updates,
             download_update
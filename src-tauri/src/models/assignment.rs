@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use crate::models::program::ProgramExerciseWithDetails;
+
+/// A routine or a program a person follows on some weekdays from `start_date`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Assignment {
+    pub id: Option<i32>,
+    pub person_id: i32,
+    pub routine_id: Option<i32>, // Exactly one of routine_id and program_id
+    pub program_id: Option<i32>,
+    pub start_date: String,       // YYYY-MM-DD format
+    pub end_date: Option<String>, // Open ended routines; programs also end after their last week
+    pub weekdays: Vec<i32>,       // 1 = Monday ... 7 = Sunday
+    pub notes: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignmentWithDetails {
+    pub id: i32,
+    pub person_id: i32,
+    pub routine_id: Option<i32>,
+    pub program_id: Option<i32>,
+    pub start_date: String,
+    pub end_date: Option<String>,
+    pub weekdays: Vec<i32>,
+    pub notes: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    // Joined data
+    pub routine_name: Option<String>,
+    pub program_name: Option<String>,
+    pub program_weeks: Option<i32>,
+}
+
+/// A session the plan puts on a date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedSession {
+    pub date: String,
+    pub assignment_id: i32,
+    pub routine_id: Option<i32>, // The day's routine, also for program days built from one
+    pub routine_name: Option<String>,
+    pub program_id: Option<i32>,
+    pub program_name: Option<String>,
+    pub week: Option<i32>,      // Program week and day, 1-based
+    pub day_index: Option<i32>,
+    pub label: Option<String>,
+    pub exercises: Vec<ProgramExerciseWithDetails>,
+    pub logged: bool,    // The person already trained on this date
+    pub scheduled: bool, // Planned entries are already written for this date
+}
+
+/// Outcome of writing a plan into workout entries
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleSummary {
+    pub assignment_id: i32,
+    pub created_dates: Vec<String>,
+    pub skipped_dates: Vec<String>, // Dates that already had workout entries, left untouched
+    pub workout_entries: i32,
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+/// What can be recorded in a measurement session. Stored as text, one row per
+/// value, so new metrics do not need a migration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyMetric {
+    Weight,     // kg
+    BodyFat,    // %
+    MuscleMass, // kg
+    Chest,      // Girths in cm
+    Waist,
+    Hips,
+    Arm,
+    Thigh,
+    Calf,
+    Neck,
+}
+
+impl BodyMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BodyMetric::Weight => "weight",
+            BodyMetric::BodyFat => "body_fat",
+            BodyMetric::MuscleMass => "muscle_mass",
+            BodyMetric::Chest => "chest",
+            BodyMetric::Waist => "waist",
+            BodyMetric::Hips => "hips",
+            BodyMetric::Arm => "arm",
+            BodyMetric::Thigh => "thigh",
+            BodyMetric::Calf => "calf",
+            BodyMetric::Neck => "neck",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "weight" => Some(BodyMetric::Weight),
+            "body_fat" => Some(BodyMetric::BodyFat),
+            "muscle_mass" => Some(BodyMetric::MuscleMass),
+            "chest" => Some(BodyMetric::Chest),
+            "waist" => Some(BodyMetric::Waist),
+            "hips" => Some(BodyMetric::Hips),
+            "arm" => Some(BodyMetric::Arm),
+            "thigh" => Some(BodyMetric::Thigh),
+            "calf" => Some(BodyMetric::Calf),
+            "neck" => Some(BodyMetric::Neck),
+            _ => None,
+        }
+    }
+
+    /// Plausible values, used to catch typos like 800 kg or a girth in mm
+    pub fn valid_range(&self) -> (f64, f64) {
+        match self {
+            BodyMetric::Weight => (20.0, 400.0),
+            BodyMetric::BodyFat => (2.0, 75.0),
+            BodyMetric::MuscleMass => (5.0, 200.0),
+            BodyMetric::Chest | BodyMetric::Waist | BodyMetric::Hips => (30.0, 250.0),
+            BodyMetric::Arm | BodyMetric::Thigh | BodyMetric::Calf | BodyMetric::Neck => (10.0, 120.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeasurementValue {
+    pub metric: BodyMetric,
+    pub value: f64,
+}
+
+/// One measurement session of a person; a session records any subset of metrics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BodyMeasurement {
+    pub id: Option<i32>,
+    pub person_id: i32,
+    pub date: String, // YYYY-MM-DD format, one session per person and day
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub values: Vec<MeasurementValue>,
+}
+
+/// A point of a metric's time series
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeasurementPoint {
+    pub date: String,
+    pub value: f64,
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// A visit to the gym; times are local, "YYYY-MM-DD HH:MM:SS"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckIn {
+    pub id: i32,
+    pub person_id: i32,
+    pub checked_in_at: String,
+    pub checked_out_at: Option<String>, // None while in the gym, or when the check-out was forgotten
+    pub duration_minutes: Option<i32>,
+    // Joined data
+    pub person_name: String,
+    pub person_last_name: String,
+    pub person_phone: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyOccupancy {
+    pub date: String,
+    pub visits: i32,
+    pub unique_people: i32,
+}
+
+/// Attendance for one hour of the day over a date range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HourlyOccupancy {
+    pub hour: i32,            // 0-23
+    pub check_ins: i32,       // Arrivals during the hour
+    pub average_present: f64, // People in the gym during the hour, averaged over the days of the range
+}
+
+/// Streaks count consecutive days, or ISO weeks, with at least one visit
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VisitStreaks {
+    pub person_id: i32,
+    pub visit_days: i32,
+    pub last_visit_date: Option<String>,
+    pub current_daily_streak: i32, // Ends today or yesterday, 0 otherwise
+    pub longest_daily_streak: i32,
+    pub current_weekly_streak: i32, // Ends this week or last week, 0 otherwise
+    pub longest_weekly_streak: i32,
+}
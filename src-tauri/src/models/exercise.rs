@@ -1,10 +1,49 @@
 use serde::{Deserialize, Serialize};
 
+/// What gets recorded for an exercise: reps and load, a duration, a distance or both
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MeasurementType {
+    #[default]
+    RepsWeight,
+    Time,
+    Distance,
+    DistanceTime,
+    BodyweightReps,
+}
+
+impl MeasurementType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MeasurementType::RepsWeight => "reps_weight",
+            MeasurementType::Time => "time",
+            MeasurementType::Distance => "distance",
+            MeasurementType::DistanceTime => "distance_time",
+            MeasurementType::BodyweightReps => "bodyweight_reps",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "reps_weight" => Some(MeasurementType::RepsWeight),
+            "time" => Some(MeasurementType::Time),
+            "distance" => Some(MeasurementType::Distance),
+            "distance_time" => Some(MeasurementType::DistanceTime),
+            "bodyweight_reps" => Some(MeasurementType::BodyweightReps),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Exercise {
     pub id: Option<i32>,
     pub name: String,
     pub code: String,
+    /// Always set when read. On create `None` means reps and weight; on update it
+    /// keeps the stored type, so renaming an exercise cannot reset it.
+    #[serde(default)]
+    pub measurement_type: Option<MeasurementType>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,4 +53,4 @@ pub struct PaginatedExerciseResponse {
     pub page: i32,
     pub page_size: i32,
     pub total_pages: i32,
-} 
\ No newline at end of file
+} 
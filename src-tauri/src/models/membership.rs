@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+
+/// A plan people can subscribe to, e.g. "Mensual" for 30 days
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MembershipPlan {
+    pub id: Option<i32>,
+    pub name: String,
+    pub price: f64,
+    pub duration_days: i32,
+    #[serde(default = "default_true")]
+    pub is_active: bool, // Retired plans keep their subscriptions but cannot be sold
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Derived from the dates and cancellation, never stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionStatus {
+    Pending, // Starts in the future
+    Active,
+    Expired,
+    Cancelled,
+}
+
+impl SubscriptionStatus {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(SubscriptionStatus::Pending),
+            "active" => Some(SubscriptionStatus::Active),
+            "expired" => Some(SubscriptionStatus::Expired),
+            "cancelled" => Some(SubscriptionStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+/// A person's membership for a period; `end_date` is the last day it covers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Subscription {
+    pub id: Option<i32>,
+    pub person_id: i32,
+    pub plan_id: i32,
+    pub start_date: String,       // YYYY-MM-DD format
+    pub end_date: Option<String>, // None = start date plus the plan duration
+    pub price: Option<f64>,       // Agreed price; None = current plan price
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionWithDetails {
+    pub id: i32,
+    pub person_id: i32,
+    pub plan_id: i32,
+    pub start_date: String,
+    pub end_date: String,
+    pub price: f64,
+    pub notes: Option<String>,
+    pub cancelled_at: Option<String>,
+    pub status: SubscriptionStatus,
+    pub days_remaining: i32, // Until end_date; negative once expired
+    pub amount_paid: f64,
+    pub balance: f64,        // Still owed
+    // Joined data
+    pub person_name: String,
+    pub person_last_name: String,
+    pub person_phone: String,
+    pub plan_name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentMethod {
+    #[default]
+    Cash,
+    Card,
+    Transfer,
+    Other,
+}
+
+impl PaymentMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentMethod::Cash => "cash",
+            PaymentMethod::Card => "card",
+            PaymentMethod::Transfer => "transfer",
+            PaymentMethod::Other => "other",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "cash" => Some(PaymentMethod::Cash),
+            "card" => Some(PaymentMethod::Card),
+            "transfer" => Some(PaymentMethod::Transfer),
+            "other" => Some(PaymentMethod::Other),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Payment {
+    pub id: Option<i32>,
+    pub subscription_id: i32,
+    pub amount: f64,
+    pub paid_on: String, // YYYY-MM-DD format
+    #[serde(default)]
+    pub method: PaymentMethod,
+    pub notes: Option<String>,
+    pub created_at: Option<String>,
+}
@@ -1,5 +1,17 @@
 pub mod person;
+pub mod membership;
+pub mod check_in;
+pub mod body_measurement;
 pub mod exercise;
+pub mod taxonomy;
 pub mod workout_entry;
+pub mod workout_set;
+pub mod personal_record;
+pub mod report;
 pub mod routine;
-pub mod routine_exercise; 
\ No newline at end of file
+pub mod routine_exercise; 
+pub mod program;
+pub mod assignment;
+pub mod progression;
+pub mod routine_revision;
+pub mod settings;
\ No newline at end of file
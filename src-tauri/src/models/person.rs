@@ -6,6 +6,50 @@ pub struct Person {
     pub name: String,
     pub last_name: String,
     pub phone: String,
+    // Profile; everything below is optional
+    #[serde(default)]
+    pub birth_date: Option<String>, // YYYY-MM-DD format
+    #[serde(default)]
+    pub sex: Option<Sex>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub height_cm: Option<f64>,
+    #[serde(default)]
+    pub training_goal: Option<String>,
+    #[serde(default)]
+    pub medical_notes: Option<String>, // Injuries, conditions, medication
+    #[serde(default)]
+    pub emergency_contact_name: Option<String>,
+    #[serde(default)]
+    pub emergency_contact_phone: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sex {
+    Female,
+    Male,
+    Other,
+}
+
+impl Sex {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Sex::Female => "female",
+            Sex::Male => "male",
+            Sex::Other => "other",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "female" => Some(Sex::Female),
+            "male" => Some(Sex::Male),
+            "other" => Some(Sex::Other),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,3 +60,24 @@ pub struct PaginatedPersonResponse {
     pub page_size: i32,
     pub total_pages: i32,
 }
+
+/// Two active people that probably are the same client; `person` is the older record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateCandidate {
+    pub person: Person,
+    pub duplicate: Person,
+    pub similarity: f64, // 0-1, of the accent-insensitive first and last names
+    pub same_phone: bool,
+}
+
+/// Rows moved to the surviving person by a merge
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PersonMergeSummary {
+    pub survivor_id: i32,
+    pub merged_id: i32,
+    pub workout_entries: i32,
+    pub subscriptions: i32,
+    pub check_ins: i32,
+    pub body_measurements: i32,
+    pub assignments: i32,
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+// Brzycki diverges at 37 reps; estimates past this point are meaningless anyway
+const BRZYCKI_MAX_REPS: i32 = 36;
+
+/// Formula used to estimate a one-rep max from a set of `reps` at `weight`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OneRepMaxFormula {
+    #[default]
+    Epley,
+    Brzycki,
+}
+
+impl OneRepMaxFormula {
+    /// Estimated 1RM rounded to 0.1 kg, `None` when the set cannot be used
+    pub fn estimate(&self, weight: f64, reps: i32) -> Option<f64> {
+        if weight <= 0.0 || reps <= 0 {
+            return None;
+        }
+        if reps == 1 {
+            return Some(weight);
+        }
+
+        let estimate = match self {
+            OneRepMaxFormula::Epley => weight * (1.0 + reps as f64 / 30.0),
+            OneRepMaxFormula::Brzycki if reps <= BRZYCKI_MAX_REPS => weight * 36.0 / (37.0 - reps as f64),
+            OneRepMaxFormula::Brzycki => return None,
+        };
+        Some((estimate * 10.0).round() / 10.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordType {
+    EstimatedOneRepMax,
+    MaxWeight,
+    MaxRepsAtWeight,
+    MaxVolume,
+}
+
+/// A new best for a person and exercise, set by a single workout entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonalRecord {
+    pub record_type: RecordType,
+    pub person_id: i32,
+    pub exercise_id: i32,
+    pub exercise_name: String,
+    pub workout_entry_id: Option<i32>,
+    pub date: String,
+    pub value: f64,                  // kg for weight/1RM, reps for reps-at-weight, kg×reps for volume
+    pub previous_value: Option<f64>,
+    pub weight: Option<f64>,         // Set behind the record, when it comes from a single set
+    pub reps: Option<i32>,
+}
+
+/// Most reps ever done at a given weight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepsAtWeight {
+    pub weight: f64,
+    pub reps: i32,
+    pub date: String,
+}
+
+/// Current bests of a person for one exercise
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExerciseStats {
+    pub person_id: i32,
+    pub exercise_id: i32,
+    pub formula: OneRepMaxFormula,
+    pub estimated_one_rep_max: Option<f64>,
+    pub estimated_one_rep_max_date: Option<String>,
+    pub best_weight: Option<f64>,
+    pub best_weight_date: Option<String>,
+    pub best_volume: Option<f64>,
+    pub best_volume_date: Option<String>,
+    pub reps_at_weight: Vec<RepsAtWeight>, // Sorted by weight
+    pub sessions: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epley_estimate() {
+        assert_eq!(OneRepMaxFormula::Epley.estimate(100.0, 5), Some(116.7));
+        assert_eq!(OneRepMaxFormula::Epley.estimate(100.0, 10), Some(133.3));
+        assert_eq!(OneRepMaxFormula::Epley.estimate(60.0, 30), Some(120.0));
+    }
+
+    #[test]
+    fn brzycki_estimate() {
+        assert_eq!(OneRepMaxFormula::Brzycki.estimate(100.0, 5), Some(112.5));
+        assert_eq!(OneRepMaxFormula::Brzycki.estimate(100.0, 10), Some(133.3));
+        assert_eq!(OneRepMaxFormula::Brzycki.estimate(10.0, BRZYCKI_MAX_REPS), Some(360.0));
+        // Past the asymptote at 37 reps
+        assert_eq!(OneRepMaxFormula::Brzycki.estimate(10.0, 37), None);
+        assert_eq!(OneRepMaxFormula::Brzycki.estimate(10.0, 50), None);
+    }
+
+    #[test]
+    fn single_reps_and_unusable_sets() {
+        for formula in [OneRepMaxFormula::Epley, OneRepMaxFormula::Brzycki] {
+            assert_eq!(formula.estimate(142.5, 1), Some(142.5));
+            assert_eq!(formula.estimate(0.0, 5), None);
+            assert_eq!(formula.estimate(-20.0, 5), None);
+            assert_eq!(formula.estimate(100.0, 0), None);
+        }
+    }
+}
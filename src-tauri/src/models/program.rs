@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+
+/// A multi-week plan (mesocycle) made of training days
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Program {
+    pub id: Option<i32>,
+    pub name: String,
+    pub code: String,
+    pub description: Option<String>,
+    pub weeks: i32,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// A training day of a program: either a routine or its own list of exercises
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgramDay {
+    pub id: Option<i32>,
+    #[serde(default)]
+    pub program_id: i32,       // Filled in by the repository when saved together with its program
+    pub week: i32,             // 1-based
+    pub day_index: i32,        // 1-based position inside the week
+    pub label: Option<String>, // e.g. "A", "Push"
+    pub routine_id: Option<i32>,
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub exercises: Vec<ProgramDayExercise>, // Inline exercises, only when there is no routine
+}
+
+/// Same prescription fields as a routine exercise
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgramDayExercise {
+    pub id: Option<i32>,
+    #[serde(default)]
+    pub program_day_id: i32,
+    pub exercise_id: i32,
+    #[serde(default)]
+    pub order_index: i32,
+    pub sets: Option<i32>,
+    pub reps: Option<i32>,
+    pub weight: Option<f64>,
+    pub notes: Option<String>,
+    pub group_number: Option<i32>,
+    pub rpe: Option<f64>,
+    pub rir: Option<i32>,
+    pub tempo: Option<String>,
+    pub rest_seconds: Option<i32>,
+    pub duration_seconds: Option<i32>,
+    pub distance_meters: Option<f64>,
+}
+
+/// An exercise of a program day, from the day's routine or inline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgramExerciseWithDetails {
+    pub program_day_exercise_id: Option<i32>, // Set for inline exercises
+    pub routine_exercise_id: Option<i32>,     // Set for exercises coming from the routine
+    pub exercise_id: i32,
+    pub order_index: i32,
+    pub sets: Option<i32>,
+    pub reps: Option<i32>,
+    pub weight: Option<f64>,
+    pub notes: Option<String>,
+    pub group_number: Option<i32>,
+    pub rpe: Option<f64>,
+    pub rir: Option<i32>,
+    pub tempo: Option<String>,
+    pub rest_seconds: Option<i32>,
+    pub duration_seconds: Option<i32>,
+    pub distance_meters: Option<f64>,
+    pub exercise_name: String,
+    pub exercise_code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgramDayWithDetails {
+    pub id: i32,
+    pub week: i32,
+    pub day_index: i32,
+    pub label: Option<String>,
+    pub routine_id: Option<i32>,
+    pub routine_name: Option<String>,
+    pub notes: Option<String>,
+    pub exercises: Vec<ProgramExerciseWithDetails>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgramWithDays {
+    pub id: Option<i32>,
+    pub name: String,
+    pub code: String,
+    pub description: Option<String>,
+    pub weeks: i32,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub days: Vec<ProgramDayWithDetails>, // By week, then day
+}
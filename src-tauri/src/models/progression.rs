@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressionKind {
+    Linear,            // +increment_kg after every completed session
+    DoubleProgression, // Reps from min_reps up to max_reps, then +increment_kg and back to min_reps
+    PercentageOfE1rm,  // percentage of the last session's estimated 1RM
+}
+
+impl ProgressionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProgressionKind::Linear => "linear",
+            ProgressionKind::DoubleProgression => "double_progression",
+            ProgressionKind::PercentageOfE1rm => "percentage_of_e1rm",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "linear" => Some(ProgressionKind::Linear),
+            "double_progression" => Some(ProgressionKind::DoubleProgression),
+            "percentage_of_e1rm" => Some(ProgressionKind::PercentageOfE1rm),
+            _ => None,
+        }
+    }
+}
+
+/// How the targets of an exercise in a routine move from one session to the next
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressionRule {
+    pub id: Option<i32>,
+    pub routine_id: i32,
+    pub exercise_id: i32,
+    pub kind: ProgressionKind,
+    pub increment_kg: Option<f64>, // Linear and double progression; rounding step for percentages
+    pub min_reps: Option<i32>,     // Double progression rep range
+    pub max_reps: Option<i32>,
+    pub percentage: Option<f64>,   // Of the estimated 1RM, 1-100
+    pub deload_after_failures: Option<i32>, // Consecutive missed sessions before a deload
+    pub deload_percentage: Option<f64>,     // Weight taken off on a deload, 1-50
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressionOutcome {
+    NoRule,     // The routine's prescription, unchanged
+    NoHistory,  // Never trained; the routine's prescription
+    Increase,   // More weight
+    AddReps,    // Same weight, one more rep
+    Hold,       // Last session was missed; same targets again
+    Deload,     // Too many missed sessions in a row
+    Percentage, // Weight from the estimated 1RM
+}
+
+/// Proposed targets for an exercise of a routine, from the person's last session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressionProposal {
+    pub routine_id: i32,
+    pub exercise_id: i32,
+    pub exercise_name: String,
+    pub outcome: ProgressionOutcome,
+    pub last_date: Option<String>,
+    pub last_weight: Option<f64>, // Top weight of the last session
+    pub last_reps: Option<i32>,   // Fewest reps over the sets at that weight
+    pub last_completed: bool,
+    pub consecutive_failures: i32,
+    pub sets: Option<i32>,
+    pub reps: Option<i32>,
+    pub weight: Option<f64>,
+}
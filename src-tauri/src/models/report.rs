@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+/// Size of each point of a report series
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportBucket {
+    Day,
+    #[default]
+    Week, // Weeks start on Monday
+    Month,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TonnagePoint {
+    pub period_start: String, // YYYY-MM-DD, first day of the bucket
+    pub tonnage: f64,         // Σ reps × weight, in kg
+    pub reps: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrequencyPoint {
+    pub period_start: String,
+    pub sessions: i32, // Distinct training days
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExerciseSetsPoint {
+    pub period_start: String,
+    pub exercise_id: i32,
+    pub exercise_name: String,
+    pub sets: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntensityPoint {
+    pub period_start: String,
+    pub average_weight: Option<f64>, // Tonnage / loaded reps
+    pub average_rpe: Option<f64>,
+}
+
+/// A workout entry planned from a routine, next to what the routine prescribed when it was planned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdherenceEntry {
+    pub workout_entry_id: i32,
+    pub date: String,
+    pub routine_id: Option<i32>,
+    pub routine_name: Option<String>,
+    pub exercise_id: i32,
+    pub exercise_name: String,
+    pub prescribed_sets: Option<i32>, // None when the exercise was not in the routine
+    pub prescribed_reps: Option<i32>,
+    pub prescribed_weight: Option<f64>,
+    pub performed_sets: i32,
+    pub performed_reps: i32,
+    pub top_weight: Option<f64>,
+    pub completed_sets: i32, // Sets reaching the prescribed reps at the prescribed weight, at most prescribed_sets
+}
+
+/// Completed vs prescribed work of one person over a date range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdherenceReport {
+    pub person_id: i32,
+    pub start_date: String,
+    pub end_date: String,
+    pub sessions: i32, // Distinct days with planned entries
+    pub prescribed_sets: i32,
+    pub completed_sets: i32,
+    pub prescribed_reps: i32,
+    pub performed_reps: i32,
+    pub completed_entries: i32, // Entries with every prescribed set completed
+    pub adherence: Option<f64>, // completed_sets / prescribed_sets, 0-1
+    pub entries: Vec<AdherenceEntry>,
+}
@@ -11,6 +11,12 @@ pub struct RoutineExercise {
     pub weight: Option<f64>,
     pub notes: Option<String>,
     pub group_number: Option<i32>,
+    pub rpe: Option<f64>,          // Rate of perceived exertion, 1-10
+    pub rir: Option<i32>,          // Reps in reserve
+    pub tempo: Option<String>,     // Eccentric-pause-concentric-pause, e.g. "3-1-1-0"
+    pub rest_seconds: Option<i32>, // Rest after each set
+    pub duration_seconds: Option<i32>, // For time based exercises (plank, rowing)
+    pub distance_meters: Option<f64>,  // For distance based exercises (running, rowing)
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
 }
@@ -26,6 +32,12 @@ pub struct RoutineExerciseWithDetails {
     pub weight: Option<f64>,
     pub notes: Option<String>,
     pub group_number: Option<i32>,
+    pub rpe: Option<f64>,
+    pub rir: Option<i32>,
+    pub tempo: Option<String>,
+    pub rest_seconds: Option<i32>,
+    pub duration_seconds: Option<i32>,
+    pub distance_meters: Option<f64>,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
     pub exercise_name: String,
@@ -53,6 +65,12 @@ impl RoutineExercise {
             weight,
             notes,
             group_number,
+            rpe: None,
+            rir: None,
+            tempo: None,
+            rest_seconds: None,
+            duration_seconds: None,
+            distance_meters: None,
             created_at: None,
             updated_at: None,
         }
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+/// An immutable snapshot of a routine, taken after every change to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutineRevision {
+    pub id: i32,
+    pub routine_id: i32,
+    pub revision: i32, // 1-based, per routine
+    pub name: String,
+    pub code: String,
+    pub comment: Option<String>,
+    pub exercise_count: i32,
+    pub created_at: Option<String>,
+}
+
+/// A routine exercise as it was at a given revision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutineRevisionExercise {
+    pub exercise_id: i32,
+    pub exercise_name: String,
+    pub exercise_code: String,
+    pub order_index: i32,
+    pub sets: Option<i32>,
+    pub reps: Option<i32>,
+    pub weight: Option<f64>,
+    pub notes: Option<String>,
+    pub group_number: Option<i32>,
+    pub rpe: Option<f64>,
+    pub rir: Option<i32>,
+    pub tempo: Option<String>,
+    pub rest_seconds: Option<i32>,
+    pub duration_seconds: Option<i32>,
+    pub distance_meters: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutineRevisionWithExercises {
+    pub revision: RoutineRevision,
+    pub exercises: Vec<RoutineRevisionExercise>,
+}
+
+/// An exercise present in both revisions whose prescription differs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutineExerciseChange {
+    pub exercise_id: i32,
+    pub exercise_name: String,
+    pub fields: Vec<String>, // e.g. ["sets", "weight"]
+    pub before: RoutineRevisionExercise,
+    pub after: RoutineRevisionExercise,
+}
+
+/// What changed from one revision of a routine to another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutineRevisionDiff {
+    pub routine_id: i32,
+    pub from_revision: i32,
+    pub to_revision: i32,
+    pub name_changed: bool,
+    pub code_changed: bool,
+    pub added: Vec<RoutineRevisionExercise>,
+    pub removed: Vec<RoutineRevisionExercise>,
+    pub changed: Vec<RoutineExerciseChange>,
+}
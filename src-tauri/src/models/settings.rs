@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+
+/// Bumped whenever the layout of `settings.json` changes; older files are migrated on load
+pub const SETTINGS_VERSION: u32 = 2;
+
+pub const RESEND_API_URL: &str = "https://api.resend.com/emails";
+
+/// Every user-editable setting of the app, stored in `settings.json` next to the database.
+/// Missing fields take their default, so older files keep loading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub version: u32,
+    pub backup: BackupConfig,
+    pub updater: UpdaterSettings,
+    pub notices: Vec<String>, // Left by migrations for the user to read; dropped on the next save
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            backup: BackupConfig::default(),
+            updater: UpdaterSettings::default(),
+            notices: Vec::new(),
+        }
+    }
+}
+
+/// The settings as sent to the webview. Secrets never leave the backend: the
+/// passphrase and target credentials are replaced by whether they are set.
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsView {
+    pub version: u32,
+    pub backup: BackupConfigView,
+    pub updater: UpdaterSettings,
+    pub notices: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupConfigView {
+    pub directory: Option<String>,
+    pub keep_daily: u32,
+    pub keep_weekly: u32,
+    pub targets: Vec<BackupTargetView>,
+    pub passphrase_set: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupTargetView {
+    #[serde(flatten)]
+    pub target: BackupTargetConfig, // With its secret blanked
+    pub secret_set: bool,
+}
+
+impl From<&AppSettings> for SettingsView {
+    fn from(settings: &AppSettings) -> Self {
+        let backup = &settings.backup;
+        Self {
+            version: settings.version,
+            backup: BackupConfigView {
+                directory: backup.directory.clone(),
+                keep_daily: backup.keep_daily,
+                keep_weekly: backup.keep_weekly,
+                targets: backup
+                    .targets
+                    .iter()
+                    .map(|target| {
+                        let mut target = target.clone();
+                        let secret_set = target.redact();
+                        BackupTargetView { target, secret_set }
+                    })
+                    .collect(),
+                passphrase_set: backup.passphrase.is_some(),
+            },
+            updater: settings.updater.clone(),
+            notices: settings.notices.clone(),
+        }
+    }
+}
+
+/// Where local backups go, how many are kept and where copies are sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    pub directory: Option<String>, // None = "backups" next to the database
+    pub keep_daily: u32,
+    pub keep_weekly: u32,
+    pub targets: Vec<BackupTargetConfig>,
+    pub passphrase: Option<String>, // Encrypts new backups when set; kept out of settings.json and the webview
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            keep_daily: 7,
+            keep_weekly: 4,
+            targets: Vec::new(),
+            passphrase: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    None, // Plain connection, only for servers on the local network
+    #[default]
+    StartTls,
+    Tls,
+}
+
+/// A destination backups are copied to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BackupTargetConfig {
+    /// Another folder, e.g. a mounted NAS share
+    Directory { path: String },
+    Resend {
+        api_key: String,
+        #[serde(default = "default_resend_url")]
+        api_url: String,
+        from: String,
+        to: Vec<String>,
+    },
+    Smtp {
+        host: String,
+        port: u16,
+        #[serde(default)]
+        security: SmtpSecurity,
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default)]
+        password: Option<String>,
+        from: String,
+        to: Vec<String>,
+    },
+    #[serde(rename = "webdav")]
+    WebDav {
+        url: String, // Collection the backups are stored in
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default)]
+        password: Option<String>,
+    },
+    /// Any S3-compatible object storage (AWS, MinIO, Garage...)
+    S3 {
+        endpoint: String,
+        region: String,
+        bucket: String,
+        #[serde(default)]
+        prefix: Option<String>,
+        access_key_id: String,
+        secret_access_key: String,
+        #[serde(default)]
+        path_style: bool, // Required by most self-hosted servers
+    },
+}
+
+fn default_resend_url() -> String {
+    RESEND_API_URL.to_string()
+}
+
+impl BackupTargetConfig {
+    // Blanks the credential; returns whether one was set
+    fn redact(&mut self) -> bool {
+        match self {
+            BackupTargetConfig::Directory { .. } => false,
+            BackupTargetConfig::Resend { api_key: secret, .. }
+            | BackupTargetConfig::S3 { secret_access_key: secret, .. } => !std::mem::take(secret).is_empty(),
+            BackupTargetConfig::Smtp { password, .. } | BackupTargetConfig::WebDav { password, .. } => {
+                password.take().is_some_and(|password| !password.is_empty())
+            }
+        }
+    }
+
+    // Same server and account, so a stored credential still belongs to it
+    fn same_account(&self, other: &BackupTargetConfig) -> bool {
+        use BackupTargetConfig::*;
+        match (self, other) {
+            (Resend { api_url, .. }, Resend { api_url: other_url, .. }) => api_url == other_url,
+            (Smtp { host, port, username, .. }, Smtp { host: other_host, port: other_port, username: other_username, .. }) => {
+                host == other_host && port == other_port && username == other_username
+            }
+            (WebDav { url, username, .. }, WebDav { url: other_url, username: other_username, .. }) => {
+                url == other_url && username == other_username
+            }
+            (S3 { endpoint, access_key_id, .. }, S3 { endpoint: other_endpoint, access_key_id: other_key, .. }) => {
+                endpoint == other_endpoint && access_key_id == other_key
+            }
+            _ => false,
+        }
+    }
+
+    /// Credentials are write-only: a blank one (or an omitted password) keeps the
+    /// credential stored for the same account in `current`. An empty password clears it.
+    pub fn keep_secret(&mut self, current: &[BackupTargetConfig]) {
+        let stored = current.iter().find(|target| self.same_account(target)).cloned();
+        match (self, stored) {
+            (BackupTargetConfig::Resend { api_key: secret, .. }, Some(BackupTargetConfig::Resend { api_key: stored, .. }))
+            | (BackupTargetConfig::S3 { secret_access_key: secret, .. }, Some(BackupTargetConfig::S3 { secret_access_key: stored, .. }))
+                if secret.is_empty() =>
+            {
+                *secret = stored;
+            }
+            (BackupTargetConfig::Smtp { password, .. }, stored) | (BackupTargetConfig::WebDav { password, .. }, stored) => {
+                match password.as_deref() {
+                    None => {
+                        *password = match stored {
+                            Some(BackupTargetConfig::Smtp { password: stored, .. })
+                            | Some(BackupTargetConfig::WebDav { password: stored, .. }) => stored,
+                            _ => None,
+                        }
+                    }
+                    Some("") => *password = None,
+                    Some(_) => {}
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct UpdaterSettings {
+    pub manifest_url: Option<String>, // Update manifest (see update-server.json); None disables update checks
+    pub channel: UpdateChannel,
+    pub public_key: Option<String>, // minisign public key, base64 as printed by `tauri signer generate`
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable, // Releases without a pre-release tag only
+    Beta,   // Also the manifest's "beta" channel and pre-releases
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+/// The lists used to classify exercises
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxonomyKind {
+    MuscleGroup,
+    Equipment,
+    MovementPattern,
+}
+
+impl TaxonomyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaxonomyKind::MuscleGroup => "muscle_group",
+            TaxonomyKind::Equipment => "equipment",
+            TaxonomyKind::MovementPattern => "movement_pattern",
+        }
+    }
+}
+
+/// An entry of a taxonomy list (e.g. "Pecho", "Mancuernas", "Empuje horizontal")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxonomyItem {
+    pub id: Option<i32>,
+    pub name: String,
+}
+
+/// Classification of a single exercise
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExerciseTaxonomy {
+    pub primary_muscle_groups: Vec<TaxonomyItem>,
+    pub secondary_muscle_groups: Vec<TaxonomyItem>,
+    pub equipment: Vec<TaxonomyItem>,
+    pub movement_patterns: Vec<TaxonomyItem>,
+}
+
+/// Ids to assign to an exercise; replaces its whole classification
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExerciseTaxonomyUpdate {
+    #[serde(default)]
+    pub primary_muscle_group_ids: Vec<i32>,
+    #[serde(default)]
+    pub secondary_muscle_group_ids: Vec<i32>,
+    #[serde(default)]
+    pub equipment_ids: Vec<i32>,
+    #[serde(default)]
+    pub movement_pattern_ids: Vec<i32>,
+}
+
+/// Optional filters for the exercise search; every filter given must match
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExerciseFilter {
+    pub muscle_group_id: Option<i32>,
+    #[serde(default)]
+    pub primary_muscle_only: bool, // Ignore exercises where the muscle group is only secondary
+    pub equipment_id: Option<i32>,
+    pub movement_pattern_id: Option<i32>,
+}
+
+impl ExerciseFilter {
+    pub fn is_empty(&self) -> bool {
+        self.muscle_group_id.is_none() && self.equipment_id.is_none() && self.movement_pattern_id.is_none()
+    }
+}
@@ -1,4 +1,32 @@
 use serde::{Deserialize, Serialize};
+use crate::models::workout_set::WorkoutSet;
+
+/// Whether an entry was trained or is only scheduled. Planned entries are left out
+/// of every statistic until they are logged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkoutEntryStatus {
+    Planned,
+    #[default]
+    Performed,
+}
+
+impl WorkoutEntryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkoutEntryStatus::Planned => "planned",
+            WorkoutEntryStatus::Performed => "performed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "planned" => Some(WorkoutEntryStatus::Planned),
+            "performed" => Some(WorkoutEntryStatus::Performed),
+            _ => None,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkoutEntry {
@@ -12,8 +40,22 @@ pub struct WorkoutEntry {
     pub notes: Option<String>,
     pub order_index: Option<i32>,  // Order of exercise in the workout session
     pub group_number: Option<i32>, // Group number for organizing exercises
+    pub rpe: Option<f64>,          // Rate of perceived exertion, 1-10
+    pub rir: Option<i32>,          // Reps in reserve
+    pub tempo: Option<String>,     // Eccentric-pause-concentric-pause, e.g. "3-1-1-0"
+    pub rest_seconds: Option<i32>, // Rest after each set
+    pub duration_seconds: Option<i32>, // For time based exercises (plank, rowing)
+    pub distance_meters: Option<f64>,  // For distance based exercises (running, rowing)
+    #[serde(default)]
+    pub routine_id: Option<i32>,          // Routine the entry was planned from
+    #[serde(default)]
+    pub routine_exercise_id: Option<i32>, // Its prescription in that routine
+    #[serde(default)]
+    pub status: WorkoutEntryStatus,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
+    #[serde(default)]
+    pub workout_sets: Option<Vec<WorkoutSet>>, // Per-set detail saved with the entry; None keeps the stored sets
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +70,15 @@ pub struct WorkoutEntryWithDetails {
     pub notes: Option<String>,
     pub order_index: Option<i32>,  // Order of exercise in the workout session
     pub group_number: Option<i32>, // Group number for organizing exercises
+    pub rpe: Option<f64>,
+    pub rir: Option<i32>,
+    pub tempo: Option<String>,
+    pub rest_seconds: Option<i32>,
+    pub duration_seconds: Option<i32>,
+    pub distance_meters: Option<f64>,
+    pub routine_id: Option<i32>,
+    pub routine_exercise_id: Option<i32>,
+    pub status: WorkoutEntryStatus,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
     // Joined data
@@ -35,6 +86,7 @@ pub struct WorkoutEntryWithDetails {
     pub person_last_name: String,
     pub exercise_name: String,
     pub exercise_code: String,
+    pub workout_sets: Vec<WorkoutSet>,
 }
 
 impl WorkoutEntry {
@@ -58,8 +110,18 @@ impl WorkoutEntry {
             notes,
             order_index: None,
             group_number: None,
+            rpe: None,
+            rir: None,
+            tempo: None,
+            rest_seconds: None,
+            duration_seconds: None,
+            distance_meters: None,
+            routine_id: None,
+            routine_exercise_id: None,
+            status: WorkoutEntryStatus::Performed,
             created_at: None,
             updated_at: None,
+            workout_sets: None,
         }
     }
 } 
\ No newline at end of file
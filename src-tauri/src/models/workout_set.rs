@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SetType {
+    WarmUp,
+    #[default]
+    Working,
+    Drop,
+    Failure,
+}
+
+impl SetType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SetType::WarmUp => "warm_up",
+            SetType::Working => "working",
+            SetType::Drop => "drop",
+            SetType::Failure => "failure",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "warm_up" => Some(SetType::WarmUp),
+            "working" => Some(SetType::Working),
+            "drop" => Some(SetType::Drop),
+            "failure" => Some(SetType::Failure),
+            _ => None,
+        }
+    }
+}
+
+/// A single set of a workout entry (e.g. 12×40 inside a 12/10/8 pyramid)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkoutSet {
+    pub id: Option<i32>,
+    #[serde(default)]
+    pub workout_entry_id: i32, // Filled in by the repository when saved together with its entry
+    pub order_index: i32,      // Position of the set inside the entry
+    pub reps: Option<i32>,
+    pub weight: Option<f64>,   // in kg
+    #[serde(default)]
+    pub completed: bool,       // false = planned, true = performed
+    #[serde(default)]
+    pub set_type: SetType,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
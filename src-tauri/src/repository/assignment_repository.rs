@@ -0,0 +1,11 @@
+use crate::error::AppResult;
+use crate::models::assignment::{Assignment, AssignmentWithDetails};
+
+pub trait AssignmentRepository: Send + Sync {
+    fn create(&self, assignment: &Assignment) -> AppResult<i32>;
+    fn get_by_id(&self, id: i32) -> AppResult<Option<AssignmentWithDetails>>;
+    fn update(&self, assignment: &Assignment) -> AppResult<()>;
+    fn delete(&self, id: i32) -> AppResult<()>;
+    /// Assignments of the person overlapping the range, oldest start first
+    fn list_by_person(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<Vec<AssignmentWithDetails>>;
+}
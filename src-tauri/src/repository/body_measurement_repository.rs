@@ -0,0 +1,15 @@
+use crate::error::AppResult;
+use crate::models::body_measurement::{BodyMeasurement, BodyMetric, MeasurementPoint};
+
+pub trait BodyMeasurementRepository: Send + Sync {
+    /// Writes the session and its values together
+    fn create(&self, measurement: &BodyMeasurement) -> AppResult<i32>;
+    fn get_by_id(&self, id: i32) -> AppResult<Option<BodyMeasurement>>;
+    /// Replaces the date, notes and every value of the session
+    fn update(&self, measurement: &BodyMeasurement) -> AppResult<()>;
+    fn delete(&self, id: i32) -> AppResult<()>;
+    /// Sessions between the dates (both included), newest first
+    fn list_by_person(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<Vec<BodyMeasurement>>;
+    /// Values of one metric between the dates, oldest first
+    fn series(&self, person_id: i32, metric: BodyMetric, start_date: &str, end_date: &str) -> AppResult<Vec<MeasurementPoint>>;
+}
@@ -0,0 +1,21 @@
+use crate::error::AppResult;
+use crate::models::check_in::{CheckIn, DailyOccupancy, HourlyOccupancy};
+
+/// Deleted people cannot check in and are left out of the open list and the reports;
+/// their history is kept for when they are restored
+pub trait CheckInRepository: Send + Sync {
+    /// Fails with `NOT_FOUND` when the person does not exist or was deleted
+    fn create(&self, person_id: i32, checked_in_at: &str) -> AppResult<i32>;
+    fn get_by_id(&self, id: i32) -> AppResult<Option<CheckIn>>;
+    /// Latest visit of the person without a check-out that started at or after `since`
+    fn find_open(&self, person_id: i32, since: &str) -> AppResult<Option<CheckIn>>;
+    fn check_out(&self, id: i32, checked_out_at: &str) -> AppResult<()>;
+    fn delete(&self, id: i32) -> AppResult<()>;
+    /// Visits without a check-out that started at or after `since`
+    fn list_open(&self, since: &str) -> AppResult<Vec<CheckIn>>;
+    fn list_by_person(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<Vec<CheckIn>>;
+    /// Distinct days with a visit, oldest first
+    fn visit_dates(&self, person_id: i32) -> AppResult<Vec<String>>;
+    fn daily_occupancy(&self, start_date: &str, end_date: &str) -> AppResult<Vec<DailyOccupancy>>;
+    fn hourly_occupancy(&self, start_date: &str, end_date: &str) -> AppResult<Vec<HourlyOccupancy>>;
+}
@@ -1,15 +1,18 @@
+use crate::error::AppResult;
 use crate::models::exercise::Exercise;
+use crate::models::taxonomy::ExerciseFilter;
 
 pub trait ExerciseRepository: Send + Sync {
-    fn create(&self, exercise: Exercise) -> Result<(), String>;
-    fn list(&self) -> Vec<Exercise>;
-    fn list_paginated(&self, page: i32, page_size: i32) -> Vec<Exercise>;
-    fn count(&self) -> i32;
-    fn delete(&self, id: i32) -> Result<(), String>;
-    fn restore(&self, id: i32) -> Result<(), String>;
-    fn update(&self, exercise: Exercise) -> Result<(), String>;
-    fn search_paginated(&self, query: &str, page: i32, page_size: i32) -> Vec<Exercise>;
-    fn search_count(&self, query: &str) -> i32;
-    fn list_deleted(&self) -> Vec<Exercise>;
-    fn count_deleted(&self) -> i32;
-} 
\ No newline at end of file
+    fn create(&self, exercise: Exercise) -> AppResult<()>;
+    fn get_by_id(&self, id: i32) -> AppResult<Option<Exercise>>;
+    fn list(&self) -> AppResult<Vec<Exercise>>;
+    fn list_paginated(&self, page: i32, page_size: i32) -> AppResult<Vec<Exercise>>;
+    fn count(&self) -> AppResult<i32>;
+    fn delete(&self, id: i32) -> AppResult<()>;
+    fn restore(&self, id: i32) -> AppResult<()>;
+    fn update(&self, exercise: Exercise) -> AppResult<()>;
+    fn search_paginated(&self, query: &str, filter: &ExerciseFilter, page: i32, page_size: i32) -> AppResult<Vec<Exercise>>;
+    fn search_count(&self, query: &str, filter: &ExerciseFilter) -> AppResult<i32>;
+    fn list_deleted(&self) -> AppResult<Vec<Exercise>>;
+    fn count_deleted(&self) -> AppResult<i32>;
+}
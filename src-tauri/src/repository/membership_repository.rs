@@ -0,0 +1,25 @@
+use crate::error::AppResult;
+use crate::models::membership::{MembershipPlan, Payment, Subscription, SubscriptionWithDetails};
+
+/// `today` (YYYY-MM-DD) is passed in so status and remaining days are computed for the same day
+pub trait MembershipRepository: Send + Sync {
+    fn list_plans(&self, include_inactive: bool) -> AppResult<Vec<MembershipPlan>>;
+    fn get_plan(&self, id: i32) -> AppResult<Option<MembershipPlan>>;
+    fn create_plan(&self, plan: &MembershipPlan) -> AppResult<i32>;
+    fn update_plan(&self, plan: &MembershipPlan) -> AppResult<()>;
+    /// Expects `end_date` and `price` to be resolved
+    fn create_subscription(&self, subscription: &Subscription) -> AppResult<i32>;
+    fn get_subscription(&self, id: i32, today: &str) -> AppResult<Option<SubscriptionWithDetails>>;
+    fn list_person_subscriptions(&self, person_id: i32, today: &str) -> AppResult<Vec<SubscriptionWithDetails>>;
+    /// Whether a non-cancelled subscription of the person covers any day of the range
+    fn has_overlapping_subscription(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<bool>;
+    fn cancel_subscription(&self, id: i32) -> AppResult<()>;
+    fn create_payment(&self, payment: &Payment) -> AppResult<i32>;
+    fn list_payments(&self, subscription_id: i32) -> AppResult<Vec<Payment>>;
+    fn delete_payment(&self, id: i32) -> AppResult<()>;
+    /// Active subscriptions ending between `today` and `until` that were not renewed
+    fn list_expiring(&self, today: &str, until: &str) -> AppResult<Vec<SubscriptionWithDetails>>;
+    /// Started, non-cancelled subscriptions that are not fully paid, and the last
+    /// subscription of people whose membership expired without a renewal
+    fn list_overdue(&self, today: &str) -> AppResult<Vec<SubscriptionWithDetails>>;
+}
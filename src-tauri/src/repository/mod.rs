@@ -1,10 +1,26 @@
 pub mod person_repository;
 pub mod sqlite_person_repository;
+pub mod membership_repository;
+pub mod sqlite_membership_repository;
+pub mod check_in_repository;
+pub mod sqlite_check_in_repository;
+pub mod body_measurement_repository;
+pub mod sqlite_body_measurement_repository;
 pub mod exercise_repository;
 pub mod sqlite_exercise_repository;
+pub mod taxonomy_repository;
+pub mod sqlite_taxonomy_repository;
 pub mod workout_entry_repository;
 pub mod sqlite_workout_entry_repository;
 pub mod routine_repository;
 pub mod sqlite_routine_repository;
 pub mod routine_exercise_repository;
-pub mod sqlite_routine_exercise_repository; 
\ No newline at end of file
+pub mod sqlite_routine_exercise_repository;
+pub mod program_repository;
+pub mod sqlite_program_repository;
+pub mod assignment_repository;
+pub mod sqlite_assignment_repository;
+pub mod progression_repository;
+pub mod sqlite_progression_repository;
+pub mod report_repository;
+pub mod sqlite_report_repository; 
\ No newline at end of file
@@ -1,17 +1,23 @@
-use crate::models::person::Person;
+use crate::error::AppResult;
+use crate::models::person::{Person, PersonMergeSummary};
 
 pub trait PersonRepository: Send + Sync {
-    fn create(&self, person: Person) -> Result<(), String>;
-    fn get_by_id(&self, id: i32) -> Option<Person>;
-    fn update(&self, person: Person) -> Result<(), String>;
-    fn delete(&self, id: i32) -> Result<(), String>;
-    fn restore(&self, id: i32) -> Result<(), String>;
-    fn list_all(&self) -> Vec<Person>;
-    fn list_paginated(&self, page: i32, page_size: i32) -> Vec<Person>;
-    fn search(&self, query: &str) -> Vec<Person>;
-    fn search_paginated(&self, query: &str, page: i32, page_size: i32) -> Vec<Person>;
-    fn count_all(&self) -> i32;
-    fn search_count(&self, query: &str) -> i32;
-    fn list_deleted(&self) -> Vec<Person>;
-    fn count_deleted(&self) -> i32;
+    fn create(&self, person: Person) -> AppResult<()>;
+    fn get_by_id(&self, id: i32) -> AppResult<Option<Person>>;
+    fn update(&self, person: Person) -> AppResult<()>;
+    fn delete(&self, id: i32) -> AppResult<()>;
+    fn restore(&self, id: i32) -> AppResult<()>;
+    fn list_all(&self) -> AppResult<Vec<Person>>;
+    fn list_paginated(&self, page: i32, page_size: i32) -> AppResult<Vec<Person>>;
+    fn search(&self, query: &str) -> AppResult<Vec<Person>>;
+    /// People whose phone has the same digits as `digits`, ignoring spaces and separators
+    fn find_by_phone(&self, digits: &str) -> AppResult<Vec<Person>>;
+    fn search_paginated(&self, query: &str, page: i32, page_size: i32) -> AppResult<Vec<Person>>;
+    fn count_all(&self) -> AppResult<i32>;
+    fn search_count(&self, query: &str) -> AppResult<i32>;
+    fn list_deleted(&self) -> AppResult<Vec<Person>>;
+    fn count_deleted(&self) -> AppResult<i32>;
+    /// Moves everything linked to `duplicate_id` to `survivor_id`, fills the survivor's
+    /// empty profile fields and soft-deletes the duplicate, in one transaction
+    fn merge(&self, survivor_id: i32, duplicate_id: i32) -> AppResult<PersonMergeSummary>;
 }
@@ -0,0 +1,18 @@
+use crate::error::AppResult;
+use crate::models::program::{Program, ProgramDay, ProgramDayWithDetails};
+
+pub trait ProgramRepository: Send + Sync {
+    /// Writes the program and all its days together
+    fn create(&self, program: &Program, days: &[ProgramDay]) -> AppResult<i32>;
+    fn get_by_id(&self, id: i32) -> AppResult<Option<Program>>;
+    /// Replaces the program fields and every day
+    fn update(&self, program: &Program, days: &[ProgramDay]) -> AppResult<()>;
+    /// Copies the program and its days under a new name and code
+    fn clone_program(&self, id: i32, name: &str, code: &str) -> AppResult<i32>;
+    fn delete(&self, id: i32) -> AppResult<()>;
+    fn restore(&self, id: i32) -> AppResult<()>;
+    fn list_all(&self) -> AppResult<Vec<Program>>;
+    /// Days by week and position, with inline exercises and the routine name;
+    /// the exercises of routine days are left empty
+    fn get_days(&self, program_id: i32) -> AppResult<Vec<ProgramDayWithDetails>>;
+}
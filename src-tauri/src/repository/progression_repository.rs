@@ -0,0 +1,9 @@
+use crate::error::AppResult;
+use crate::models::progression::ProgressionRule;
+
+pub trait ProgressionRepository: Send + Sync {
+    /// Creates the rule or replaces the one of the same routine and exercise
+    fn save(&self, rule: &ProgressionRule) -> AppResult<i32>;
+    fn delete(&self, routine_id: i32, exercise_id: i32) -> AppResult<()>;
+    fn list_by_routine(&self, routine_id: i32) -> AppResult<Vec<ProgressionRule>>;
+}
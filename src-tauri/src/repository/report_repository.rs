@@ -0,0 +1,13 @@
+use crate::error::AppResult;
+use crate::models::report::{AdherenceEntry, ExerciseSetsPoint, FrequencyPoint, IntensityPoint, ReportBucket, TonnagePoint};
+
+/// Aggregated views over `workout_entries` for one person and an inclusive date range.
+/// Series are ordered by period and include empty periods, except `sets_per_exercise`.
+pub trait ReportRepository: Send + Sync {
+    fn tonnage(&self, person_id: i32, start_date: &str, end_date: &str, bucket: ReportBucket) -> AppResult<Vec<TonnagePoint>>;
+    fn frequency(&self, person_id: i32, start_date: &str, end_date: &str, bucket: ReportBucket) -> AppResult<Vec<FrequencyPoint>>;
+    fn sets_per_exercise(&self, person_id: i32, start_date: &str, end_date: &str, bucket: ReportBucket) -> AppResult<Vec<ExerciseSetsPoint>>;
+    fn intensity(&self, person_id: i32, start_date: &str, end_date: &str, bucket: ReportBucket) -> AppResult<Vec<IntensityPoint>>;
+    /// Entries planned from a routine with their prescription, by date and order
+    fn adherence(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<Vec<AdherenceEntry>>;
+}
@@ -1,24 +1,31 @@
+use crate::error::AppResult;
 use crate::models::routine::{Routine, RoutineWithExercises};
 use crate::models::routine_exercise::{RoutineExercise, RoutineExerciseWithDetails};
+use crate::models::routine_revision::{RoutineRevision, RoutineRevisionWithExercises};
 
 pub trait RoutineRepository: Send + Sync {
-    fn create(&self, routine: Routine) -> Result<i32, String>;
-    fn get_by_id(&self, id: i32) -> Option<Routine>;
-    fn get_with_exercises(&self, id: i32) -> Option<RoutineWithExercises>;
-    fn update(&self, id: i32, name: String, code: String) -> Result<(), String>;
-    fn delete(&self, id: i32) -> Result<(), String>;
-    fn restore(&self, id: i32) -> Result<(), String>;
-    fn list_all(&self) -> Vec<Routine>;
-    fn list_deleted(&self) -> Vec<Routine>;
-    fn count_deleted(&self) -> i32;
-    fn list_routines_paginated(&self, page: i32, page_size: i32) -> Vec<Routine>;
-    fn search_routines(&self, query: &str) -> Vec<Routine>;
-    fn search_routines_paginated(&self, query: &str, page: i32, page_size: i32) -> Vec<Routine>;
-    fn add_exercise_to_routine(&self, routine_exercise: RoutineExercise) -> Result<(), String>;
-    fn update_routine_exercise(&self, routine_exercise: RoutineExercise) -> Result<(), String>;
-    fn remove_exercise_from_routine(&self, routine_id: i32, exercise_id: i32) -> Result<(), String>;
-    fn get_routine_exercises(&self, routine_id: i32) -> Vec<RoutineExerciseWithDetails>;
-    fn reorder_routine_exercises(&self, routine_id: i32, exercise_orders: Vec<(i32, i32)>) -> Result<(), String>;
-    fn replace_routine_exercises(&self, routine_id: i32, exercises: Vec<RoutineExercise>) -> Result<(), String>;
-    fn renumber_routine_groups(&self, routine_id: i32) -> Result<(), String>;
-} 
\ No newline at end of file
+    fn create(&self, routine: Routine) -> AppResult<i32>;
+    fn create_with_exercises(&self, routine: Routine, exercises: Vec<RoutineExercise>) -> AppResult<i32>;
+    fn get_by_id(&self, id: i32) -> AppResult<Option<Routine>>;
+    fn get_with_exercises(&self, id: i32) -> AppResult<Option<RoutineWithExercises>>;
+    fn update(&self, id: i32, name: String, code: String) -> AppResult<()>;
+    fn delete(&self, id: i32) -> AppResult<()>;
+    fn restore(&self, id: i32) -> AppResult<()>;
+    fn list_all(&self) -> AppResult<Vec<Routine>>;
+    fn list_deleted(&self) -> AppResult<Vec<Routine>>;
+    fn count_deleted(&self) -> AppResult<i32>;
+    fn list_routines_paginated(&self, page: i32, page_size: i32) -> AppResult<Vec<Routine>>;
+    fn search_routines(&self, query: &str) -> AppResult<Vec<Routine>>;
+    fn search_routines_paginated(&self, query: &str, page: i32, page_size: i32) -> AppResult<Vec<Routine>>;
+    fn add_exercise_to_routine(&self, routine_exercise: RoutineExercise) -> AppResult<()>;
+    fn update_routine_exercise(&self, routine_exercise: RoutineExercise) -> AppResult<()>;
+    fn remove_exercise_from_routine(&self, routine_id: i32, exercise_id: i32) -> AppResult<()>;
+    fn get_routine_exercises(&self, routine_id: i32) -> AppResult<Vec<RoutineExerciseWithDetails>>;
+    fn reorder_routine_exercises(&self, routine_id: i32, exercise_orders: Vec<(i32, i32)>) -> AppResult<()>;
+    fn replace_routine_exercises(&self, routine_id: i32, exercises: Vec<RoutineExercise>, comment: Option<String>) -> AppResult<()>;
+    fn renumber_routine_groups(&self, routine_id: i32) -> AppResult<()>;
+    fn list_revisions(&self, routine_id: i32) -> AppResult<Vec<RoutineRevision>>;
+    fn get_revision(&self, routine_id: i32, revision: i32) -> AppResult<Option<RoutineRevisionWithExercises>>;
+    /// Puts the routine back as it was at `revision`, recorded as a new revision; returns its number
+    fn restore_revision(&self, routine_id: i32, revision: i32, comment: Option<String>) -> AppResult<i32>;
+}
@@ -0,0 +1,144 @@
+use rusqlite::types::Type;
+use rusqlite::{params, OptionalExtension, Row};
+use crate::config::db::{DbPool, PooledConnection};
+use crate::error::{AppError, AppResult};
+use crate::models::assignment::{Assignment, AssignmentWithDetails};
+use crate::repository::assignment_repository::AssignmentRepository;
+
+const ASSIGNMENT_SELECT: &str =
+    "SELECT a.id, a.person_id, a.routine_id, a.program_id, a.start_date, a.end_date, a.weekdays, a.notes,
+            a.created_at, a.updated_at, r.name, pr.name, pr.weeks
+     FROM routine_assignments a
+     LEFT JOIN routines r ON a.routine_id = r.id
+     LEFT JOIN programs pr ON a.program_id = pr.id";
+
+pub struct SqliteAssignmentRepository {
+    pool: Option<DbPool>,
+}
+
+impl SqliteAssignmentRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool: Some(pool) }
+    }
+
+    /// Dummy constructor: every call fails with `DATABASE_UNAVAILABLE`
+    pub fn new_dummy() -> Self {
+        Self { pool: None }
+    }
+
+    fn get_connection(&self) -> AppResult<PooledConnection> {
+        match &self.pool {
+            Some(pool) => Ok(pool.get()?),
+            None => Err(AppError::database_unavailable("Assignment repository unavailable")),
+        }
+    }
+
+    fn assignment_from_row(row: &Row) -> rusqlite::Result<AssignmentWithDetails> {
+        let weekdays: String = row.get(6)?;
+        Ok(AssignmentWithDetails {
+            id: row.get(0)?,
+            person_id: row.get(1)?,
+            routine_id: row.get(2)?,
+            program_id: row.get(3)?,
+            start_date: row.get(4)?,
+            end_date: row.get(5)?,
+            weekdays: parse_weekdays(&weekdays)
+                .ok_or_else(|| rusqlite::Error::FromSqlConversionFailure(6, Type::Text, format!("Unknown weekdays: {}", weekdays).into()))?,
+            notes: row.get(7)?,
+            created_at: row.get(8)?,
+            updated_at: row.get(9)?,
+            routine_name: row.get(10)?,
+            program_name: row.get(11)?,
+            program_weeks: row.get(12)?,
+        })
+    }
+}
+
+// Stored as "1,3,5"
+fn format_weekdays(weekdays: &[i32]) -> String {
+    weekdays.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(",")
+}
+
+fn parse_weekdays(value: &str) -> Option<Vec<i32>> {
+    value.split(',').map(|d| d.trim().parse().ok()).collect()
+}
+
+impl AssignmentRepository for SqliteAssignmentRepository {
+    fn create(&self, assignment: &Assignment) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        conn.execute(
+            "INSERT INTO routine_assignments (person_id, routine_id, program_id, start_date, end_date, weekdays, notes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                assignment.person_id,
+                assignment.routine_id,
+                assignment.program_id,
+                assignment.start_date,
+                assignment.end_date,
+                format_weekdays(&assignment.weekdays),
+                assignment.notes,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn get_by_id(&self, id: i32) -> AppResult<Option<AssignmentWithDetails>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!("{} WHERE a.id = ?1", ASSIGNMENT_SELECT))?;
+        let assignment = stmt.query_row(params![id], Self::assignment_from_row).optional()?;
+        Ok(assignment)
+    }
+
+    fn update(&self, assignment: &Assignment) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let updated = conn.execute(
+            "UPDATE routine_assignments
+             SET routine_id = ?1, program_id = ?2, start_date = ?3, end_date = ?4, weekdays = ?5, notes = ?6,
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?7",
+            params![
+                assignment.routine_id,
+                assignment.program_id,
+                assignment.start_date,
+                assignment.end_date,
+                format_weekdays(&assignment.weekdays),
+                assignment.notes,
+                assignment.id,
+            ],
+        )?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Assignment not found"));
+        }
+        Ok(())
+    }
+
+    fn delete(&self, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        // Workout entries already written from the plan are kept
+        let deleted = conn.execute("DELETE FROM routine_assignments WHERE id = ?1", params![id])?;
+
+        if deleted == 0 {
+            return Err(AppError::not_found("Assignment not found"));
+        }
+        Ok(())
+    }
+
+    fn list_by_person(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<Vec<AssignmentWithDetails>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "{} WHERE a.person_id = ?1 AND a.start_date <= ?3 AND (a.end_date IS NULL OR a.end_date >= ?2)
+             ORDER BY a.start_date, a.id",
+            ASSIGNMENT_SELECT
+        ))?;
+        let assignments = stmt
+            .query_map(params![person_id, start_date, end_date], Self::assignment_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(assignments)
+    }
+}
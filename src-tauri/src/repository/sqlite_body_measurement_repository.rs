@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::config::db::{DbPool, PooledConnection};
+use crate::error::{AppError, AppResult};
+use crate::models::body_measurement::{BodyMeasurement, BodyMetric, MeasurementPoint, MeasurementValue};
+use crate::repository::body_measurement_repository::BodyMeasurementRepository;
+
+pub struct SqliteBodyMeasurementRepository {
+    pool: Option<DbPool>,
+}
+
+impl SqliteBodyMeasurementRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool: Some(pool) }
+    }
+
+    /// Dummy constructor: every call fails with `DATABASE_UNAVAILABLE`
+    pub fn new_dummy() -> Self {
+        Self { pool: None }
+    }
+
+    fn get_connection(&self) -> AppResult<PooledConnection> {
+        match &self.pool {
+            Some(pool) => Ok(pool.get()?),
+            None => Err(AppError::database_unavailable("Body measurement repository unavailable")),
+        }
+    }
+
+    fn measurement_from_row(row: &Row) -> rusqlite::Result<BodyMeasurement> {
+        Ok(BodyMeasurement {
+            id: Some(row.get(0)?),
+            person_id: row.get(1)?,
+            date: row.get(2)?,
+            notes: row.get(3)?,
+            values: Vec::new(),
+        })
+    }
+
+    // Expects (measurement_id, metric, value)
+    fn value_from_row(row: &Row) -> rusqlite::Result<(i32, MeasurementValue)> {
+        let metric: String = row.get(1)?;
+        let metric = BodyMetric::parse(&metric).ok_or_else(|| {
+            rusqlite::Error::FromSqlConversionFailure(1, Type::Text, format!("Unknown body metric '{}'", metric).into())
+        })?;
+
+        Ok((row.get(0)?, MeasurementValue { metric, value: row.get(2)? }))
+    }
+
+    fn insert_values(conn: &Connection, measurement_id: i32, values: &[MeasurementValue]) -> rusqlite::Result<()> {
+        let mut stmt = conn.prepare_cached(
+            "INSERT INTO body_measurement_values (measurement_id, metric, value) VALUES (?1, ?2, ?3)"
+        )?;
+        for value in values {
+            stmt.execute(params![measurement_id, value.metric.as_str(), value.value])?;
+        }
+        Ok(())
+    }
+}
+
+impl BodyMeasurementRepository for SqliteBodyMeasurementRepository {
+    fn create(&self, measurement: &BodyMeasurement) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO body_measurements (person_id, date, notes) VALUES (?1, ?2, ?3)",
+            params![measurement.person_id, measurement.date, measurement.notes],
+        )?;
+        let id = tx.last_insert_rowid() as i32;
+        Self::insert_values(&tx, id, &measurement.values)?;
+        tx.commit()?;
+
+        Ok(id)
+    }
+
+    fn get_by_id(&self, id: i32) -> AppResult<Option<BodyMeasurement>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached("SELECT id, person_id, date, notes FROM body_measurements WHERE id = ?1")?;
+        let mut measurement = stmt.query_row(params![id], Self::measurement_from_row).optional()?;
+
+        if let Some(measurement) = measurement.as_mut() {
+            let mut stmt = conn.prepare_cached(
+                "SELECT measurement_id, metric, value FROM body_measurement_values WHERE measurement_id = ?1 ORDER BY id"
+            )?;
+            measurement.values = stmt
+                .query_map(params![id], Self::value_from_row)?
+                .map(|row| row.map(|(_, value)| value))
+                .collect::<Result<Vec<_>, _>>()?;
+        }
+        Ok(measurement)
+    }
+
+    fn update(&self, measurement: &BodyMeasurement) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let tx = conn.unchecked_transaction()?;
+        let updated = tx.execute(
+            "UPDATE body_measurements SET date = ?1, notes = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?3",
+            params![measurement.date, measurement.notes, measurement.id],
+        )?;
+        if updated == 0 {
+            return Err(AppError::not_found("Body measurement not found"));
+        }
+
+        tx.execute("DELETE FROM body_measurement_values WHERE measurement_id = ?1", params![measurement.id])?;
+        if let Some(id) = measurement.id {
+            Self::insert_values(&tx, id, &measurement.values)?;
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    fn delete(&self, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        // Values go with the session (ON DELETE CASCADE)
+        let deleted = conn.execute("DELETE FROM body_measurements WHERE id = ?1", params![id])?;
+
+        if deleted == 0 {
+            return Err(AppError::not_found("Body measurement not found"));
+        }
+        Ok(())
+    }
+
+    fn list_by_person(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<Vec<BodyMeasurement>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, person_id, date, notes FROM body_measurements
+             WHERE person_id = ?1 AND date BETWEEN ?2 AND ?3
+             ORDER BY date DESC"
+        )?;
+        let mut measurements = stmt
+            .query_map(params![person_id, start_date, end_date], Self::measurement_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Values of every session in the range, in one query
+        let mut stmt = conn.prepare_cached(
+            "SELECT v.measurement_id, v.metric, v.value
+             FROM body_measurement_values v
+             JOIN body_measurements m ON v.measurement_id = m.id
+             WHERE m.person_id = ?1 AND m.date BETWEEN ?2 AND ?3
+             ORDER BY v.id"
+        )?;
+        let mut values_by_measurement: HashMap<i32, Vec<MeasurementValue>> = HashMap::new();
+        for row in stmt.query_map(params![person_id, start_date, end_date], Self::value_from_row)? {
+            let (measurement_id, value) = row?;
+            values_by_measurement.entry(measurement_id).or_default().push(value);
+        }
+
+        for measurement in measurements.iter_mut() {
+            if let Some(values) = measurement.id.and_then(|id| values_by_measurement.remove(&id)) {
+                measurement.values = values;
+            }
+        }
+        Ok(measurements)
+    }
+
+    fn series(&self, person_id: i32, metric: BodyMetric, start_date: &str, end_date: &str) -> AppResult<Vec<MeasurementPoint>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT m.date, v.value
+             FROM body_measurement_values v
+             JOIN body_measurements m ON v.measurement_id = m.id
+             WHERE m.person_id = ?1 AND v.metric = ?2 AND m.date BETWEEN ?3 AND ?4
+             ORDER BY m.date"
+        )?;
+        let points = stmt
+            .query_map(params![person_id, metric.as_str(), start_date, end_date], |row| {
+                Ok(MeasurementPoint {
+                    date: row.get(0)?,
+                    value: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(points)
+    }
+}
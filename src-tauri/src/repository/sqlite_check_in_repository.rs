@@ -0,0 +1,235 @@
+use rusqlite::{params, OptionalExtension, Row};
+use crate::config::db::{DbPool, PooledConnection};
+use crate::error::{AppError, AppResult};
+use crate::models::check_in::{CheckIn, DailyOccupancy, HourlyOccupancy};
+use crate::repository::check_in_repository::CheckInRepository;
+
+// Shared projection for every query returning `CheckIn`
+const CHECK_IN_SELECT: &str =
+    "SELECT c.id, c.person_id, c.checked_in_at, c.checked_out_at,
+            CASE WHEN c.checked_out_at IS NOT NULL
+                 THEN CAST(ROUND((julianday(c.checked_out_at) - julianday(c.checked_in_at)) * 1440) AS INTEGER) END,
+            p.name, p.last_name, p.phone
+     FROM check_ins c
+     JOIN people p ON c.person_id = p.id";
+
+const ACTIVE_PERSON: &str = "(p.deleted_at IS NULL OR p.deleted_at = '') AND (p.is_active = 1 OR p.is_active IS NULL)";
+
+// Visits between the dates ?1 and ?2, both included. Compared as text so the
+// index on checked_in_at is used.
+const IN_RANGE: &str = "c.checked_in_at >= ?1 AND c.checked_in_at < date(?2, '+1 day')";
+
+pub struct SqliteCheckInRepository {
+    pool: Option<DbPool>,
+}
+
+impl SqliteCheckInRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool: Some(pool) }
+    }
+
+    /// Dummy constructor: every call fails with `DATABASE_UNAVAILABLE`
+    pub fn new_dummy() -> Self {
+        Self { pool: None }
+    }
+
+    fn get_connection(&self) -> AppResult<PooledConnection> {
+        match &self.pool {
+            Some(pool) => Ok(pool.get()?),
+            None => Err(AppError::database_unavailable("Check-in repository unavailable")),
+        }
+    }
+
+    fn check_in_from_row(row: &Row) -> rusqlite::Result<CheckIn> {
+        Ok(CheckIn {
+            id: row.get(0)?,
+            person_id: row.get(1)?,
+            checked_in_at: row.get(2)?,
+            checked_out_at: row.get(3)?,
+            duration_minutes: row.get(4)?,
+            person_name: row.get(5)?,
+            person_last_name: row.get(6)?,
+            person_phone: row.get(7)?,
+        })
+    }
+}
+
+impl CheckInRepository for SqliteCheckInRepository {
+    fn create(&self, person_id: i32, checked_in_at: &str) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        let inserted = conn.execute(
+            &format!(
+                "INSERT INTO check_ins (person_id, checked_in_at)
+                 SELECT p.id, ?2 FROM people p WHERE p.id = ?1 AND {}",
+                ACTIVE_PERSON
+            ),
+            params![person_id, checked_in_at],
+        )?;
+
+        if inserted == 0 {
+            return Err(AppError::not_found("Person not found"));
+        }
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn get_by_id(&self, id: i32) -> AppResult<Option<CheckIn>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!("{} WHERE c.id = ?1", CHECK_IN_SELECT))?;
+        let check_in = stmt.query_row(params![id], Self::check_in_from_row).optional()?;
+        Ok(check_in)
+    }
+
+    fn find_open(&self, person_id: i32, since: &str) -> AppResult<Option<CheckIn>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "{} WHERE c.person_id = ?1 AND c.checked_out_at IS NULL AND c.checked_in_at >= ?2 AND {}
+             ORDER BY c.checked_in_at DESC
+             LIMIT 1",
+            CHECK_IN_SELECT, ACTIVE_PERSON
+        ))?;
+        let check_in = stmt.query_row(params![person_id, since], Self::check_in_from_row).optional()?;
+        Ok(check_in)
+    }
+
+    fn check_out(&self, id: i32, checked_out_at: &str) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let updated = conn.execute(
+            "UPDATE check_ins SET checked_out_at = ?1 WHERE id = ?2 AND checked_out_at IS NULL",
+            params![checked_out_at, id],
+        )?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Open check-in not found"));
+        }
+        Ok(())
+    }
+
+    fn delete(&self, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let deleted = conn.execute("DELETE FROM check_ins WHERE id = ?1", params![id])?;
+
+        if deleted == 0 {
+            return Err(AppError::not_found("Check-in not found"));
+        }
+        Ok(())
+    }
+
+    fn list_open(&self, since: &str) -> AppResult<Vec<CheckIn>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "{} WHERE c.checked_out_at IS NULL AND c.checked_in_at >= ?1 AND {}
+             ORDER BY c.checked_in_at",
+            CHECK_IN_SELECT, ACTIVE_PERSON
+        ))?;
+        let check_ins = stmt.query_map(params![since], Self::check_in_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(check_ins)
+    }
+
+    fn list_by_person(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<Vec<CheckIn>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "{} WHERE c.person_id = ?3 AND {}
+             ORDER BY c.checked_in_at DESC",
+            CHECK_IN_SELECT, IN_RANGE
+        ))?;
+        let check_ins = stmt
+            .query_map(params![start_date, end_date, person_id], Self::check_in_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(check_ins)
+    }
+
+    fn visit_dates(&self, person_id: i32) -> AppResult<Vec<String>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT DISTINCT date(checked_in_at) FROM check_ins WHERE person_id = ?1 ORDER BY 1"
+        )?;
+        let dates = stmt.query_map(params![person_id], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+        Ok(dates)
+    }
+
+    fn daily_occupancy(&self, start_date: &str, end_date: &str) -> AppResult<Vec<DailyOccupancy>> {
+        let conn = self.get_connection()?;
+
+        // Every day of the range, so charts get explicit zeros for closed days
+        let mut stmt = conn.prepare_cached(&format!(
+            "WITH RECURSIVE days(day) AS (
+                 SELECT date(?1)
+                 UNION ALL
+                 SELECT date(day, '+1 day') FROM days WHERE day < ?2
+             ),
+             visits AS (
+                 SELECT c.id, c.person_id, date(c.checked_in_at) AS day
+                 FROM check_ins c
+                 JOIN people p ON c.person_id = p.id
+                 WHERE {} AND {}
+             )
+             SELECT d.day, COUNT(v.id), COUNT(DISTINCT v.person_id)
+             FROM days d
+             LEFT JOIN visits v ON v.day = d.day
+             GROUP BY d.day
+             ORDER BY d.day",
+            IN_RANGE, ACTIVE_PERSON
+        ))?;
+
+        let points = stmt
+            .query_map(params![start_date, end_date], |row| {
+                Ok(DailyOccupancy {
+                    date: row.get(0)?,
+                    visits: row.get(1)?,
+                    unique_people: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(points)
+    }
+
+    fn hourly_occupancy(&self, start_date: &str, end_date: &str) -> AppResult<Vec<HourlyOccupancy>> {
+        let conn = self.get_connection()?;
+
+        // A visit counts as present from its check-in hour to its check-out hour;
+        // without a check-out only the arrival hour is known
+        let mut stmt = conn.prepare_cached(&format!(
+            "WITH RECURSIVE hours(hour) AS (
+                 SELECT 0
+                 UNION ALL
+                 SELECT hour + 1 FROM hours WHERE hour < 23
+             ),
+             visits AS (
+                 SELECT CAST(strftime('%H', c.checked_in_at) AS INTEGER) AS first_hour,
+                        CASE WHEN c.checked_out_at IS NULL THEN CAST(strftime('%H', c.checked_in_at) AS INTEGER)
+                             WHEN date(c.checked_out_at) > date(c.checked_in_at) THEN 23
+                             ELSE CAST(strftime('%H', c.checked_out_at) AS INTEGER) END AS last_hour
+                 FROM check_ins c
+                 JOIN people p ON c.person_id = p.id
+                 WHERE {} AND {}
+             )
+             SELECT h.hour,
+                    COALESCE(SUM(v.first_hour = h.hour), 0),
+                    ROUND(COUNT(v.first_hour) / (julianday(?2) - julianday(?1) + 1), 2)
+             FROM hours h
+             LEFT JOIN visits v ON h.hour BETWEEN v.first_hour AND v.last_hour
+             GROUP BY h.hour
+             ORDER BY h.hour",
+            IN_RANGE, ACTIVE_PERSON
+        ))?;
+
+        let points = stmt
+            .query_map(params![start_date, end_date], |row| {
+                Ok(HourlyOccupancy {
+                    hour: row.get(0)?,
+                    check_ins: row.get(1)?,
+                    average_present: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(points)
+    }
+}
@@ -1,366 +1,386 @@
-use crate::models::exercise::Exercise;
+use crate::models::exercise::{Exercise, MeasurementType};
+use crate::models::taxonomy::ExerciseFilter;
 use crate::repository::exercise_repository::ExerciseRepository;
-use rusqlite::{Connection, params, Result as SqliteResult};
+use crate::config::db::{DbPool, PooledConnection};
+use crate::error::{AppError, AppResult};
+use rusqlite::types::{Type, Value};
+use rusqlite::{params, params_from_iter, OptionalExtension, Row};
 
 pub struct SqliteExerciseRepository {
-    db_path: String,
-    is_dummy: bool,
+    pool: Option<DbPool>,
 }
 
 impl SqliteExerciseRepository {
-    pub fn new(db_path: &str) -> Self {
-        let repo = Self {
-            db_path: db_path.to_string(),
-            is_dummy: false,
-        };
-        repo.create_table().expect("Failed to create exercise table");
-        repo
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool: Some(pool) }
     }
 
-    pub fn new_safe(db_path: &str) -> Result<Self, String> {
-        let repo = Self {
-            db_path: db_path.to_string(),
-            is_dummy: false,
-        };
-        repo.create_table().map_err(|e| format!("Failed to create exercise table: {}", e))?;
-        Ok(repo)
+    /// Dummy constructor: every call fails with `DATABASE_UNAVAILABLE`
+    pub fn new_dummy() -> Self {
+        Self { pool: None }
     }
 
-    pub fn new_dummy() -> Self {
-        Self {
-            db_path: String::new(),
-            is_dummy: true,
+    fn get_connection(&self) -> AppResult<PooledConnection> {
+        match &self.pool {
+            Some(pool) => Ok(pool.get()?),
+            None => Err(AppError::database_unavailable("Exercise repository unavailable")),
         }
     }
 
-    fn create_table(&self) -> SqliteResult<()> {
-        if self.is_dummy {
-            return Ok(());
-        }
-        
-        let conn = Connection::open(&self.db_path)?;
-        
-        // Check if logical deletion migration is needed
-        if self.check_if_logical_deletion_migration_needed(&conn)? {
-            self.migrate_logical_deletion(&conn)?;
-        } else {
-            // Create new table with logical deletion fields
-            conn.execute(
-                "CREATE TABLE IF NOT EXISTS exercise (
-                    id INTEGER PRIMARY KEY AUTOINCREMENT,
-                    name TEXT NOT NULL,
-                    code TEXT NOT NULL UNIQUE,
-                    deleted_at DATETIME NULL,
-                    is_active BOOLEAN DEFAULT 1
-                )",
-                [],
-            )?;
-        }
-        Ok(())
+    fn exercise_from_row(row: &Row) -> rusqlite::Result<Exercise> {
+        let measurement_type: String = row.get(3)?;
+        let measurement_type = MeasurementType::parse(&measurement_type).ok_or_else(|| {
+            rusqlite::Error::FromSqlConversionFailure(3, Type::Text, format!("Unknown measurement type '{}'", measurement_type).into())
+        })?;
+
+        Ok(Exercise {
+            id: Some(row.get(0)?),
+            name: row.get(1)?,
+            code: row.get(2)?,
+            measurement_type: Some(measurement_type),
+        })
     }
 
-    fn check_if_logical_deletion_migration_needed(&self, conn: &Connection) -> SqliteResult<bool> {
-        if self.is_dummy { return Ok(false); }
-        
-        // Check if table exists but doesn't have logical deletion fields
-        let mut stmt = conn.prepare(
-            "SELECT sql FROM sqlite_master WHERE type='table' AND name='exercise'"
-        )?;
-        
-        let table_sql: Result<String, _> = stmt.query_row([], |row| {
-            Ok(row.get::<_, String>(0)?)
-        });
-        
-        match table_sql {
-            Ok(sql) => Ok(!sql.contains("deleted_at") && !sql.contains("is_active")),
-            Err(_) => Ok(false), // Table doesn't exist, no migration needed
+    // WHERE clause and parameters shared by search_paginated and search_count.
+    // A blank query only applies the taxonomy filters.
+    fn search_conditions(query: &str, filter: &ExerciseFilter) -> (String, Vec<Value>) {
+        let mut conditions = vec![
+            "(deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)".to_string(),
+        ];
+        let mut values: Vec<Value> = Vec::new();
+
+        if !query.trim().is_empty() {
+            values.push(Value::Text(format!("%{}%", query)));
+            conditions.push(format!("(name LIKE ?{0} OR code LIKE ?{0})", values.len()));
         }
-    }
 
-    fn migrate_logical_deletion(&self, conn: &Connection) -> SqliteResult<()> {
-        if self.is_dummy { return Ok(()); }
-        println!("Adding logical deletion fields to exercise table...");
-        
-        // Add logical deletion columns
-        conn.execute(
-            "ALTER TABLE exercise ADD COLUMN deleted_at DATETIME NULL",
-            [],
-        )?;
-        
-        conn.execute(
-            "ALTER TABLE exercise ADD COLUMN is_active BOOLEAN DEFAULT 1",
-            [],
-        )?;
-        
-        println!("Logical deletion migration completed successfully!");
-        Ok(())
-    }
+        if let Some(muscle_group_id) = filter.muscle_group_id {
+            values.push(Value::Integer(muscle_group_id as i64));
+            conditions.push(format!(
+                "EXISTS (SELECT 1 FROM exercise_muscle_groups emg
+                         WHERE emg.exercise_id = exercise.id AND emg.muscle_group_id = ?{}{})",
+                values.len(),
+                if filter.primary_muscle_only { " AND emg.is_primary = 1" } else { "" }
+            ));
+        }
+
+        if let Some(equipment_id) = filter.equipment_id {
+            values.push(Value::Integer(equipment_id as i64));
+            conditions.push(format!(
+                "EXISTS (SELECT 1 FROM exercise_equipment ee
+                         WHERE ee.exercise_id = exercise.id AND ee.equipment_id = ?{})",
+                values.len()
+            ));
+        }
 
-    fn get_connection(&self) -> SqliteResult<Connection> {
-        if self.is_dummy {
-            return Err(rusqlite::Error::InvalidQuery);
+        if let Some(movement_pattern_id) = filter.movement_pattern_id {
+            values.push(Value::Integer(movement_pattern_id as i64));
+            conditions.push(format!(
+                "EXISTS (SELECT 1 FROM exercise_movement_patterns emp
+                         WHERE emp.exercise_id = exercise.id AND emp.movement_pattern_id = ?{})",
+                values.len()
+            ));
         }
-        Connection::open(&self.db_path)
+
+        (conditions.join(" AND "), values)
     }
 }
 
 impl ExerciseRepository for SqliteExerciseRepository {
-    fn create(&self, exercise: Exercise) -> Result<(), String> {
-        if self.is_dummy {
-            return Ok(());
-        }
-        
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
+    fn create(&self, exercise: Exercise) -> AppResult<()> {
+        let conn = self.get_connection()?;
         conn.execute(
-            "INSERT INTO exercise (name, code) VALUES (?1, ?2)",
-            params![exercise.name, exercise.code],
-        ).map_err(|e| e.to_string())?;
+            "INSERT INTO exercise (name, code, measurement_type) VALUES (?1, ?2, ?3)",
+            params![exercise.name, exercise.code, exercise.measurement_type.unwrap_or_default().as_str()],
+        )?;
         Ok(())
     }
 
-    fn list(&self) -> Vec<Exercise> {
-        if self.is_dummy {
-            return Vec::new();
-        }
-        
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
-        
-        let mut stmt = match conn.prepare(
-            "SELECT id, name, code FROM exercise 
+    fn get_by_id(&self, id: i32) -> AppResult<Option<Exercise>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached("SELECT id, name, code, measurement_type FROM exercise WHERE id = ?1")?;
+        let exercise = stmt.query_row(params![id], Self::exercise_from_row).optional()?;
+
+        Ok(exercise)
+    }
+
+    fn list(&self) -> AppResult<Vec<Exercise>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, name, code, measurement_type FROM exercise
              WHERE (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)
              ORDER BY name"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
-        
-        let exercise_iter = match stmt.query_map([], |row| {
-            Ok(Exercise {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                code: row.get(2)?,
-            })
-        }) {
-            Ok(iter) => iter,
-            Err(_) => return Vec::new(),
-        };
+        )?;
 
-        exercise_iter.filter_map(|exercise| exercise.ok()).collect()
+        let exercises = stmt.query_map([], Self::exercise_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(exercises)
     }
 
-    fn list_paginated(&self, page: i32, page_size: i32) -> Vec<Exercise> {
-        if self.is_dummy {
-            return Vec::new();
-        }
-        
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
+    fn list_paginated(&self, page: i32, page_size: i32) -> AppResult<Vec<Exercise>> {
+        let conn = self.get_connection()?;
 
         let offset = (page - 1) * page_size;
-        
-        let mut stmt = match conn.prepare(
-            "SELECT id, name, code FROM exercise 
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, name, code, measurement_type FROM exercise
              WHERE (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)
              ORDER BY name
              LIMIT ?1 OFFSET ?2"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
-
-        let exercise_iter = match stmt.query_map(params![page_size, offset], |row| {
-            Ok(Exercise {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                code: row.get(2)?,
-            })
-        }) {
-            Ok(iter) => iter,
-            Err(_) => return Vec::new(),
-        };
+        )?;
 
-        exercise_iter.filter_map(|exercise| exercise.ok()).collect()
+        let exercises = stmt
+            .query_map(params![page_size, offset], Self::exercise_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(exercises)
     }
 
-    fn delete(&self, id: i32) -> Result<(), String> {
-        if self.is_dummy {
-            return Ok(());
-        }
-        
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+    fn delete(&self, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
         // Logical deletion instead of physical deletion
-        conn.execute(
+        let updated = conn.execute(
             "UPDATE exercise SET deleted_at = datetime('now'), is_active = 0 WHERE id = ?1",
             params![id]
-        ).map_err(|e| e.to_string())?;
+        )?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Exercise not found"));
+        }
         Ok(())
     }
 
-    fn restore(&self, id: i32) -> Result<(), String> {
-        if self.is_dummy {
-            return Ok(());
-        }
-        
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+    fn restore(&self, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
         // Restore logically deleted exercise
-        conn.execute(
+        let updated = conn.execute(
             "UPDATE exercise SET deleted_at = NULL, is_active = 1 WHERE id = ?1",
             params![id]
-        ).map_err(|e| e.to_string())?;
+        )?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Exercise not found"));
+        }
         Ok(())
     }
 
-    fn update(&self, exercise: Exercise) -> Result<(), String> {
-        if self.is_dummy {
-            return Ok(());
+    fn update(&self, exercise: Exercise) -> AppResult<()> {
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE exercise SET name = ?1, code = ?2, measurement_type = COALESCE(?3, measurement_type) WHERE id = ?4",
+            params![exercise.name, exercise.code, exercise.measurement_type.map(|t| t.as_str()), exercise.id],
+        )?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Exercise not found"));
         }
-        
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        conn.execute(
-            "UPDATE exercise SET name = ?1, code = ?2 WHERE id = ?3",
-            params![exercise.name, exercise.code, exercise.id],
-        ).map_err(|e| e.to_string())?;
         Ok(())
     }
 
-    fn count(&self) -> i32 {
-        if self.is_dummy {
-            return 0;
-        }
-        
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return 0,
-        };
-        
-        let count: Result<i32, _> = conn.query_row(
-            "SELECT COUNT(*) FROM exercise 
+    fn count(&self) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        let count = conn.query_row(
+            "SELECT COUNT(*) FROM exercise
              WHERE (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)",
             [],
             |row| row.get(0),
-        );
-        
-        count.unwrap_or(0)
+        )?;
+        Ok(count)
     }
 
-    fn search_paginated(&self, query: &str, page: i32, page_size: i32) -> Vec<Exercise> {
-        if self.is_dummy {
-            return Vec::new();
-        }
-        
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
+    fn search_paginated(&self, query: &str, filter: &ExerciseFilter, page: i32, page_size: i32) -> AppResult<Vec<Exercise>> {
+        let conn = self.get_connection()?;
 
         let offset = (page - 1) * page_size;
-        let search_pattern = format!("%{}%", query);
-        
-        let mut stmt = match conn.prepare(
-            "SELECT id, name, code FROM exercise 
-             WHERE (name LIKE ?1 OR code LIKE ?1) 
-             AND (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)
-             ORDER BY name
-             LIMIT ?2 OFFSET ?3"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
+        let (conditions, mut values) = Self::search_conditions(query, filter);
+        values.push(Value::Integer(page_size as i64));
+        values.push(Value::Integer(offset as i64));
 
-        let exercise_iter = match stmt.query_map(params![search_pattern, page_size, offset], |row| {
-            Ok(Exercise {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                code: row.get(2)?,
-            })
-        }) {
-            Ok(iter) => iter,
-            Err(_) => return Vec::new(),
-        };
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT id, name, code, measurement_type FROM exercise
+             WHERE {}
+             ORDER BY name
+             LIMIT ?{} OFFSET ?{}",
+            conditions,
+            values.len() - 1,
+            values.len()
+        ))?;
 
-        exercise_iter.filter_map(|exercise| exercise.ok()).collect()
+        let exercises = stmt
+            .query_map(params_from_iter(values.iter()), Self::exercise_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(exercises)
     }
 
-    fn search_count(&self, query: &str) -> i32 {
-        if self.is_dummy {
-            return 0;
-        }
-        
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return 0,
-        };
-        
-        let search_pattern = format!("%{}%", query);
-        
-        let count: Result<i32, _> = conn.query_row(
-            "SELECT COUNT(*) FROM exercise 
-             WHERE (name LIKE ?1 OR code LIKE ?1) 
-             AND (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)",
-            params![search_pattern],
+    fn search_count(&self, query: &str, filter: &ExerciseFilter) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        let (conditions, values) = Self::search_conditions(query, filter);
+
+        let count = conn.query_row(
+            &format!("SELECT COUNT(*) FROM exercise WHERE {}", conditions),
+            params_from_iter(values.iter()),
             |row| row.get(0),
-        );
-        
-        count.unwrap_or(0)
+        )?;
+        Ok(count)
     }
 
-    fn list_deleted(&self) -> Vec<Exercise> {
-        if self.is_dummy {
-            return Vec::new();
-        }
-        
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
-        
-        let mut stmt = match conn.prepare(
-            "SELECT id, name, code FROM exercise 
+    fn list_deleted(&self) -> AppResult<Vec<Exercise>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, name, code, measurement_type FROM exercise
              WHERE deleted_at IS NOT NULL AND deleted_at != '' AND is_active = 0
              ORDER BY deleted_at DESC"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
-        
-        let exercise_iter = match stmt.query_map([], |row| {
-            Ok(Exercise {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                code: row.get(2)?,
-            })
-        }) {
-            Ok(iter) => iter,
-            Err(_) => return Vec::new(),
-        };
+        )?;
 
-        exercise_iter.filter_map(|exercise| exercise.ok()).collect()
+        let exercises = stmt.query_map([], Self::exercise_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(exercises)
     }
 
-    fn count_deleted(&self) -> i32 {
-        if self.is_dummy {
-            return 0;
-        }
-        
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return 0,
-        };
-        
-        let count: Result<i32, _> = conn.query_row(
-            "SELECT COUNT(*) FROM exercise 
+    fn count_deleted(&self) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        let count = conn.query_row(
+            "SELECT COUNT(*) FROM exercise
              WHERE deleted_at IS NOT NULL AND deleted_at != '' AND is_active = 0",
             [],
             |row| row.get(0),
-        );
-        
-        count.unwrap_or(0)
+        )?;
+        Ok(count)
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::db::test_pool;
+    use crate::models::taxonomy::{ExerciseTaxonomyUpdate, TaxonomyKind};
+    use crate::repository::sqlite_taxonomy_repository::SqliteTaxonomyRepository;
+    use crate::repository::taxonomy_repository::TaxonomyRepository;
+
+    fn exercise(name: &str, code: &str, measurement_type: Option<MeasurementType>) -> Exercise {
+        Exercise { id: None, name: name.into(), code: code.into(), measurement_type }
+    }
+
+    fn by_code(repository: &SqliteExerciseRepository, code: &str) -> Exercise {
+        repository.list().unwrap().into_iter().find(|e| e.code == code).unwrap()
+    }
+
+    #[test]
+    fn create_defaults_to_reps_and_weight() {
+        let (_dir, pool) = test_pool();
+        let repository = SqliteExerciseRepository::new(pool);
+        repository.create(exercise("Sentadilla", "SQ", None)).unwrap();
+
+        let stored = by_code(&repository, "SQ");
+        assert_eq!(stored.measurement_type, Some(MeasurementType::RepsWeight));
+    }
+
+    #[test]
+    fn update_without_type_keeps_the_stored_one() {
+        let (_dir, pool) = test_pool();
+        let repository = SqliteExerciseRepository::new(pool);
+        repository.create(exercise("Plancha", "PL", Some(MeasurementType::Time))).unwrap();
+        let id = by_code(&repository, "PL").id;
+
+        repository.update(Exercise { id, ..exercise("Plancha frontal", "PLF", None) }).unwrap();
+        let stored = repository.get_by_id(id.unwrap()).unwrap().unwrap();
+        assert_eq!(stored.name, "Plancha frontal");
+        assert_eq!(stored.measurement_type, Some(MeasurementType::Time));
+
+        repository.update(Exercise { id, ..exercise("Plancha frontal", "PLF", Some(MeasurementType::DistanceTime)) }).unwrap();
+        let stored = repository.get_by_id(id.unwrap()).unwrap().unwrap();
+        assert_eq!(stored.measurement_type, Some(MeasurementType::DistanceTime));
+    }
+
+    // Chest press variations: dumbbell bench (chest, triceps secondary, horizontal push),
+    // barbell bench (same with a barbell), dumbbell fly (chest only) and a dumbbell row
+    fn classified(pool: &DbPool) -> (SqliteExerciseRepository, [i32; 5]) {
+        let repository = SqliteExerciseRepository::new(pool.clone());
+        for (name, code) in [("Press banca mancuernas", "PBM"), ("Press banca barra", "PBB"), ("Aperturas", "AP"), ("Remo mancuerna", "RM")] {
+            repository.create(exercise(name, code, None)).unwrap();
+        }
+        let taxonomy = SqliteTaxonomyRepository::new(pool.clone());
+        let chest = taxonomy.create(TaxonomyKind::MuscleGroup, "Pecho").unwrap();
+        let triceps = taxonomy.create(TaxonomyKind::MuscleGroup, "Tríceps").unwrap();
+        let dumbbells = taxonomy.create(TaxonomyKind::Equipment, "Mancuernas").unwrap();
+        let barbell = taxonomy.create(TaxonomyKind::Equipment, "Barra").unwrap();
+        let push = taxonomy.create(TaxonomyKind::MovementPattern, "Empuje horizontal").unwrap();
+
+        let classify = |code: &str, primary: Vec<i32>, secondary: Vec<i32>, equipment: Vec<i32>, patterns: Vec<i32>| {
+            let id = by_code(&repository, code).id.unwrap();
+            let update = ExerciseTaxonomyUpdate {
+                primary_muscle_group_ids: primary,
+                secondary_muscle_group_ids: secondary,
+                equipment_ids: equipment,
+                movement_pattern_ids: patterns,
+            };
+            taxonomy.set_exercise_taxonomy(id, &update).unwrap();
+        };
+        classify("PBM", vec![chest], vec![triceps], vec![dumbbells], vec![push]);
+        classify("PBB", vec![chest], vec![triceps], vec![barbell], vec![push]);
+        classify("AP", vec![chest], vec![], vec![dumbbells], vec![]);
+        classify("RM", vec![], vec![], vec![dumbbells], vec![]);
+        (repository, [chest, triceps, dumbbells, barbell, push])
+    }
+
+    fn search(repository: &SqliteExerciseRepository, query: &str, filter: &ExerciseFilter) -> Vec<String> {
+        let codes: Vec<_> = repository.search_paginated(query, filter, 1, 50).unwrap().into_iter().map(|e| e.code).collect();
+        assert_eq!(repository.search_count(query, filter).unwrap() as usize, codes.len());
+        codes
+    }
+
+    #[test]
+    fn every_taxonomy_filter_given_must_match() {
+        let (_dir, pool) = test_pool();
+        let (repository, [chest, _, dumbbells, barbell, push]) = classified(&pool);
+
+        let chest_with_dumbbells = ExerciseFilter { muscle_group_id: Some(chest), equipment_id: Some(dumbbells), ..Default::default() };
+        assert_eq!(search(&repository, "", &chest_with_dumbbells), ["AP", "PBM"]);
+
+        let pushing = ExerciseFilter { movement_pattern_id: Some(push), ..chest_with_dumbbells.clone() };
+        assert_eq!(search(&repository, "", &pushing), ["PBM"]);
+
+        let barbell_push = ExerciseFilter { equipment_id: Some(barbell), movement_pattern_id: Some(push), ..Default::default() };
+        assert_eq!(search(&repository, "", &barbell_push), ["PBB"]);
+
+        // The text query narrows the filtered results down further
+        assert_eq!(search(&repository, "press", &chest_with_dumbbells), ["PBM"]);
+        assert!(search(&repository, "remo", &chest_with_dumbbells).is_empty());
+    }
+
+    #[test]
+    fn primary_muscle_only_skips_secondary_muscles() {
+        let (_dir, pool) = test_pool();
+        let (repository, [chest, triceps, ..]) = classified(&pool);
+
+        let triceps_work = ExerciseFilter { muscle_group_id: Some(triceps), ..Default::default() };
+        assert_eq!(search(&repository, "", &triceps_work), ["PBB", "PBM"]);
+        let primary_triceps = ExerciseFilter { primary_muscle_only: true, ..triceps_work };
+        assert!(search(&repository, "", &primary_triceps).is_empty());
+
+        let primary_chest = ExerciseFilter { muscle_group_id: Some(chest), primary_muscle_only: true, ..Default::default() };
+        assert_eq!(search(&repository, "", &primary_chest), ["AP", "PBB", "PBM"]);
+    }
+
+    #[test]
+    fn blank_query_applies_only_the_filters() {
+        let (_dir, pool) = test_pool();
+        let (repository, [_, _, dumbbells, ..]) = classified(&pool);
+        let with_dumbbells = ExerciseFilter { equipment_id: Some(dumbbells), ..Default::default() };
+
+        assert_eq!(search(&repository, "  ", &with_dumbbells), ["AP", "PBM", "RM"]);
+        assert_eq!(search(&repository, "", &ExerciseFilter::default()), ["AP", "PBB", "PBM", "RM"]);
+
+        // Deleted exercises stay hidden
+        repository.delete(by_code(&repository, "RM").id.unwrap()).unwrap();
+        assert_eq!(search(&repository, "", &with_dumbbells), ["AP", "PBM"]);
+
+        let page: Vec<_> = repository.search_paginated("", &with_dumbbells, 2, 1).unwrap().into_iter().map(|e| e.code).collect();
+        assert_eq!(page, ["PBM"]);
+    }
+}
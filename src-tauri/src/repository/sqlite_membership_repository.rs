@@ -0,0 +1,423 @@
+use rusqlite::types::Type;
+use rusqlite::{params, OptionalExtension, Row};
+use crate::config::db::{DbPool, PooledConnection};
+use crate::error::{AppError, AppResult};
+use crate::models::membership::{MembershipPlan, Payment, PaymentMethod, Subscription, SubscriptionStatus, SubscriptionWithDetails};
+use crate::repository::membership_repository::MembershipRepository;
+
+// Shared projection for every query returning `SubscriptionWithDetails`; ?1 is today
+const DETAILS_SELECT: &str =
+    "SELECT s.id, s.person_id, s.plan_id, s.start_date, s.end_date, s.price, s.notes, s.cancelled_at,
+            CASE WHEN s.cancelled_at IS NOT NULL THEN 'cancelled'
+                 WHEN s.start_date > ?1 THEN 'pending'
+                 WHEN s.end_date < ?1 THEN 'expired'
+                 ELSE 'active' END,
+            CAST(julianday(s.end_date) - julianday(?1) AS INTEGER),
+            ROUND(COALESCE(pay.amount_paid, 0), 2),
+            ROUND(s.price - COALESCE(pay.amount_paid, 0), 2),
+            p.name, p.last_name, p.phone, mp.name
+     FROM subscriptions s
+     JOIN people p ON s.person_id = p.id
+     JOIN membership_plans mp ON s.plan_id = mp.id
+     LEFT JOIN (
+         SELECT subscription_id, SUM(amount) AS amount_paid FROM payments GROUP BY subscription_id
+     ) pay ON pay.subscription_id = s.id";
+
+// Leaves out people that were deleted
+const ACTIVE_PERSON: &str = "(p.deleted_at IS NULL OR p.deleted_at = '') AND (p.is_active = 1 OR p.is_active IS NULL)";
+
+pub struct SqliteMembershipRepository {
+    pool: Option<DbPool>,
+}
+
+impl SqliteMembershipRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool: Some(pool) }
+    }
+
+    /// Dummy constructor: every call fails with `DATABASE_UNAVAILABLE`
+    pub fn new_dummy() -> Self {
+        Self { pool: None }
+    }
+
+    fn get_connection(&self) -> AppResult<PooledConnection> {
+        match &self.pool {
+            Some(pool) => Ok(pool.get()?),
+            None => Err(AppError::database_unavailable("Membership repository unavailable")),
+        }
+    }
+
+    fn plan_from_row(row: &Row) -> rusqlite::Result<MembershipPlan> {
+        Ok(MembershipPlan {
+            id: Some(row.get(0)?),
+            name: row.get(1)?,
+            price: row.get(2)?,
+            duration_days: row.get(3)?,
+            is_active: row.get(4)?,
+        })
+    }
+
+    fn details_from_row(row: &Row) -> rusqlite::Result<SubscriptionWithDetails> {
+        let status: String = row.get(8)?;
+        let status = SubscriptionStatus::parse(&status).ok_or_else(|| {
+            rusqlite::Error::FromSqlConversionFailure(8, Type::Text, format!("Unknown subscription status '{}'", status).into())
+        })?;
+
+        Ok(SubscriptionWithDetails {
+            id: row.get(0)?,
+            person_id: row.get(1)?,
+            plan_id: row.get(2)?,
+            start_date: row.get(3)?,
+            end_date: row.get(4)?,
+            price: row.get(5)?,
+            notes: row.get(6)?,
+            cancelled_at: row.get(7)?,
+            status,
+            days_remaining: row.get(9)?,
+            amount_paid: row.get(10)?,
+            balance: row.get(11)?,
+            person_name: row.get(12)?,
+            person_last_name: row.get(13)?,
+            person_phone: row.get(14)?,
+            plan_name: row.get(15)?,
+        })
+    }
+
+    fn payment_from_row(row: &Row) -> rusqlite::Result<Payment> {
+        let method: String = row.get(4)?;
+        let method = PaymentMethod::parse(&method).ok_or_else(|| {
+            rusqlite::Error::FromSqlConversionFailure(4, Type::Text, format!("Unknown payment method '{}'", method).into())
+        })?;
+
+        Ok(Payment {
+            id: Some(row.get(0)?),
+            subscription_id: row.get(1)?,
+            amount: row.get(2)?,
+            paid_on: row.get(3)?,
+            method,
+            notes: row.get(5)?,
+            created_at: row.get(6)?,
+        })
+    }
+}
+
+impl MembershipRepository for SqliteMembershipRepository {
+    fn list_plans(&self, include_inactive: bool) -> AppResult<Vec<MembershipPlan>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, name, price, duration_days, is_active FROM membership_plans
+             WHERE is_active = 1 OR ?1
+             ORDER BY is_active DESC, duration_days, name"
+        )?;
+        let plans = stmt.query_map(params![include_inactive], Self::plan_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(plans)
+    }
+
+    fn get_plan(&self, id: i32) -> AppResult<Option<MembershipPlan>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached("SELECT id, name, price, duration_days, is_active FROM membership_plans WHERE id = ?1")?;
+        let plan = stmt.query_row(params![id], Self::plan_from_row).optional()?;
+        Ok(plan)
+    }
+
+    fn create_plan(&self, plan: &MembershipPlan) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        conn.execute(
+            "INSERT INTO membership_plans (name, price, duration_days, is_active) VALUES (?1, ?2, ?3, ?4)",
+            params![plan.name, plan.price, plan.duration_days, plan.is_active],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn update_plan(&self, plan: &MembershipPlan) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let updated = conn.execute(
+            "UPDATE membership_plans
+             SET name = ?1, price = ?2, duration_days = ?3, is_active = ?4, updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?5",
+            params![plan.name, plan.price, plan.duration_days, plan.is_active, plan.id],
+        )?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Membership plan not found"));
+        }
+        Ok(())
+    }
+
+    fn create_subscription(&self, subscription: &Subscription) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        conn.execute(
+            "INSERT INTO subscriptions (person_id, plan_id, start_date, end_date, price, notes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                subscription.person_id,
+                subscription.plan_id,
+                subscription.start_date,
+                subscription.end_date,
+                subscription.price,
+                subscription.notes,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn get_subscription(&self, id: i32, today: &str) -> AppResult<Option<SubscriptionWithDetails>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!("{} WHERE s.id = ?2", DETAILS_SELECT))?;
+        let subscription = stmt.query_row(params![today, id], Self::details_from_row).optional()?;
+        Ok(subscription)
+    }
+
+    fn list_person_subscriptions(&self, person_id: i32, today: &str) -> AppResult<Vec<SubscriptionWithDetails>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "{} WHERE s.person_id = ?2 ORDER BY s.start_date DESC, s.id DESC",
+            DETAILS_SELECT
+        ))?;
+        let subscriptions = stmt
+            .query_map(params![today, person_id], Self::details_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(subscriptions)
+    }
+
+    fn has_overlapping_subscription(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<bool> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT EXISTS(
+                SELECT 1 FROM subscriptions
+                WHERE person_id = ?1 AND cancelled_at IS NULL AND start_date <= ?3 AND end_date >= ?2
+            )"
+        )?;
+        let overlaps = stmt.query_row(params![person_id, start_date, end_date], |row| row.get(0))?;
+        Ok(overlaps)
+    }
+
+    fn cancel_subscription(&self, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let updated = conn.execute(
+            "UPDATE subscriptions SET cancelled_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?1 AND cancelled_at IS NULL",
+            params![id],
+        )?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Active subscription not found"));
+        }
+        Ok(())
+    }
+
+    fn create_payment(&self, payment: &Payment) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        conn.execute(
+            "INSERT INTO payments (subscription_id, amount, paid_on, method, notes) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![payment.subscription_id, payment.amount, payment.paid_on, payment.method.as_str(), payment.notes],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn list_payments(&self, subscription_id: i32) -> AppResult<Vec<Payment>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, subscription_id, amount, paid_on, method, notes, created_at FROM payments
+             WHERE subscription_id = ?1
+             ORDER BY paid_on, id"
+        )?;
+        let payments = stmt.query_map(params![subscription_id], Self::payment_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(payments)
+    }
+
+    fn delete_payment(&self, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let deleted = conn.execute("DELETE FROM payments WHERE id = ?1", params![id])?;
+
+        if deleted == 0 {
+            return Err(AppError::not_found("Payment not found"));
+        }
+        Ok(())
+    }
+
+    fn list_expiring(&self, today: &str, until: &str) -> AppResult<Vec<SubscriptionWithDetails>> {
+        let conn = self.get_connection()?;
+
+        // A later subscription of the same person counts as a renewal
+        let mut stmt = conn.prepare_cached(&format!(
+            "{} WHERE s.cancelled_at IS NULL AND s.start_date <= ?1 AND s.end_date BETWEEN ?1 AND ?2
+               AND {}
+               AND NOT EXISTS (
+                   SELECT 1 FROM subscriptions r
+                   WHERE r.person_id = s.person_id AND r.cancelled_at IS NULL AND r.end_date > s.end_date
+               )
+             ORDER BY s.end_date, p.name, p.last_name",
+            DETAILS_SELECT, ACTIVE_PERSON
+        ))?;
+        let subscriptions = stmt
+            .query_map(params![today, until], Self::details_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(subscriptions)
+    }
+
+    fn list_overdue(&self, today: &str) -> AppResult<Vec<SubscriptionWithDetails>> {
+        let conn = self.get_connection()?;
+
+        // Payment is due on the first day, and a renewal the day after the end; cents
+        // left over from rounding do not count
+        let mut stmt = conn.prepare_cached(&format!(
+            "{} WHERE s.cancelled_at IS NULL AND s.start_date <= ?1
+               AND (
+                   s.price - COALESCE(pay.amount_paid, 0) >= 0.01
+                   OR (s.end_date < ?1 AND NOT EXISTS (
+                       SELECT 1 FROM subscriptions r
+                       WHERE r.person_id = s.person_id AND r.cancelled_at IS NULL AND r.end_date > s.end_date
+                   ))
+               )
+               AND {}
+             ORDER BY CASE WHEN s.price - COALESCE(pay.amount_paid, 0) >= 0.01 THEN s.start_date
+                           ELSE date(s.end_date, '+1 day') END,
+                      p.name, p.last_name",
+            DETAILS_SELECT, ACTIVE_PERSON
+        ))?;
+        let subscriptions = stmt
+            .query_map(params![today], Self::details_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(subscriptions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::db::test_pool;
+    use crate::models::membership::PaymentMethod;
+    use crate::models::person::Person;
+    use crate::repository::person_repository::PersonRepository;
+    use crate::repository::sqlite_person_repository::SqlitePersonRepository;
+
+    const TODAY: &str = "2024-05-10";
+
+    struct Fixture {
+        _dir: tempfile::TempDir,
+        people: SqlitePersonRepository,
+        memberships: SqliteMembershipRepository,
+        plan_id: i32,
+    }
+
+    fn fixture() -> Fixture {
+        let (dir, pool) = test_pool();
+        let memberships = SqliteMembershipRepository::new(pool.clone());
+        let plan_id = memberships
+            .create_plan(&MembershipPlan { id: None, name: "Mensual".into(), price: 30.0, duration_days: 30, is_active: true })
+            .unwrap();
+        Fixture { _dir: dir, people: SqlitePersonRepository::new(pool), memberships, plan_id }
+    }
+
+    impl Fixture {
+        fn person(&self, name: &str) -> i32 {
+            let person: Person = serde_json::from_value(serde_json::json!({
+                "id": null, "name": name, "last_name": "Ruiz", "phone": format!("600{}", name.len())
+            }))
+            .unwrap();
+            self.people.create(person).unwrap();
+            self.people.list_all().unwrap().into_iter().find(|p| p.name == name).and_then(|p| p.id).unwrap()
+        }
+
+        fn subscribe(&self, person_id: i32, start_date: &str, end_date: &str) -> i32 {
+            let subscription = Subscription {
+                id: None,
+                person_id,
+                plan_id: self.plan_id,
+                start_date: start_date.into(),
+                end_date: Some(end_date.into()),
+                price: Some(30.0),
+                notes: None,
+            };
+            self.memberships.create_subscription(&subscription).unwrap()
+        }
+
+        fn pay(&self, subscription_id: i32, amount: f64) {
+            let payment = Payment {
+                id: None,
+                subscription_id,
+                amount,
+                paid_on: "2024-04-01".into(),
+                method: PaymentMethod::Cash,
+                notes: None,
+                created_at: None,
+            };
+            self.memberships.create_payment(&payment).unwrap();
+        }
+
+        fn names(&self, subscriptions: Vec<SubscriptionWithDetails>) -> Vec<String> {
+            subscriptions.into_iter().map(|s| s.person_name).collect()
+        }
+    }
+
+    #[test]
+    fn expiring_window_includes_both_ends_and_skips_renewals() {
+        let f = fixture();
+        let last_day = f.person("LastDay");
+        f.subscribe(last_day, "2024-04-18", "2024-05-17");
+        let today = f.person("Today");
+        f.subscribe(today, "2024-04-11", TODAY);
+        let expired = f.person("Expired");
+        f.subscribe(expired, "2024-04-10", "2024-05-09");
+        let later = f.person("Later");
+        f.subscribe(later, "2024-04-19", "2024-05-18");
+        let renewed = f.person("Renewed");
+        f.subscribe(renewed, "2024-04-13", "2024-05-12");
+        f.subscribe(renewed, "2024-05-13", "2024-06-11");
+        let cancelled = f.person("Cancelled");
+        let id = f.subscribe(cancelled, "2024-04-16", "2024-05-15");
+        f.memberships.cancel_subscription(id).unwrap();
+        let pending = f.person("Pending");
+        f.subscribe(pending, "2024-05-11", "2024-05-16");
+
+        let expiring = f.memberships.list_expiring(TODAY, "2024-05-17").unwrap();
+        assert_eq!(f.names(expiring.clone()), ["Today", "LastDay"]);
+        assert_eq!(expiring[0].days_remaining, 0);
+        assert_eq!(expiring[1].days_remaining, 7);
+    }
+
+    #[test]
+    fn overdue_counts_unpaid_and_lapsed_memberships() {
+        let f = fixture();
+        let unpaid = f.person("Unpaid");
+        f.subscribe(unpaid, "2024-05-01", "2024-05-30");
+        let rounding = f.person("Rounding");
+        let id = f.subscribe(rounding, "2024-05-01", "2024-05-30");
+        f.pay(id, 29.996);
+        let pending = f.person("Pending");
+        f.subscribe(pending, "2024-05-11", "2024-06-09");
+        let cancelled = f.person("Cancelled");
+        let id = f.subscribe(cancelled, "2024-05-01", "2024-05-30");
+        f.memberships.cancel_subscription(id).unwrap();
+        let lapsed = f.person("Lapsed");
+        let id = f.subscribe(lapsed, "2024-03-01", "2024-03-30");
+        f.pay(id, 30.0);
+        let renewed = f.person("Renewed");
+        let id = f.subscribe(renewed, "2024-03-01", "2024-03-30");
+        f.pay(id, 30.0);
+        let id = f.subscribe(renewed, "2024-04-20", "2024-05-19");
+        f.pay(id, 30.0);
+        let deleted = f.person("Deleted");
+        f.subscribe(deleted, "2024-05-01", "2024-05-30");
+        f.people.delete(deleted).unwrap();
+
+        let overdue = f.memberships.list_overdue(TODAY).unwrap();
+        // Oldest debt first: the renewal was due on 2024-03-31
+        assert_eq!(f.names(overdue.clone()), ["Lapsed", "Unpaid"]);
+        assert_eq!((overdue[0].status, overdue[0].balance), (SubscriptionStatus::Expired, 0.0));
+        assert_eq!(overdue[1].balance, 30.0);
+    }
+}
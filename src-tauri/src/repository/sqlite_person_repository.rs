@@ -1,427 +1,521 @@
-use rusqlite::{Connection, Result as SqliteResult, params};
-use crate::models::person::Person;
+use std::collections::HashMap;
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::config::db::{DbPool, PooledConnection};
+use crate::error::{AppError, AppResult};
+use crate::models::person::{Person, PersonMergeSummary, Sex};
 use crate::repository::person_repository::PersonRepository;
 
+const PERSON_COLUMNS: &str =
+    "id, name, last_name, phone, birth_date, sex, email, height_cm, training_goal, medical_notes,
+     emergency_contact_name, emergency_contact_phone";
+
+// Free-text search over the name and the profile; ?1 is the lowercase LIKE pattern
+const SEARCH_FILTER: &str =
+    "(LOWER(name) LIKE ?1 OR LOWER(last_name) LIKE ?1 OR LOWER(name || ' ' || last_name) LIKE ?1
+      OR phone LIKE ?1 OR LOWER(email) LIKE ?1 OR LOWER(training_goal) LIKE ?1
+      OR LOWER(medical_notes) LIKE ?1 OR LOWER(emergency_contact_name) LIKE ?1)";
+
 pub struct SqlitePersonRepository {
-    db_path: String,
-    is_dummy: bool,
+    pool: Option<DbPool>,
 }
 
 impl SqlitePersonRepository {
-    pub fn new_safe(db_path: &str) -> Result<Self, String> {
-        let repo = Self {
-            db_path: db_path.to_string(),
-            is_dummy: false,
-        };
-        repo.create_table().map_err(|e| format!("Failed to create people table: {}", e))?;
-        Ok(repo)
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool: Some(pool) }
     }
 
+    /// Dummy constructor: every call fails with `DATABASE_UNAVAILABLE`
     pub fn new_dummy() -> Self {
-        Self {
-            db_path: String::new(),
-            is_dummy: true,
-        }
+        Self { pool: None }
     }
 
-    fn create_table(&self) -> SqliteResult<()> {
-        if self.is_dummy {
-            return Ok(());
+    fn get_connection(&self) -> AppResult<PooledConnection> {
+        match &self.pool {
+            Some(pool) => Ok(pool.get()?),
+            None => Err(AppError::database_unavailable("Person repository unavailable")),
         }
-        
-        let conn = Connection::open(&self.db_path)?;
-        
-        // Check if logical deletion migration is needed
-        if self.check_if_logical_deletion_migration_needed(&conn)? {
-            self.migrate_logical_deletion(&conn)?;
-        } else {
-            // Create new table with logical deletion fields
-            conn.execute(
-                "CREATE TABLE IF NOT EXISTS people (
-                    id INTEGER PRIMARY KEY AUTOINCREMENT,
-                    name TEXT NOT NULL,
-                    last_name TEXT NOT NULL,
-                    phone TEXT NOT NULL,
-                    deleted_at DATETIME NULL,
-                    is_active BOOLEAN DEFAULT 1
-                )",
-                [],
-            )?;
-        }
-        Ok(())
     }
 
-    fn check_if_logical_deletion_migration_needed(&self, conn: &Connection) -> SqliteResult<bool> {
-        if self.is_dummy { return Ok(false); }
-        
-        // Check if table exists but doesn't have logical deletion fields
+    fn person_from_row(row: &Row) -> rusqlite::Result<Person> {
+        let sex = match row.get::<_, Option<String>>(5)? {
+            Some(sex) => Some(Sex::parse(&sex).ok_or_else(|| {
+                rusqlite::Error::FromSqlConversionFailure(5, Type::Text, format!("Unknown sex '{}'", sex).into())
+            })?),
+            None => None,
+        };
+
+        Ok(Person {
+            id: Some(row.get(0)?),
+            name: row.get(1)?,
+            last_name: row.get(2)?,
+            phone: row.get(3)?,
+            birth_date: row.get(4)?,
+            sex,
+            email: row.get(6)?,
+            height_cm: row.get(7)?,
+            training_goal: row.get(8)?,
+            medical_notes: row.get(9)?,
+            emergency_contact_name: row.get(10)?,
+            emergency_contact_phone: row.get(11)?,
+        })
+    }
+
+    // Appends the duplicate's workouts after the survivor's on days both trained,
+    // so order and superset groups do not interleave
+    fn move_workout_entries(conn: &Connection, survivor_id: i32, duplicate_id: i32) -> rusqlite::Result<i32> {
         let mut stmt = conn.prepare(
-            "SELECT sql FROM sqlite_master WHERE type='table' AND name='people'"
+            "SELECT date, MAX(order_index) + 1, MAX(group_number) FROM workout_entries WHERE person_id = ?1 GROUP BY date"
         )?;
-        
-        let table_sql: Result<String, _> = stmt.query_row([], |row| {
-            Ok(row.get::<_, String>(0)?)
-        });
-        
-        match table_sql {
-            Ok(sql) => Ok(!sql.contains("deleted_at") && !sql.contains("is_active")),
-            Err(_) => Ok(false), // Table doesn't exist, no migration needed
+        let offsets = stmt
+            .query_map(params![survivor_id], |row| Ok((row.get::<_, String>(0)?, (row.get::<_, i32>(1)?, row.get::<_, i32>(2)?))))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+
+        let mut stmt = conn.prepare("SELECT id, date FROM workout_entries WHERE person_id = ?1")?;
+        let entries = stmt
+            .query_map(params![duplicate_id], |row| Ok((row.get::<_, i32>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut update = conn.prepare(
+            "UPDATE workout_entries
+             SET person_id = ?1, order_index = COALESCE(order_index, 0) + ?2, group_number = COALESCE(group_number, 1) + ?3
+             WHERE id = ?4"
+        )?;
+        for (id, date) in &entries {
+            let (order_offset, group_offset) = offsets.get(date).copied().unwrap_or((0, 0));
+            update.execute(params![survivor_id, order_offset, group_offset, id])?;
         }
+        Ok(entries.len() as i32)
     }
 
-    fn migrate_logical_deletion(&self, conn: &Connection) -> SqliteResult<()> {
-        if self.is_dummy { return Ok(()); }
-        println!("Adding logical deletion fields to people table...");
-        
-        // Add logical deletion columns
+    // One session per person and day: on shared days the duplicate's values are
+    // added to the survivor's session unless the survivor already has that metric
+    fn move_body_measurements(conn: &Connection, survivor_id: i32, duplicate_id: i32) -> rusqlite::Result<i32> {
         conn.execute(
-            "ALTER TABLE people ADD COLUMN deleted_at DATETIME NULL",
-            [],
+            "INSERT OR IGNORE INTO body_measurement_values (measurement_id, metric, value)
+             SELECT s.id, v.metric, v.value
+             FROM body_measurements d
+             JOIN body_measurements s ON s.person_id = ?1 AND s.date = d.date
+             JOIN body_measurement_values v ON v.measurement_id = d.id
+             WHERE d.person_id = ?2",
+            params![survivor_id, duplicate_id],
         )?;
-        
-        conn.execute(
-            "ALTER TABLE people ADD COLUMN is_active BOOLEAN DEFAULT 1",
-            [],
+        let merged = conn.execute(
+            "DELETE FROM body_measurements
+             WHERE person_id = ?2 AND date IN (SELECT date FROM body_measurements WHERE person_id = ?1)",
+            params![survivor_id, duplicate_id],
         )?;
-        
-        println!("Logical deletion migration completed successfully!");
-        Ok(())
-    }
-
-    fn get_connection(&self) -> SqliteResult<Connection> {
-        if self.is_dummy {
-            return Err(rusqlite::Error::InvalidQuery);
-        }
-        Connection::open(&self.db_path)
+        let moved = conn.execute(
+            "UPDATE body_measurements SET person_id = ?1 WHERE person_id = ?2",
+            params![survivor_id, duplicate_id],
+        )?;
+        Ok((merged + moved) as i32)
     }
 }
 
 impl PersonRepository for SqlitePersonRepository {
-    fn create(&self, person: Person) -> Result<(), String> {
-        if self.is_dummy {
-            return Ok(());
-        }
-        
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+    fn create(&self, person: Person) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
         conn.execute(
-            "INSERT INTO people (name, last_name, phone) VALUES (?1, ?2, ?3)",
-            params![person.name, person.last_name, person.phone],
-        ).map_err(|e| e.to_string())?;
+            "INSERT INTO people (name, last_name, phone, birth_date, sex, email, height_cm, training_goal,
+                                 medical_notes, emergency_contact_name, emergency_contact_phone)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                person.name,
+                person.last_name,
+                person.phone,
+                person.birth_date,
+                person.sex.map(|s| s.as_str()),
+                person.email,
+                person.height_cm,
+                person.training_goal,
+                person.medical_notes,
+                person.emergency_contact_name,
+                person.emergency_contact_phone,
+            ],
+        )?;
 
         Ok(())
     }
 
-    fn get_by_id(&self, id: i32) -> Option<Person> {
-        if self.is_dummy {
-            return None;
-        }
-        
-        let conn = self.get_connection().ok()?;
-        
-        let mut stmt = conn.prepare("SELECT id, name, last_name, phone FROM people WHERE id = ?1").ok()?;
-        
-        let person = stmt.query_row(params![id], |row| {
-            Ok(Person {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                last_name: row.get(2)?,
-                phone: row.get(3)?,
-            })
-        }).ok()?;
-
-        Some(person)
+    fn get_by_id(&self, id: i32) -> AppResult<Option<Person>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!("SELECT {} FROM people WHERE id = ?1", PERSON_COLUMNS))?;
+        let person = stmt.query_row(params![id], Self::person_from_row).optional()?;
+
+        Ok(person)
     }
 
-    fn list_all(&self) -> Vec<Person> {
-        if self.is_dummy {
-            return Vec::new();
-        }
-        
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
+    fn list_all(&self) -> AppResult<Vec<Person>> {
+        let conn = self.get_connection()?;
 
-        let mut stmt = match conn.prepare(
-            "SELECT id, name, last_name, phone FROM people 
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM people
              WHERE (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)
-             ORDER BY name, last_name"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
+             ORDER BY name, last_name",
+            PERSON_COLUMNS
+        ))?;
 
-        let person_iter = match stmt.query_map([], |row| {
-            Ok(Person {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                last_name: row.get(2)?,
-                phone: row.get(3)?,
-            })
-        }) {
-            Ok(iter) => iter,
-            Err(_) => return Vec::new(),
-        };
-
-        person_iter.filter_map(|person| person.ok()).collect()
+        let people = stmt.query_map([], Self::person_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(people)
     }
 
-    fn search(&self, query: &str) -> Vec<Person> {
-        if self.is_dummy {
-            return Vec::new();
-        }
-        
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
+    fn search(&self, query: &str) -> AppResult<Vec<Person>> {
+        let conn = self.get_connection()?;
 
         let search_pattern = format!("%{}%", query.to_lowercase());
-        
-        let mut stmt = match conn.prepare(
-            "SELECT id, name, last_name, phone FROM people 
-             WHERE (LOWER(name) LIKE ?1 OR LOWER(last_name) LIKE ?1)
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM people
+             WHERE {}
              AND (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)
-             ORDER BY name, last_name"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
+             ORDER BY name, last_name",
+            PERSON_COLUMNS, SEARCH_FILTER
+        ))?;
 
-        let person_iter = match stmt.query_map(params![search_pattern], |row| {
-            Ok(Person {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                last_name: row.get(2)?,
-                phone: row.get(3)?,
-            })
-        }) {
-            Ok(iter) => iter,
-            Err(_) => return Vec::new(),
-        };
+        let people = stmt.query_map(params![search_pattern], Self::person_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(people)
+    }
 
-        person_iter.filter_map(|person| person.ok()).collect()
+    fn find_by_phone(&self, digits: &str) -> AppResult<Vec<Person>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM people
+             WHERE REPLACE(REPLACE(REPLACE(REPLACE(REPLACE(REPLACE(phone, ' ', ''), '-', ''), '(', ''), ')', ''), '.', ''), '+', '') = ?1
+             AND (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)
+             ORDER BY name, last_name",
+            PERSON_COLUMNS
+        ))?;
+
+        let people = stmt.query_map(params![digits], Self::person_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(people)
     }
 
-    fn update(&self, person: Person) -> Result<(), String> {
-        if self.is_dummy {
-            return Ok(());
-        }
-        
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
-        conn.execute(
-            "UPDATE people SET name = ?1, last_name = ?2, phone = ?3 WHERE id = ?4",
-            params![person.name, person.last_name, person.phone, person.id],
-        ).map_err(|e| e.to_string())?;
+    fn update(&self, person: Person) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let updated = conn.execute(
+            "UPDATE people
+             SET name = ?1, last_name = ?2, phone = ?3, birth_date = ?4, sex = ?5, email = ?6, height_cm = ?7,
+                 training_goal = ?8, medical_notes = ?9, emergency_contact_name = ?10, emergency_contact_phone = ?11
+             WHERE id = ?12",
+            params![
+                person.name,
+                person.last_name,
+                person.phone,
+                person.birth_date,
+                person.sex.map(|s| s.as_str()),
+                person.email,
+                person.height_cm,
+                person.training_goal,
+                person.medical_notes,
+                person.emergency_contact_name,
+                person.emergency_contact_phone,
+                person.id,
+            ],
+        )?;
 
+        if updated == 0 {
+            return Err(AppError::not_found("Person not found"));
+        }
         Ok(())
     }
 
-    fn delete(&self, id: i32) -> Result<(), String> {
-        if self.is_dummy {
-            return Ok(());
-        }
-        
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+    fn delete(&self, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
         // Logical deletion instead of physical deletion
-        conn.execute(
+        let updated = conn.execute(
             "UPDATE people SET deleted_at = datetime('now'), is_active = 0 WHERE id = ?1",
             params![id]
-        ).map_err(|e| e.to_string())?;
+        )?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Person not found"));
+        }
         Ok(())
     }
 
-    fn search_paginated(&self, query: &str, page: i32, page_size: i32) -> Vec<Person> {
-        if self.is_dummy {
-            return Vec::new();
-        }
-        
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
+    fn search_paginated(&self, query: &str, page: i32, page_size: i32) -> AppResult<Vec<Person>> {
+        let conn = self.get_connection()?;
 
         let search_pattern = format!("%{}%", query.to_lowercase());
         let offset = (page - 1) * page_size;
-        
-        let mut stmt = match conn.prepare(
-            "SELECT id, name, last_name, phone FROM people 
-             WHERE (LOWER(name) LIKE ?1 OR LOWER(last_name) LIKE ?1)
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM people
+             WHERE {}
              AND (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)
              ORDER BY name, last_name
-             LIMIT ?2 OFFSET ?3"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
-
-        let person_iter = match stmt.query_map(params![search_pattern, page_size, offset], |row| {
-            Ok(Person {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                last_name: row.get(2)?,
-                phone: row.get(3)?,
-            })
-        }) {
-            Ok(iter) => iter,
-            Err(_) => return Vec::new(),
-        };
-
-        person_iter.filter_map(|person| person.ok()).collect()
+             LIMIT ?2 OFFSET ?3",
+            PERSON_COLUMNS, SEARCH_FILTER
+        ))?;
+
+        let people = stmt
+            .query_map(params![search_pattern, page_size, offset], Self::person_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(people)
     }
 
-    fn list_paginated(&self, page: i32, page_size: i32) -> Vec<Person> {
-        if self.is_dummy {
-            return Vec::new();
-        }
-        
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
+    fn list_paginated(&self, page: i32, page_size: i32) -> AppResult<Vec<Person>> {
+        let conn = self.get_connection()?;
 
         let offset = (page - 1) * page_size;
-        
-        let mut stmt = match conn.prepare(
-            "SELECT id, name, last_name, phone FROM people 
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM people
              WHERE (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)
              ORDER BY name, last_name
-             LIMIT ?1 OFFSET ?2"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
-
-        let person_iter = match stmt.query_map(params![page_size, offset], |row| {
-            Ok(Person {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                last_name: row.get(2)?,
-                phone: row.get(3)?,
-            })
-        }) {
-            Ok(iter) => iter,
-            Err(_) => return Vec::new(),
-        };
-
-        person_iter.filter_map(|person| person.ok()).collect()
+             LIMIT ?1 OFFSET ?2",
+            PERSON_COLUMNS
+        ))?;
+
+        let people = stmt
+            .query_map(params![page_size, offset], Self::person_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(people)
     }
 
-    fn count_all(&self) -> i32 {
-        if self.is_dummy {
-            return 0;
-        }
-        
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return 0,
-        };
+    fn count_all(&self) -> AppResult<i32> {
+        let conn = self.get_connection()?;
 
-        match conn.query_row(
-            "SELECT COUNT(*) FROM people 
+        let count = conn.query_row(
+            "SELECT COUNT(*) FROM people
              WHERE (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)",
             [],
             |row| row.get(0)
-        ) {
-            Ok(count) => count,
-            Err(_) => 0,
-        }
+        )?;
+        Ok(count)
     }
 
-    fn search_count(&self, query: &str) -> i32 {
-        if self.is_dummy {
-            return 0;
-        }
-        
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return 0,
-        };
+    fn search_count(&self, query: &str) -> AppResult<i32> {
+        let conn = self.get_connection()?;
 
         let search_pattern = format!("%{}%", query.to_lowercase());
-        
-        match conn.query_row(
-            "SELECT COUNT(*) FROM people 
-             WHERE (LOWER(name) LIKE ?1 OR LOWER(last_name) LIKE ?1)
-             AND (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)",
+
+        let count = conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM people
+                 WHERE {}
+                 AND (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)",
+                SEARCH_FILTER
+            ),
             params![search_pattern],
             |row| row.get(0)
-        ) {
-            Ok(count) => count,
-            Err(_) => 0,
-        }
+        )?;
+        Ok(count)
     }
 
-    fn restore(&self, id: i32) -> Result<(), String> {
-        if self.is_dummy {
-            return Ok(());
-        }
-        
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+    fn restore(&self, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
         // Restore logically deleted person
-        conn.execute(
+        let updated = conn.execute(
             "UPDATE people SET deleted_at = NULL, is_active = 1 WHERE id = ?1",
             params![id]
-        ).map_err(|e| e.to_string())?;
+        )?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Person not found"));
+        }
         Ok(())
     }
 
-    fn list_deleted(&self) -> Vec<Person> {
-        if self.is_dummy {
-            return Vec::new();
-        }
-        
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
-        
-        let mut stmt = match conn.prepare(
-            "SELECT id, name, last_name, phone FROM people 
+    fn list_deleted(&self) -> AppResult<Vec<Person>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM people
              WHERE deleted_at IS NOT NULL AND deleted_at != '' AND is_active = 0
-             ORDER BY deleted_at DESC"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
-        
-        let person_iter = match stmt.query_map([], |row| {
-            Ok(Person {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                last_name: row.get(2)?,
-                phone: row.get(3)?,
-            })
-        }) {
-            Ok(iter) => iter,
-            Err(_) => return Vec::new(),
-        };
+             ORDER BY deleted_at DESC",
+            PERSON_COLUMNS
+        ))?;
 
-        person_iter.filter_map(|person| person.ok()).collect()
+        let people = stmt.query_map([], Self::person_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(people)
     }
 
-    fn count_deleted(&self) -> i32 {
-        if self.is_dummy {
-            return 0;
-        }
-        
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return 0,
-        };
-        
-        let count: Result<i32, _> = conn.query_row(
-            "SELECT COUNT(*) FROM people 
+    fn count_deleted(&self) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        let count = conn.query_row(
+            "SELECT COUNT(*) FROM people
              WHERE deleted_at IS NOT NULL AND deleted_at != '' AND is_active = 0",
             [],
             |row| row.get(0),
-        );
-        
-        count.unwrap_or(0)
+        )?;
+        Ok(count)
+    }
+
+    fn merge(&self, survivor_id: i32, duplicate_id: i32) -> AppResult<PersonMergeSummary> {
+        let conn = self.get_connection()?;
+
+        let tx = conn.unchecked_transaction()?;
+        let active: i32 = tx.query_row(
+            "SELECT COUNT(*) FROM people
+             WHERE id IN (?1, ?2) AND (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)",
+            params![survivor_id, duplicate_id],
+            |row| row.get(0),
+        )?;
+        if active != 2 {
+            return Err(AppError::not_found("Person not found"));
+        }
+
+        // Every table with a person_id has to be handled here
+        let workout_entries = Self::move_workout_entries(&tx, survivor_id, duplicate_id)?;
+        let subscriptions = tx.execute(
+            "UPDATE subscriptions SET person_id = ?1 WHERE person_id = ?2",
+            params![survivor_id, duplicate_id],
+        )? as i32;
+        let check_ins = tx.execute(
+            "UPDATE check_ins SET person_id = ?1 WHERE person_id = ?2",
+            params![survivor_id, duplicate_id],
+        )? as i32;
+        let body_measurements = Self::move_body_measurements(&tx, survivor_id, duplicate_id)?;
+        let assignments = tx.execute(
+            "UPDATE routine_assignments SET person_id = ?1 WHERE person_id = ?2",
+            params![survivor_id, duplicate_id],
+        )? as i32;
+
+        // The survivor's data wins; the emergency contact is copied as a whole
+        tx.execute(
+            "UPDATE people SET
+                birth_date = COALESCE(people.birth_date, d.birth_date),
+                sex = COALESCE(people.sex, d.sex),
+                email = COALESCE(people.email, d.email),
+                height_cm = COALESCE(people.height_cm, d.height_cm),
+                training_goal = COALESCE(people.training_goal, d.training_goal),
+                medical_notes = COALESCE(people.medical_notes, d.medical_notes),
+                emergency_contact_name = CASE WHEN people.emergency_contact_phone IS NULL
+                    THEN d.emergency_contact_name ELSE people.emergency_contact_name END,
+                emergency_contact_phone = COALESCE(people.emergency_contact_phone, d.emergency_contact_phone)
+             FROM (SELECT * FROM people WHERE id = ?2) AS d
+             WHERE people.id = ?1",
+            params![survivor_id, duplicate_id],
+        )?;
+        tx.execute(
+            "UPDATE people SET deleted_at = datetime('now'), is_active = 0 WHERE id = ?1",
+            params![duplicate_id],
+        )?;
+        tx.commit()?;
+
+        Ok(PersonMergeSummary {
+            survivor_id,
+            merged_id: duplicate_id,
+            workout_entries,
+            subscriptions,
+            check_ins,
+            body_measurements,
+            assignments,
+        })
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::db::test_pool;
+
+    fn person(name: &str, last_name: &str, phone: &str) -> Person {
+        serde_json::from_value(serde_json::json!({ "id": null, "name": name, "last_name": last_name, "phone": phone })).unwrap()
+    }
+
+    // (date, order_index, group_number) of a person's entries, in session order
+    fn entries(conn: &Connection, person_id: i32) -> Vec<(String, i32, i32)> {
+        conn.prepare("SELECT date, order_index, group_number FROM workout_entries WHERE person_id = ?1 ORDER BY date, order_index, id")
+            .unwrap()
+            .query_map(params![person_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    fn merged_pair() -> (tempfile::TempDir, DbPool, SqlitePersonRepository) {
+        let (dir, pool) = test_pool();
+        let repository = SqlitePersonRepository::new(pool.clone());
+        repository.create(person("Juan", "Perez", "11 5555-1234")).unwrap();
+        repository.create(person("juan", "Pérez", "+1155551234")).unwrap();
+        pool.get().unwrap().execute_batch("INSERT INTO exercise (name, code) VALUES ('Squat', 'SQ'), ('Bench', 'BP');").unwrap();
+        (dir, pool, repository)
+    }
+
+    #[test]
+    fn shared_days_append_the_duplicate_session_after_the_survivor() {
+        let (_dir, pool, repository) = merged_pair();
+        let conn = pool.get().unwrap();
+        conn.execute_batch(
+            "INSERT INTO workout_entries (person_id, exercise_id, date, order_index, group_number) VALUES
+                (1, 1, '2024-01-01', 0, 1), (1, 2, '2024-01-01', 1, 2),
+                (2, 1, '2024-01-01', 0, 1), (2, 2, '2024-01-01', 1, 1),
+                (2, 1, '2024-01-02', 0, 1);",
+        )
+        .unwrap();
+
+        let summary = repository.merge(1, 2).unwrap();
+        assert_eq!(summary.workout_entries, 3);
+
+        // The duplicate's superset becomes the survivor's third group; its other day is untouched
+        assert_eq!(
+            entries(&conn, 1),
+            vec![
+                ("2024-01-01".to_string(), 0, 1),
+                ("2024-01-01".to_string(), 1, 2),
+                ("2024-01-01".to_string(), 2, 3),
+                ("2024-01-01".to_string(), 3, 3),
+                ("2024-01-02".to_string(), 0, 1),
+            ]
+        );
+        assert!(entries(&conn, 2).is_empty());
+    }
+
+    #[test]
+    fn merge_moves_every_record_and_soft_deletes_the_duplicate() {
+        let (_dir, pool, repository) = merged_pair();
+        let conn = pool.get().unwrap();
+        conn.execute_batch(
+            "INSERT INTO workout_entries (person_id, exercise_id, date, order_index, group_number) VALUES (2, 1, '2024-01-01', 0, 1);
+             INSERT INTO check_ins (person_id, checked_in_at) VALUES (2, '2024-01-01 10:00:00');
+             INSERT INTO body_measurements (person_id, date) VALUES (1, '2024-01-01'), (2, '2024-01-01'), (2, '2024-01-05');
+             INSERT INTO body_measurement_values (measurement_id, metric, value) VALUES (1, 'weight', 80), (2, 'weight', 81), (2, 'waist', 90), (3, 'arm', 30);
+             UPDATE people SET email = 'juan@example.com', emergency_contact_name = 'Rosa', emergency_contact_phone = '600000009' WHERE id = 2;",
+        )
+        .unwrap();
+
+        let summary = repository.merge(1, 2).unwrap();
+        assert_eq!((summary.workout_entries, summary.check_ins, summary.body_measurements, summary.subscriptions), (1, 1, 2, 0));
+
+        // Same-day measurements are combined, the survivor's value wins
+        let values: Vec<(String, String, f64)> = conn
+            .prepare(
+                "SELECT m.date, v.metric, v.value FROM body_measurement_values v
+                 JOIN body_measurements m ON m.id = v.measurement_id
+                 WHERE m.person_id = 1 ORDER BY m.date, v.metric",
+            )
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            values,
+            vec![
+                ("2024-01-01".to_string(), "waist".to_string(), 90.0),
+                ("2024-01-01".to_string(), "weight".to_string(), 80.0),
+                ("2024-01-05".to_string(), "arm".to_string(), 30.0),
+            ]
+        );
+
+        let survivor = repository.get_by_id(1).unwrap().unwrap();
+        assert_eq!(survivor.email.as_deref(), Some("juan@example.com"));
+        assert_eq!(survivor.emergency_contact_name.as_deref(), Some("Rosa"));
+        assert_eq!(repository.count_deleted().unwrap(), 1);
+        assert_eq!(repository.merge(1, 2).unwrap_err().code(), "NOT_FOUND");
+
+        // The merged record can still be brought back, without its moved history
+        repository.restore(2).unwrap();
+        assert!(entries(&conn, 2).is_empty());
+    }
+}
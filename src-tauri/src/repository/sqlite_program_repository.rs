@@ -0,0 +1,390 @@
+use std::collections::HashMap;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::config::db::{DbPool, PooledConnection};
+use crate::error::{AppError, AppResult};
+use crate::models::program::{Program, ProgramDay, ProgramDayWithDetails, ProgramExerciseWithDetails};
+use crate::repository::program_repository::ProgramRepository;
+
+const PROGRAM_COLUMNS: &str = "id, name, code, description, weeks, created_at, updated_at";
+
+const DAY_EXERCISE_COLUMNS: &str =
+    "exercise_id, order_index, sets, reps, weight, notes, group_number, rpe, rir, tempo, rest_seconds,
+     duration_seconds, distance_meters";
+
+pub struct SqliteProgramRepository {
+    pool: Option<DbPool>,
+}
+
+impl SqliteProgramRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool: Some(pool) }
+    }
+
+    /// Dummy constructor: every call fails with `DATABASE_UNAVAILABLE`
+    pub fn new_dummy() -> Self {
+        Self { pool: None }
+    }
+
+    fn get_connection(&self) -> AppResult<PooledConnection> {
+        match &self.pool {
+            Some(pool) => Ok(pool.get()?),
+            None => Err(AppError::database_unavailable("Program repository unavailable")),
+        }
+    }
+
+    fn program_from_row(row: &Row) -> rusqlite::Result<Program> {
+        Ok(Program {
+            id: Some(row.get(0)?),
+            name: row.get(1)?,
+            code: row.get(2)?,
+            description: row.get(3)?,
+            weeks: row.get(4)?,
+            created_at: row.get(5)?,
+            updated_at: row.get(6)?,
+        })
+    }
+
+    // Expects (program_day_id, id, exercise name, exercise code, DAY_EXERCISE_COLUMNS...)
+    fn exercise_from_row(row: &Row) -> rusqlite::Result<(i32, ProgramExerciseWithDetails)> {
+        Ok((
+            row.get(0)?,
+            ProgramExerciseWithDetails {
+                program_day_exercise_id: Some(row.get(1)?),
+                routine_exercise_id: None,
+                exercise_name: row.get(2)?,
+                exercise_code: row.get(3)?,
+                exercise_id: row.get(4)?,
+                order_index: row.get(5)?,
+                sets: row.get(6)?,
+                reps: row.get(7)?,
+                weight: row.get(8)?,
+                notes: row.get(9)?,
+                group_number: row.get(10)?,
+                rpe: row.get(11)?,
+                rir: row.get(12)?,
+                tempo: row.get(13)?,
+                rest_seconds: row.get(14)?,
+                duration_seconds: row.get(15)?,
+                distance_meters: row.get(16)?,
+            },
+        ))
+    }
+
+    fn insert_days(conn: &Connection, program_id: i32, days: &[ProgramDay]) -> rusqlite::Result<()> {
+        let mut day_stmt = conn.prepare_cached(
+            "INSERT INTO program_days (program_id, week, day_index, label, routine_id, notes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+        )?;
+        let mut exercise_stmt = conn.prepare_cached(&format!(
+            "INSERT INTO program_day_exercises (program_day_id, {})
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            DAY_EXERCISE_COLUMNS
+        ))?;
+
+        for day in days {
+            day_stmt.execute(params![program_id, day.week, day.day_index, day.label, day.routine_id, day.notes])?;
+            let day_id = conn.last_insert_rowid() as i32;
+
+            for exercise in &day.exercises {
+                exercise_stmt.execute(params![
+                    day_id,
+                    exercise.exercise_id,
+                    exercise.order_index,
+                    exercise.sets,
+                    exercise.reps,
+                    exercise.weight,
+                    exercise.notes,
+                    exercise.group_number,
+                    exercise.rpe,
+                    exercise.rir,
+                    exercise.tempo,
+                    exercise.rest_seconds,
+                    exercise.duration_seconds,
+                    exercise.distance_meters,
+                ])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ProgramRepository for SqliteProgramRepository {
+    fn create(&self, program: &Program, days: &[ProgramDay]) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO programs (name, code, description, weeks) VALUES (?1, ?2, ?3, ?4)",
+            params![program.name, program.code, program.description, program.weeks],
+        )?;
+        let program_id = tx.last_insert_rowid() as i32;
+        Self::insert_days(&tx, program_id, days)?;
+        tx.commit()?;
+
+        Ok(program_id)
+    }
+
+    fn get_by_id(&self, id: i32) -> AppResult<Option<Program>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!("SELECT {} FROM programs WHERE id = ?1", PROGRAM_COLUMNS))?;
+        let program = stmt.query_row(params![id], Self::program_from_row).optional()?;
+        Ok(program)
+    }
+
+    fn update(&self, program: &Program, days: &[ProgramDay]) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let tx = conn.unchecked_transaction()?;
+        let updated = tx.execute(
+            "UPDATE programs SET name = ?1, code = ?2, description = ?3, weeks = ?4, updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?5",
+            params![program.name, program.code, program.description, program.weeks, program.id],
+        )?;
+        if updated == 0 {
+            return Err(AppError::not_found("Program not found"));
+        }
+
+        // Day exercises go with their days (ON DELETE CASCADE)
+        tx.execute("DELETE FROM program_days WHERE program_id = ?1", params![program.id])?;
+        if let Some(program_id) = program.id {
+            Self::insert_days(&tx, program_id, days)?;
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    fn clone_program(&self, id: i32, name: &str, code: &str) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        let tx = conn.unchecked_transaction()?;
+        let inserted = tx.execute(
+            "INSERT INTO programs (name, code, description, weeks)
+             SELECT ?2, ?3, description, weeks FROM programs WHERE id = ?1",
+            params![id, name, code],
+        )?;
+        if inserted == 0 {
+            return Err(AppError::not_found("Program not found"));
+        }
+        let clone_id = tx.last_insert_rowid() as i32;
+
+        // Day ids differ, so copy day by day to keep each day's exercises with it
+        let mut stmt = tx.prepare("SELECT id FROM program_days WHERE program_id = ?1 ORDER BY week, day_index")?;
+        let days = stmt.query_map(params![id], |row| row.get::<_, i32>(0))?.collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+        for day_id in days {
+            tx.execute(
+                "INSERT INTO program_days (program_id, week, day_index, label, routine_id, notes)
+                 SELECT ?1, week, day_index, label, routine_id, notes FROM program_days WHERE id = ?2",
+                params![clone_id, day_id],
+            )?;
+            let clone_day_id = tx.last_insert_rowid() as i32;
+            tx.execute(
+                &format!(
+                    "INSERT INTO program_day_exercises (program_day_id, {0})
+                     SELECT ?1, {0} FROM program_day_exercises WHERE program_day_id = ?2 ORDER BY order_index, id",
+                    DAY_EXERCISE_COLUMNS
+                ),
+                params![clone_day_id, day_id],
+            )?;
+        }
+        tx.commit()?;
+
+        Ok(clone_id)
+    }
+
+    fn delete(&self, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        // Logical deletion instead of physical deletion
+        let updated = conn.execute(
+            "UPDATE programs SET deleted_at = datetime('now'), is_active = 0 WHERE id = ?1",
+            params![id]
+        )?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Program not found"));
+        }
+        Ok(())
+    }
+
+    fn restore(&self, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let updated = conn.execute(
+            "UPDATE programs SET deleted_at = NULL, is_active = 1 WHERE id = ?1",
+            params![id]
+        )?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Program not found"));
+        }
+        Ok(())
+    }
+
+    fn list_all(&self) -> AppResult<Vec<Program>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM programs
+             WHERE (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)
+             ORDER BY name",
+            PROGRAM_COLUMNS
+        ))?;
+        let programs = stmt.query_map([], Self::program_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(programs)
+    }
+
+    fn get_days(&self, program_id: i32) -> AppResult<Vec<ProgramDayWithDetails>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT d.id, d.week, d.day_index, d.label, d.routine_id, r.name, d.notes
+             FROM program_days d
+             LEFT JOIN routines r ON d.routine_id = r.id
+             WHERE d.program_id = ?1
+             ORDER BY d.week, d.day_index"
+        )?;
+        let mut days = stmt
+            .query_map(params![program_id], |row| {
+                Ok(ProgramDayWithDetails {
+                    id: row.get(0)?,
+                    week: row.get(1)?,
+                    day_index: row.get(2)?,
+                    label: row.get(3)?,
+                    routine_id: row.get(4)?,
+                    routine_name: row.get(5)?,
+                    notes: row.get(6)?,
+                    exercises: Vec::new(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Inline exercises of every day, in one query
+        let mut stmt = conn.prepare_cached(
+            "SELECT pe.program_day_id, pe.id, e.name, e.code,
+                    pe.exercise_id, pe.order_index, pe.sets, pe.reps, pe.weight, pe.notes, pe.group_number,
+                    pe.rpe, pe.rir, pe.tempo, pe.rest_seconds, pe.duration_seconds, pe.distance_meters
+             FROM program_day_exercises pe
+             JOIN program_days d ON pe.program_day_id = d.id
+             JOIN exercise e ON pe.exercise_id = e.id
+             WHERE d.program_id = ?1
+             ORDER BY pe.order_index, pe.id"
+        )?;
+        let mut exercises_by_day: HashMap<i32, Vec<ProgramExerciseWithDetails>> = HashMap::new();
+        for row in stmt.query_map(params![program_id], Self::exercise_from_row)? {
+            let (day_id, exercise) = row?;
+            exercises_by_day.entry(day_id).or_default().push(exercise);
+        }
+
+        for day in days.iter_mut() {
+            if let Some(exercises) = exercises_by_day.remove(&day.id) {
+                day.exercises = exercises;
+            }
+        }
+        Ok(days)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::db::test_pool;
+    use crate::models::exercise::Exercise;
+    use crate::models::program::ProgramDayExercise;
+    use crate::models::routine::Routine;
+    use crate::repository::exercise_repository::ExerciseRepository;
+    use crate::repository::routine_repository::RoutineRepository;
+    use crate::repository::sqlite_exercise_repository::SqliteExerciseRepository;
+    use crate::repository::sqlite_routine_repository::SqliteRoutineRepository;
+
+    fn exercise(exercise_id: i32, order_index: i32, tempo: Option<&str>) -> ProgramDayExercise {
+        ProgramDayExercise {
+            id: None,
+            program_day_id: 0,
+            exercise_id,
+            order_index,
+            sets: Some(4),
+            reps: Some(6),
+            weight: Some(80.0),
+            notes: Some("Pausa abajo".into()),
+            group_number: Some(order_index + 1),
+            rpe: Some(8.0),
+            rir: None,
+            tempo: tempo.map(str::to_string),
+            rest_seconds: Some(120),
+            duration_seconds: None,
+            distance_meters: None,
+        }
+    }
+
+    #[test]
+    fn clone_copies_days_and_their_exercises() {
+        let (_dir, pool) = test_pool();
+        let exercises = SqliteExerciseRepository::new(pool.clone());
+        for (name, code) in [("Squat", "SQ"), ("Bench", "BP")] {
+            exercises.create(Exercise { id: None, name: name.into(), code: code.into(), measurement_type: Default::default() }).unwrap();
+        }
+        let routine_id = SqliteRoutineRepository::new(pool.clone()).create(Routine::new("Legs".into(), "LEGS".into())).unwrap();
+        let repository = SqliteProgramRepository::new(pool);
+        let program = Program {
+            id: None,
+            name: "Block".into(),
+            code: "BLK".into(),
+            description: Some("Fuerza".into()),
+            weeks: 2,
+            created_at: None,
+            updated_at: None,
+        };
+        let days = [
+            ProgramDay { id: None, program_id: 0, week: 1, day_index: 1, label: Some("A".into()), routine_id: Some(routine_id), notes: None, exercises: vec![] },
+            ProgramDay {
+                id: None,
+                program_id: 0,
+                week: 1,
+                day_index: 3,
+                label: Some("B".into()),
+                routine_id: None,
+                notes: Some("Ligero".into()),
+                exercises: vec![exercise(2, 0, Some("3-0-1-0")), exercise(1, 1, None)],
+            },
+            ProgramDay { id: None, program_id: 0, week: 2, day_index: 1, label: None, routine_id: None, notes: None, exercises: vec![exercise(1, 0, None)] },
+        ];
+        let id = repository.create(&program, &days).unwrap();
+
+        let clone_id = repository.clone_program(id, "Block 2", "BLK2").unwrap();
+        assert_ne!(clone_id, id);
+        let clone = repository.get_by_id(clone_id).unwrap().unwrap();
+        assert_eq!((clone.name.as_str(), clone.code.as_str()), ("Block 2", "BLK2"));
+        assert_eq!((clone.description.as_deref(), clone.weeks), (Some("Fuerza"), 2));
+
+        let original_days = repository.get_days(id).unwrap();
+        let cloned_days = repository.get_days(clone_id).unwrap();
+        assert_eq!(cloned_days.len(), 3);
+        for (original, cloned) in original_days.iter().zip(&cloned_days) {
+            assert_ne!(original.id, cloned.id);
+            assert_eq!(
+                (original.week, original.day_index, &original.label, original.routine_id, &original.notes),
+                (cloned.week, cloned.day_index, &cloned.label, cloned.routine_id, &cloned.notes)
+            );
+            assert_eq!(original.exercises.len(), cloned.exercises.len());
+            for (a, b) in original.exercises.iter().zip(&cloned.exercises) {
+                assert_ne!(a.program_day_exercise_id, b.program_day_exercise_id);
+                assert_eq!(
+                    (a.exercise_id, a.order_index, a.sets, a.reps, a.weight, &a.notes, a.group_number, a.rpe, &a.tempo, a.rest_seconds),
+                    (b.exercise_id, b.order_index, b.sets, b.reps, b.weight, &b.notes, b.group_number, b.rpe, &b.tempo, b.rest_seconds)
+                );
+            }
+        }
+        let codes: Vec<_> = cloned_days[1].exercises.iter().map(|e| e.exercise_code.as_str()).collect();
+        assert_eq!(codes, ["BP", "SQ"]);
+        assert!(cloned_days[0].exercises.is_empty());
+
+        // The copy is independent of the original
+        repository.update(&Program { id: Some(clone_id), ..clone }, &days[..1]).unwrap();
+        assert_eq!(repository.get_days(id).unwrap().len(), 3);
+
+        assert_eq!(repository.clone_program(99, "x", "X").unwrap_err().code(), "NOT_FOUND");
+    }
+}
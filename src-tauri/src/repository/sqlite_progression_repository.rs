@@ -0,0 +1,111 @@
+use rusqlite::types::Type;
+use rusqlite::{params, Row};
+use crate::config::db::{DbPool, PooledConnection};
+use crate::error::{AppError, AppResult};
+use crate::models::progression::{ProgressionKind, ProgressionRule};
+use crate::repository::progression_repository::ProgressionRepository;
+
+pub struct SqliteProgressionRepository {
+    pool: Option<DbPool>,
+}
+
+impl SqliteProgressionRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool: Some(pool) }
+    }
+
+    /// Dummy constructor: every call fails with `DATABASE_UNAVAILABLE`
+    pub fn new_dummy() -> Self {
+        Self { pool: None }
+    }
+
+    fn get_connection(&self) -> AppResult<PooledConnection> {
+        match &self.pool {
+            Some(pool) => Ok(pool.get()?),
+            None => Err(AppError::database_unavailable("Progression repository unavailable")),
+        }
+    }
+
+    fn rule_from_row(row: &Row) -> rusqlite::Result<ProgressionRule> {
+        let kind: String = row.get(3)?;
+        Ok(ProgressionRule {
+            id: Some(row.get(0)?),
+            routine_id: row.get(1)?,
+            exercise_id: row.get(2)?,
+            kind: ProgressionKind::parse(&kind)
+                .ok_or_else(|| rusqlite::Error::FromSqlConversionFailure(3, Type::Text, format!("Unknown progression kind: {}", kind).into()))?,
+            increment_kg: row.get(4)?,
+            min_reps: row.get(5)?,
+            max_reps: row.get(6)?,
+            percentage: row.get(7)?,
+            deload_after_failures: row.get(8)?,
+            deload_percentage: row.get(9)?,
+            created_at: row.get(10)?,
+            updated_at: row.get(11)?,
+        })
+    }
+}
+
+impl ProgressionRepository for SqliteProgressionRepository {
+    fn save(&self, rule: &ProgressionRule) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        let id = conn.query_row(
+            "INSERT INTO progression_rules
+                (routine_id, exercise_id, kind, increment_kg, min_reps, max_reps, percentage,
+                 deload_after_failures, deload_percentage)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+             ON CONFLICT (routine_id, exercise_id) DO UPDATE SET
+                kind = excluded.kind,
+                increment_kg = excluded.increment_kg,
+                min_reps = excluded.min_reps,
+                max_reps = excluded.max_reps,
+                percentage = excluded.percentage,
+                deload_after_failures = excluded.deload_after_failures,
+                deload_percentage = excluded.deload_percentage,
+                updated_at = CURRENT_TIMESTAMP
+             RETURNING id",
+            params![
+                rule.routine_id,
+                rule.exercise_id,
+                rule.kind.as_str(),
+                rule.increment_kg,
+                rule.min_reps,
+                rule.max_reps,
+                rule.percentage,
+                rule.deload_after_failures,
+                rule.deload_percentage,
+            ],
+            |row| row.get(0),
+        )?;
+        Ok(id)
+    }
+
+    fn delete(&self, routine_id: i32, exercise_id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let deleted = conn.execute(
+            "DELETE FROM progression_rules WHERE routine_id = ?1 AND exercise_id = ?2",
+            params![routine_id, exercise_id],
+        )?;
+
+        if deleted == 0 {
+            return Err(AppError::not_found("Progression rule not found"));
+        }
+        Ok(())
+    }
+
+    fn list_by_routine(&self, routine_id: i32) -> AppResult<Vec<ProgressionRule>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, routine_id, exercise_id, kind, increment_kg, min_reps, max_reps, percentage,
+                    deload_after_failures, deload_percentage, created_at, updated_at
+             FROM progression_rules
+             WHERE routine_id = ?1
+             ORDER BY id"
+        )?;
+        let rules = stmt.query_map(params![routine_id], Self::rule_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(rules)
+    }
+}
@@ -0,0 +1,475 @@
+use rusqlite::params;
+use crate::config::db::{DbPool, PooledConnection};
+use crate::error::{AppError, AppResult};
+use crate::models::report::{AdherenceEntry, ExerciseSetsPoint, FrequencyPoint, IntensityPoint, ReportBucket, TonnagePoint};
+use crate::repository::report_repository::ReportRepository;
+
+// Per-entry totals for ?1 = person_id, ?2..?3 = date range. Entries with per-set
+// detail are measured from their completed, non warm-up sets; the rest from the
+// sets × reps × weight summary. Planned entries have not been trained and are left
+// out. Timed and distance exercises count sets but no reps or tonnage, even with
+// added load. `{period}` is replaced by the bucket expression.
+const ENTRY_TOTALS: &str =
+    "entry_totals AS (
+        SELECT we.date, we.exercise_id, we.rpe,
+               {period} AS period_start,
+               CASE WHEN ws.workout_entry_id IS NULL THEN COALESCE(we.sets, 1) ELSE ws.sets END AS sets,
+               counts_reps * CASE WHEN ws.workout_entry_id IS NULL
+                    THEN COALESCE(we.sets, 1) * COALESCE(we.reps, 0) ELSE ws.reps END AS reps,
+               counts_reps * CASE WHEN ws.workout_entry_id IS NULL
+                    THEN CASE WHEN COALESCE(we.weight, 0) > 0 THEN COALESCE(we.sets, 1) * COALESCE(we.reps, 0) ELSE 0 END
+                    ELSE ws.loaded_reps END AS loaded_reps,
+               counts_reps * CASE WHEN ws.workout_entry_id IS NULL
+                    THEN COALESCE(we.sets, 1) * COALESCE(we.reps, 0) * COALESCE(we.weight, 0)
+                    ELSE ws.tonnage END AS tonnage
+        FROM workout_entries we
+        JOIN (
+            SELECT id, measurement_type IN ('reps_weight', 'bodyweight_reps') AS counts_reps FROM exercise
+        ) e ON e.id = we.exercise_id
+        LEFT JOIN (
+            SELECT workout_entry_id,
+                   SUM(performed) AS sets,
+                   SUM(performed * COALESCE(reps, 0)) AS reps,
+                   SUM(CASE WHEN COALESCE(weight, 0) > 0 THEN performed * COALESCE(reps, 0) ELSE 0 END) AS loaded_reps,
+                   SUM(performed * COALESCE(reps, 0) * COALESCE(weight, 0)) AS tonnage
+            FROM (
+                SELECT workout_entry_id, reps, weight,
+                       CASE WHEN completed = 1 AND set_type != 'warm_up' THEN 1 ELSE 0 END AS performed
+                FROM workout_sets
+                WHERE workout_entry_id IN (
+                    SELECT id FROM workout_entries WHERE person_id = ?1 AND date BETWEEN ?2 AND ?3
+                )
+            )
+            GROUP BY workout_entry_id
+        ) ws ON ws.workout_entry_id = we.id
+        WHERE we.person_id = ?1 AND we.date BETWEEN ?2 AND ?3 AND we.status = 'performed'
+    )";
+
+// Entries planned from a routine for ?1 between ?2 and ?3, with the prescription
+// copied from the routine when they were planned. Later routine edits leave it alone.
+const PLANNED_ENTRIES: &str =
+    "planned AS (
+        SELECT we.id, we.date, we.order_index, we.routine_id, r.name AS routine_name, we.exercise_id, e.name AS exercise_name,
+               we.status, we.sets, we.reps, we.weight, we.prescribed_sets, we.prescribed_reps, we.prescribed_weight
+        FROM workout_entries we
+        JOIN exercise e ON we.exercise_id = e.id
+        LEFT JOIN routines r ON we.routine_id = r.id
+        WHERE we.person_id = ?1 AND we.date BETWEEN ?2 AND ?3
+          AND (we.routine_id IS NOT NULL OR we.routine_exercise_id IS NOT NULL)
+    )";
+
+// Every bucket between ?2 and ?3, so charts get explicit zeros for idle periods
+const PERIODS: &str =
+    "periods(period_start) AS (
+        SELECT {first}
+        UNION ALL
+        SELECT {next} FROM periods WHERE {next} <= ?3
+    )";
+
+pub struct SqliteReportRepository {
+    pool: Option<DbPool>,
+}
+
+impl SqliteReportRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool: Some(pool) }
+    }
+
+    /// Dummy constructor: every call fails with `DATABASE_UNAVAILABLE`
+    pub fn new_dummy() -> Self {
+        Self { pool: None }
+    }
+
+    fn get_connection(&self) -> AppResult<PooledConnection> {
+        match &self.pool {
+            Some(pool) => Ok(pool.get()?),
+            None => Err(AppError::database_unavailable("Report repository unavailable")),
+        }
+    }
+
+    // First day of the bucket containing the date in `column`
+    fn period_start(bucket: ReportBucket, column: &str) -> String {
+        match bucket {
+            ReportBucket::Day => format!("date({})", column),
+            ReportBucket::Week => format!("date({0}, '-' || ((CAST(strftime('%w', {0}) AS INTEGER) + 6) % 7) || ' days')", column),
+            ReportBucket::Month => format!("date({}, 'start of month')", column),
+        }
+    }
+
+    fn next_period(bucket: ReportBucket) -> &'static str {
+        match bucket {
+            ReportBucket::Day => "date(period_start, '+1 day')",
+            ReportBucket::Week => "date(period_start, '+7 days')",
+            ReportBucket::Month => "date(period_start, '+1 month')",
+        }
+    }
+
+    /// `WITH RECURSIVE` prefix defining `entry_totals` and, when `with_periods`, `periods`
+    fn with_clause(bucket: ReportBucket, with_periods: bool) -> String {
+        let entry_totals = ENTRY_TOTALS.replace("{period}", &Self::period_start(bucket, "we.date"));
+        if !with_periods {
+            return format!("WITH {}", entry_totals);
+        }
+
+        let periods = PERIODS
+            .replace("{first}", &Self::period_start(bucket, "?2"))
+            .replace("{next}", Self::next_period(bucket));
+        format!("WITH RECURSIVE {}, {}", entry_totals, periods)
+    }
+}
+
+impl ReportRepository for SqliteReportRepository {
+    fn tonnage(&self, person_id: i32, start_date: &str, end_date: &str, bucket: ReportBucket) -> AppResult<Vec<TonnagePoint>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "{}
+             SELECT p.period_start, COALESCE(SUM(et.tonnage), 0), COALESCE(SUM(et.reps), 0)
+             FROM periods p
+             LEFT JOIN entry_totals et ON et.period_start = p.period_start
+             GROUP BY p.period_start
+             ORDER BY p.period_start",
+            Self::with_clause(bucket, true)
+        ))?;
+
+        let points = stmt
+            .query_map(params![person_id, start_date, end_date], |row| {
+                Ok(TonnagePoint {
+                    period_start: row.get(0)?,
+                    tonnage: row.get(1)?,
+                    reps: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(points)
+    }
+
+    fn frequency(&self, person_id: i32, start_date: &str, end_date: &str, bucket: ReportBucket) -> AppResult<Vec<FrequencyPoint>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "{}
+             SELECT p.period_start, COUNT(DISTINCT et.date)
+             FROM periods p
+             LEFT JOIN entry_totals et ON et.period_start = p.period_start
+             GROUP BY p.period_start
+             ORDER BY p.period_start",
+            Self::with_clause(bucket, true)
+        ))?;
+
+        let points = stmt
+            .query_map(params![person_id, start_date, end_date], |row| {
+                Ok(FrequencyPoint {
+                    period_start: row.get(0)?,
+                    sessions: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(points)
+    }
+
+    fn sets_per_exercise(&self, person_id: i32, start_date: &str, end_date: &str, bucket: ReportBucket) -> AppResult<Vec<ExerciseSetsPoint>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "{}
+             SELECT et.period_start, et.exercise_id, e.name, SUM(et.sets)
+             FROM entry_totals et
+             JOIN exercise e ON et.exercise_id = e.id
+             GROUP BY et.period_start, et.exercise_id
+             ORDER BY et.period_start, e.name",
+            Self::with_clause(bucket, false)
+        ))?;
+
+        let points = stmt
+            .query_map(params![person_id, start_date, end_date], |row| {
+                Ok(ExerciseSetsPoint {
+                    period_start: row.get(0)?,
+                    exercise_id: row.get(1)?,
+                    exercise_name: row.get(2)?,
+                    sets: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(points)
+    }
+
+    fn intensity(&self, person_id: i32, start_date: &str, end_date: &str, bucket: ReportBucket) -> AppResult<Vec<IntensityPoint>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "{}
+             SELECT p.period_start,
+                    CASE WHEN SUM(et.loaded_reps) > 0 THEN ROUND(SUM(et.tonnage) / SUM(et.loaded_reps), 1) END,
+                    ROUND(AVG(et.rpe), 1)
+             FROM periods p
+             LEFT JOIN entry_totals et ON et.period_start = p.period_start
+             GROUP BY p.period_start
+             ORDER BY p.period_start",
+            Self::with_clause(bucket, true)
+        ))?;
+
+        let points = stmt
+            .query_map(params![person_id, start_date, end_date], |row| {
+                Ok(IntensityPoint {
+                    period_start: row.get(0)?,
+                    average_weight: row.get(1)?,
+                    average_rpe: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(points)
+    }
+
+    fn adherence(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<Vec<AdherenceEntry>> {
+        let conn = self.get_connection()?;
+
+        // Same performed-set rules as ENTRY_TOTALS: an entry with per-set detail counts
+        // only its sets recorded as done, even when none is. A set counts as completed
+        // when it reaches the prescribed reps at the prescribed weight (when there is one).
+        // Entries still planned were not trained and count as nothing done.
+        let mut stmt = conn.prepare_cached(&format!(
+            "WITH {}
+             SELECT pl.id, pl.date, pl.routine_id, pl.routine_name, pl.exercise_id, pl.exercise_name,
+                    pl.prescribed_sets, pl.prescribed_reps, pl.prescribed_weight,
+                    CASE WHEN pl.status = 'planned' THEN 0
+                         WHEN ws.workout_entry_id IS NULL THEN COALESCE(pl.sets, 1) ELSE ws.sets END,
+                    CASE WHEN pl.status = 'planned' THEN 0
+                         WHEN ws.workout_entry_id IS NULL THEN COALESCE(pl.sets, 1) * COALESCE(pl.reps, 0) ELSE ws.reps END,
+                    CASE WHEN pl.status = 'planned' THEN NULL
+                         WHEN ws.workout_entry_id IS NULL THEN pl.weight ELSE ws.top_weight END,
+                    CASE WHEN pl.status = 'planned' THEN 0
+                         WHEN ws.workout_entry_id IS NOT NULL THEN ws.completed_sets
+                         WHEN COALESCE(pl.reps, 0) >= COALESCE(pl.prescribed_reps, 0)
+                          AND COALESCE(pl.weight, 0) >= COALESCE(pl.prescribed_weight, 0) - 0.001
+                         THEN COALESCE(pl.sets, 1)
+                         ELSE 0 END
+             FROM planned pl
+             LEFT JOIN (
+                 SELECT s.workout_entry_id,
+                        SUM(s.performed) AS sets,
+                        SUM(s.performed * COALESCE(s.reps, 0)) AS reps,
+                        MAX(CASE WHEN s.performed = 1 THEN s.weight END) AS top_weight,
+                        SUM(CASE WHEN s.performed = 1
+                                  AND COALESCE(s.reps, 0) >= COALESCE(p.prescribed_reps, 0)
+                                  AND COALESCE(s.weight, 0) >= COALESCE(p.prescribed_weight, 0) - 0.001
+                                 THEN 1 ELSE 0 END) AS completed_sets
+                 FROM (
+                     SELECT workout_entry_id, reps, weight,
+                            CASE WHEN completed = 1 AND set_type != 'warm_up' THEN 1 ELSE 0 END AS performed
+                     FROM workout_sets
+                 ) s
+                 JOIN planned p ON s.workout_entry_id = p.id
+                 GROUP BY s.workout_entry_id
+             ) ws ON ws.workout_entry_id = pl.id
+             ORDER BY pl.date, pl.order_index, pl.id",
+            PLANNED_ENTRIES
+        ))?;
+
+        let entries = stmt
+            .query_map(params![person_id, start_date, end_date], |row| {
+                let prescribed_sets: Option<i32> = row.get(6)?;
+                let completed_sets: i32 = row.get(12)?;
+                Ok(AdherenceEntry {
+                    workout_entry_id: row.get(0)?,
+                    date: row.get(1)?,
+                    routine_id: row.get(2)?,
+                    routine_name: row.get(3)?,
+                    exercise_id: row.get(4)?,
+                    exercise_name: row.get(5)?,
+                    prescribed_sets,
+                    prescribed_reps: row.get(7)?,
+                    prescribed_weight: row.get(8)?,
+                    performed_sets: row.get(9)?,
+                    performed_reps: row.get(10)?,
+                    top_weight: row.get(11)?,
+                    completed_sets: completed_sets.min(prescribed_sets.unwrap_or(completed_sets)),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::db::test_pool;
+    use crate::models::exercise::{Exercise, MeasurementType};
+    use crate::models::person::Person;
+    use crate::models::workout_entry::{WorkoutEntry, WorkoutEntryStatus};
+    use crate::models::workout_set::{SetType, WorkoutSet};
+    use crate::repository::exercise_repository::ExerciseRepository;
+    use crate::repository::person_repository::PersonRepository;
+    use crate::repository::sqlite_exercise_repository::SqliteExerciseRepository;
+    use crate::repository::sqlite_person_repository::SqlitePersonRepository;
+    use crate::repository::sqlite_workout_entry_repository::SqliteWorkoutEntryRepository;
+    use crate::repository::workout_entry_repository::WorkoutEntryRepository;
+
+    const SQUAT: i32 = 1;
+    const BENCH: i32 = 2;
+    const PLANK: i32 = 3;
+    const RUN: i32 = 4;
+
+    struct Fixture {
+        _dir: tempfile::TempDir,
+        entries: SqliteWorkoutEntryRepository,
+        reports: SqliteReportRepository,
+    }
+
+    fn fixture() -> Fixture {
+        let (dir, pool) = test_pool();
+        let person: Person =
+            serde_json::from_value(serde_json::json!({ "id": null, "name": "Ana", "last_name": "Ruiz", "phone": "600000001" })).unwrap();
+        SqlitePersonRepository::new(pool.clone()).create(person).unwrap();
+
+        let exercises = SqliteExerciseRepository::new(pool.clone());
+        for (name, code, measurement_type) in [
+            ("Sentadilla", "SQ", MeasurementType::RepsWeight),
+            ("Banca", "BP", MeasurementType::RepsWeight),
+            ("Plancha", "PL", MeasurementType::Time),
+            ("Carrera", "RUN", MeasurementType::Distance),
+        ] {
+            exercises
+                .create(Exercise { id: None, name: name.into(), code: code.into(), measurement_type: Some(measurement_type) })
+                .unwrap();
+        }
+
+        Fixture { _dir: dir, entries: SqliteWorkoutEntryRepository::new(pool.clone()), reports: SqliteReportRepository::new(pool) }
+    }
+
+    fn set(order_index: i32, reps: i32, weight: f64, completed: bool, set_type: SetType) -> WorkoutSet {
+        WorkoutSet {
+            id: None,
+            workout_entry_id: 0,
+            order_index,
+            reps: Some(reps),
+            weight: Some(weight),
+            completed,
+            set_type,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    impl Fixture {
+        // sets × reps @ weight, logged as performed
+        fn log(&self, exercise_id: i32, date: &str, sets: i32, reps: i32, weight: f64) -> WorkoutEntry {
+            let mut entry = WorkoutEntry::new(1, exercise_id, date.to_string(), Some(sets), Some(reps), Some(weight), None);
+            entry.group_number = Some(1);
+            entry
+        }
+
+        fn save(&self, entry: WorkoutEntry) {
+            self.entries.create(entry).unwrap();
+        }
+    }
+
+    fn starts<T>(points: &[T], period_start: impl Fn(&T) -> &str) -> Vec<String> {
+        points.iter().map(|point| period_start(point).to_string()).collect()
+    }
+
+    #[test]
+    fn weeks_start_on_monday_across_the_year_boundary() {
+        let f = fixture();
+        f.save(f.log(SQUAT, "2024-12-29", 1, 10, 10.0)); // Sunday
+        f.save(f.log(SQUAT, "2024-12-30", 1, 10, 20.0)); // Monday
+        f.save(f.log(SQUAT, "2025-01-01", 1, 10, 30.0));
+        f.save(f.log(SQUAT, "2025-01-05", 1, 10, 40.0)); // Sunday
+
+        let tonnage = f.reports.tonnage(1, "2024-12-25", "2025-01-10", ReportBucket::Week).unwrap();
+        assert_eq!(starts(&tonnage, |p| &p.period_start), ["2024-12-23", "2024-12-30", "2025-01-06"]);
+        assert_eq!(tonnage.iter().map(|p| p.tonnage).collect::<Vec<_>>(), [100.0, 900.0, 0.0]);
+
+        let frequency = f.reports.frequency(1, "2024-12-25", "2025-01-10", ReportBucket::Week).unwrap();
+        assert_eq!(frequency.iter().map(|p| p.sessions).collect::<Vec<_>>(), [1, 3, 0]);
+    }
+
+    #[test]
+    fn months_span_the_year_boundary_with_empty_ones_zero_filled() {
+        let f = fixture();
+        f.save(f.log(SQUAT, "2024-11-30", 2, 5, 100.0));
+        f.save(f.log(SQUAT, "2025-01-31", 1, 5, 100.0));
+
+        let tonnage = f.reports.tonnage(1, "2024-11-15", "2025-02-10", ReportBucket::Month).unwrap();
+        assert_eq!(starts(&tonnage, |p| &p.period_start), ["2024-11-01", "2024-12-01", "2025-01-01", "2025-02-01"]);
+        assert_eq!(tonnage.iter().map(|p| p.tonnage).collect::<Vec<_>>(), [1000.0, 0.0, 500.0, 0.0]);
+        assert_eq!(tonnage.iter().map(|p| p.reps).collect::<Vec<_>>(), [10, 0, 5, 0]);
+
+        let intensity = f.reports.intensity(1, "2024-11-15", "2025-02-10", ReportBucket::Month).unwrap();
+        assert_eq!(intensity.len(), 4);
+        assert_eq!(intensity[1].average_weight, None);
+        assert_eq!(intensity[1].average_rpe, None);
+
+        // Sets per exercise only lists periods with training
+        let sets = f.reports.sets_per_exercise(1, "2024-11-15", "2025-02-10", ReportBucket::Month).unwrap();
+        assert_eq!(starts(&sets, |p| &p.period_start), ["2024-11-01", "2025-01-01"]);
+    }
+
+    #[test]
+    fn per_set_detail_replaces_the_summary() {
+        let f = fixture();
+        // Summary only: 3 × 5 @ 100
+        let mut summary = f.log(SQUAT, "2024-05-06", 3, 5, 100.0);
+        summary.rpe = Some(8.0);
+        f.save(summary);
+        // The summary says 4 × 10 @ 80, but only one working set was done
+        let mut detailed = f.log(BENCH, "2024-05-06", 4, 10, 80.0);
+        detailed.rpe = Some(9.0);
+        detailed.workout_sets = Some(vec![
+            set(0, 10, 20.0, true, SetType::WarmUp),
+            set(1, 8, 60.0, true, SetType::Working),
+            set(2, 8, 60.0, false, SetType::Working),
+        ]);
+        f.save(detailed);
+
+        let tonnage = f.reports.tonnage(1, "2024-05-06", "2024-05-06", ReportBucket::Day).unwrap();
+        assert_eq!(tonnage[0].tonnage, 1500.0 + 480.0);
+        assert_eq!(tonnage[0].reps, 15 + 8);
+
+        let sets = f.reports.sets_per_exercise(1, "2024-05-06", "2024-05-06", ReportBucket::Day).unwrap();
+        assert_eq!(sets.iter().map(|p| (p.exercise_name.as_str(), p.sets)).collect::<Vec<_>>(), [("Banca", 1), ("Sentadilla", 3)]);
+
+        let intensity = f.reports.intensity(1, "2024-05-06", "2024-05-06", ReportBucket::Day).unwrap();
+        assert_eq!(intensity[0].average_weight, Some(86.1)); // 1980 kg / 23 reps
+        assert_eq!(intensity[0].average_rpe, Some(8.5));
+    }
+
+    #[test]
+    fn planned_entries_are_left_out() {
+        let f = fixture();
+        f.save(f.log(SQUAT, "2024-05-06", 3, 5, 100.0));
+        let mut planned = f.log(BENCH, "2024-05-08", 3, 5, 80.0);
+        planned.status = WorkoutEntryStatus::Planned;
+        f.save(planned);
+
+        let tonnage = f.reports.tonnage(1, "2024-05-06", "2024-05-12", ReportBucket::Week).unwrap();
+        assert_eq!(tonnage[0].tonnage, 1500.0);
+        let frequency = f.reports.frequency(1, "2024-05-06", "2024-05-12", ReportBucket::Week).unwrap();
+        assert_eq!(frequency[0].sessions, 1);
+        let sets = f.reports.sets_per_exercise(1, "2024-05-06", "2024-05-12", ReportBucket::Week).unwrap();
+        assert_eq!(sets.len(), 1);
+        assert_eq!(sets[0].exercise_id, SQUAT);
+    }
+
+    #[test]
+    fn timed_and_distance_exercises_add_sets_but_no_tonnage() {
+        let f = fixture();
+        // Weighted plank: 3 × 60 s with a 20 kg plate
+        let mut plank = WorkoutEntry::new(1, PLANK, "2024-05-06".to_string(), Some(3), None, Some(20.0), None);
+        plank.group_number = Some(1);
+        plank.duration_seconds = Some(60);
+        f.save(plank);
+        let mut run = WorkoutEntry::new(1, RUN, "2024-05-06".to_string(), Some(1), None, None, None);
+        run.group_number = Some(2);
+        run.distance_meters = Some(5000.0);
+        f.save(run);
+
+        let tonnage = f.reports.tonnage(1, "2024-05-06", "2024-05-06", ReportBucket::Day).unwrap();
+        assert_eq!((tonnage[0].tonnage, tonnage[0].reps), (0.0, 0));
+        let intensity = f.reports.intensity(1, "2024-05-06", "2024-05-06", ReportBucket::Day).unwrap();
+        assert_eq!(intensity[0].average_weight, None);
+        let sets = f.reports.sets_per_exercise(1, "2024-05-06", "2024-05-06", ReportBucket::Day).unwrap();
+        assert_eq!(sets.iter().map(|p| p.sets).sum::<i32>(), 4);
+    }
+}
@@ -1,47 +1,61 @@
-use rusqlite::{Connection, Result, params};
+use rusqlite::{params, Row};
+use crate::config::db::{DbPool, PooledConnection};
+use crate::error::{AppError, AppResult};
 use crate::models::routine_exercise::{RoutineExercise, RoutineExerciseWithDetails};
 use crate::repository::routine_exercise_repository::RoutineExerciseRepository;
 
 pub struct SqliteRoutineExerciseRepository {
-    db_path: String,
-    is_dummy: bool,
+    pool: Option<DbPool>,
 }
 
 impl SqliteRoutineExerciseRepository {
-    /// Private constructor
-    fn new(db_path: &str, is_dummy: bool) -> Self {
-        Self {
-            db_path: db_path.to_string(),
-            is_dummy,
-        }
-    }
-
-    /// Safe constructor (could be extended to check DB)
-    pub fn new_safe(db_path: &str) -> Self {
-        Self::new(db_path, false)
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool: Some(pool) }
     }
 
-    /// Dummy constructor
+    /// Dummy constructor: every call fails with `DATABASE_UNAVAILABLE`
     pub fn new_dummy() -> Self {
-        Self::new("", true)
+        Self { pool: None }
     }
 
-    fn get_connection(&self) -> Result<Connection> {
-        if self.is_dummy {
-            return Err(rusqlite::Error::InvalidQuery);
+    fn get_connection(&self) -> AppResult<PooledConnection> {
+        match &self.pool {
+            Some(pool) => Ok(pool.get()?),
+            None => Err(AppError::database_unavailable("RoutineExercise repository unavailable")),
         }
-        Connection::open(&self.db_path)
+    }
+
+    fn routine_exercise_from_row(row: &Row) -> rusqlite::Result<RoutineExerciseWithDetails> {
+        Ok(RoutineExerciseWithDetails {
+            id: row.get(0)?,
+            routine_id: row.get(1)?,
+            exercise_id: row.get(2)?,
+            order_index: row.get(3)?,
+            sets: row.get(4)?,
+            reps: row.get(5)?,
+            weight: row.get(6)?,
+            notes: row.get(7)?,
+            group_number: row.get(8)?,
+            rpe: row.get(13)?,
+            rir: row.get(14)?,
+            tempo: row.get(15)?,
+            rest_seconds: row.get(16)?,
+            duration_seconds: row.get(17)?,
+            distance_meters: row.get(18)?,
+            created_at: row.get(9)?,
+            updated_at: row.get(10)?,
+            exercise_name: row.get(11)?,
+            exercise_code: row.get(12)?,
+        })
     }
 }
 
 impl RoutineExerciseRepository for SqliteRoutineExerciseRepository {
-    fn create(&self, routine_exercise: RoutineExercise) -> Result<(), String> {
-        if self.is_dummy { return Err("RoutineExercise repository unavailable".to_string()); }
-        
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+    fn create(&self, routine_exercise: RoutineExercise) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
         conn.execute(
-            "INSERT INTO routine_exercises (routine_id, exercise_id, order_index, sets, reps, weight, notes, group_number, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, datetime('now'), datetime('now'))",
+            "INSERT INTO routine_exercises (routine_id, exercise_id, order_index, sets, reps, weight, notes, group_number, rpe, rir, tempo, rest_seconds, duration_seconds, distance_meters, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, datetime('now'), datetime('now'))",
             params![
                 routine_exercise.routine_id,
                 routine_exercise.exercise_id,
@@ -51,61 +65,41 @@ impl RoutineExerciseRepository for SqliteRoutineExerciseRepository {
                 routine_exercise.weight,
                 routine_exercise.notes,
                 routine_exercise.group_number,
+                routine_exercise.rpe,
+                routine_exercise.rir,
+                routine_exercise.tempo,
+                routine_exercise.rest_seconds,
+                routine_exercise.duration_seconds,
+                routine_exercise.distance_meters,
             ],
-        ).map_err(|e| e.to_string())?;
-        
+        )?;
+
         Ok(())
     }
 
-    fn get_by_routine_id(&self, routine_id: i32) -> Vec<RoutineExerciseWithDetails> {
-        if self.is_dummy { return Vec::new(); }
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
-
-        let mut stmt = match conn.prepare(
-            "SELECT re.id, re.routine_id, re.exercise_id, re.order_index, re.sets, re.reps, re.weight, re.notes, re.group_number, re.created_at, re.updated_at, e.name, e.code 
-             FROM routine_exercises re 
-             JOIN exercise e ON re.exercise_id = e.id 
-             WHERE re.routine_id = ? 
+    fn get_by_routine_id(&self, routine_id: i32) -> AppResult<Vec<RoutineExerciseWithDetails>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT re.id, re.routine_id, re.exercise_id, re.order_index, re.sets, re.reps, re.weight, re.notes, re.group_number, re.created_at, re.updated_at, e.name, e.code,
+                    re.rpe, re.rir, re.tempo, re.rest_seconds, re.duration_seconds, re.distance_meters
+             FROM routine_exercises re
+             JOIN exercise e ON re.exercise_id = e.id
+             WHERE re.routine_id = ?
              ORDER BY re.order_index"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
-
-        let rows = match stmt.query_map(params![routine_id], |row| {
-            Ok(RoutineExerciseWithDetails {
-                id: row.get(0)?,
-                routine_id: row.get(1)?,
-                exercise_id: row.get(2)?,
-                order_index: row.get(3)?,
-                sets: row.get(4)?,
-                reps: row.get(5)?,
-                weight: row.get(6)?,
-                notes: row.get(7)?,
-                group_number: row.get(8)?,
-                created_at: row.get(9)?,
-                updated_at: row.get(10)?,
-                exercise_name: row.get(11)?,
-                exercise_code: row.get(12)?,
-            })
-        }) {
-            Ok(rows) => rows,
-            Err(_) => return Vec::new(),
-        };
-
-        rows.filter_map(|row| row.ok()).collect()
+        )?;
+
+        let rows = stmt
+            .query_map(params![routine_id], Self::routine_exercise_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
     }
 
-    fn update(&self, routine_exercise: RoutineExercise) -> Result<(), String> {
-        if self.is_dummy { return Err("RoutineExercise repository unavailable".to_string()); }
-        
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
-        conn.execute(
-            "UPDATE routine_exercises SET routine_id = ?1, exercise_id = ?2, order_index = ?3, sets = ?4, reps = ?5, weight = ?6, notes = ?7, group_number = ?8, updated_at = datetime('now') WHERE id = ?9",
+    fn update(&self, routine_exercise: RoutineExercise) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let updated = conn.execute(
+            "UPDATE routine_exercises SET routine_id = ?1, exercise_id = ?2, order_index = ?3, sets = ?4, reps = ?5, weight = ?6, notes = ?7, group_number = ?8, rpe = ?9, rir = ?10, tempo = ?11, rest_seconds = ?12, duration_seconds = ?13, distance_meters = ?14, updated_at = datetime('now') WHERE id = ?15",
             params![
                 routine_exercise.routine_id,
                 routine_exercise.exercise_id,
@@ -115,65 +109,70 @@ impl RoutineExerciseRepository for SqliteRoutineExerciseRepository {
                 routine_exercise.weight,
                 routine_exercise.notes,
                 routine_exercise.group_number,
+                routine_exercise.rpe,
+                routine_exercise.rir,
+                routine_exercise.tempo,
+                routine_exercise.rest_seconds,
+                routine_exercise.duration_seconds,
+                routine_exercise.distance_meters,
                 routine_exercise.id,
             ],
-        ).map_err(|e| e.to_string())?;
-        
+        )?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Routine exercise not found"));
+        }
         Ok(())
     }
 
-    fn delete(&self, id: i32) -> Result<(), String> {
-        if self.is_dummy { return Err("RoutineExercise repository unavailable".to_string()); }
-        
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
-        conn.execute("DELETE FROM routine_exercises WHERE id = ?", params![id])
-            .map_err(|e| e.to_string())?;
-        
+    fn delete(&self, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let deleted = conn.execute("DELETE FROM routine_exercises WHERE id = ?", params![id])?;
+
+        if deleted == 0 {
+            return Err(AppError::not_found("Routine exercise not found"));
+        }
         Ok(())
     }
 
-    fn delete_by_routine_and_exercise(&self, routine_id: i32, exercise_id: i32) -> Result<(), String> {
-        if self.is_dummy { return Err("RoutineExercise repository unavailable".to_string()); }
-        
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
-        conn.execute(
-            "DELETE FROM routine_exercises WHERE routine_id = ? AND exercise_id = ?", 
+    fn delete_by_routine_and_exercise(&self, routine_id: i32, exercise_id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let deleted = conn.execute(
+            "DELETE FROM routine_exercises WHERE routine_id = ? AND exercise_id = ?",
             params![routine_id, exercise_id]
-        ).map_err(|e| e.to_string())?;
-        
+        )?;
+
+        if deleted == 0 {
+            return Err(AppError::not_found("Routine exercise not found"));
+        }
         Ok(())
     }
 
-    fn update_order(&self, exercise_orders: Vec<(i32, i32)>) -> Result<(), String> {
-        if self.is_dummy { return Err("RoutineExercise repository unavailable".to_string()); }
-        
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+    fn update_order(&self, exercise_orders: Vec<(i32, i32)>) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
         for (id, order) in exercise_orders {
             conn.execute(
                 "UPDATE routine_exercises SET order_index = ? WHERE id = ?",
                 params![order, id],
-            ).map_err(|e| e.to_string())?;
+            )?;
         }
-        
+
         Ok(())
     }
 
-    fn replace_routine_exercises(&self, routine_id: i32, exercises: Vec<RoutineExercise>) -> Result<(), String> {
-        if self.is_dummy { return Err("RoutineExercise repository unavailable".to_string()); }
-        
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+    fn replace_routine_exercises(&self, routine_id: i32, exercises: Vec<RoutineExercise>) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
         // Delete existing exercises for this routine
-        conn.execute("DELETE FROM routine_exercises WHERE routine_id = ?", params![routine_id])
-            .map_err(|e| e.to_string())?;
-        
+        conn.execute("DELETE FROM routine_exercises WHERE routine_id = ?", params![routine_id])?;
+
         // Insert new exercises
         for exercise in exercises {
             conn.execute(
-                "INSERT INTO routine_exercises (routine_id, exercise_id, order_index, sets, reps, weight, notes, group_number, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, datetime('now'), datetime('now'))",
+                "INSERT INTO routine_exercises (routine_id, exercise_id, order_index, sets, reps, weight, notes, group_number, rpe, rir, tempo, rest_seconds, duration_seconds, distance_meters, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, datetime('now'), datetime('now'))",
                 params![
                     routine_id,
                     exercise.exercise_id,
@@ -183,10 +182,16 @@ impl RoutineExerciseRepository for SqliteRoutineExerciseRepository {
                     exercise.weight,
                     exercise.notes,
                     exercise.group_number,
+                    exercise.rpe,
+                    exercise.rir,
+                    exercise.tempo,
+                    exercise.rest_seconds,
+                    exercise.duration_seconds,
+                    exercise.distance_meters,
                 ],
-            ).map_err(|e| e.to_string())?;
+            )?;
         }
-        
+
         Ok(())
     }
-} 
\ No newline at end of file
+}
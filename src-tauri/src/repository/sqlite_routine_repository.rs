@@ -1,438 +1,409 @@
-use rusqlite::{Connection, Result as SqliteResult, params};
+use std::collections::{HashMap, HashSet};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::config::db::{DbPool, PooledConnection};
+use crate::error::{AppError, AppResult};
 use crate::models::routine::{Routine, RoutineWithExercises};
 use crate::models::routine_exercise::{RoutineExercise, RoutineExerciseWithDetails};
+use crate::models::routine_revision::{RoutineRevision, RoutineRevisionExercise, RoutineRevisionWithExercises};
 use crate::repository::routine_repository::RoutineRepository;
 
 pub struct SqliteRoutineRepository {
-    db_path: String,
-    is_dummy: bool,
+    pool: Option<DbPool>,
 }
 
 impl SqliteRoutineRepository {
-    /// Private constructor
-    fn new(db_path: &str, is_dummy: bool) -> Self {
-        Self {
-            db_path: db_path.to_string(),
-            is_dummy,
-        }
-    }
-
-    /// Safe constructor: returns error if table creation fails
-    pub fn new_safe(db_path: &str) -> Result<Self, String> {
-        let repo = Self::new(db_path, false);
-        if let Err(e) = repo.create_tables() {
-            return Err(format!("Failed to create routine tables: {}", e));
-        }
-        Ok(repo)
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool: Some(pool) }
     }
 
-    /// Dummy constructor: always returns empty results or errors
+    /// Dummy constructor: every call fails with `DATABASE_UNAVAILABLE`
     pub fn new_dummy() -> Self {
-        Self::new("", true)
+        Self { pool: None }
     }
 
-    fn create_tables(&self) -> SqliteResult<()> {
-        if self.is_dummy { return Ok(()); }
-        let conn = self.get_connection()?;
-        
-        // Create routines table
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS routines (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT NOT NULL,
-                code TEXT NOT NULL UNIQUE,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-            [],
-        )?;
-
-        // Create routine_exercises table (junction table)
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS routine_exercises (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                routine_id INTEGER NOT NULL,
-                exercise_id INTEGER NOT NULL,
-                order_index INTEGER NOT NULL DEFAULT 0,
-                sets INTEGER,
-                reps INTEGER,
-                weight REAL,
-                notes TEXT,
-                group_number INTEGER DEFAULT 1,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (routine_id) REFERENCES routines (id) ON DELETE CASCADE,
-                FOREIGN KEY (exercise_id) REFERENCES exercise (id) ON DELETE CASCADE,
-                UNIQUE(routine_id, exercise_id)
-            )",
-            [],
-        )?;
-
-        // Check if group_number migration is needed
-        if self.check_if_routine_group_migration_needed()? {
-            self.migrate_routine_group_column()?;
+    fn get_connection(&self) -> AppResult<PooledConnection> {
+        match &self.pool {
+            Some(pool) => Ok(pool.get()?),
+            None => Err(AppError::database_unavailable("Routine repository unavailable")),
         }
+    }
 
-        // Check if logical deletion migration is needed
-        if self.check_if_logical_deletion_migration_needed()? {
-            self.migrate_logical_deletion()?;
-        }
+    fn routine_from_row(row: &Row) -> rusqlite::Result<Routine> {
+        Ok(Routine {
+            id: Some(row.get(0)?),
+            name: row.get(1)?,
+            code: row.get(2)?,
+            created_at: row.get(3)?,
+            updated_at: row.get(4)?,
+        })
+    }
 
-        // Create indexes for better performance
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_routine_exercises_routine_id 
-             ON routine_exercises (routine_id)",
-            [],
-        )?;
+    fn routine_exercise_from_row(row: &Row) -> rusqlite::Result<RoutineExerciseWithDetails> {
+        Ok(RoutineExerciseWithDetails {
+            id: row.get(0)?,
+            routine_id: row.get(1)?,
+            exercise_id: row.get(2)?,
+            order_index: row.get(3)?,
+            sets: row.get(4)?,
+            reps: row.get(5)?,
+            weight: row.get(6)?,
+            notes: row.get(7)?,
+            group_number: row.get(8)?,
+            rpe: row.get(13)?,
+            rir: row.get(14)?,
+            tempo: row.get(15)?,
+            rest_seconds: row.get(16)?,
+            duration_seconds: row.get(17)?,
+            distance_meters: row.get(18)?,
+            created_at: row.get(9)?,
+            updated_at: row.get(10)?,
+            exercise_name: row.get(11)?,
+            exercise_code: row.get(12)?,
+        })
+    }
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_routine_exercises_order 
-             ON routine_exercises (routine_id, order_index)",
-            [],
+    fn insert_routine_exercise(conn: &Connection, routine_exercise: &RoutineExercise) -> rusqlite::Result<()> {
+        let mut stmt = conn.prepare_cached(
+            "INSERT INTO routine_exercises (routine_id, exercise_id, order_index, sets, reps, weight, notes, group_number, rpe, rir, tempo, rest_seconds, duration_seconds, distance_meters)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)"
         )?;
 
+        stmt.execute(params![
+            routine_exercise.routine_id,
+            routine_exercise.exercise_id,
+            routine_exercise.order_index,
+            routine_exercise.sets,
+            routine_exercise.reps,
+            routine_exercise.weight,
+            routine_exercise.notes,
+            routine_exercise.group_number,
+            routine_exercise.rpe,
+            routine_exercise.rir,
+            routine_exercise.tempo,
+            routine_exercise.rest_seconds,
+            routine_exercise.duration_seconds,
+            routine_exercise.distance_meters
+        ])?;
         Ok(())
     }
 
-    fn check_if_routine_group_migration_needed(&self) -> SqliteResult<bool> {
-        if self.is_dummy { return Ok(false); }
-        let conn = self.get_connection()?;
-        
-        let mut stmt = conn.prepare("PRAGMA table_info(routine_exercises)")?;
-        let column_info: Result<Vec<String>, _> = stmt.query_map([], |row| {
-            Ok(row.get::<_, String>(1)?) // Column name is at index 1
-        })?.collect();
-        
-        match column_info {
-            Ok(columns) => Ok(!columns.contains(&"group_number".to_string())),
-            Err(_) => Ok(false),
+    // Makes the routine's exercise list match `exercises`, matched by exercise. Rows of
+    // exercises that stay are updated in place so workout entries keep their link to them.
+    fn sync_routine_exercises(conn: &Connection, routine_id: i32, exercises: &[RoutineExercise]) -> rusqlite::Result<()> {
+        let existing: HashMap<i32, i32> = conn
+            .prepare_cached("SELECT exercise_id, id FROM routine_exercises WHERE routine_id = ?1")?
+            .query_map(params![routine_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+
+        let kept: HashSet<i32> = exercises.iter().map(|exercise| exercise.exercise_id).collect();
+        for (exercise_id, id) in &existing {
+            if !kept.contains(exercise_id) {
+                conn.prepare_cached("DELETE FROM routine_exercises WHERE id = ?1")?.execute(params![id])?;
+            }
         }
-    }
 
-    fn migrate_routine_group_column(&self) -> SqliteResult<()> {
-        if self.is_dummy { return Ok(()); }
-        let conn = self.get_connection()?;
-        
-        println!("Adding group_number column to routine_exercises table...");
-        
-        conn.execute(
-            "ALTER TABLE routine_exercises ADD COLUMN group_number INTEGER DEFAULT 1",
-            [],
-        )?;
-        
-        println!("group_number column added successfully to routine_exercises table");
+        for exercise in exercises {
+            let exercise = RoutineExercise { routine_id, ..exercise.clone() };
+            match existing.get(&exercise.exercise_id) {
+                Some(id) => {
+                    conn.prepare_cached(
+                        "UPDATE routine_exercises
+                         SET order_index = ?1, sets = ?2, reps = ?3, weight = ?4, notes = ?5, group_number = ?6,
+                             rpe = ?7, rir = ?8, tempo = ?9, rest_seconds = ?10, duration_seconds = ?11, distance_meters = ?12,
+                             updated_at = CURRENT_TIMESTAMP
+                         WHERE id = ?13"
+                    )?.execute(params![
+                        exercise.order_index,
+                        exercise.sets,
+                        exercise.reps,
+                        exercise.weight,
+                        exercise.notes,
+                        exercise.group_number,
+                        exercise.rpe,
+                        exercise.rir,
+                        exercise.tempo,
+                        exercise.rest_seconds,
+                        exercise.duration_seconds,
+                        exercise.distance_meters,
+                        id
+                    ])?;
+                }
+                None => Self::insert_routine_exercise(conn, &exercise)?,
+            }
+        }
         Ok(())
     }
 
-    fn check_if_logical_deletion_migration_needed(&self) -> SqliteResult<bool> {
-        if self.is_dummy { return Ok(false); }
-        let conn = self.get_connection()?;
-        
-        let mut stmt = conn.prepare("PRAGMA table_info(routines)")?;
-        let column_info: Result<Vec<String>, _> = stmt.query_map([], |row| {
-            Ok(row.get::<_, String>(1)?) // Column name is at index 1
-        })?.collect();
-        
-        match column_info {
-            Ok(columns) => Ok(!columns.contains(&"deleted_at".to_string())),
-            Err(_) => Ok(false),
-        }
+    /// Snapshots the routine as it is now as its next revision; returns the revision number.
+    /// Called inside the transaction of every change so no edit goes unrecorded.
+    fn record_revision(conn: &Connection, routine_id: i32, comment: Option<&str>) -> rusqlite::Result<i32> {
+        let (revision_id, revision): (i32, i32) = conn.prepare_cached(
+            "INSERT INTO routine_revisions (routine_id, revision, name, code, comment)
+             SELECT id, COALESCE((SELECT MAX(revision) FROM routine_revisions WHERE routine_id = ?1), 0) + 1, name, code, ?2
+             FROM routines WHERE id = ?1
+             RETURNING id, revision"
+        )?.query_row(params![routine_id, comment], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        conn.prepare_cached(
+            "INSERT INTO routine_revision_exercises (revision_id, exercise_id, order_index, sets, reps, weight, notes, group_number,
+                                                     rpe, rir, tempo, rest_seconds, duration_seconds, distance_meters)
+             SELECT ?1, exercise_id, order_index, sets, reps, weight, notes, group_number,
+                    rpe, rir, tempo, rest_seconds, duration_seconds, distance_meters
+             FROM routine_exercises WHERE routine_id = ?2"
+        )?.execute(params![revision_id, routine_id])?;
+        Ok(revision)
     }
 
-    fn migrate_logical_deletion(&self) -> SqliteResult<()> {
-        if self.is_dummy { return Ok(()); }
-        let conn = self.get_connection()?;
-        
-        println!("Adding logical deletion columns to routines table...");
-        
-        conn.execute(
-            "ALTER TABLE routines ADD COLUMN deleted_at DATETIME",
-            [],
-        )?;
-        
-        conn.execute(
-            "ALTER TABLE routines ADD COLUMN is_active INTEGER DEFAULT 1",
-            [],
-        )?;
-        
-        println!("Logical deletion columns added successfully to routines table");
-        Ok(())
+    fn revision_from_row(row: &Row) -> rusqlite::Result<RoutineRevision> {
+        Ok(RoutineRevision {
+            id: row.get(0)?,
+            routine_id: row.get(1)?,
+            revision: row.get(2)?,
+            name: row.get(3)?,
+            code: row.get(4)?,
+            comment: row.get(5)?,
+            exercise_count: row.get(6)?,
+            created_at: row.get(7)?,
+        })
     }
 
-    fn get_connection(&self) -> SqliteResult<Connection> {
-        if self.is_dummy {
-            return Err(rusqlite::Error::InvalidQuery);
-        }
-        Connection::open(&self.db_path)
+    fn revision_exercise_from_row(row: &Row) -> rusqlite::Result<RoutineRevisionExercise> {
+        Ok(RoutineRevisionExercise {
+            exercise_id: row.get(0)?,
+            exercise_name: row.get(1)?,
+            exercise_code: row.get(2)?,
+            order_index: row.get(3)?,
+            sets: row.get(4)?,
+            reps: row.get(5)?,
+            weight: row.get(6)?,
+            notes: row.get(7)?,
+            group_number: row.get(8)?,
+            rpe: row.get(9)?,
+            rir: row.get(10)?,
+            tempo: row.get(11)?,
+            rest_seconds: row.get(12)?,
+            duration_seconds: row.get(13)?,
+            distance_meters: row.get(14)?,
+        })
     }
 }
 
+const REVISION_SELECT: &str =
+    "SELECT rr.id, rr.routine_id, rr.revision, rr.name, rr.code, rr.comment,
+            (SELECT COUNT(*) FROM routine_revision_exercises rre WHERE rre.revision_id = rr.id),
+            rr.created_at
+     FROM routine_revisions rr";
+
 impl RoutineRepository for SqliteRoutineRepository {
-    fn create(&self, routine: Routine) -> Result<i32, String> {
-        if self.is_dummy { return Err("Routine repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
-        conn.execute(
+    fn create(&self, routine: Routine) -> AppResult<i32> {
+        self.create_with_exercises(routine, Vec::new())
+    }
+
+    fn create_with_exercises(&self, routine: Routine, exercises: Vec<RoutineExercise>) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        tx.execute(
             "INSERT INTO routines (name, code) VALUES (?1, ?2)",
             params![routine.name, routine.code]
-        ).map_err(|e| e.to_string())?;
-        
+        )?;
+
         // Get the ID of the newly created routine
-        let routine_id = conn.last_insert_rowid() as i32;
+        let routine_id = tx.last_insert_rowid() as i32;
+        for exercise in exercises {
+            Self::insert_routine_exercise(&tx, &RoutineExercise { routine_id, ..exercise })?;
+        }
+        // The routine starts out at revision 1, exercises included
+        Self::record_revision(&tx, routine_id, None)?;
+
+        tx.commit()?;
         Ok(routine_id)
     }
 
-    fn get_by_id(&self, id: i32) -> Option<Routine> {
-        if self.is_dummy { return None; }
-        let conn = self.get_connection().ok()?;
-        
-        let mut stmt = conn.prepare(
+    fn get_by_id(&self, id: i32) -> AppResult<Option<Routine>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
             "SELECT id, name, code, created_at, updated_at FROM routines WHERE id = ?1"
-        ).ok()?;
-        
-        stmt.query_row(params![id], |row| {
-            Ok(Routine {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                code: row.get(2)?,
-                created_at: row.get(3)?,
-                updated_at: row.get(4)?,
-            })
-        }).ok()
-    }
-
-    fn get_with_exercises(&self, id: i32) -> Option<RoutineWithExercises> {
-        if self.is_dummy { return None; }
-        let routine = self.get_by_id(id)?;
-        let exercises = self.get_routine_exercises(id);
-        
-        Some(RoutineWithExercises {
+        )?;
+
+        let routine = stmt.query_row(params![id], Self::routine_from_row).optional()?;
+        Ok(routine)
+    }
+
+    fn get_with_exercises(&self, id: i32) -> AppResult<Option<RoutineWithExercises>> {
+        let routine = match self.get_by_id(id)? {
+            Some(routine) => routine,
+            None => return Ok(None),
+        };
+        let exercises = self.get_routine_exercises(id)?;
+
+        Ok(Some(RoutineWithExercises {
             id: routine.id,
             name: routine.name,
             code: routine.code,
             created_at: routine.created_at,
             updated_at: routine.updated_at,
             exercises,
-        })
+        }))
     }
 
-    fn update(&self, id: i32, name: String, code: String) -> Result<(), String> {
-        if self.is_dummy { return Err("Routine repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
-        conn.execute(
+    fn update(&self, id: i32, name: String, code: String) -> AppResult<()> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        let updated = tx.execute(
             "UPDATE routines SET name = ?1, code = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?3",
             params![name, code, id]
-        ).map_err(|e| e.to_string())?;
-        
+        )?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Routine not found"));
+        }
+        Self::record_revision(&tx, id, None)?;
+
+        tx.commit()?;
         Ok(())
     }
 
-    fn delete(&self, id: i32) -> Result<(), String> {
-        if self.is_dummy { return Err("Routine repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+    fn delete(&self, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
         // Logical deletion instead of physical deletion
-        conn.execute(
+        let updated = tx.execute(
             "UPDATE routines SET deleted_at = datetime('now'), is_active = 0 WHERE id = ?1",
             params![id]
-        ).map_err(|e| e.to_string())?;
+        )?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Routine not found"));
+        }
+        Self::record_revision(&tx, id, Some("Routine deleted"))?;
+
+        tx.commit()?;
         Ok(())
     }
 
-    fn restore(&self, id: i32) -> Result<(), String> {
-        if self.is_dummy { return Err("Routine repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+    fn restore(&self, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
         // Restore logically deleted routine
-        conn.execute(
+        let updated = tx.execute(
             "UPDATE routines SET deleted_at = NULL, is_active = 1 WHERE id = ?1",
             params![id]
-        ).map_err(|e| e.to_string())?;
+        )?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Routine not found"));
+        }
+        Self::record_revision(&tx, id, Some("Routine restored"))?;
+
+        tx.commit()?;
         Ok(())
     }
 
-    fn list_all(&self) -> Vec<Routine> {
-        if self.is_dummy { return Vec::new(); }
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
-        
-        let mut stmt = match conn.prepare(
-            "SELECT id, name, code, created_at, updated_at FROM routines 
+    fn list_all(&self) -> AppResult<Vec<Routine>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, name, code, created_at, updated_at FROM routines
              WHERE (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)
              ORDER BY name"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
-        
-        let routine_iter = match stmt.query_map([], |row| {
-            Ok(Routine {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                code: row.get(2)?,
-                created_at: row.get(3)?,
-                updated_at: row.get(4)?,
-            })
-        }) {
-            Ok(iter) => iter,
-            Err(_) => return Vec::new(),
-        };
-        
-        routine_iter.filter_map(|routine| routine.ok()).collect()
+        )?;
+
+        let routines = stmt.query_map([], Self::routine_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(routines)
     }
 
-    fn list_routines_paginated(&self, page: i32, page_size: i32) -> Vec<Routine> {
-        if self.is_dummy { return Vec::new(); }
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
-        
+    fn list_routines_paginated(&self, page: i32, page_size: i32) -> AppResult<Vec<Routine>> {
+        let conn = self.get_connection()?;
+
         let offset = (page - 1) * page_size;
-        
-        let mut stmt = match conn.prepare(
-            "SELECT id, name, code, created_at, updated_at FROM routines 
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, name, code, created_at, updated_at FROM routines
              WHERE (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)
              ORDER BY name LIMIT ?1 OFFSET ?2"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
-        
-        let routine_iter = match stmt.query_map(params![page_size, offset], |row| {
-            Ok(Routine {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                code: row.get(2)?,
-                created_at: row.get(3)?,
-                updated_at: row.get(4)?,
-            })
-        }) {
-            Ok(iter) => iter,
-            Err(_) => return Vec::new(),
-        };
-        
-        routine_iter.filter_map(|routine| routine.ok()).collect()
+        )?;
+
+        let routines = stmt
+            .query_map(params![page_size, offset], Self::routine_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(routines)
     }
 
-    fn search_routines(&self, query: &str) -> Vec<Routine> {
-        if self.is_dummy { return Vec::new(); }
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
-        
+    fn search_routines(&self, query: &str) -> AppResult<Vec<Routine>> {
+        let conn = self.get_connection()?;
+
         let search_pattern = format!("%{}%", query);
-        
-        let mut stmt = match conn.prepare(
-            "SELECT id, name, code, created_at, updated_at FROM routines 
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, name, code, created_at, updated_at FROM routines
              WHERE (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)
-             AND (name LIKE ?1 OR code LIKE ?1) 
+             AND (name LIKE ?1 OR code LIKE ?1)
              ORDER BY name"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
-        
-        let routine_iter = match stmt.query_map(params![search_pattern], |row| {
-            Ok(Routine {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                code: row.get(2)?,
-                created_at: row.get(3)?,
-                updated_at: row.get(4)?,
-            })
-        }) {
-            Ok(iter) => iter,
-            Err(_) => return Vec::new(),
-        };
-        
-        routine_iter.filter_map(|routine| routine.ok()).collect()
+        )?;
+
+        let routines = stmt
+            .query_map(params![search_pattern], Self::routine_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(routines)
     }
 
-    fn search_routines_paginated(&self, query: &str, page: i32, page_size: i32) -> Vec<Routine> {
-        if self.is_dummy { return Vec::new(); }
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
-        
+    fn search_routines_paginated(&self, query: &str, page: i32, page_size: i32) -> AppResult<Vec<Routine>> {
+        let conn = self.get_connection()?;
+
         let offset = (page - 1) * page_size;
         let search_pattern = format!("%{}%", query);
-        
-        let mut stmt = match conn.prepare(
-            "SELECT id, name, code, created_at, updated_at FROM routines 
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, name, code, created_at, updated_at FROM routines
              WHERE (deleted_at IS NULL OR deleted_at = '') AND (is_active = 1 OR is_active IS NULL)
-             AND (name LIKE ?1 OR code LIKE ?1) 
+             AND (name LIKE ?1 OR code LIKE ?1)
              ORDER BY name LIMIT ?2 OFFSET ?3"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
-        
-        let routine_iter = match stmt.query_map(params![search_pattern, page_size, offset], |row| {
-            Ok(Routine {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                code: row.get(2)?,
-                created_at: row.get(3)?,
-                updated_at: row.get(4)?,
-            })
-        }) {
-            Ok(iter) => iter,
-            Err(_) => return Vec::new(),
-        };
-        
-        routine_iter.filter_map(|routine| routine.ok()).collect()
+        )?;
+
+        let routines = stmt
+            .query_map(params![search_pattern, page_size, offset], Self::routine_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(routines)
     }
 
-    fn add_exercise_to_routine(&self, routine_exercise: RoutineExercise) -> Result<(), String> {
-        if self.is_dummy { return Err("Routine repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+    fn add_exercise_to_routine(&self, routine_exercise: RoutineExercise) -> AppResult<()> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
         // First, check if the exercise already exists in this routine
-        let mut stmt = conn.prepare(
+        let count: i32 = tx.prepare_cached(
             "SELECT COUNT(*) FROM routine_exercises WHERE routine_id = ?1 AND exercise_id = ?2"
-        ).map_err(|e| e.to_string())?;
-        
-        let count: i32 = stmt.query_row(
-            params![routine_exercise.routine_id, routine_exercise.exercise_id], 
+        )?.query_row(
+            params![routine_exercise.routine_id, routine_exercise.exercise_id],
             |row| row.get(0)
-        ).map_err(|e| e.to_string())?;
-        
+        )?;
+
         if count > 0 {
-            return Err("Este ejercicio ya está en la rutina".to_string());
+            return Err(AppError::duplicate("exercise_id", "Este ejercicio ya está en la rutina"));
         }
-        
-        conn.execute(
-            "INSERT INTO routine_exercises (routine_id, exercise_id, order_index, sets, reps, weight, notes, group_number)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            params![
-                routine_exercise.routine_id,
-                routine_exercise.exercise_id,
-                routine_exercise.order_index,
-                routine_exercise.sets,
-                routine_exercise.reps,
-                routine_exercise.weight,
-                routine_exercise.notes,
-                routine_exercise.group_number
-            ],
-        ).map_err(|e| e.to_string())?;
 
+        Self::insert_routine_exercise(&tx, &routine_exercise)?;
+        Self::record_revision(&tx, routine_exercise.routine_id, None)?;
+
+        tx.commit()?;
         Ok(())
     }
 
-    fn update_routine_exercise(&self, routine_exercise: RoutineExercise) -> Result<(), String> {
-        if self.is_dummy { return Err("Routine repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
-        conn.execute(
-            "UPDATE routine_exercises 
-             SET order_index = ?1, sets = ?2, reps = ?3, weight = ?4, notes = ?5, group_number = ?6, updated_at = CURRENT_TIMESTAMP
-             WHERE id = ?7",
+    fn update_routine_exercise(&self, routine_exercise: RoutineExercise) -> AppResult<()> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        let updated = tx.execute(
+            "UPDATE routine_exercises
+             SET order_index = ?1, sets = ?2, reps = ?3, weight = ?4, notes = ?5, group_number = ?6,
+                 rpe = ?7, rir = ?8, tempo = ?9, rest_seconds = ?10,
+                 duration_seconds = ?11, distance_meters = ?12, updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?13",
             params![
                 routine_exercise.order_index,
                 routine_exercise.sets,
@@ -440,211 +411,255 @@ impl RoutineRepository for SqliteRoutineRepository {
                 routine_exercise.weight,
                 routine_exercise.notes,
                 routine_exercise.group_number,
+                routine_exercise.rpe,
+                routine_exercise.rir,
+                routine_exercise.tempo,
+                routine_exercise.rest_seconds,
+                routine_exercise.duration_seconds,
+                routine_exercise.distance_meters,
                 routine_exercise.id
             ],
-        ).map_err(|e| e.to_string())?;
+        )?;
 
+        if updated == 0 {
+            return Err(AppError::not_found("Routine exercise not found"));
+        }
+        // The row knows its routine; the caller's routine_id is not trusted for this
+        let routine_id: i32 = tx.query_row(
+            "SELECT routine_id FROM routine_exercises WHERE id = ?1",
+            params![routine_exercise.id],
+            |row| row.get(0),
+        )?;
+        Self::record_revision(&tx, routine_id, None)?;
+
+        tx.commit()?;
         Ok(())
     }
 
-    fn remove_exercise_from_routine(&self, routine_id: i32, exercise_id: i32) -> Result<(), String> {
-        if self.is_dummy { return Err("Routine repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
-        conn.execute(
+    fn remove_exercise_from_routine(&self, routine_id: i32, exercise_id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        let deleted = tx.execute(
             "DELETE FROM routine_exercises WHERE routine_id = ?1 AND exercise_id = ?2",
             params![routine_id, exercise_id]
-        ).map_err(|e| e.to_string())?;
+        )?;
 
+        if deleted == 0 {
+            return Err(AppError::not_found("Routine exercise not found"));
+        }
+        Self::record_revision(&tx, routine_id, None)?;
+
+        tx.commit()?;
         Ok(())
     }
 
-    fn get_routine_exercises(&self, routine_id: i32) -> Vec<RoutineExerciseWithDetails> {
-        if self.is_dummy { return Vec::new(); }
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
-        
-        let mut stmt = match conn.prepare(
+    fn get_routine_exercises(&self, routine_id: i32) -> AppResult<Vec<RoutineExerciseWithDetails>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
             "SELECT re.id, re.routine_id, re.exercise_id, re.order_index, re.sets, re.reps, re.weight, re.notes, re.group_number, re.created_at, re.updated_at,
-                    e.name as exercise_name, e.code as exercise_code
+                    e.name as exercise_name, e.code as exercise_code,
+                    re.rpe, re.rir, re.tempo, re.rest_seconds, re.duration_seconds, re.distance_meters
              FROM routine_exercises re
              JOIN exercise e ON re.exercise_id = e.id
              WHERE re.routine_id = ?1
              ORDER BY re.group_number, re.order_index"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
-        
-        let exercise_iter = match stmt.query_map(params![routine_id], |row| {
-            Ok(RoutineExerciseWithDetails {
-                id: row.get(0)?,
-                routine_id: row.get(1)?,
-                exercise_id: row.get(2)?,
-                order_index: row.get(3)?,
-                sets: row.get(4)?,
-                reps: row.get(5)?,
-                weight: row.get(6)?,
-                notes: row.get(7)?,
-                group_number: row.get(8)?,
-                created_at: row.get(9)?,
-                updated_at: row.get(10)?,
-                exercise_name: row.get(11)?,
-                exercise_code: row.get(12)?,
-            })
-        }) {
-            Ok(iter) => iter,
-            Err(_) => return Vec::new(),
-        };
-        
-        exercise_iter.filter_map(|exercise| exercise.ok()).collect()
+        )?;
+
+        let exercises = stmt
+            .query_map(params![routine_id], Self::routine_exercise_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(exercises)
     }
 
-    fn reorder_routine_exercises(&self, _routine_id: i32, exercise_orders: Vec<(i32, i32)>) -> Result<(), String> {
-        if self.is_dummy { return Err("Routine repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
-        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
-        
+    fn reorder_routine_exercises(&self, routine_id: i32, exercise_orders: Vec<(i32, i32)>) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let tx = conn.unchecked_transaction()?;
+
         {
-            let mut stmt = tx.prepare("UPDATE routine_exercises SET order_index = ?1 WHERE id = ?2")
-                .map_err(|e| e.to_string())?;
-            
+            let mut stmt = tx.prepare_cached("UPDATE routine_exercises SET order_index = ?1 WHERE id = ?2 AND routine_id = ?3")?;
+
             for (id, order) in exercise_orders {
-                stmt.execute(params![order, id]).map_err(|e| e.to_string())?;
+                stmt.execute(params![order, id, routine_id])?;
             }
         }
-        
-        tx.commit().map_err(|e| e.to_string())?;
+        Self::record_revision(&tx, routine_id, None)?;
+
+        tx.commit()?;
         Ok(())
     }
 
-    fn replace_routine_exercises(&self, routine_id: i32, exercises: Vec<RoutineExercise>) -> Result<(), String> {
-        if self.is_dummy { return Err("Routine repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
-        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
-        
-        {
-            // Delete existing exercises for this routine
-            tx.execute("DELETE FROM routine_exercises WHERE routine_id = ?1", params![routine_id])
-                .map_err(|e| e.to_string())?;
-            
-            // Insert new exercises
-            if !exercises.is_empty() {
-                let mut stmt = tx.prepare(
-                    "INSERT INTO routine_exercises (routine_id, exercise_id, order_index, sets, reps, weight, notes, group_number)
-                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
-                ).map_err(|e| e.to_string())?;
-
-                for exercise in exercises {
-                    stmt.execute(params![
-                        exercise.routine_id,
-                        exercise.exercise_id,
-                        exercise.order_index,
-                        exercise.sets,
-                        exercise.reps,
-                        exercise.weight,
-                        exercise.notes,
-                        exercise.group_number
-                    ]).map_err(|e| e.to_string())?;
-                }
-            }
-        }
-        
-        tx.commit().map_err(|e| e.to_string())?;
+    fn replace_routine_exercises(&self, routine_id: i32, exercises: Vec<RoutineExercise>, comment: Option<String>) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let tx = conn.unchecked_transaction()?;
+        Self::sync_routine_exercises(&tx, routine_id, &exercises)?;
+        Self::record_revision(&tx, routine_id, comment.as_deref())?;
+
+        tx.commit()?;
         Ok(())
     }
 
-    fn renumber_routine_groups(&self, routine_id: i32) -> Result<(), String> {
-        if self.is_dummy { return Err("Routine repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+    fn renumber_routine_groups(&self, routine_id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
         // Start transaction
-        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
-        
+        let tx = conn.unchecked_transaction()?;
+
         {
             // Get all distinct group numbers for this routine, ordered by group number
-            let mut stmt = tx.prepare(
-                "SELECT DISTINCT group_number FROM routine_exercises 
-                 WHERE routine_id = ?1 
+            let mut stmt = tx.prepare_cached(
+                "SELECT DISTINCT group_number FROM routine_exercises
+                 WHERE routine_id = ?1
                  ORDER BY group_number"
-            ).map_err(|e| e.to_string())?;
-            
-            let group_numbers: Result<Vec<i32>, _> = stmt.query_map(params![routine_id], |row| {
-                Ok(row.get::<_, i32>(0)?)
-            }).map_err(|e| e.to_string())?.collect();
-            
-            let group_numbers = group_numbers.map_err(|e| e.to_string())?;
-            
+            )?;
+
+            let group_numbers = stmt
+                .query_map(params![routine_id], |row| row.get::<_, i32>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+
             // Renumber groups consecutively starting from 1
+            let mut renumbered = false;
             for (new_group_number, old_group_number) in group_numbers.iter().enumerate() {
                 let new_group = (new_group_number + 1) as i32;
-                
+
                 // Only update if the group number needs to change
                 if new_group != *old_group_number {
                     tx.execute(
-                        "UPDATE routine_exercises 
-                         SET group_number = ?1 
+                        "UPDATE routine_exercises
+                         SET group_number = ?1
                          WHERE routine_id = ?2 AND group_number = ?3",
                         params![new_group, routine_id, old_group_number]
-                    ).map_err(|e| e.to_string())?;
+                    )?;
+                    renumbered = true;
                 }
             }
+
+            // Already consecutive groups are not a change worth a revision
+            if renumbered {
+                Self::record_revision(&tx, routine_id, None)?;
+            }
         }
-        
-        tx.commit().map_err(|e| e.to_string())?;
+
+        tx.commit()?;
         Ok(())
     }
 
-    fn list_deleted(&self) -> Vec<Routine> {
-        if self.is_dummy { return Vec::new(); }
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
-        
-        let mut stmt = match conn.prepare(
-            "SELECT id, name, code, created_at, updated_at FROM routines 
+    fn list_deleted(&self) -> AppResult<Vec<Routine>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, name, code, created_at, updated_at FROM routines
              WHERE deleted_at IS NOT NULL AND deleted_at != '' AND is_active = 0
              ORDER BY deleted_at DESC"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
-        
-        let routine_iter = match stmt.query_map([], |row| {
-            Ok(Routine {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                code: row.get(2)?,
-                created_at: row.get(3)?,
-                updated_at: row.get(4)?,
-            })
-        }) {
-            Ok(iter) => iter,
-            Err(_) => return Vec::new(),
-        };
-        
-        routine_iter.filter_map(|routine| routine.ok()).collect()
+        )?;
+
+        let routines = stmt.query_map([], Self::routine_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(routines)
     }
 
-    fn count_deleted(&self) -> i32 {
-        if self.is_dummy { return 0; }
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return 0,
-        };
-        
-        let count: Result<i32, _> = conn.query_row(
-            "SELECT COUNT(*) FROM routines 
+    fn count_deleted(&self) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        let count = conn.query_row(
+            "SELECT COUNT(*) FROM routines
              WHERE deleted_at IS NOT NULL AND deleted_at != '' AND is_active = 0",
             [],
             |row| row.get(0),
-        );
-        
-        count.unwrap_or(0)
+        )?;
+        Ok(count)
     }
-} 
\ No newline at end of file
+
+    fn list_revisions(&self, routine_id: i32) -> AppResult<Vec<RoutineRevision>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "{} WHERE rr.routine_id = ?1 ORDER BY rr.revision DESC",
+            REVISION_SELECT
+        ))?;
+
+        let revisions = stmt
+            .query_map(params![routine_id], Self::revision_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(revisions)
+    }
+
+    fn get_revision(&self, routine_id: i32, revision: i32) -> AppResult<Option<RoutineRevisionWithExercises>> {
+        let conn = self.get_connection()?;
+
+        let revision = match conn
+            .prepare_cached(&format!("{} WHERE rr.routine_id = ?1 AND rr.revision = ?2", REVISION_SELECT))?
+            .query_row(params![routine_id, revision], Self::revision_from_row)
+            .optional()?
+        {
+            Some(revision) => revision,
+            None => return Ok(None),
+        };
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT rre.exercise_id, e.name, e.code, rre.order_index, rre.sets, rre.reps, rre.weight, rre.notes, rre.group_number,
+                    rre.rpe, rre.rir, rre.tempo, rre.rest_seconds, rre.duration_seconds, rre.distance_meters
+             FROM routine_revision_exercises rre
+             JOIN exercise e ON rre.exercise_id = e.id
+             WHERE rre.revision_id = ?1
+             ORDER BY rre.group_number, rre.order_index, rre.id"
+        )?;
+        let exercises = stmt
+            .query_map(params![revision.id], Self::revision_exercise_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Some(RoutineRevisionWithExercises { revision, exercises }))
+    }
+
+    fn restore_revision(&self, routine_id: i32, revision: i32, comment: Option<String>) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+        let tx = conn.unchecked_transaction()?;
+
+        let revision_id: i32 = match tx
+            .query_row(
+                "SELECT id FROM routine_revisions WHERE routine_id = ?1 AND revision = ?2",
+                params![routine_id, revision],
+                |row| row.get(0),
+            )
+            .optional()?
+        {
+            Some(id) => id,
+            None => return Err(AppError::not_found("Routine revision not found")),
+        };
+
+        tx.execute(
+            "UPDATE routines SET (name, code) = (SELECT name, code FROM routine_revisions WHERE id = ?1),
+                    updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?2",
+            params![revision_id, routine_id],
+        )?;
+        let exercises = tx
+            .prepare_cached(
+                "SELECT exercise_id, order_index, sets, reps, weight, notes, group_number,
+                        rpe, rir, tempo, rest_seconds, duration_seconds, distance_meters
+                 FROM routine_revision_exercises WHERE revision_id = ?1
+                 ORDER BY id",
+            )?
+            .query_map(params![revision_id], |row| {
+                Ok(RoutineExercise {
+                    rpe: row.get(7)?,
+                    rir: row.get(8)?,
+                    tempo: row.get(9)?,
+                    rest_seconds: row.get(10)?,
+                    duration_seconds: row.get(11)?,
+                    distance_meters: row.get(12)?,
+                    ..RoutineExercise::new(routine_id, row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?)
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::sync_routine_exercises(&tx, routine_id, &exercises)?;
+
+        let comment = comment.unwrap_or_else(|| format!("Restored revision {}", revision));
+        let restored = Self::record_revision(&tx, routine_id, Some(&comment))?;
+
+        tx.commit()?;
+        Ok(restored)
+    }
+}
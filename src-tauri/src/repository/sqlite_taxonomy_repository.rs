@@ -0,0 +1,262 @@
+use rusqlite::{params, Connection, Row};
+use crate::config::db::{DbPool, PooledConnection};
+use crate::error::{AppError, AppResult};
+use crate::models::taxonomy::{ExerciseTaxonomy, ExerciseTaxonomyUpdate, TaxonomyItem, TaxonomyKind};
+use crate::repository::taxonomy_repository::TaxonomyRepository;
+
+pub struct SqliteTaxonomyRepository {
+    pool: Option<DbPool>,
+}
+
+impl SqliteTaxonomyRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool: Some(pool) }
+    }
+
+    /// Dummy constructor: every call fails with `DATABASE_UNAVAILABLE`
+    pub fn new_dummy() -> Self {
+        Self { pool: None }
+    }
+
+    fn get_connection(&self) -> AppResult<PooledConnection> {
+        match &self.pool {
+            Some(pool) => Ok(pool.get()?),
+            None => Err(AppError::database_unavailable("Taxonomy repository unavailable")),
+        }
+    }
+
+    fn table(kind: TaxonomyKind) -> &'static str {
+        match kind {
+            TaxonomyKind::MuscleGroup => "muscle_groups",
+            TaxonomyKind::Equipment => "equipment",
+            TaxonomyKind::MovementPattern => "movement_patterns",
+        }
+    }
+
+    fn item_from_row(row: &Row) -> rusqlite::Result<TaxonomyItem> {
+        Ok(TaxonomyItem {
+            id: Some(row.get(0)?),
+            name: row.get(1)?,
+        })
+    }
+
+    fn linked_items(conn: &Connection, sql: &str, exercise_id: i32) -> rusqlite::Result<Vec<TaxonomyItem>> {
+        let mut stmt = conn.prepare_cached(sql)?;
+        let items = stmt.query_map(params![exercise_id], Self::item_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(items)
+    }
+}
+
+impl TaxonomyRepository for SqliteTaxonomyRepository {
+    fn list(&self, kind: TaxonomyKind) -> AppResult<Vec<TaxonomyItem>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!("SELECT id, name FROM {} ORDER BY name", Self::table(kind)))?;
+        let items = stmt.query_map([], Self::item_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(items)
+    }
+
+    fn create(&self, kind: TaxonomyKind, name: &str) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        conn.execute(&format!("INSERT INTO {} (name) VALUES (?1)", Self::table(kind)), params![name])?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn rename(&self, kind: TaxonomyKind, id: i32, name: &str) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let updated = conn.execute(&format!("UPDATE {} SET name = ?1 WHERE id = ?2", Self::table(kind)), params![name, id])?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Taxonomy item not found"));
+        }
+        Ok(())
+    }
+
+    // Links to exercises are removed by ON DELETE CASCADE
+    fn delete(&self, kind: TaxonomyKind, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let deleted = conn.execute(&format!("DELETE FROM {} WHERE id = ?1", Self::table(kind)), params![id])?;
+
+        if deleted == 0 {
+            return Err(AppError::not_found("Taxonomy item not found"));
+        }
+        Ok(())
+    }
+
+    fn get_exercise_taxonomy(&self, exercise_id: i32) -> AppResult<ExerciseTaxonomy> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT mg.id, mg.name, emg.is_primary
+             FROM exercise_muscle_groups emg
+             JOIN muscle_groups mg ON emg.muscle_group_id = mg.id
+             WHERE emg.exercise_id = ?1
+             ORDER BY mg.name"
+        )?;
+        let muscle_groups = stmt
+            .query_map(params![exercise_id], |row| Ok((Self::item_from_row(row)?, row.get::<_, bool>(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut taxonomy = ExerciseTaxonomy::default();
+        for (item, is_primary) in muscle_groups {
+            if is_primary {
+                taxonomy.primary_muscle_groups.push(item);
+            } else {
+                taxonomy.secondary_muscle_groups.push(item);
+            }
+        }
+
+        taxonomy.equipment = Self::linked_items(
+            &conn,
+            "SELECT eq.id, eq.name
+             FROM exercise_equipment ee
+             JOIN equipment eq ON ee.equipment_id = eq.id
+             WHERE ee.exercise_id = ?1
+             ORDER BY eq.name",
+            exercise_id,
+        )?;
+        taxonomy.movement_patterns = Self::linked_items(
+            &conn,
+            "SELECT mp.id, mp.name
+             FROM exercise_movement_patterns emp
+             JOIN movement_patterns mp ON emp.movement_pattern_id = mp.id
+             WHERE emp.exercise_id = ?1
+             ORDER BY mp.name",
+            exercise_id,
+        )?;
+
+        Ok(taxonomy)
+    }
+
+    fn set_exercise_taxonomy(&self, exercise_id: i32, taxonomy: &ExerciseTaxonomyUpdate) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        // The whole classification is replaced at once
+        let tx = conn.unchecked_transaction()?;
+
+        tx.execute("DELETE FROM exercise_muscle_groups WHERE exercise_id = ?1", params![exercise_id])?;
+        tx.execute("DELETE FROM exercise_equipment WHERE exercise_id = ?1", params![exercise_id])?;
+        tx.execute("DELETE FROM exercise_movement_patterns WHERE exercise_id = ?1", params![exercise_id])?;
+
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO exercise_muscle_groups (exercise_id, muscle_group_id, is_primary) VALUES (?1, ?2, ?3)"
+            )?;
+            for muscle_group_id in &taxonomy.primary_muscle_group_ids {
+                stmt.execute(params![exercise_id, muscle_group_id, true])?;
+            }
+            for muscle_group_id in &taxonomy.secondary_muscle_group_ids {
+                stmt.execute(params![exercise_id, muscle_group_id, false])?;
+            }
+
+            let mut stmt = tx.prepare_cached("INSERT INTO exercise_equipment (exercise_id, equipment_id) VALUES (?1, ?2)")?;
+            for equipment_id in &taxonomy.equipment_ids {
+                stmt.execute(params![exercise_id, equipment_id])?;
+            }
+
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO exercise_movement_patterns (exercise_id, movement_pattern_id) VALUES (?1, ?2)"
+            )?;
+            for movement_pattern_id in &taxonomy.movement_pattern_ids {
+                stmt.execute(params![exercise_id, movement_pattern_id])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::db::test_pool;
+    use crate::models::exercise::Exercise;
+    use crate::repository::exercise_repository::ExerciseRepository;
+    use crate::repository::sqlite_exercise_repository::SqliteExerciseRepository;
+
+    fn names(items: &[TaxonomyItem]) -> Vec<&str> {
+        items.iter().map(|item| item.name.as_str()).collect()
+    }
+
+    #[test]
+    fn each_kind_keeps_its_own_list() {
+        let (_dir, pool) = test_pool();
+        let repository = SqliteTaxonomyRepository::new(pool);
+
+        for kind in [TaxonomyKind::MuscleGroup, TaxonomyKind::Equipment, TaxonomyKind::MovementPattern] {
+            assert!(repository.list(kind).unwrap().is_empty(), "{} starts empty", kind.as_str());
+        }
+        repository.create(TaxonomyKind::MuscleGroup, "Pecho").unwrap();
+        let back = repository.create(TaxonomyKind::MuscleGroup, "Espalda").unwrap();
+        repository.create(TaxonomyKind::Equipment, "Barra").unwrap();
+
+        assert_eq!(names(&repository.list(TaxonomyKind::MuscleGroup).unwrap()), ["Espalda", "Pecho"]);
+        assert_eq!(names(&repository.list(TaxonomyKind::Equipment).unwrap()), ["Barra"]);
+        assert!(repository.list(TaxonomyKind::MovementPattern).unwrap().is_empty());
+
+        // Names are unique per list regardless of case
+        assert_eq!(repository.create(TaxonomyKind::MuscleGroup, "pecho").unwrap_err().code(), "DUPLICATE");
+        repository.create(TaxonomyKind::MovementPattern, "Pecho").unwrap();
+
+        repository.rename(TaxonomyKind::MuscleGroup, back, "Dorsales").unwrap();
+        assert_eq!(names(&repository.list(TaxonomyKind::MuscleGroup).unwrap()), ["Dorsales", "Pecho"]);
+        assert_eq!(repository.rename(TaxonomyKind::Equipment, back + 100, "Mancuernas").unwrap_err().code(), "NOT_FOUND");
+        assert_eq!(repository.delete(TaxonomyKind::Equipment, back + 100).unwrap_err().code(), "NOT_FOUND");
+    }
+
+    #[test]
+    fn exercise_links_are_replaced_and_follow_deletions() {
+        let (_dir, pool) = test_pool();
+        SqliteExerciseRepository::new(pool.clone())
+            .create(Exercise { id: None, name: "Press banca".into(), code: "PB".into(), measurement_type: Default::default() })
+            .unwrap();
+        let repository = SqliteTaxonomyRepository::new(pool);
+        let chest = repository.create(TaxonomyKind::MuscleGroup, "Pecho").unwrap();
+        let triceps = repository.create(TaxonomyKind::MuscleGroup, "Tríceps").unwrap();
+        let shoulders = repository.create(TaxonomyKind::MuscleGroup, "Hombros").unwrap();
+        let barbell = repository.create(TaxonomyKind::Equipment, "Barra").unwrap();
+        let bench = repository.create(TaxonomyKind::Equipment, "Banco").unwrap();
+        let push = repository.create(TaxonomyKind::MovementPattern, "Empuje horizontal").unwrap();
+
+        repository
+            .set_exercise_taxonomy(
+                1,
+                &ExerciseTaxonomyUpdate {
+                    primary_muscle_group_ids: vec![chest],
+                    secondary_muscle_group_ids: vec![triceps, shoulders],
+                    equipment_ids: vec![barbell, bench],
+                    movement_pattern_ids: vec![push],
+                },
+            )
+            .unwrap();
+        let taxonomy = repository.get_exercise_taxonomy(1).unwrap();
+        assert_eq!(names(&taxonomy.primary_muscle_groups), ["Pecho"]);
+        assert_eq!(names(&taxonomy.secondary_muscle_groups), ["Hombros", "Tríceps"]);
+        assert_eq!(names(&taxonomy.equipment), ["Banco", "Barra"]);
+        assert_eq!(names(&taxonomy.movement_patterns), ["Empuje horizontal"]);
+
+        // Deleting an item drops its links; the rest of the classification stays
+        repository.delete(TaxonomyKind::MuscleGroup, shoulders).unwrap();
+        repository.delete(TaxonomyKind::Equipment, bench).unwrap();
+        let taxonomy = repository.get_exercise_taxonomy(1).unwrap();
+        assert_eq!(names(&taxonomy.secondary_muscle_groups), ["Tríceps"]);
+        assert_eq!(names(&taxonomy.equipment), ["Barra"]);
+
+        // A new classification replaces the whole previous one
+        repository
+            .set_exercise_taxonomy(1, &ExerciseTaxonomyUpdate { primary_muscle_group_ids: vec![triceps], ..Default::default() })
+            .unwrap();
+        let taxonomy = repository.get_exercise_taxonomy(1).unwrap();
+        assert_eq!(names(&taxonomy.primary_muscle_groups), ["Tríceps"]);
+        assert!(taxonomy.secondary_muscle_groups.is_empty() && taxonomy.equipment.is_empty() && taxonomy.movement_patterns.is_empty());
+
+        // A failed update leaves the stored classification untouched
+        let invalid = ExerciseTaxonomyUpdate { primary_muscle_group_ids: vec![chest], equipment_ids: vec![999], ..Default::default() };
+        assert!(repository.set_exercise_taxonomy(1, &invalid).is_err());
+        assert_eq!(names(&repository.get_exercise_taxonomy(1).unwrap().primary_muscle_groups), ["Tríceps"]);
+    }
+}
@@ -1,418 +1,456 @@
-use rusqlite::{Connection, Result as SqliteResult, params};
-use crate::models::workout_entry::{WorkoutEntry, WorkoutEntryWithDetails};
+use std::collections::{HashMap, HashSet};
+use rusqlite::types::Type;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use crate::config::db::{DbPool, PooledConnection};
+use crate::error::{AppError, AppResult};
+use crate::models::workout_entry::{WorkoutEntry, WorkoutEntryStatus, WorkoutEntryWithDetails};
+use crate::models::workout_set::{SetType, WorkoutSet};
 use crate::repository::workout_entry_repository::WorkoutEntryRepository;
 
+// Shared projection for every query returning `WorkoutEntryWithDetails`
+const DETAILS_SELECT: &str =
+    "SELECT
+        we.id, we.person_id, we.exercise_id, we.date, we.sets, we.reps, we.weight, we.notes, we.order_index, we.group_number,
+        we.created_at, we.updated_at,
+        p.name as person_name, p.last_name as person_last_name,
+        e.name as exercise_name, e.code as exercise_code,
+        we.rpe, we.rir, we.tempo, we.rest_seconds,
+        we.duration_seconds, we.distance_meters, we.routine_id, we.routine_exercise_id, we.status
+     FROM workout_entries we
+     JOIN people p ON we.person_id = p.id
+     JOIN exercise e ON we.exercise_id = e.id";
+
+const SET_COLUMNS: &str = "id, workout_entry_id, order_index, reps, weight, completed, set_type, created_at, updated_at";
+
+// Keeps `IN (...)` lists well below SQLite's bound parameter limit
+const SET_LOOKUP_CHUNK: usize = 500;
+
 pub struct SqliteWorkoutEntryRepository {
-    db_path: String,
-    is_dummy: bool,
+    pool: Option<DbPool>,
 }
 
 impl SqliteWorkoutEntryRepository {
-    /// Private constructor
-    fn new(db_path: &str, is_dummy: bool) -> Self {
-        Self {
-            db_path: db_path.to_string(),
-            is_dummy,
-        }
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool: Some(pool) }
     }
 
-    /// Safe constructor: returns error if table creation fails
-    pub fn new_safe(db_path: &str) -> Result<Self, String> {
-        let repo = Self::new(db_path, false);
-        if let Err(e) = repo.create_table() {
-            return Err(format!("Failed to create workout_entries table: {}", e));
-        }
-        Ok(repo)
-    }
-
-    /// Dummy constructor: always returns empty results or errors
+    /// Dummy constructor: every call fails with `DATABASE_UNAVAILABLE`
     pub fn new_dummy() -> Self {
-        Self::new("", true)
+        Self { pool: None }
     }
 
-    fn create_table(&self) -> SqliteResult<()> {
-        if self.is_dummy { return Ok(()); }
-        let conn = self.get_connection()?;
-        
-        // Check if we need to migrate from old schema
-        if self.check_if_migration_needed(&conn)? {
-            self.migrate_date_column(&conn)?;
-        } else if self.check_if_order_migration_needed(&conn)? {
-            self.migrate_order_column(&conn)?;
-        } else if self.check_if_group_migration_needed(&conn)? {
-            self.migrate_group_column(&conn)?;
-        } else {
-            // Create new table with DATE type, order column, and group_number column
-            conn.execute(
-                "CREATE TABLE IF NOT EXISTS workout_entries (
-                    id INTEGER PRIMARY KEY AUTOINCREMENT,
-                    person_id INTEGER NOT NULL,
-                    exercise_id INTEGER NOT NULL,
-                    date DATE NOT NULL,
-                    sets INTEGER,
-                    reps INTEGER,
-                    weight REAL,
-                    notes TEXT,
-                    order_index INTEGER DEFAULT 0,
-                    group_number INTEGER DEFAULT 1,
-                    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                    FOREIGN KEY (person_id) REFERENCES people (id) ON DELETE CASCADE,
-                    FOREIGN KEY (exercise_id) REFERENCES exercise (id) ON DELETE CASCADE
-                )",
-                [],
-            )?;
+    fn get_connection(&self) -> AppResult<PooledConnection> {
+        match &self.pool {
+            Some(pool) => Ok(pool.get()?),
+            None => Err(AppError::database_unavailable("WorkoutEntry repository unavailable")),
         }
+    }
 
-        // Create index for better query performance
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_workout_entries_person_date 
-             ON workout_entries (person_id, date)",
-            [],
+    // Entries planned from a routine keep a copy of its prescription as it is now
+    fn insert_entries(conn: &Connection, workout_entries: Vec<WorkoutEntry>) -> rusqlite::Result<()> {
+        let mut stmt = conn.prepare_cached(
+            "INSERT INTO workout_entries (person_id, exercise_id, date, sets, reps, weight, notes, order_index, group_number, rpe, rir, tempo, rest_seconds, duration_seconds, distance_meters,
+                                          routine_id, routine_exercise_id, status, prescribed_sets, prescribed_reps, prescribed_weight)
+             SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, re.sets, re.reps, re.weight
+             FROM (SELECT 1)
+             LEFT JOIN routine_exercises re ON re.id = COALESCE(
+                 ?17,
+                 (SELECT id FROM routine_exercises WHERE routine_id = ?16 AND exercise_id = ?2)
+             )"
         )?;
 
+        for workout_entry in workout_entries {
+            stmt.execute(params![
+                workout_entry.person_id,
+                workout_entry.exercise_id,
+                workout_entry.date,
+                workout_entry.sets,
+                workout_entry.reps,
+                workout_entry.weight,
+                workout_entry.notes,
+                workout_entry.order_index.unwrap_or(0),
+                workout_entry.group_number.unwrap_or(1),
+                workout_entry.rpe,
+                workout_entry.rir,
+                workout_entry.tempo,
+                workout_entry.rest_seconds,
+                workout_entry.duration_seconds,
+                workout_entry.distance_meters,
+                workout_entry.routine_id,
+                workout_entry.routine_exercise_id,
+                workout_entry.status.as_str()
+            ])?;
+
+            let workout_entry_id = conn.last_insert_rowid() as i32;
+            for workout_set in workout_entry.workout_sets.iter().flatten() {
+                Self::insert_set(conn, workout_entry_id, workout_set)?;
+            }
+        }
         Ok(())
     }
 
-    fn check_if_migration_needed(&self, conn: &Connection) -> SqliteResult<bool> {
-        if self.is_dummy { return Ok(false); }
-        // Check if table exists and has TEXT date column
-        let mut stmt = conn.prepare(
-            "SELECT sql FROM sqlite_master WHERE type='table' AND name='workout_entries'"
-        )?;
-        
-        let table_sql: Result<String, _> = stmt.query_row([], |row| {
-            Ok(row.get::<_, String>(0)?)
-        });
-        
-        match table_sql {
-            Ok(sql) => Ok(sql.contains("date TEXT")),
-            Err(_) => Ok(false), // Table doesn't exist, no migration needed
+    fn insert_set(conn: &Connection, workout_entry_id: i32, workout_set: &WorkoutSet) -> rusqlite::Result<i32> {
+        conn.prepare_cached(
+            "INSERT INTO workout_sets (workout_entry_id, order_index, reps, weight, completed, set_type)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+        )?.execute(params![
+            workout_entry_id,
+            workout_set.order_index,
+            workout_set.reps,
+            workout_set.weight,
+            workout_set.completed,
+            workout_set.set_type.as_str()
+        ])?;
+
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    fn update_session_entry(conn: &Connection, workout_entry: &WorkoutEntry) -> rusqlite::Result<()> {
+        // The stored prescription only follows the routine when the entry is linked to another exercise
+        conn.prepare_cached(
+            "UPDATE workout_entries
+             SET (prescribed_sets, prescribed_reps, prescribed_weight) = (
+                 SELECT re.sets, re.reps, re.weight
+                 FROM (SELECT 1)
+                 LEFT JOIN routine_exercises re ON re.id = COALESCE(
+                     ?3,
+                     (SELECT id FROM routine_exercises WHERE routine_id = ?2 AND exercise_id = ?1)
+                 )
+             )
+             WHERE id = ?4 AND (exercise_id IS NOT ?1 OR routine_id IS NOT ?2 OR routine_exercise_id IS NOT ?3)"
+        )?.execute(params![
+            workout_entry.exercise_id,
+            workout_entry.routine_id,
+            workout_entry.routine_exercise_id,
+            workout_entry.id
+        ])?;
+
+        conn.prepare_cached(
+            "UPDATE workout_entries
+             SET exercise_id = ?1, sets = ?2, reps = ?3, weight = ?4, notes = ?5, order_index = ?6, group_number = ?7, rpe = ?8, rir = ?9,
+                 tempo = ?10, rest_seconds = ?11, duration_seconds = ?12, distance_meters = ?13, routine_id = ?14, routine_exercise_id = ?15,
+                 status = ?16, updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?17"
+        )?.execute(params![
+            workout_entry.exercise_id,
+            workout_entry.sets,
+            workout_entry.reps,
+            workout_entry.weight,
+            workout_entry.notes,
+            workout_entry.order_index.unwrap_or(0),
+            workout_entry.group_number.unwrap_or(1),
+            workout_entry.rpe,
+            workout_entry.rir,
+            workout_entry.tempo,
+            workout_entry.rest_seconds,
+            workout_entry.duration_seconds,
+            workout_entry.distance_meters,
+            workout_entry.routine_id,
+            workout_entry.routine_exercise_id,
+            workout_entry.status.as_str(),
+            workout_entry.id
+        ])?;
+
+        // Sets are edited on their own; a session saved without them leaves them untouched,
+        // while an empty list clears them
+        if let (Some(workout_entry_id), Some(workout_sets)) = (workout_entry.id, &workout_entry.workout_sets) {
+            Self::sync_sets(conn, workout_entry_id, workout_sets)?;
         }
+        Ok(())
     }
 
-    fn check_if_order_migration_needed(&self, conn: &Connection) -> SqliteResult<bool> {
-        if self.is_dummy { return Ok(false); }
-        // Check if table exists but doesn't have order_index column
-        let mut stmt = conn.prepare(
-            "SELECT sql FROM sqlite_master WHERE type='table' AND name='workout_entries'"
-        )?;
-        
-        let table_sql: Result<String, _> = stmt.query_row([], |row| {
-            Ok(row.get::<_, String>(0)?)
-        });
-        
-        match table_sql {
-            Ok(sql) => Ok(!sql.contains("order_index") && sql.contains("date DATE")),
-            Err(_) => Ok(false), // Table doesn't exist, no migration needed
+    // Updates the entry's sets in place by id, inserts the new ones and deletes the rest
+    fn sync_sets(conn: &Connection, workout_entry_id: i32, workout_sets: &[WorkoutSet]) -> rusqlite::Result<()> {
+        let existing_ids = conn
+            .prepare_cached("SELECT id FROM workout_sets WHERE workout_entry_id = ?1")?
+            .query_map(params![workout_entry_id], |row| row.get::<_, i32>(0))?
+            .collect::<Result<HashSet<_>, _>>()?;
+
+        let mut kept_ids = HashSet::new();
+        for workout_set in workout_sets {
+            match workout_set.id.filter(|id| existing_ids.contains(id)) {
+                Some(id) => {
+                    conn.prepare_cached(
+                        "UPDATE workout_sets
+                         SET order_index = ?1, reps = ?2, weight = ?3, completed = ?4, set_type = ?5, updated_at = CURRENT_TIMESTAMP
+                         WHERE id = ?6"
+                    )?.execute(params![
+                        workout_set.order_index,
+                        workout_set.reps,
+                        workout_set.weight,
+                        workout_set.completed,
+                        workout_set.set_type.as_str(),
+                        id
+                    ])?;
+                    kept_ids.insert(id);
+                }
+                None => {
+                    Self::insert_set(conn, workout_entry_id, workout_set)?;
+                }
+            }
+        }
+
+        for id in existing_ids.difference(&kept_ids) {
+            conn.prepare_cached("DELETE FROM workout_sets WHERE id = ?1")?.execute(params![id])?;
         }
+        Ok(())
     }
 
-    fn check_if_group_migration_needed(&self, conn: &Connection) -> SqliteResult<bool> {
-        if self.is_dummy { return Ok(false); }
-        // Check if table exists but doesn't have group_number column
-        let mut stmt = conn.prepare(
-            "SELECT sql FROM sqlite_master WHERE type='table' AND name='workout_entries'"
-        )?;
-        
-        let table_sql: Result<String, _> = stmt.query_row([], |row| {
-            Ok(row.get::<_, String>(0)?)
-        });
-        
-        match table_sql {
-            Ok(sql) => Ok(!sql.contains("group_number") && sql.contains("order_index")),
-            Err(_) => Ok(false), // Table doesn't exist, no migration needed
+    fn load_sets(conn: &Connection, workout_entry_ids: &[i32]) -> rusqlite::Result<HashMap<i32, Vec<WorkoutSet>>> {
+        let mut sets_by_entry: HashMap<i32, Vec<WorkoutSet>> = HashMap::new();
+
+        for chunk in workout_entry_ids.chunks(SET_LOOKUP_CHUNK) {
+            let placeholders = chunk.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM workout_sets WHERE workout_entry_id IN ({}) ORDER BY order_index, id",
+                SET_COLUMNS, placeholders
+            ))?;
+
+            let rows = stmt.query_map(params_from_iter(chunk.iter()), Self::set_from_row)?;
+            for workout_set in rows {
+                let workout_set = workout_set?;
+                sets_by_entry.entry(workout_set.workout_entry_id).or_default().push(workout_set);
+            }
         }
+
+        Ok(sets_by_entry)
     }
 
-    fn migrate_order_column(&self, conn: &Connection) -> SqliteResult<()> {
-        if self.is_dummy { return Ok(()); }
-        println!("Adding order_index column to workout_entries table...");
-        
-        // Add the order_index column with default value 0
-        conn.execute(
-            "ALTER TABLE workout_entries ADD COLUMN order_index INTEGER DEFAULT 0",
-            [],
-        )?;
-        
-        println!("Order column migration completed successfully!");
+    fn attach_sets(conn: &Connection, workout_entries: &mut [WorkoutEntryWithDetails]) -> rusqlite::Result<()> {
+        let ids: Vec<i32> = workout_entries.iter().filter_map(|entry| entry.id).collect();
+        let mut sets_by_entry = Self::load_sets(conn, &ids)?;
+
+        for workout_entry in workout_entries.iter_mut() {
+            if let Some(sets) = workout_entry.id.and_then(|id| sets_by_entry.remove(&id)) {
+                workout_entry.workout_sets = sets;
+            }
+        }
         Ok(())
     }
 
-    fn migrate_date_column(&self, conn: &Connection) -> SqliteResult<()> {
-        if self.is_dummy { return Ok(()); }
-        println!("Migrating workout_entries table from TEXT to DATE...");
-        
-        // Start transaction
-        let tx = conn.unchecked_transaction()?;
-        
-        // Create new table with DATE type and order column
-        tx.execute(
-            "CREATE TABLE workout_entries_new (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                person_id INTEGER NOT NULL,
-                exercise_id INTEGER NOT NULL,
-                date DATE NOT NULL,
-                sets INTEGER,
-                reps INTEGER,
-                weight REAL,
-                notes TEXT,
-                order_index INTEGER DEFAULT 0,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (person_id) REFERENCES people (id) ON DELETE CASCADE,
-                FOREIGN KEY (exercise_id) REFERENCES exercise (id) ON DELETE CASCADE
-            )",
-            [],
-        )?;
-        
-        // Copy data from old table to new table, ensuring date format is correct
-        tx.execute(
-            "INSERT INTO workout_entries_new (id, person_id, exercise_id, date, sets, reps, weight, notes, order_index, created_at, updated_at)
-             SELECT id, person_id, exercise_id, 
-                    CASE 
-                        WHEN length(date) = 10 AND date LIKE '____-__-__' THEN date
-                        ELSE date('now')
-                    END as date,
-                    sets, reps, weight, notes, 0 as order_index, created_at, updated_at
-             FROM workout_entries",
-            [],
-        )?;
-        
-        // Drop old table
-        tx.execute("DROP TABLE workout_entries", [])?;
-        
-        // Rename new table
-        tx.execute("ALTER TABLE workout_entries_new RENAME TO workout_entries", [])?;
-        
-        // Commit transaction
-        tx.commit()?;
-        
-        println!("Migration completed successfully!");
-        Ok(())
+    fn set_from_row(row: &Row) -> rusqlite::Result<WorkoutSet> {
+        let set_type: String = row.get(6)?;
+        let set_type = SetType::parse(&set_type).ok_or_else(|| {
+            rusqlite::Error::FromSqlConversionFailure(6, Type::Text, format!("Unknown set type '{}'", set_type).into())
+        })?;
+
+        Ok(WorkoutSet {
+            id: Some(row.get(0)?),
+            workout_entry_id: row.get(1)?,
+            order_index: row.get(2)?,
+            reps: row.get(3)?,
+            weight: row.get(4)?,
+            completed: row.get(5)?,
+            set_type,
+            created_at: row.get(7)?,
+            updated_at: row.get(8)?,
+        })
     }
 
-    fn migrate_group_column(&self, conn: &Connection) -> SqliteResult<()> {
-        if self.is_dummy { return Ok(()); }
-        println!("Adding group_number column to workout_entries table...");
-        
-        // Add the group_number column with default value 1
-        conn.execute(
-            "ALTER TABLE workout_entries ADD COLUMN group_number INTEGER DEFAULT 1",
-            [],
-        )?;
-        
-        println!("Group column migration completed successfully!");
+    // A set recorded as done means its planned entry was trained
+    fn mark_entry_performed(conn: &Connection, workout_set_id: i32) -> rusqlite::Result<()> {
+        conn.prepare_cached(
+            "UPDATE workout_entries SET status = 'performed'
+             WHERE id = (SELECT workout_entry_id FROM workout_sets WHERE id = ?1) AND status = 'planned'"
+        )?.execute(params![workout_set_id])?;
         Ok(())
     }
 
-    fn get_connection(&self) -> SqliteResult<Connection> {
-        if self.is_dummy {
-            return Err(rusqlite::Error::InvalidQuery);
-        }
-        Connection::open(&self.db_path)
+    fn status_from_row(row: &Row, idx: usize) -> rusqlite::Result<WorkoutEntryStatus> {
+        let status: String = row.get(idx)?;
+        WorkoutEntryStatus::parse(&status).ok_or_else(|| {
+            rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, format!("Unknown workout entry status '{}'", status).into())
+        })
+    }
+
+    fn entry_from_row(row: &Row) -> rusqlite::Result<WorkoutEntry> {
+        Ok(WorkoutEntry {
+            id: Some(row.get(0)?),
+            person_id: row.get(1)?,
+            exercise_id: row.get(2)?,
+            date: row.get(3)?,
+            sets: row.get(4)?,
+            reps: row.get(5)?,
+            weight: row.get(6)?,
+            notes: row.get(7)?,
+            order_index: row.get(8)?,
+            group_number: row.get(9)?,
+            rpe: row.get(12)?,
+            rir: row.get(13)?,
+            tempo: row.get(14)?,
+            rest_seconds: row.get(15)?,
+            duration_seconds: row.get(16)?,
+            distance_meters: row.get(17)?,
+            routine_id: row.get(18)?,
+            routine_exercise_id: row.get(19)?,
+            status: Self::status_from_row(row, 20)?,
+            created_at: row.get(10)?,
+            updated_at: row.get(11)?,
+            workout_sets: None,
+        })
+    }
+
+    fn details_from_row(row: &Row) -> rusqlite::Result<WorkoutEntryWithDetails> {
+        Ok(WorkoutEntryWithDetails {
+            id: Some(row.get(0)?),
+            person_id: row.get(1)?,
+            exercise_id: row.get(2)?,
+            date: row.get(3)?,
+            sets: row.get(4)?,
+            reps: row.get(5)?,
+            weight: row.get(6)?,
+            notes: row.get(7)?,
+            order_index: row.get(8)?,
+            group_number: row.get(9)?,
+            rpe: row.get(16)?,
+            rir: row.get(17)?,
+            tempo: row.get(18)?,
+            rest_seconds: row.get(19)?,
+            duration_seconds: row.get(20)?,
+            distance_meters: row.get(21)?,
+            routine_id: row.get(22)?,
+            routine_exercise_id: row.get(23)?,
+            status: Self::status_from_row(row, 24)?,
+            created_at: row.get(10)?,
+            updated_at: row.get(11)?,
+            person_name: row.get(12)?,
+            person_last_name: row.get(13)?,
+            exercise_name: row.get(14)?,
+            exercise_code: row.get(15)?,
+            workout_sets: Vec::new(),
+        })
     }
 }
 
 impl WorkoutEntryRepository for SqliteWorkoutEntryRepository {
-    fn create(&self, workout_entry: WorkoutEntry) -> Result<(), String> {
-        if self.is_dummy { return Err("WorkoutEntry repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
-        conn.execute(
-            "INSERT INTO workout_entries (person_id, exercise_id, date, sets, reps, weight, notes, order_index, group_number)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-            params![
-                workout_entry.person_id,
-                workout_entry.exercise_id,
-                workout_entry.date,
-                workout_entry.sets,
-                workout_entry.reps,
-                workout_entry.weight,
-                workout_entry.notes,
-                workout_entry.order_index.unwrap_or(0),
-                workout_entry.group_number.unwrap_or(1)
-            ],
-        ).map_err(|e| e.to_string())?;
+    fn create(&self, workout_entry: WorkoutEntry) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        // The entry and its sets are written together
+        let tx = conn.unchecked_transaction()?;
+        Self::insert_entries(&tx, vec![workout_entry])?;
+        tx.commit()?;
 
         Ok(())
     }
 
-    fn create_batch(&self, workout_entries: Vec<WorkoutEntry>) -> Result<(), String> {
-        if self.is_dummy { return Err("WorkoutEntry repository unavailable".to_string()); }
+    fn create_batch(&self, workout_entries: Vec<WorkoutEntry>) -> AppResult<()> {
         if workout_entries.is_empty() {
             return Ok(());
         }
 
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+        let conn = self.get_connection()?;
+
         // Start a transaction for batch insert
-        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
-        
-        {
-            let mut stmt = tx.prepare(
-                "INSERT INTO workout_entries (person_id, exercise_id, date, sets, reps, weight, notes, order_index, group_number)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"
-            ).map_err(|e| e.to_string())?;
-
-            for workout_entry in workout_entries {
-                stmt.execute(params![
-                    workout_entry.person_id,
-                    workout_entry.exercise_id,
-                    workout_entry.date,
-                    workout_entry.sets,
-                    workout_entry.reps,
-                    workout_entry.weight,
-                    workout_entry.notes,
-                    workout_entry.order_index.unwrap_or(0),
-                    workout_entry.group_number.unwrap_or(1)
-                ]).map_err(|e| e.to_string())?;
+        let tx = conn.unchecked_transaction()?;
+        Self::insert_entries(&tx, workout_entries)?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    // Skips entries whose person already has the exercise on that date. The check runs in
+    // the insert's transaction, so two schedules of the same range cannot both write it.
+    fn create_planned(&self, workout_entries: Vec<WorkoutEntry>) -> AppResult<Vec<WorkoutEntry>> {
+        let conn = self.get_connection()?;
+
+        let tx = conn.unchecked_transaction()?;
+        let mut written = Vec::new();
+        for workout_entry in workout_entries {
+            let exists: bool = tx
+                .prepare_cached(
+                    "SELECT EXISTS (
+                         SELECT 1 FROM workout_entries WHERE person_id = ?1 AND date(date) = date(?2) AND exercise_id = ?3
+                     )"
+                )?
+                .query_row(params![workout_entry.person_id, workout_entry.date, workout_entry.exercise_id], |row| row.get(0))?;
+            if !exists {
+                Self::insert_entries(&tx, vec![workout_entry.clone()])?;
+                written.push(workout_entry);
             }
         }
-        
-        // Commit the transaction
-        tx.commit().map_err(|e| e.to_string())?;
-        
-        Ok(())
+        tx.commit()?;
+
+        Ok(written)
     }
 
-    fn get_by_id(&self, id: i32) -> Option<WorkoutEntry> {
-        if self.is_dummy { return None; }
-        let conn = self.get_connection().ok()?;
-        
-        let mut stmt = conn.prepare(
-            "SELECT id, person_id, exercise_id, date, sets, reps, weight, notes, order_index, group_number, created_at, updated_at
+    fn get_by_id(&self, id: i32) -> AppResult<Option<WorkoutEntry>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, person_id, exercise_id, date, sets, reps, weight, notes, order_index, group_number, created_at, updated_at,
+                    rpe, rir, tempo, rest_seconds, duration_seconds, distance_meters, routine_id, routine_exercise_id, status
              FROM workout_entries WHERE id = ?1"
-        ).ok()?;
-
-        let workout_entry = stmt.query_row(params![id], |row| {
-            Ok(WorkoutEntry {
-                id: Some(row.get(0)?),
-                person_id: row.get(1)?,
-                exercise_id: row.get(2)?,
-                date: row.get(3)?,
-                sets: row.get(4)?,
-                reps: row.get(5)?,
-                weight: row.get(6)?,
-                notes: row.get(7)?,
-                order_index: row.get(8)?,
-                group_number: row.get(9)?,
-                created_at: row.get(10)?,
-                updated_at: row.get(11)?,
-            })
-        }).ok()?;
-
-        Some(workout_entry)
-    }
-
-    fn get_by_person_and_date_range(&self, person_id: i32, start_date: &str, end_date: &str) -> Vec<WorkoutEntryWithDetails> {
-        if self.is_dummy { return Vec::new(); }
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
-
-        let mut stmt = match conn.prepare(
-            "SELECT 
-                we.id, we.person_id, we.exercise_id, we.date, we.sets, we.reps, we.weight, we.notes, we.order_index, we.group_number,
-                we.created_at, we.updated_at,
-                p.name as person_name, p.last_name as person_last_name,
-                e.name as exercise_name, e.code as exercise_code
-             FROM workout_entries we
-             JOIN people p ON we.person_id = p.id
-             JOIN exercise e ON we.exercise_id = e.id
+        )?;
+
+        let mut workout_entry = stmt.query_row(params![id], Self::entry_from_row).optional()?;
+        if let Some(entry) = workout_entry.as_mut() {
+            entry.workout_sets = Some(Self::load_sets(&conn, &[id])?.remove(&id).unwrap_or_default());
+        }
+        Ok(workout_entry)
+    }
+
+    fn get_by_person_and_date_range(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<Vec<WorkoutEntryWithDetails>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "{}
              WHERE we.person_id = ?1 AND date(we.date) >= date(?2) AND date(we.date) <= date(?3)
-             ORDER BY we.date DESC, we.order_index ASC, we.created_at DESC"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
-
-        let workout_entries = match stmt.query_map(params![person_id, start_date, end_date], |row| {
-            Ok(WorkoutEntryWithDetails {
-                id: Some(row.get(0)?),
-                person_id: row.get(1)?,
-                exercise_id: row.get(2)?,
-                date: row.get(3)?,
-                sets: row.get(4)?,
-                reps: row.get(5)?,
-                weight: row.get(6)?,
-                notes: row.get(7)?,
-                order_index: row.get(8)?,
-                group_number: row.get(9)?,
-                created_at: row.get(10)?,
-                updated_at: row.get(11)?,
-                person_name: row.get(12)?,
-                person_last_name: row.get(13)?,
-                exercise_name: row.get(14)?,
-                exercise_code: row.get(15)?,
-            })
-        }) {
-            Ok(rows) => rows,
-            Err(_) => return Vec::new(),
-        };
-
-        workout_entries.filter_map(|entry| entry.ok()).collect()
-    }
-
-    fn get_by_person(&self, person_id: i32) -> Vec<WorkoutEntryWithDetails> {
-        if self.is_dummy { return Vec::new(); }
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
-
-        let mut stmt = match conn.prepare(
-            "SELECT 
-                we.id, we.person_id, we.exercise_id, we.date, we.sets, we.reps, we.weight, we.notes, we.order_index, we.group_number,
-                we.created_at, we.updated_at,
-                p.name as person_name, p.last_name as person_last_name,
-                e.name as exercise_name, e.code as exercise_code
-             FROM workout_entries we
-             JOIN people p ON we.person_id = p.id
-             JOIN exercise e ON we.exercise_id = e.id
+             ORDER BY we.date DESC, we.order_index ASC, we.created_at DESC",
+            DETAILS_SELECT
+        ))?;
+
+        let mut workout_entries = stmt
+            .query_map(params![person_id, start_date, end_date], Self::details_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::attach_sets(&conn, &mut workout_entries)?;
+        Ok(workout_entries)
+    }
+
+    fn get_by_person(&self, person_id: i32) -> AppResult<Vec<WorkoutEntryWithDetails>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "{}
              WHERE we.person_id = ?1
-             ORDER BY we.date DESC, we.order_index ASC, we.created_at DESC"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
-
-        let workout_entries = match stmt.query_map(params![person_id], |row| {
-            Ok(WorkoutEntryWithDetails {
-                id: Some(row.get(0)?),
-                person_id: row.get(1)?,
-                exercise_id: row.get(2)?,
-                date: row.get(3)?,
-                sets: row.get(4)?,
-                reps: row.get(5)?,
-                weight: row.get(6)?,
-                notes: row.get(7)?,
-                order_index: row.get(8)?,
-                group_number: row.get(9)?,
-                created_at: row.get(10)?,
-                updated_at: row.get(11)?,
-                person_name: row.get(12)?,
-                person_last_name: row.get(13)?,
-                exercise_name: row.get(14)?,
-                exercise_code: row.get(15)?,
-            })
-        }) {
-            Ok(rows) => rows,
-            Err(_) => return Vec::new(),
-        };
+             ORDER BY we.date DESC, we.order_index ASC, we.created_at DESC",
+            DETAILS_SELECT
+        ))?;
+
+        let mut workout_entries = stmt
+            .query_map(params![person_id], Self::details_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::attach_sets(&conn, &mut workout_entries)?;
+        Ok(workout_entries)
+    }
+
+    fn get_by_person_and_exercise(&self, person_id: i32, exercise_id: i32) -> AppResult<Vec<WorkoutEntryWithDetails>> {
+        let conn = self.get_connection()?;
 
-        workout_entries.filter_map(|entry| entry.ok()).collect()
+        let mut stmt = conn.prepare_cached(&format!(
+            "{}
+             WHERE we.person_id = ?1 AND we.exercise_id = ?2
+             ORDER BY we.date DESC, we.order_index ASC, we.created_at DESC",
+            DETAILS_SELECT
+        ))?;
+
+        let mut workout_entries = stmt
+            .query_map(params![person_id, exercise_id], Self::details_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::attach_sets(&conn, &mut workout_entries)?;
+        Ok(workout_entries)
     }
 
-    fn update(&self, workout_entry: WorkoutEntry) -> Result<(), String> {
-        if self.is_dummy { return Err("WorkoutEntry repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
-        conn.execute(
-            "UPDATE workout_entries 
-             SET person_id = ?1, exercise_id = ?2, date = ?3, sets = ?4, reps = ?5, weight = ?6, notes = ?7, order_index = ?8, group_number = ?9
-             WHERE id = ?10",
+    // Sets are managed through the *_set methods and are left untouched here, and
+    // so is the link to the routine the entry was planned from
+    fn update(&self, workout_entry: WorkoutEntry) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let updated = conn.execute(
+            "UPDATE workout_entries
+             SET person_id = ?1, exercise_id = ?2, date = ?3, sets = ?4, reps = ?5, weight = ?6, notes = ?7, order_index = ?8, group_number = ?9,
+                 rpe = ?10, rir = ?11, tempo = ?12, rest_seconds = ?13, duration_seconds = ?14, distance_meters = ?15, status = ?16
+             WHERE id = ?17",
             params![
                 workout_entry.person_id,
                 workout_entry.exercise_id,
@@ -423,230 +461,502 @@ impl WorkoutEntryRepository for SqliteWorkoutEntryRepository {
                 workout_entry.notes,
                 workout_entry.order_index.unwrap_or(0),
                 workout_entry.group_number.unwrap_or(1),
+                workout_entry.rpe,
+                workout_entry.rir,
+                workout_entry.tempo,
+                workout_entry.rest_seconds,
+                workout_entry.duration_seconds,
+                workout_entry.distance_meters,
+                workout_entry.status.as_str(),
                 workout_entry.id
             ],
-        ).map_err(|e| e.to_string())?;
+        )?;
 
+        if updated == 0 {
+            return Err(AppError::not_found("Workout entry not found"));
+        }
         Ok(())
     }
 
-    fn delete(&self, id: i32) -> Result<(), String> {
-        if self.is_dummy { return Err("WorkoutEntry repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
-        conn.execute("DELETE FROM workout_entries WHERE id = ?1", params![id])
-            .map_err(|e| e.to_string())?;
+    fn delete(&self, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let deleted = conn.execute("DELETE FROM workout_entries WHERE id = ?1", params![id])?;
 
+        if deleted == 0 {
+            return Err(AppError::not_found("Workout entry not found"));
+        }
         Ok(())
     }
 
-    fn delete_by_person_and_date(&self, person_id: i32, date: &str) -> Result<(), String> {
-        if self.is_dummy { return Err("WorkoutEntry repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+    fn delete_by_person_and_date(&self, person_id: i32, date: &str) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
         conn.execute(
-            "DELETE FROM workout_entries WHERE person_id = ?1 AND date(date) = date(?2)", 
+            "DELETE FROM workout_entries WHERE person_id = ?1 AND date(date) = date(?2)",
             params![person_id, date]
-        ).map_err(|e| e.to_string())?;
+        )?;
 
         Ok(())
     }
 
-    fn list_all(&self) -> Vec<WorkoutEntryWithDetails> {
-        if self.is_dummy { return Vec::new(); }
-        let conn = match self.get_connection() {
-            Ok(conn) => conn,
-            Err(_) => return Vec::new(),
-        };
-
-        let mut stmt = match conn.prepare(
-            "SELECT 
-                we.id, we.person_id, we.exercise_id, we.date, we.sets, we.reps, we.weight, we.notes, we.order_index, we.group_number,
-                we.created_at, we.updated_at,
-                p.name as person_name, p.last_name as person_last_name,
-                e.name as exercise_name, e.code as exercise_code
-             FROM workout_entries we
-             JOIN people p ON we.person_id = p.id
-             JOIN exercise e ON we.exercise_id = e.id
-             ORDER BY we.date DESC, we.order_index ASC, we.created_at DESC"
-        ) {
-            Ok(stmt) => stmt,
-            Err(_) => return Vec::new(),
-        };
-
-        let workout_entries = match stmt.query_map([], |row| {
-            Ok(WorkoutEntryWithDetails {
-                id: Some(row.get(0)?),
-                person_id: row.get(1)?,
-                exercise_id: row.get(2)?,
-                date: row.get(3)?,
-                sets: row.get(4)?,
-                reps: row.get(5)?,
-                weight: row.get(6)?,
-                notes: row.get(7)?,
-                order_index: row.get(8)?,
-                group_number: row.get(9)?,
-                created_at: row.get(10)?,
-                updated_at: row.get(11)?,
-                person_name: row.get(12)?,
-                person_last_name: row.get(13)?,
-                exercise_name: row.get(14)?,
-                exercise_code: row.get(15)?,
-            })
-        }) {
-            Ok(rows) => rows,
-            Err(_) => return Vec::new(),
-        };
+    fn list_all(&self) -> AppResult<Vec<WorkoutEntryWithDetails>> {
+        let conn = self.get_connection()?;
 
-        workout_entries.filter_map(|entry| entry.ok()).collect()
+        let mut stmt = conn.prepare_cached(&format!(
+            "{}
+             ORDER BY we.date DESC, we.order_index ASC, we.created_at DESC",
+            DETAILS_SELECT
+        ))?;
+
+        let mut workout_entries = stmt
+            .query_map([], Self::details_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::attach_sets(&conn, &mut workout_entries)?;
+        Ok(workout_entries)
     }
 
-    fn replace_session(&self, person_id: i32, date: &str, workout_entries: Vec<WorkoutEntry>) -> Result<(), String> {
-        if self.is_dummy { return Err("WorkoutEntry repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+    fn replace_session(&self, person_id: i32, date: &str, workout_entries: Vec<WorkoutEntry>) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
         // Start a transaction for the replace operation
-        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
-        
-        {
-            // First, delete existing entries for this person and date
-            tx.execute(
-                "DELETE FROM workout_entries WHERE person_id = ?1 AND date(date) = date(?2)",
-                params![person_id, date]
-            ).map_err(|e| e.to_string())?;
-            
-            // Then, insert new entries if any
-            if !workout_entries.is_empty() {
-                let mut stmt = tx.prepare(
-                    "INSERT INTO workout_entries (person_id, exercise_id, date, sets, reps, weight, notes, order_index, group_number)
-                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"
-                ).map_err(|e| e.to_string())?;
-
-                for workout_entry in workout_entries {
-                    stmt.execute(params![
-                        workout_entry.person_id,
-                        workout_entry.exercise_id,
-                        workout_entry.date,
-                        workout_entry.sets,
-                        workout_entry.reps,
-                        workout_entry.weight,
-                        workout_entry.notes,
-                        workout_entry.order_index.unwrap_or(0),
-                        workout_entry.group_number.unwrap_or(1)
-                    ]).map_err(|e| e.to_string())?;
-                }
-            }
+        let tx = conn.unchecked_transaction()?;
+
+        let existing_ids = tx
+            .prepare_cached("SELECT id FROM workout_entries WHERE person_id = ?1 AND date(date) = date(?2)")?
+            .query_map(params![person_id, date], |row| row.get::<_, i32>(0))?
+            .collect::<Result<HashSet<_>, _>>()?;
+
+        // Entries already in the session keep their id and per-set rows; ids from
+        // elsewhere are not trusted and saved as new entries
+        let (kept, new): (Vec<_>, Vec<_>) = workout_entries
+            .into_iter()
+            .partition(|entry| entry.id.is_some_and(|id| existing_ids.contains(&id)));
+        let kept_ids: HashSet<i32> = kept.iter().filter_map(|entry| entry.id).collect();
+
+        for id in existing_ids.difference(&kept_ids) {
+            tx.prepare_cached("DELETE FROM workout_entries WHERE id = ?1")?.execute(params![id])?;
         }
-        
-        // Commit the transaction
-        tx.commit().map_err(|e| e.to_string())?;
-        
+        for workout_entry in &kept {
+            Self::update_session_entry(&tx, workout_entry)?;
+        }
+        Self::insert_entries(&tx, new)?;
+
+        tx.commit()?;
+
         Ok(())
     }
 
-    fn replace_session_granular(&self, ids_to_delete: Vec<i32>, workout_entries_to_insert: Vec<WorkoutEntry>) -> Result<(), String> {
-        if self.is_dummy { return Err("WorkoutEntry repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+    fn replace_session_granular(&self, ids_to_delete: Vec<i32>, workout_entries_to_insert: Vec<WorkoutEntry>) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
         // Start a transaction for the granular replace operation
-        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
-        
-        {
-            // First, delete specific entries by ID
-            if !ids_to_delete.is_empty() {
-                let placeholders = ids_to_delete.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-                let delete_query = format!("DELETE FROM workout_entries WHERE id IN ({})", placeholders);
-                
-                let params: Vec<&dyn rusqlite::ToSql> = ids_to_delete.iter().map(|id| id as &dyn rusqlite::ToSql).collect();
-                tx.execute(&delete_query, &params[..]).map_err(|e| e.to_string())?;
-            }
-            
-            // Then, batch insert new entries
-            if !workout_entries_to_insert.is_empty() {
-                let mut stmt = tx.prepare(
-                    "INSERT INTO workout_entries (person_id, exercise_id, date, sets, reps, weight, notes, order_index, group_number)
-                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"
-                ).map_err(|e| e.to_string())?;
-
-                for workout_entry in workout_entries_to_insert {
-                    stmt.execute(params![
-                        workout_entry.person_id,
-                        workout_entry.exercise_id,
-                        workout_entry.date,
-                        workout_entry.sets,
-                        workout_entry.reps,
-                        workout_entry.weight,
-                        workout_entry.notes,
-                        workout_entry.order_index.unwrap_or(0),
-                        workout_entry.group_number.unwrap_or(1)
-                    ]).map_err(|e| e.to_string())?;
-                }
-            }
+        let tx = conn.unchecked_transaction()?;
+
+        // First, delete specific entries by ID
+        if !ids_to_delete.is_empty() {
+            let placeholders = ids_to_delete.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            let delete_query = format!("DELETE FROM workout_entries WHERE id IN ({})", placeholders);
+
+            let params: Vec<&dyn rusqlite::ToSql> = ids_to_delete.iter().map(|id| id as &dyn rusqlite::ToSql).collect();
+            tx.execute(&delete_query, &params[..])?;
         }
-        
-        // Commit the transaction
-        tx.commit().map_err(|e| e.to_string())?;
-        
+
+        // Then, batch insert new entries
+        Self::insert_entries(&tx, workout_entries_to_insert)?;
+
+        tx.commit()?;
+
         Ok(())
     }
 
-    fn update_exercise_order(&self, exercise_orders: Vec<(i32, i32)>) -> Result<(), String> {
-        if self.is_dummy { return Err("WorkoutEntry repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
-        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
-        
+    fn update_exercise_order(&self, exercise_orders: Vec<(i32, i32)>) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let tx = conn.unchecked_transaction()?;
+
         {
-            let mut stmt = tx.prepare("UPDATE workout_entries SET order_index = ?1 WHERE id = ?2")
-                .map_err(|e| e.to_string())?;
-            
+            let mut stmt = tx.prepare_cached("UPDATE workout_entries SET order_index = ?1 WHERE id = ?2")?;
+
             for (id, order) in exercise_orders {
-                stmt.execute(params![order, id]).map_err(|e| e.to_string())?;
+                stmt.execute(params![order, id])?;
             }
         }
-        
-        tx.commit().map_err(|e| e.to_string())?;
+
+        tx.commit()?;
         Ok(())
     }
 
-    fn renumber_groups(&self, person_id: i32, date: &str) -> Result<(), String> {
-        if self.is_dummy { return Err("WorkoutEntry repository unavailable".to_string()); }
-        let conn = self.get_connection().map_err(|e| e.to_string())?;
-        
+    fn renumber_groups(&self, person_id: i32, date: &str) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
         // Start transaction
-        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
-        
+        let tx = conn.unchecked_transaction()?;
+
         {
             // Get all distinct group numbers for this person and date, ordered by group number
-            let mut stmt = tx.prepare(
-                "SELECT DISTINCT group_number FROM workout_entries 
-                 WHERE person_id = ?1 AND date(date) = date(?2) 
+            let mut stmt = tx.prepare_cached(
+                "SELECT DISTINCT group_number FROM workout_entries
+                 WHERE person_id = ?1 AND date(date) = date(?2)
                  ORDER BY group_number"
-            ).map_err(|e| e.to_string())?;
-            
-            let group_numbers: Result<Vec<i32>, _> = stmt.query_map(params![person_id, date], |row| {
-                Ok(row.get::<_, i32>(0)?)
-            }).map_err(|e| e.to_string())?.collect();
-            
-            let group_numbers = group_numbers.map_err(|e| e.to_string())?;
-            
+            )?;
+
+            let group_numbers = stmt
+                .query_map(params![person_id, date], |row| row.get::<_, i32>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+
             // Renumber groups consecutively starting from 1
             for (new_group_number, old_group_number) in group_numbers.iter().enumerate() {
                 let new_group = (new_group_number + 1) as i32;
-                
+
                 // Only update if the group number needs to change
                 if new_group != *old_group_number {
                     tx.execute(
-                        "UPDATE workout_entries 
-                         SET group_number = ?1 
+                        "UPDATE workout_entries
+                         SET group_number = ?1
                          WHERE person_id = ?2 AND date(date) = date(?3) AND group_number = ?4",
                         params![new_group, person_id, date, old_group_number]
-                    ).map_err(|e| e.to_string())?;
+                    )?;
                 }
             }
         }
-        
-        tx.commit().map_err(|e| e.to_string())?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn mark_performed(&self, person_id: i32, date: &str) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        let logged = conn.execute(
+            "UPDATE workout_entries SET status = 'performed', updated_at = CURRENT_TIMESTAMP
+             WHERE person_id = ?1 AND date(date) = date(?2) AND status = 'planned'",
+            params![person_id, date],
+        )?;
+        Ok(logged as i32)
+    }
+
+    fn get_sets(&self, workout_entry_id: i32) -> AppResult<Vec<WorkoutSet>> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM workout_sets WHERE workout_entry_id = ?1 ORDER BY order_index, id",
+            SET_COLUMNS
+        ))?;
+
+        let workout_sets = stmt
+            .query_map(params![workout_entry_id], Self::set_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(workout_sets)
+    }
+
+    fn create_set(&self, workout_set: WorkoutSet) -> AppResult<i32> {
+        let conn = self.get_connection()?;
+
+        let tx = conn.unchecked_transaction()?;
+        let id = Self::insert_set(&tx, workout_set.workout_entry_id, &workout_set)?;
+        if workout_set.completed {
+            Self::mark_entry_performed(&tx, id)?;
+        }
+        tx.commit()?;
+
+        Ok(id)
+    }
+
+    fn update_set(&self, workout_set: WorkoutSet) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let tx = conn.unchecked_transaction()?;
+        let updated = tx.execute(
+            "UPDATE workout_sets
+             SET order_index = ?1, reps = ?2, weight = ?3, completed = ?4, set_type = ?5, updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?6",
+            params![
+                workout_set.order_index,
+                workout_set.reps,
+                workout_set.weight,
+                workout_set.completed,
+                workout_set.set_type.as_str(),
+                workout_set.id
+            ],
+        )?;
+
+        if updated == 0 {
+            return Err(AppError::not_found("Workout set not found"));
+        }
+        if let (true, Some(id)) = (workout_set.completed, workout_set.id) {
+            Self::mark_entry_performed(&tx, id)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn delete_set(&self, id: i32) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let deleted = conn.execute("DELETE FROM workout_sets WHERE id = ?1", params![id])?;
+
+        if deleted == 0 {
+            return Err(AppError::not_found("Workout set not found"));
+        }
+        Ok(())
+    }
+
+    fn update_set_order(&self, set_orders: Vec<(i32, i32)>) -> AppResult<()> {
+        let conn = self.get_connection()?;
+
+        let tx = conn.unchecked_transaction()?;
+
+        {
+            let mut stmt = tx.prepare_cached("UPDATE workout_sets SET order_index = ?1 WHERE id = ?2")?;
+
+            for (id, order) in set_orders {
+                stmt.execute(params![order, id])?;
+            }
+        }
+
+        tx.commit()?;
         Ok(())
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+    use crate::config::db::test_pool;
+
+    const BENCH_ITERATIONS: u32 = 500;
+
+    // Eight weeks of five-exercise sessions, three sets each, for person 1
+    fn seed(repository: &SqliteWorkoutEntryRepository, conn: &Connection) {
+        conn.execute_batch(
+            "INSERT INTO people (name, last_name, phone) VALUES ('Ana', 'Ruiz', '600000001');
+             INSERT INTO exercise (name, code) VALUES ('Squat', 'SQ'), ('Bench', 'BP'), ('Row', 'RW'), ('Press', 'OHP'), ('Curl', 'CU');",
+        )
+        .unwrap();
+
+        let start = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        for day in 0..56 {
+            let date = (start + chrono::Duration::days(day)).format("%Y-%m-%d").to_string();
+            let entries = (1..=5)
+                .map(|exercise_id| {
+                    let mut entry = WorkoutEntry::new(1, exercise_id, date.clone(), Some(3), Some(8), Some(50.0), None);
+                    entry.order_index = Some(exercise_id - 1);
+                    entry.group_number = Some(exercise_id);
+                    entry.workout_sets = Some(
+                        (0..3)
+                            .map(|order_index| WorkoutSet {
+                                id: None,
+                                workout_entry_id: 0,
+                                order_index,
+                                reps: Some(8),
+                                weight: Some(50.0),
+                                completed: true,
+                                set_type: SetType::Working,
+                                created_at: None,
+                                updated_at: None,
+                            })
+                            .collect(),
+                    );
+                    entry
+                })
+                .collect();
+            repository.create_batch(entries).unwrap();
+        }
+    }
+
+    // What every call cost before the pool: a fresh connection, no pragmas, no statement cache
+    fn open_per_call(path: &std::path::Path, start_date: &str, end_date: &str) -> Vec<WorkoutEntryWithDetails> {
+        let conn = Connection::open(path).unwrap();
+        let mut stmt = conn
+            .prepare(&format!(
+                "{}
+                 WHERE we.person_id = ?1 AND date(we.date) >= date(?2) AND date(we.date) <= date(?3)
+                 ORDER BY we.date DESC, we.order_index ASC, we.created_at DESC",
+                DETAILS_SELECT
+            ))
+            .unwrap();
+        let mut entries = stmt
+            .query_map(params![1, start_date, end_date], SqliteWorkoutEntryRepository::details_from_row)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        SqliteWorkoutEntryRepository::attach_sets(&conn, &mut entries).unwrap();
+        entries
+    }
+
+    fn working_set(order_index: i32, reps: i32, weight: f64) -> WorkoutSet {
+        WorkoutSet {
+            id: None,
+            workout_entry_id: 0,
+            order_index,
+            reps: Some(reps),
+            weight: Some(weight),
+            completed: true,
+            set_type: SetType::Working,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    // Squat with two logged sets and bench without detail, both on 2024-01-01
+    fn saved_session() -> (tempfile::TempDir, SqliteWorkoutEntryRepository, Vec<WorkoutEntryWithDetails>) {
+        let (dir, pool) = test_pool();
+        pool.get()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO people (name, last_name, phone) VALUES ('Ana', 'Ruiz', '600000001');
+                 INSERT INTO exercise (name, code) VALUES ('Squat', 'SQ'), ('Bench', 'BP'), ('Row', 'RW');",
+            )
+            .unwrap();
+        let repository = SqliteWorkoutEntryRepository::new(pool);
+
+        let mut squat = WorkoutEntry::new(1, 1, "2024-01-01".into(), Some(2), Some(5), Some(100.0), None);
+        squat.workout_sets = Some(vec![working_set(0, 5, 100.0), working_set(1, 5, 100.0)]);
+        let mut bench = WorkoutEntry::new(1, 2, "2024-01-01".into(), Some(3), Some(8), Some(60.0), None);
+        bench.order_index = Some(1);
+        bench.group_number = Some(2);
+        repository.create_batch(vec![squat, bench]).unwrap();
+
+        let session = repository.get_by_person_and_date_range(1, "2024-01-01", "2024-01-01").unwrap();
+        (dir, repository, session)
+    }
+
+    fn as_entry(entry: &WorkoutEntryWithDetails, with_sets: bool) -> WorkoutEntry {
+        let mut workout_entry = WorkoutEntry::new(entry.person_id, entry.exercise_id, entry.date.clone(), entry.sets, entry.reps, entry.weight, entry.notes.clone());
+        workout_entry.id = entry.id;
+        workout_entry.order_index = entry.order_index;
+        workout_entry.group_number = entry.group_number;
+        if with_sets {
+            workout_entry.workout_sets = Some(entry.workout_sets.clone());
+        }
+        workout_entry
+    }
+
+    #[test]
+    fn editing_a_session_keeps_its_entries_and_sets() {
+        let (_dir, repository, session) = saved_session();
+        let squat = session.iter().find(|entry| entry.exercise_id == 1).unwrap();
+        let set_ids: Vec<_> = squat.workout_sets.iter().map(|set| set.id).collect();
+        assert_eq!(set_ids.len(), 2);
+
+        // The editor sends the entries without their sets: squat edited, bench dropped, row added
+        let mut edited = as_entry(squat, false);
+        edited.weight = Some(102.5);
+        edited.notes = Some("Felt easy".into());
+        let mut row = WorkoutEntry::new(1, 3, "2024-01-01".into(), Some(3), Some(10), Some(50.0), None);
+        row.order_index = Some(1);
+        row.group_number = Some(2);
+        repository.replace_session(1, "2024-01-01", vec![edited, row]).unwrap();
+
+        let after = repository.get_by_person_and_date_range(1, "2024-01-01", "2024-01-01").unwrap();
+        assert_eq!(after.iter().map(|entry| entry.exercise_id).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(after[0].id, squat.id);
+        assert_eq!(after[0].weight, Some(102.5));
+        assert_eq!(after[0].notes.as_deref(), Some("Felt easy"));
+        assert_eq!(after[0].workout_sets.iter().map(|set| set.id).collect::<Vec<_>>(), set_ids);
+        assert!(after[1].id.is_some() && after[1].id != session[1].id);
+    }
+
+    #[test]
+    fn sets_sent_with_the_session_are_updated_in_place() {
+        let (_dir, repository, session) = saved_session();
+        let squat = session.iter().find(|entry| entry.exercise_id == 1).unwrap();
+        let (first, second) = (squat.workout_sets[0].id, squat.workout_sets[1].id);
+
+        // First set corrected, second removed, a third added
+        let mut edited = as_entry(squat, true);
+        let sets = edited.workout_sets.as_mut().unwrap();
+        sets[0].reps = Some(4);
+        sets.remove(1);
+        sets.push(working_set(2, 3, 100.0));
+        let bench = as_entry(&session[1], true);
+        repository.replace_session(1, "2024-01-01", vec![edited, bench]).unwrap();
+
+        let after = repository.get_sets(squat.id.unwrap()).unwrap();
+        assert_eq!(after.len(), 2);
+        assert_eq!((after[0].id, after[0].reps), (first, Some(4)));
+        assert!(after[1].id.is_some() && after[1].id != second);
+        assert_eq!(after[1].reps, Some(3));
+    }
+
+    #[test]
+    fn an_empty_set_list_clears_the_sets() {
+        let (_dir, repository, session) = saved_session();
+        let squat = session.iter().find(|entry| entry.exercise_id == 1).unwrap();
+
+        let mut cleared = as_entry(squat, false);
+        cleared.workout_sets = Some(Vec::new());
+        repository.replace_session(1, "2024-01-01", vec![cleared, as_entry(&session[1], false)]).unwrap();
+
+        let after = repository.get_by_person_and_date_range(1, "2024-01-01", "2024-01-01").unwrap();
+        assert_eq!(after[0].id, squat.id);
+        assert!(after[0].workout_sets.is_empty());
+        assert_eq!((after[0].sets, after[0].reps, after[0].weight), (Some(2), Some(5), Some(100.0)));
+    }
+
+    #[test]
+    fn ids_from_another_session_are_saved_as_new_entries() {
+        let (_dir, repository, session) = saved_session();
+
+        // Copying 2024-01-01 to 2024-01-08 with the original ids still attached
+        let copy: Vec<_> = session
+            .iter()
+            .map(|entry| {
+                let mut copy = as_entry(entry, false);
+                copy.date = "2024-01-08".into();
+                copy
+            })
+            .collect();
+        repository.replace_session(1, "2024-01-08", copy).unwrap();
+
+        let original = repository.get_by_person_and_date_range(1, "2024-01-01", "2024-01-01").unwrap();
+        assert_eq!(original.iter().map(|entry| entry.id).collect::<Vec<_>>(), session.iter().map(|entry| entry.id).collect::<Vec<_>>());
+        assert_eq!(original[0].workout_sets.len(), 2);
+        let copied = repository.get_by_person_and_date_range(1, "2024-01-08", "2024-01-08").unwrap();
+        assert_eq!(copied.len(), 2);
+        assert!(copied.iter().all(|entry| !session.iter().any(|original| original.id == entry.id)));
+    }
+
+    fn per_call(elapsed: Duration) -> f64 {
+        elapsed.as_secs_f64() * 1_000_000.0 / BENCH_ITERATIONS as f64
+    }
+
+    /// Latency of loading a week of workouts through the pool vs opening a connection per call.
+    /// Run with `cargo test --release -- --ignored --nocapture bench_get_by_person_and_date_range`.
+    #[test]
+    #[ignore]
+    fn bench_get_by_person_and_date_range() {
+        let (dir, pool) = test_pool();
+        let repository = SqliteWorkoutEntryRepository::new(pool.clone());
+        seed(&repository, &pool.get().unwrap());
+        let path = dir.path().join("test.db");
+        let (start_date, end_date) = ("2024-02-05", "2024-02-11");
+
+        // Warm both paths up so the first open or prepare is not measured
+        let expected = repository.get_by_person_and_date_range(1, start_date, end_date).unwrap().len();
+        assert_eq!(expected, 35);
+        assert_eq!(open_per_call(&path, start_date, end_date).len(), expected);
+
+        let started = Instant::now();
+        for _ in 0..BENCH_ITERATIONS {
+            assert_eq!(repository.get_by_person_and_date_range(1, start_date, end_date).unwrap().len(), expected);
+        }
+        let pooled = started.elapsed();
+
+        let started = Instant::now();
+        for _ in 0..BENCH_ITERATIONS {
+            assert_eq!(open_per_call(&path, start_date, end_date).len(), expected);
+        }
+        let unpooled = started.elapsed();
+
+        println!(
+            "get_by_person_and_date_range, {} calls: pooled {:.1} µs/call, open per call {:.1} µs/call ({:.1}x)",
+            BENCH_ITERATIONS,
+            per_call(pooled),
+            per_call(unpooled),
+            unpooled.as_secs_f64() / pooled.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,11 @@
+use crate::error::AppResult;
+use crate::models::taxonomy::{ExerciseTaxonomy, ExerciseTaxonomyUpdate, TaxonomyItem, TaxonomyKind};
+
+pub trait TaxonomyRepository: Send + Sync {
+    fn list(&self, kind: TaxonomyKind) -> AppResult<Vec<TaxonomyItem>>;
+    fn create(&self, kind: TaxonomyKind, name: &str) -> AppResult<i32>;
+    fn rename(&self, kind: TaxonomyKind, id: i32, name: &str) -> AppResult<()>;
+    fn delete(&self, kind: TaxonomyKind, id: i32) -> AppResult<()>;
+    fn get_exercise_taxonomy(&self, exercise_id: i32) -> AppResult<ExerciseTaxonomy>;
+    fn set_exercise_taxonomy(&self, exercise_id: i32, taxonomy: &ExerciseTaxonomyUpdate) -> AppResult<()>;
+}
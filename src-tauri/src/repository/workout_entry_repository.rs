@@ -1,17 +1,27 @@
+use crate::error::AppResult;
 use crate::models::workout_entry::{WorkoutEntry, WorkoutEntryWithDetails};
+use crate::models::workout_set::WorkoutSet;
 
 pub trait WorkoutEntryRepository {
-    fn create(&self, workout_entry: WorkoutEntry) -> Result<(), String>;
-    fn create_batch(&self, workout_entries: Vec<WorkoutEntry>) -> Result<(), String>;
-    fn replace_session(&self, person_id: i32, date: &str, workout_entries: Vec<WorkoutEntry>) -> Result<(), String>;
-    fn replace_session_granular(&self, ids_to_delete: Vec<i32>, workout_entries_to_insert: Vec<WorkoutEntry>) -> Result<(), String>;
-    fn get_by_id(&self, id: i32) -> Option<WorkoutEntry>;
-    fn get_by_person_and_date_range(&self, person_id: i32, start_date: &str, end_date: &str) -> Vec<WorkoutEntryWithDetails>;
-    fn get_by_person(&self, person_id: i32) -> Vec<WorkoutEntryWithDetails>;
-    fn update(&self, workout_entry: WorkoutEntry) -> Result<(), String>;
-    fn delete(&self, id: i32) -> Result<(), String>;
-    fn delete_by_person_and_date(&self, person_id: i32, date: &str) -> Result<(), String>;
-    fn list_all(&self) -> Vec<WorkoutEntryWithDetails>;
-    fn update_exercise_order(&self, exercise_orders: Vec<(i32, i32)>) -> Result<(), String>; // (id, order)
-    fn renumber_groups(&self, person_id: i32, date: &str) -> Result<(), String>;
-} 
\ No newline at end of file
+    fn create(&self, workout_entry: WorkoutEntry) -> AppResult<()>;
+    fn create_batch(&self, workout_entries: Vec<WorkoutEntry>) -> AppResult<()>;
+    fn create_planned(&self, workout_entries: Vec<WorkoutEntry>) -> AppResult<Vec<WorkoutEntry>>; // Those written
+    fn replace_session(&self, person_id: i32, date: &str, workout_entries: Vec<WorkoutEntry>) -> AppResult<()>;
+    fn replace_session_granular(&self, ids_to_delete: Vec<i32>, workout_entries_to_insert: Vec<WorkoutEntry>) -> AppResult<()>;
+    fn get_by_id(&self, id: i32) -> AppResult<Option<WorkoutEntry>>;
+    fn get_by_person_and_date_range(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<Vec<WorkoutEntryWithDetails>>;
+    fn get_by_person(&self, person_id: i32) -> AppResult<Vec<WorkoutEntryWithDetails>>;
+    fn get_by_person_and_exercise(&self, person_id: i32, exercise_id: i32) -> AppResult<Vec<WorkoutEntryWithDetails>>;
+    fn update(&self, workout_entry: WorkoutEntry) -> AppResult<()>;
+    fn delete(&self, id: i32) -> AppResult<()>;
+    fn delete_by_person_and_date(&self, person_id: i32, date: &str) -> AppResult<()>;
+    fn list_all(&self) -> AppResult<Vec<WorkoutEntryWithDetails>>;
+    fn update_exercise_order(&self, exercise_orders: Vec<(i32, i32)>) -> AppResult<()>; // (id, order)
+    fn renumber_groups(&self, person_id: i32, date: &str) -> AppResult<()>;
+    fn mark_performed(&self, person_id: i32, date: &str) -> AppResult<i32>; // Planned entries logged
+    fn get_sets(&self, workout_entry_id: i32) -> AppResult<Vec<WorkoutSet>>;
+    fn create_set(&self, workout_set: WorkoutSet) -> AppResult<i32>;
+    fn update_set(&self, workout_set: WorkoutSet) -> AppResult<()>;
+    fn delete_set(&self, id: i32) -> AppResult<()>;
+    fn update_set_order(&self, set_orders: Vec<(i32, i32)>) -> AppResult<()>; // (id, order)
+}
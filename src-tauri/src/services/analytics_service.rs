@@ -0,0 +1,404 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use crate::error::{AppError, AppResult};
+use crate::models::personal_record::{ExerciseStats, OneRepMaxFormula, PersonalRecord, RecordType, RepsAtWeight};
+use crate::models::workout_entry::{WorkoutEntryStatus, WorkoutEntryWithDetails};
+use crate::models::workout_set::SetType;
+use crate::repository::workout_entry_repository::WorkoutEntryRepository;
+
+// Differences below this are rounding noise, not a new record
+const EPSILON: f64 = 1e-6;
+
+pub struct AnalyticsService {
+    repository: Arc<dyn WorkoutEntryRepository + Send + Sync>,
+}
+
+impl AnalyticsService {
+    pub fn new(repository: Arc<dyn WorkoutEntryRepository + Send + Sync>) -> Self {
+        Self { repository }
+    }
+
+    pub fn get_exercise_stats(&self, person_id: i32, exercise_id: i32, formula: OneRepMaxFormula) -> AppResult<ExerciseStats> {
+        Self::validate_ids(person_id, Some(exercise_id))?;
+
+        let mut entries = self.repository.get_by_person_and_exercise(person_id, exercise_id)?;
+        sort_chronologically(&mut entries);
+
+        let mut tracker = RecordTracker::new(formula);
+        for entry in &entries {
+            tracker.apply(entry);
+        }
+        let sessions = entries
+            .iter()
+            .filter(|entry| entry.status == WorkoutEntryStatus::Performed)
+            .map(|entry| entry.date.as_str())
+            .collect::<HashSet<_>>()
+            .len() as i32;
+
+        let bests = tracker.bests.remove(&exercise_id).unwrap_or_default();
+        Ok(ExerciseStats {
+            person_id,
+            exercise_id,
+            formula,
+            estimated_one_rep_max: bests.one_rep_max.as_ref().map(|best| best.value),
+            estimated_one_rep_max_date: bests.one_rep_max.map(|best| best.date),
+            best_weight: bests.weight.as_ref().map(|best| best.value),
+            best_weight_date: bests.weight.map(|best| best.date),
+            best_volume: bests.volume.as_ref().map(|best| best.value),
+            best_volume_date: bests.volume.map(|best| best.date),
+            reps_at_weight: bests.reps_at_weight.into_values().collect(),
+            sessions,
+        })
+    }
+
+    /// Every record ever set, newest first. Without `exercise_id` all exercises of the person are included.
+    pub fn get_personal_record_history(
+        &self,
+        person_id: i32,
+        exercise_id: Option<i32>,
+        formula: OneRepMaxFormula,
+    ) -> AppResult<Vec<PersonalRecord>> {
+        Self::validate_ids(person_id, exercise_id)?;
+
+        let mut entries = match exercise_id {
+            Some(exercise_id) => self.repository.get_by_person_and_exercise(person_id, exercise_id)?,
+            None => self.repository.get_by_person(person_id)?,
+        };
+        sort_chronologically(&mut entries);
+
+        let mut tracker = RecordTracker::new(formula);
+        let mut records: Vec<PersonalRecord> = entries.iter().flat_map(|entry| tracker.apply(entry)).collect();
+        records.reverse();
+        Ok(records)
+    }
+
+    fn validate_ids(person_id: i32, exercise_id: Option<i32>) -> AppResult<()> {
+        if person_id <= 0 {
+            return Err(AppError::validation("person_id", "Invalid person ID"));
+        }
+        if exercise_id.is_some_and(|id| id <= 0) {
+            return Err(AppError::validation("exercise_id", "Invalid exercise ID"));
+        }
+        Ok(())
+    }
+}
+
+/// Records set by the entries saved on `session_date`, measured against every other day
+/// on record for the person. `entries` is the full history of the person.
+pub fn detect_session_records(
+    session_date: &str,
+    entries: Vec<WorkoutEntryWithDetails>,
+    formula: OneRepMaxFormula,
+) -> Vec<PersonalRecord> {
+    let (mut session, mut history): (Vec<_>, Vec<_>) = entries.into_iter().partition(|entry| entry.date == session_date);
+    sort_chronologically(&mut history);
+    sort_chronologically(&mut session);
+
+    let mut tracker = RecordTracker::new(formula);
+    for entry in &history {
+        tracker.apply(entry);
+    }
+    session.iter().flat_map(|entry| tracker.apply(entry)).collect()
+}
+
+fn sort_chronologically(entries: &mut [WorkoutEntryWithDetails]) {
+    entries.sort_by(|a, b| a.date.cmp(&b.date).then(a.order_index.cmp(&b.order_index)).then(a.id.cmp(&b.id)));
+}
+
+/// (weight, reps, count) of the sets actually performed, `count` being how many times
+/// that set was done. Per-set detail wins over the entry summary, which is a single
+/// tuple counting its sets; warm-ups, sets not marked completed and planned entries are ignored.
+pub fn performed_sets(entry: &WorkoutEntryWithDetails) -> Vec<(f64, i32, i32)> {
+    if entry.status == WorkoutEntryStatus::Planned {
+        return Vec::new();
+    }
+    if !entry.workout_sets.is_empty() {
+        return entry
+            .workout_sets
+            .iter()
+            .filter(|set| set.completed && set.set_type != SetType::WarmUp)
+            .filter_map(|set| set.reps.filter(|reps| *reps > 0).map(|reps| (set.weight.unwrap_or(0.0), reps, 1)))
+            .collect();
+    }
+
+    match entry.reps.filter(|reps| *reps > 0) {
+        Some(reps) => vec![(entry.weight.unwrap_or(0.0), reps, entry.sets.unwrap_or(1).max(1))],
+        None => Vec::new(),
+    }
+}
+
+// Weights are compared in grams so they can be used as map keys
+fn weight_key(weight: f64) -> i64 {
+    (weight * 1000.0).round() as i64
+}
+
+struct Best {
+    value: f64,
+    date: String,
+}
+
+#[derive(Default)]
+struct ExerciseBests {
+    one_rep_max: Option<Best>,
+    weight: Option<Best>,
+    volume: Option<Best>,
+    reps_at_weight: BTreeMap<i64, RepsAtWeight>,
+}
+
+/// Running bests per exercise; fed entries in chronological order
+struct RecordTracker {
+    formula: OneRepMaxFormula,
+    bests: HashMap<i32, ExerciseBests>,
+}
+
+impl RecordTracker {
+    fn new(formula: OneRepMaxFormula) -> Self {
+        Self { formula, bests: HashMap::new() }
+    }
+
+    /// Updates the bests with `entry` and returns the records it beats. The first
+    /// session of an exercise only sets the baseline.
+    fn apply(&mut self, entry: &WorkoutEntryWithDetails) -> Vec<PersonalRecord> {
+        let sets = performed_sets(entry);
+        if sets.is_empty() {
+            return Vec::new();
+        }
+
+        let record = |record_type, value, previous_value, set: Option<(f64, i32)>| PersonalRecord {
+            record_type,
+            person_id: entry.person_id,
+            exercise_id: entry.exercise_id,
+            exercise_name: entry.exercise_name.clone(),
+            workout_entry_id: entry.id,
+            date: entry.date.clone(),
+            value,
+            previous_value,
+            weight: set.map(|(weight, _)| weight),
+            reps: set.map(|(_, reps)| reps),
+        };
+
+        let bests = self.bests.entry(entry.exercise_id).or_default();
+        let mut records = Vec::new();
+
+        let one_rep_max = sets
+            .iter()
+            .filter_map(|&(weight, reps, _)| self.formula.estimate(weight, reps).map(|estimate| (estimate, (weight, reps))))
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some((value, set)) = one_rep_max {
+            if let Some(previous) = improve(&mut bests.one_rep_max, value, &entry.date) {
+                records.push(record(RecordType::EstimatedOneRepMax, value, Some(previous), Some(set)));
+            }
+        }
+
+        let heaviest = sets
+            .iter()
+            .map(|&(weight, reps, _)| (weight, reps))
+            .filter(|(weight, _)| *weight > 0.0)
+            .max_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        if let Some(set) = heaviest {
+            if let Some(previous) = improve(&mut bests.weight, set.0, &entry.date) {
+                records.push(record(RecordType::MaxWeight, set.0, Some(previous), Some(set)));
+            }
+        }
+
+        let volume: f64 = sets.iter().map(|(weight, reps, count)| weight * *reps as f64 * *count as f64).sum();
+        if volume > 0.0 {
+            if let Some(previous) = improve(&mut bests.volume, volume, &entry.date) {
+                records.push(record(RecordType::MaxVolume, volume, Some(previous), None));
+            }
+        }
+
+        let mut reps_by_weight: BTreeMap<i64, (f64, i32)> = BTreeMap::new();
+        for &(weight, reps, _) in &sets {
+            let best = reps_by_weight.entry(weight_key(weight)).or_insert((weight, reps));
+            best.1 = best.1.max(reps);
+        }
+        for (key, (weight, reps)) in reps_by_weight {
+            match bests.reps_at_weight.get_mut(&key) {
+                Some(best) if reps > best.reps => {
+                    records.push(record(RecordType::MaxRepsAtWeight, reps as f64, Some(best.reps as f64), Some((weight, reps))));
+                    best.reps = reps;
+                    best.date = entry.date.clone();
+                }
+                Some(_) => {}
+                None => {
+                    bests.reps_at_weight.insert(key, RepsAtWeight { weight, reps, date: entry.date.clone() });
+                }
+            }
+        }
+
+        records
+    }
+}
+
+// Stores `value` when it beats the current best; returns the beaten value. The
+// first value seen becomes the baseline and is not reported.
+fn improve(best: &mut Option<Best>, value: f64, date: &str) -> Option<f64> {
+    match best {
+        Some(current) if value > current.value + EPSILON => {
+            let previous = current.value;
+            *current = Best { value, date: date.to_string() };
+            Some(previous)
+        }
+        Some(_) => None,
+        None => {
+            *best = Some(Best { value, date: date.to_string() });
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::workout_set::WorkoutSet;
+
+    fn entry(id: i32, date: &str, summary: (i32, i32, f64), workout_sets: Vec<WorkoutSet>) -> WorkoutEntryWithDetails {
+        let (sets, reps, weight) = summary;
+        WorkoutEntryWithDetails {
+            id: Some(id),
+            person_id: 1,
+            exercise_id: 1,
+            date: date.into(),
+            sets: Some(sets),
+            reps: Some(reps),
+            weight: Some(weight),
+            notes: None,
+            order_index: Some(0),
+            group_number: Some(1),
+            rpe: None,
+            rir: None,
+            tempo: None,
+            rest_seconds: None,
+            duration_seconds: None,
+            distance_meters: None,
+            routine_id: None,
+            routine_exercise_id: None,
+            status: WorkoutEntryStatus::Performed,
+            created_at: None,
+            updated_at: None,
+            person_name: "Ana".into(),
+            person_last_name: "Ruiz".into(),
+            exercise_name: "Squat".into(),
+            exercise_code: "SQ".into(),
+            workout_sets,
+        }
+    }
+
+    fn set(reps: i32, weight: f64, completed: bool, set_type: SetType) -> WorkoutSet {
+        WorkoutSet {
+            id: None,
+            workout_entry_id: 0,
+            order_index: 0,
+            reps: Some(reps),
+            weight: Some(weight),
+            completed,
+            set_type,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    fn record(records: &[PersonalRecord], record_type: RecordType) -> Option<&PersonalRecord> {
+        records.iter().find(|record| record.record_type == record_type)
+    }
+
+    #[test]
+    fn summary_entry_is_one_tuple_whatever_its_set_count() {
+        let mut many = entry(1, "2024-01-01", (1_000_000, 5, 100.0), Vec::new());
+        assert_eq!(performed_sets(&many), vec![(100.0, 5, 1_000_000)]);
+
+        many.sets = None;
+        assert_eq!(performed_sets(&many), vec![(100.0, 5, 1)]);
+        many.status = WorkoutEntryStatus::Planned;
+        assert!(performed_sets(&many).is_empty());
+    }
+
+    #[test]
+    fn per_set_detail_wins_and_skips_warm_ups_and_missed_sets() {
+        let sets = vec![
+            set(5, 60.0, true, SetType::WarmUp),
+            set(5, 100.0, true, SetType::Working),
+            set(3, 100.0, false, SetType::Working),
+            set(8, 80.0, true, SetType::Working),
+        ];
+        let detailed = entry(1, "2024-01-01", (3, 5, 100.0), sets);
+        assert_eq!(performed_sets(&detailed), vec![(100.0, 5, 1), (80.0, 8, 1)]);
+    }
+
+    #[test]
+    fn first_session_is_only_the_baseline() {
+        let records = detect_session_records("2024-01-01", vec![entry(1, "2024-01-01", (3, 5, 100.0), Vec::new())], OneRepMaxFormula::Epley);
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn heavier_session_beats_every_best() {
+        let entries = vec![entry(1, "2024-01-01", (3, 5, 100.0), Vec::new()), entry(2, "2024-01-08", (3, 5, 105.0), Vec::new())];
+        let records = detect_session_records("2024-01-08", entries, OneRepMaxFormula::Epley);
+
+        let one_rep_max = record(&records, RecordType::EstimatedOneRepMax).unwrap();
+        assert_eq!(one_rep_max.value, 122.5);
+        assert_eq!(one_rep_max.previous_value, Some(116.7));
+        assert_eq!((one_rep_max.weight, one_rep_max.reps), (Some(105.0), Some(5)));
+        assert_eq!(one_rep_max.workout_entry_id, Some(2));
+
+        let weight = record(&records, RecordType::MaxWeight).unwrap();
+        assert_eq!((weight.value, weight.previous_value), (105.0, Some(100.0)));
+
+        // Summary volume counts every set: 3 × 5 × 105
+        let volume = record(&records, RecordType::MaxVolume).unwrap();
+        assert_eq!((volume.value, volume.previous_value), (1575.0, Some(1500.0)));
+
+        // 105 kg had never been lifted before, so it is a baseline
+        assert!(record(&records, RecordType::MaxRepsAtWeight).is_none());
+    }
+
+    #[test]
+    fn more_reps_at_a_known_weight_is_a_record() {
+        let entries = vec![entry(1, "2024-01-01", (3, 5, 100.0), Vec::new()), entry(2, "2024-01-08", (1, 6, 100.0), Vec::new())];
+        let records = detect_session_records("2024-01-08", entries, OneRepMaxFormula::Epley);
+
+        let reps = record(&records, RecordType::MaxRepsAtWeight).unwrap();
+        assert_eq!((reps.value, reps.previous_value), (6.0, Some(5.0)));
+        assert_eq!(reps.weight, Some(100.0));
+        assert_eq!(record(&records, RecordType::EstimatedOneRepMax).unwrap().value, 120.0);
+        // Fewer sets: less volume, same top weight
+        assert!(record(&records, RecordType::MaxVolume).is_none());
+        assert!(record(&records, RecordType::MaxWeight).is_none());
+    }
+
+    #[test]
+    fn matching_a_best_is_not_a_record() {
+        let entries = vec![entry(1, "2024-01-01", (3, 5, 100.0), Vec::new()), entry(2, "2024-01-08", (3, 5, 100.0), Vec::new())];
+        assert!(detect_session_records("2024-01-08", entries, OneRepMaxFormula::Epley).is_empty());
+    }
+
+    #[test]
+    fn session_is_measured_against_later_days_too() {
+        // Logging an old session after a better one was already on record
+        let entries = vec![entry(1, "2024-01-08", (3, 5, 110.0), Vec::new()), entry(2, "2024-01-01", (3, 5, 100.0), Vec::new())];
+        assert!(detect_session_records("2024-01-01", entries, OneRepMaxFormula::Epley).is_empty());
+    }
+
+    #[test]
+    fn formula_choice_changes_the_estimated_max() {
+        let entries = vec![entry(1, "2024-01-01", (1, 1, 110.0), Vec::new()), entry(2, "2024-01-08", (1, 5, 100.0), Vec::new())];
+
+        // Epley: 116.7 beats 110; Brzycki: 112.5 beats it by less
+        let epley = detect_session_records("2024-01-08", entries.clone(), OneRepMaxFormula::Epley);
+        assert_eq!(record(&epley, RecordType::EstimatedOneRepMax).unwrap().value, 116.7);
+        let brzycki = detect_session_records("2024-01-08", entries, OneRepMaxFormula::Brzycki);
+        assert_eq!(record(&brzycki, RecordType::EstimatedOneRepMax).unwrap().value, 112.5);
+    }
+
+    #[test]
+    fn warm_ups_and_planned_entries_set_no_records() {
+        let mut planned = entry(2, "2024-01-08", (3, 5, 150.0), Vec::new());
+        planned.status = WorkoutEntryStatus::Planned;
+        let warm_up = entry(3, "2024-01-08", (1, 5, 100.0), vec![set(10, 140.0, true, SetType::WarmUp)]);
+        let entries = vec![entry(1, "2024-01-01", (3, 5, 100.0), Vec::new()), planned, warm_up];
+
+        assert!(detect_session_records("2024-01-08", entries, OneRepMaxFormula::Epley).is_empty());
+    }
+}
@@ -0,0 +1,503 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use crate::error::{AppError, AppResult};
+use crate::models::assignment::{Assignment, AssignmentWithDetails, PlannedSession, ScheduleSummary};
+use crate::models::program::ProgramExerciseWithDetails;
+use crate::models::workout_entry::{WorkoutEntry, WorkoutEntryStatus};
+use crate::repository::assignment_repository::AssignmentRepository;
+use crate::repository::person_repository::PersonRepository;
+use crate::repository::routine_repository::RoutineRepository;
+use crate::services::program_service::{from_routine_exercise, ProgramService};
+use crate::services::validation::{format_date, parse_date};
+use crate::services::workout_entry_service::WorkoutEntryService;
+
+const MAX_SCHEDULE_RANGE_DAYS: i64 = 366;
+
+#[derive(Default)]
+struct EntryDates {
+    logged: HashSet<String>,
+    scheduled: HashSet<String>,
+}
+
+pub struct AssignmentService {
+    repository: Arc<dyn AssignmentRepository>,
+    person_repository: Arc<dyn PersonRepository>,
+    routine_repository: Arc<dyn RoutineRepository>,
+    program_service: ProgramService,
+    workout_entry_service: WorkoutEntryService,
+}
+
+impl AssignmentService {
+    pub fn new(
+        repository: Arc<dyn AssignmentRepository>,
+        person_repository: Arc<dyn PersonRepository>,
+        routine_repository: Arc<dyn RoutineRepository>,
+        program_service: ProgramService,
+        workout_entry_service: WorkoutEntryService,
+    ) -> Self {
+        Self { repository, person_repository, routine_repository, program_service, workout_entry_service }
+    }
+
+    pub fn create_assignment(&self, assignment: Assignment) -> AppResult<i32> {
+        let assignment = self.validate_assignment(assignment)?;
+        self.repository.create(&assignment)
+    }
+
+    /// The person cannot change; assign the plan to the other person instead
+    pub fn update_assignment(&self, assignment: Assignment) -> AppResult<()> {
+        let id = match assignment.id {
+            Some(id) if id > 0 => id,
+            _ => return Err(AppError::validation("id", "Invalid assignment ID")),
+        };
+        let existing = self.get_assignment(id)?;
+        if existing.person_id != assignment.person_id {
+            return Err(AppError::validation("person_id", "An assignment cannot be moved to another person"));
+        }
+
+        let assignment = self.validate_assignment(assignment)?;
+        self.repository.update(&assignment)
+    }
+
+    /// Workout entries already written from the assignment are kept
+    pub fn delete_assignment(&self, id: i32) -> AppResult<()> {
+        if id <= 0 {
+            return Err(AppError::validation("id", "Invalid assignment ID"));
+        }
+        self.repository.delete(id)
+    }
+
+    pub fn get_person_assignments(&self, person_id: i32) -> AppResult<Vec<AssignmentWithDetails>> {
+        if person_id <= 0 {
+            return Err(AppError::validation("person_id", "Invalid person ID"));
+        }
+        self.repository.list_by_person(person_id, "0001-01-01", "9999-12-31")
+    }
+
+    /// Sessions the person's assignments put between the dates, both included
+    pub fn get_planned_sessions(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<Vec<PlannedSession>> {
+        if person_id <= 0 {
+            return Err(AppError::validation("person_id", "Invalid person ID"));
+        }
+        let (start, end) = validate_range(start_date, end_date)?;
+        let dates = self.entry_dates(person_id, start, end)?;
+
+        let mut sessions = Vec::new();
+        for assignment in self.repository.list_by_person(person_id, &format_date(start), &format_date(end))? {
+            sessions.extend(self.plan(&assignment, start, end, &dates)?);
+        }
+        sessions.sort_by(|a, b| a.date.cmp(&b.date).then(a.assignment_id.cmp(&b.assignment_id)));
+        Ok(sessions)
+    }
+
+    /// What the person is expected to train today
+    pub fn get_due_sessions(&self, person_id: i32) -> AppResult<Vec<PlannedSession>> {
+        let today = format_date(Local::now().date_naive());
+        self.get_planned_sessions(person_id, &today, &today)
+    }
+
+    /// Writes the planned sessions between the dates as planned workout entries, prefilled
+    /// with the prescription, all in one transaction. Dates on which the person already has
+    /// entries are skipped, so running it again over the same range only fills the gaps.
+    pub fn schedule_assignment(&self, id: i32, start_date: &str, end_date: &str) -> AppResult<ScheduleSummary> {
+        let assignment = self.get_assignment(id)?;
+        let (start, end) = validate_range(start_date, end_date)?;
+        let dates = self.entry_dates(assignment.person_id, start, end)?;
+
+        let mut summary = ScheduleSummary { assignment_id: id, ..Default::default() };
+        let mut planned_dates = Vec::new();
+        let mut sessions = Vec::new();
+        for session in self.plan(&assignment, start, end, &dates)? {
+            if session.logged || session.scheduled {
+                summary.skipped_dates.push(session.date);
+                continue;
+            }
+            if session.exercises.is_empty() {
+                continue;
+            }
+
+            let entries = session
+                .exercises
+                .iter()
+                .map(|exercise| planned_entry(assignment.person_id, &session.date, session.routine_id, exercise))
+                .collect::<Vec<_>>();
+            planned_dates.push(session.date);
+            sessions.push(entries);
+        }
+
+        // Entries saved since the dates were read above are not written twice
+        let written = self.workout_entry_service.create_planned_sessions(sessions)?;
+        summary.workout_entries = written.len() as i32;
+        for date in planned_dates {
+            if written.iter().any(|entry| entry.date == date) {
+                summary.created_dates.push(date);
+            } else {
+                summary.skipped_dates.push(date);
+            }
+        }
+        summary.skipped_dates.sort();
+        Ok(summary)
+    }
+
+    fn get_assignment(&self, id: i32) -> AppResult<AssignmentWithDetails> {
+        self.repository.get_by_id(id)?.ok_or_else(|| AppError::not_found("Assignment not found"))
+    }
+
+    // Dates the person trained on and dates that only hold planned entries
+    fn entry_dates(&self, person_id: i32, start: NaiveDate, end: NaiveDate) -> AppResult<EntryDates> {
+        let entries = self
+            .workout_entry_service
+            .get_workout_entries_by_person_and_date_range(person_id, &format_date(start), &format_date(end))?;
+
+        let mut dates = EntryDates::default();
+        for entry in entries {
+            match entry.status {
+                WorkoutEntryStatus::Performed => dates.logged.insert(entry.date),
+                WorkoutEntryStatus::Planned => dates.scheduled.insert(entry.date),
+            };
+        }
+        Ok(dates)
+    }
+
+    // The assignment's sessions between `start` and `end`. Program weeks are 7-day
+    // blocks from the start date; the n-th assigned weekday of a block is program day n.
+    fn plan(
+        &self,
+        assignment: &AssignmentWithDetails,
+        start: NaiveDate,
+        end: NaiveDate,
+        dates: &EntryDates,
+    ) -> AppResult<Vec<PlannedSession>> {
+        let assigned_start = parse_date("start_date", &assignment.start_date)?;
+        let mut last = match &assignment.end_date {
+            Some(end_date) => parse_date("end_date", end_date)?.min(end),
+            None => end,
+        };
+        if let Some(weeks) = assignment.program_weeks {
+            last = last.min(assigned_start + Duration::days(weeks as i64 * 7 - 1));
+        }
+
+        let first = start.max(assigned_start);
+        if first > last {
+            return Ok(Vec::new());
+        }
+
+        let program = match assignment.program_id {
+            Some(program_id) => Some(
+                self.program_service
+                    .get_program(program_id)?
+                    .ok_or_else(|| AppError::not_found("Program not found"))?,
+            ),
+            None => None,
+        };
+        let routine_exercises = match assignment.routine_id {
+            Some(routine_id) => self
+                .routine_repository
+                .get_routine_exercises(routine_id)?
+                .into_iter()
+                .map(from_routine_exercise)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let mut sessions = Vec::new();
+        for offset in 0..=(last - first).num_days() {
+            let date = first + Duration::days(offset);
+            let weekday = date.weekday().number_from_monday() as i32;
+            if !assignment.weekdays.contains(&weekday) {
+                continue;
+            }
+
+            let mut session = PlannedSession {
+                date: format_date(date),
+                assignment_id: assignment.id,
+                routine_id: assignment.routine_id,
+                routine_name: assignment.routine_name.clone(),
+                program_id: assignment.program_id,
+                program_name: assignment.program_name.clone(),
+                week: None,
+                day_index: None,
+                label: None,
+                exercises: routine_exercises.clone(),
+                logged: dates.logged.contains(&format_date(date)),
+                scheduled: dates.scheduled.contains(&format_date(date)),
+            };
+
+            if let Some(program) = &program {
+                let week = ((date - assigned_start).num_days() / 7) as i32 + 1;
+                let block_start = assigned_start + Duration::days((week as i64 - 1) * 7);
+                let day_index = (0..=(date - block_start).num_days())
+                    .map(|days| block_start + Duration::days(days))
+                    .filter(|d| assignment.weekdays.contains(&(d.weekday().number_from_monday() as i32)))
+                    .count() as i32;
+
+                // A weekday without a program day is a rest day
+                match program.days.iter().find(|d| d.week == week && d.day_index == day_index) {
+                    Some(day) => {
+                        session.routine_id = day.routine_id;
+                        session.routine_name = day.routine_name.clone();
+                        session.week = Some(week);
+                        session.day_index = Some(day_index);
+                        session.label = day.label.clone();
+                        session.exercises = day.exercises.clone();
+                    }
+                    None => continue,
+                }
+            }
+            sessions.push(session);
+        }
+        Ok(sessions)
+    }
+
+    fn validate_assignment(&self, mut assignment: Assignment) -> AppResult<Assignment> {
+        if assignment.person_id <= 0 {
+            return Err(AppError::validation("person_id", "Invalid person ID"));
+        }
+        if self.person_repository.get_by_id(assignment.person_id)?.is_none() {
+            return Err(AppError::not_found("Person not found"));
+        }
+
+        let start = parse_date("start_date", &assignment.start_date)?;
+        assignment.start_date = format_date(start);
+        if let Some(end_date) = &assignment.end_date {
+            let end = parse_date("end_date", end_date)?;
+            if end < start {
+                return Err(AppError::validation("end_date", "End date cannot be before start date"));
+            }
+            assignment.end_date = Some(format_date(end));
+        }
+
+        assignment.weekdays.sort_unstable();
+        assignment.weekdays.dedup();
+        if assignment.weekdays.is_empty() {
+            return Err(AppError::validation("weekdays", "Pick at least one weekday"));
+        }
+        if assignment.weekdays.iter().any(|d| !(1..=7).contains(d)) {
+            return Err(AppError::validation("weekdays", "Weekdays go from 1 (Monday) to 7 (Sunday)"));
+        }
+        assignment.notes = assignment.notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+
+        match (assignment.routine_id, assignment.program_id) {
+            (Some(routine_id), None) => {
+                if self.routine_repository.get_by_id(routine_id)?.is_none() {
+                    return Err(AppError::not_found("Routine not found"));
+                }
+            }
+            (None, Some(program_id)) => {
+                let program = self
+                    .program_service
+                    .get_program(program_id)?
+                    .ok_or_else(|| AppError::not_found("Program not found"))?;
+
+                // Days past the number of weekdays would never be scheduled
+                let days_per_week = program.days.iter().map(|d| d.day_index).max().unwrap_or(0);
+                if days_per_week > assignment.weekdays.len() as i32 {
+                    return Err(AppError::validation(
+                        "weekdays",
+                        format!("The program trains {} days a week; pick at least {} weekdays", days_per_week, days_per_week),
+                    ));
+                }
+            }
+            _ => return Err(AppError::validation("routine_id", "Assign either a routine or a program")),
+        }
+
+        Ok(assignment)
+    }
+}
+
+fn planned_entry(person_id: i32, date: &str, routine_id: Option<i32>, exercise: &ProgramExerciseWithDetails) -> WorkoutEntry {
+    let mut entry = WorkoutEntry::new(
+        person_id,
+        exercise.exercise_id,
+        date.to_string(),
+        exercise.sets,
+        exercise.reps,
+        exercise.weight,
+        exercise.notes.clone(),
+    );
+    entry.order_index = Some(exercise.order_index);
+    entry.group_number = exercise.group_number;
+    entry.rpe = exercise.rpe;
+    entry.rir = exercise.rir;
+    entry.tempo = exercise.tempo.clone();
+    entry.rest_seconds = exercise.rest_seconds;
+    entry.duration_seconds = exercise.duration_seconds;
+    entry.distance_meters = exercise.distance_meters;
+    entry.status = WorkoutEntryStatus::Planned;
+    // Inline program exercises have no routine to measure adherence against
+    if exercise.routine_exercise_id.is_some() {
+        entry.routine_id = routine_id;
+        entry.routine_exercise_id = exercise.routine_exercise_id;
+    }
+    entry
+}
+
+fn validate_range(start_date: &str, end_date: &str) -> AppResult<(NaiveDate, NaiveDate)> {
+    let start = parse_date("start_date", start_date)?;
+    let end = parse_date("end_date", end_date)?;
+    if end < start {
+        return Err(AppError::validation("end_date", "End date cannot be before start date"));
+    }
+    if (end - start).num_days() >= MAX_SCHEDULE_RANGE_DAYS {
+        return Err(AppError::validation("end_date", format!("Range cannot exceed {} days", MAX_SCHEDULE_RANGE_DAYS)));
+    }
+    Ok((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::db::{test_pool, DbPool};
+    use crate::models::exercise::Exercise;
+    use crate::models::person::Person;
+    use crate::models::personal_record::OneRepMaxFormula;
+    use crate::models::report::ReportBucket;
+    use crate::models::routine::Routine;
+    use crate::models::routine_exercise::RoutineExercise;
+    use crate::repository::exercise_repository::ExerciseRepository;
+    use crate::repository::sqlite_assignment_repository::SqliteAssignmentRepository;
+    use crate::repository::sqlite_exercise_repository::SqliteExerciseRepository;
+    use crate::repository::sqlite_person_repository::SqlitePersonRepository;
+    use crate::repository::sqlite_program_repository::SqliteProgramRepository;
+    use crate::repository::sqlite_report_repository::SqliteReportRepository;
+    use crate::repository::sqlite_routine_repository::SqliteRoutineRepository;
+    use crate::repository::sqlite_workout_entry_repository::SqliteWorkoutEntryRepository;
+    use crate::services::analytics_service::AnalyticsService;
+    use crate::services::report_service::ReportService;
+
+    // A person with a Monday/Thursday squat routine starting Monday 2024-01-01
+    fn setup(pool: &DbPool) -> (AssignmentService, WorkoutEntryService, i32) {
+        let exercise_repository = Arc::new(SqliteExerciseRepository::new(pool.clone()));
+        exercise_repository
+            .create(Exercise { id: None, name: "Squat".into(), code: "SQ".into(), measurement_type: Default::default() })
+            .unwrap();
+        let person_repository = Arc::new(SqlitePersonRepository::new(pool.clone()));
+        let person: Person =
+            serde_json::from_value(serde_json::json!({ "id": null, "name": "Ana", "last_name": "Ruiz", "phone": "600000001" })).unwrap();
+        person_repository.create(person).unwrap();
+        let routine_repository = Arc::new(SqliteRoutineRepository::new(pool.clone()));
+        let routine_id = routine_repository.create(Routine::new("Legs".into(), "LEGS".into())).unwrap();
+        routine_repository
+            .add_exercise_to_routine(RoutineExercise::new(routine_id, 1, 0, Some(3), Some(5), Some(100.0), None, Some(1)))
+            .unwrap();
+
+        let workout_entry_repository = Arc::new(SqliteWorkoutEntryRepository::new(pool.clone()));
+        let service = AssignmentService::new(
+            Arc::new(SqliteAssignmentRepository::new(pool.clone())),
+            person_repository,
+            routine_repository.clone(),
+            ProgramService::new(Arc::new(SqliteProgramRepository::new(pool.clone())), routine_repository),
+            WorkoutEntryService::new(workout_entry_repository.clone(), exercise_repository.clone()),
+        );
+        let assignment_id = service
+            .create_assignment(Assignment {
+                id: None,
+                person_id: 1,
+                routine_id: Some(routine_id),
+                program_id: None,
+                start_date: "2024-01-01".into(),
+                end_date: None,
+                weekdays: vec![1, 4],
+                notes: None,
+                created_at: None,
+                updated_at: None,
+            })
+            .unwrap();
+        (service, WorkoutEntryService::new(workout_entry_repository, exercise_repository), assignment_id)
+    }
+
+    #[test]
+    fn scheduled_sessions_are_planned_until_logged() {
+        let (_dir, pool) = test_pool();
+        let (service, workout_entries, assignment_id) = setup(&pool);
+
+        let summary = service.schedule_assignment(assignment_id, "2024-01-01", "2024-01-07").unwrap();
+        assert_eq!(summary.created_dates, vec!["2024-01-01", "2024-01-04"]);
+        assert_eq!(summary.workout_entries, 2);
+
+        let entries = workout_entries.get_workout_entries_by_person(1).unwrap();
+        assert!(entries.iter().all(|entry| entry.status == WorkoutEntryStatus::Planned));
+        let sessions = service.get_planned_sessions(1, "2024-01-01", "2024-01-07").unwrap();
+        assert!(sessions.iter().all(|session| session.scheduled && !session.logged));
+
+        // Scheduling again leaves the planned dates alone
+        let again = service.schedule_assignment(assignment_id, "2024-01-01", "2024-01-07").unwrap();
+        assert!(again.created_dates.is_empty());
+        assert_eq!(again.skipped_dates, vec!["2024-01-01", "2024-01-04"]);
+        assert_eq!(workout_entries.get_workout_entries_by_person(1).unwrap().len(), 2);
+
+        workout_entries.log_planned_session(1, "2024-01-01", OneRepMaxFormula::default()).unwrap();
+        let sessions = service.get_planned_sessions(1, "2024-01-01", "2024-01-07").unwrap();
+        assert!(sessions[0].logged && !sessions[0].scheduled);
+        assert!(!sessions[1].logged && sessions[1].scheduled);
+        assert_eq!(
+            workout_entries.log_planned_session(1, "2024-01-01", OneRepMaxFormula::default()).unwrap_err().code(),
+            "NOT_FOUND"
+        );
+    }
+
+    #[test]
+    fn planned_entries_stay_out_of_statistics() {
+        let (_dir, pool) = test_pool();
+        let (service, workout_entries, assignment_id) = setup(&pool);
+        service.schedule_assignment(assignment_id, "2024-01-01", "2024-01-07").unwrap();
+
+        let analytics = AnalyticsService::new(Arc::new(SqliteWorkoutEntryRepository::new(pool.clone())));
+        let reports = ReportService::new(Arc::new(SqliteReportRepository::new(pool.clone())));
+        let stats = analytics.get_exercise_stats(1, 1, OneRepMaxFormula::Epley).unwrap();
+        assert_eq!(stats.sessions, 0);
+        assert!(stats.estimated_one_rep_max.is_none());
+        let tonnage = reports.get_tonnage_report(1, "2024-01-01", "2024-01-07", ReportBucket::Week).unwrap();
+        assert_eq!(tonnage[0].tonnage, 0.0);
+        let adherence = reports.get_adherence_report(1, "2024-01-01", "2024-01-07").unwrap();
+        assert_eq!(adherence.completed_sets, 0);
+
+        workout_entries.log_planned_session(1, "2024-01-04", OneRepMaxFormula::default()).unwrap();
+        let stats = analytics.get_exercise_stats(1, 1, OneRepMaxFormula::Epley).unwrap();
+        assert_eq!(stats.sessions, 1);
+        assert_eq!(stats.best_weight, Some(100.0));
+        let tonnage = reports.get_tonnage_report(1, "2024-01-01", "2024-01-07", ReportBucket::Week).unwrap();
+        assert_eq!(tonnage[0].tonnage, 1500.0);
+        let adherence = reports.get_adherence_report(1, "2024-01-01", "2024-01-07").unwrap();
+        assert_eq!(adherence.completed_sets, 3);
+    }
+
+    #[test]
+    fn scheduling_writes_every_session_or_none() {
+        let (_dir, pool) = test_pool();
+        let (_, workout_entries, _) = setup(&pool);
+
+        let entry = |exercise_id: i32, date: &str| {
+            let mut entry = WorkoutEntry::new(1, exercise_id, date.to_string(), Some(3), Some(5), Some(100.0), None);
+            entry.group_number = Some(1);
+            entry
+        };
+        let sessions = vec![vec![entry(1, "2024-01-01")], vec![entry(0, "2024-01-04")]];
+        assert_eq!(workout_entries.create_planned_sessions(sessions).unwrap_err().field(), Some("exercise_id"));
+
+        // Exercise 2 does not exist: the first session is rolled back with the second
+        let sessions = vec![vec![entry(1, "2024-01-01")], vec![entry(2, "2024-01-04")]];
+        assert!(workout_entries.create_planned_sessions(sessions).is_err());
+        assert!(workout_entries.get_workout_entries_by_person(1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn the_same_session_is_never_planned_twice() {
+        let (_dir, pool) = test_pool();
+        let (_, workout_entries, _) = setup(&pool);
+
+        let entry = |exercise_id: i32, date: &str| {
+            let mut entry = WorkoutEntry::new(1, exercise_id, date.to_string(), Some(3), Some(5), Some(100.0), None);
+            entry.group_number = Some(1);
+            entry
+        };
+        let session = || vec![vec![entry(1, "2024-01-01")]];
+        assert_eq!(workout_entries.create_planned_sessions(session()).unwrap().len(), 1);
+        // Scheduled again after the dates were read, e.g. from a second window
+        assert!(workout_entries.create_planned_sessions(session()).unwrap().is_empty());
+        // Repeated within one batch
+        let written = workout_entries.create_planned_sessions(vec![vec![entry(1, "2024-01-04")], vec![entry(1, "2024-01-04")]]).unwrap();
+        assert_eq!(written.len(), 1);
+        assert_eq!(workout_entries.get_workout_entries_by_person(1).unwrap().len(), 2);
+    }
+}
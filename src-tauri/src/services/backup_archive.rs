@@ -0,0 +1,275 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{Engine as _, engine::general_purpose};
+use chacha20poly1305::aead::{Aead, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit, Nonce};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+pub const ARCHIVE_EXTENSION: &str = ".gymbak";
+pub const MIN_PASSPHRASE_LENGTH: usize = 8;
+
+// Archive layout, version 1:
+//
+//   "QGYMBAK" | version: u8 | header length: u32 LE | header (JSON) | payload
+//
+// The header carries the backup metadata and, for encrypted archives, the key
+// derivation parameters. The payload is the gzipped database, sealed with
+// ChaCha20-Poly1305 under an Argon2id key when a passphrase is set. Everything
+// before the payload is authenticated as associated data, so the metadata
+// cannot be altered either.
+const MAGIC: &[u8] = b"QGYMBAK";
+const FORMAT_VERSION: u8 = 1;
+const PREFIX_LENGTH: usize = MAGIC.len() + 1 + 4;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+
+// Upper bounds for the key derivation parameters read from an archive. The header
+// is untrusted, so without them a crafted file could ask for gigabytes of memory
+// or hours of hashing before the passphrase is even checked.
+const MAX_MEMORY_KIB: u32 = 256 * 1024;
+const MAX_ITERATIONS: u32 = 16;
+const MAX_PARALLELISM: u32 = 8;
+
+#[derive(Serialize, Deserialize)]
+struct ArchiveHeader<M> {
+    metadata: M,
+    encryption: Option<EncryptionParams>,
+}
+
+/// Everything needed to derive the key again; stored so the defaults can change
+#[derive(Serialize, Deserialize)]
+struct EncryptionParams {
+    cipher: String, // "chacha20poly1305"
+    kdf: String,    // "argon2id"
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    salt: String,   // base64
+    nonce: String,  // base64
+}
+
+/// Wraps the gzipped database `payload` in an archive, encrypted when `passphrase` is set
+pub fn seal<M: Serialize>(payload: &[u8], metadata: &M, passphrase: Option<&str>) -> Result<Vec<u8>, String> {
+    let passphrase = match passphrase {
+        Some(passphrase) => passphrase,
+        None => {
+            let mut archive = prefix(&ArchiveHeader { metadata, encryption: None })?;
+            archive.extend_from_slice(payload);
+            return Ok(archive);
+        }
+    };
+
+    let mut salt = [0u8; SALT_LENGTH];
+    let mut nonce = [0u8; NONCE_LENGTH];
+    getrandom::getrandom(&mut salt).map_err(|e| format!("Failed to generate salt: {}", e))?;
+    getrandom::getrandom(&mut nonce).map_err(|e| format!("Failed to generate nonce: {}", e))?;
+
+    let kdf_params = Params::default();
+    let params = EncryptionParams {
+        cipher: "chacha20poly1305".to_string(),
+        kdf: "argon2id".to_string(),
+        memory_kib: kdf_params.m_cost(),
+        iterations: kdf_params.t_cost(),
+        parallelism: kdf_params.p_cost(),
+        salt: general_purpose::STANDARD.encode(salt),
+        nonce: general_purpose::STANDARD.encode(nonce),
+    };
+    let key = derive_key(passphrase, &salt, &params)?;
+
+    let mut archive = prefix(&ArchiveHeader { metadata, encryption: Some(params) })?;
+    let ciphertext = ChaCha20Poly1305::new(&key)
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: payload, aad: &archive })
+        .map_err(|_| "Failed to encrypt backup".to_string())?;
+    archive.extend_from_slice(&ciphertext);
+    Ok(archive)
+}
+
+/// Metadata of an archive and whether it is encrypted, without touching the payload
+pub fn read_header<M: DeserializeOwned>(archive: &[u8]) -> Result<(M, bool), String> {
+    let (header, _) = split::<M>(archive)?;
+    Ok((header.metadata, header.encryption.is_some()))
+}
+
+/// Returns the metadata and the gzipped database, decrypting with `passphrase` when needed
+pub fn open<M: DeserializeOwned>(archive: &[u8], passphrase: Option<&str>) -> Result<(M, Vec<u8>), String> {
+    let (header, payload_start) = split::<M>(archive)?;
+    let (associated_data, payload) = archive.split_at(payload_start);
+
+    let params = match header.encryption {
+        Some(params) => params,
+        None => return Ok((header.metadata, payload.to_vec())),
+    };
+    let passphrase = passphrase.ok_or_else(|| "This backup is encrypted, a passphrase is required".to_string())?;
+    if params.cipher != "chacha20poly1305" || params.kdf != "argon2id" {
+        return Err(format!("Unsupported backup encryption: {} / {}", params.cipher, params.kdf));
+    }
+    if params.memory_kib > MAX_MEMORY_KIB || params.iterations > MAX_ITERATIONS || params.parallelism > MAX_PARALLELISM {
+        return Err("Invalid backup header: key derivation parameters out of range".to_string());
+    }
+
+    let salt = decode(&params.salt, SALT_LENGTH)?;
+    let nonce = decode(&params.nonce, NONCE_LENGTH)?;
+    let key = derive_key(passphrase, &salt, &params)?;
+
+    let plaintext = ChaCha20Poly1305::new(&key)
+        .decrypt(Nonce::from_slice(&nonce), Payload { msg: payload, aad: associated_data })
+        .map_err(|_| "Wrong passphrase or damaged backup".to_string())?;
+    Ok((header.metadata, plaintext))
+}
+
+fn prefix<M: Serialize>(header: &ArchiveHeader<M>) -> Result<Vec<u8>, String> {
+    let header_json = serde_json::to_vec(header)
+        .map_err(|e| format!("Failed to serialize backup header: {}", e))?;
+
+    let mut archive = Vec::with_capacity(PREFIX_LENGTH + header_json.len());
+    archive.extend_from_slice(MAGIC);
+    archive.push(FORMAT_VERSION);
+    archive.extend_from_slice(&(header_json.len() as u32).to_le_bytes());
+    archive.extend_from_slice(&header_json);
+    Ok(archive)
+}
+
+// Parses the header; returns it with the offset where the payload starts
+fn split<M: DeserializeOwned>(archive: &[u8]) -> Result<(ArchiveHeader<M>, usize), String> {
+    if archive.len() < PREFIX_LENGTH || !archive.starts_with(MAGIC) {
+        return Err("Not a backup archive".to_string());
+    }
+    let version = archive[MAGIC.len()];
+    if version != FORMAT_VERSION {
+        return Err(format!("Unsupported backup format version {}, update the app", version));
+    }
+
+    let mut length = [0u8; 4];
+    length.copy_from_slice(&archive[MAGIC.len() + 1..PREFIX_LENGTH]);
+    let payload_start = PREFIX_LENGTH + u32::from_le_bytes(length) as usize;
+    if archive.len() < payload_start {
+        return Err("Backup archive is truncated".to_string());
+    }
+
+    let header = serde_json::from_slice(&archive[PREFIX_LENGTH..payload_start])
+        .map_err(|e| format!("Invalid backup header: {}", e))?;
+    Ok((header, payload_start))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], params: &EncryptionParams) -> Result<Key, String> {
+    let kdf_params = Params::new(params.memory_kib, params.iterations, params.parallelism, Some(KEY_LENGTH))
+        .map_err(|e| format!("Invalid key derivation parameters: {}", e))?;
+
+    let mut key = Key::default();
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, kdf_params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive key: {}", e))?;
+    Ok(key)
+}
+
+fn decode(value: &str, expected_length: usize) -> Result<Vec<u8>, String> {
+    match general_purpose::STANDARD.decode(value) {
+        Ok(bytes) if bytes.len() == expected_length => Ok(bytes),
+        _ => Err("Invalid backup header: bad encryption parameters".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    const PAYLOAD: &[u8] = b"gzipped database";
+    const PASSPHRASE: &str = "correct horse";
+
+    fn metadata() -> Value {
+        json!({ "created_at": "2024-05-01T10:00:00", "total_records": 3 })
+    }
+
+    // Offset where the payload starts, i.e. the end of the authenticated prefix
+    fn payload_start(archive: &[u8]) -> usize {
+        let mut length = [0u8; 4];
+        length.copy_from_slice(&archive[MAGIC.len() + 1..PREFIX_LENGTH]);
+        PREFIX_LENGTH + u32::from_le_bytes(length) as usize
+    }
+
+    // Rewrites the header JSON, keeping the payload as is
+    fn with_header(archive: &[u8], edit: impl FnOnce(&mut Value)) -> Vec<u8> {
+        let start = payload_start(archive);
+        let mut header: Value = serde_json::from_slice(&archive[PREFIX_LENGTH..start]).unwrap();
+        edit(&mut header);
+        let header_json = serde_json::to_vec(&header).unwrap();
+
+        let mut rewritten = archive[..MAGIC.len() + 1].to_vec();
+        rewritten.extend_from_slice(&(header_json.len() as u32).to_le_bytes());
+        rewritten.extend_from_slice(&header_json);
+        rewritten.extend_from_slice(&archive[start..]);
+        rewritten
+    }
+
+    #[test]
+    fn plain_archive_round_trips() {
+        let archive = seal(PAYLOAD, &metadata(), None).unwrap();
+        assert!(archive.starts_with(MAGIC));
+        assert_eq!(read_header::<Value>(&archive).unwrap(), (metadata(), false));
+
+        let (opened, payload) = open::<Value>(&archive, None).unwrap();
+        assert_eq!(opened, metadata());
+        assert_eq!(payload, PAYLOAD);
+    }
+
+    #[test]
+    fn encrypted_archive_round_trips() {
+        let archive = seal(PAYLOAD, &metadata(), Some(PASSPHRASE)).unwrap();
+        assert!(!archive.windows(PAYLOAD.len()).any(|window| window == PAYLOAD));
+        assert_eq!(read_header::<Value>(&archive).unwrap(), (metadata(), true));
+
+        let (opened, payload) = open::<Value>(&archive, Some(PASSPHRASE)).unwrap();
+        assert_eq!(opened, metadata());
+        assert_eq!(payload, PAYLOAD);
+    }
+
+    #[test]
+    fn encrypted_archive_needs_the_right_passphrase() {
+        let archive = seal(PAYLOAD, &metadata(), Some(PASSPHRASE)).unwrap();
+
+        assert!(open::<Value>(&archive, None).unwrap_err().contains("passphrase is required"));
+        assert!(open::<Value>(&archive, Some("wrong passphrase")).unwrap_err().contains("Wrong passphrase"));
+    }
+
+    #[test]
+    fn tampered_header_or_ciphertext_is_rejected() {
+        let archive = seal(PAYLOAD, &metadata(), Some(PASSPHRASE)).unwrap();
+
+        let tampered = with_header(&archive, |header| header["metadata"]["total_records"] = json!(99));
+        assert!(open::<Value>(&tampered, Some(PASSPHRASE)).unwrap_err().contains("damaged"));
+
+        let mut tampered = archive.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 0x01;
+        assert!(open::<Value>(&tampered, Some(PASSPHRASE)).unwrap_err().contains("damaged"));
+    }
+
+    #[test]
+    fn truncated_archive_is_rejected() {
+        let archive = seal(PAYLOAD, &metadata(), Some(PASSPHRASE)).unwrap();
+
+        assert!(open::<Value>(&archive[..PREFIX_LENGTH - 1], Some(PASSPHRASE)).unwrap_err().contains("Not a backup"));
+        assert!(open::<Value>(&archive[..PREFIX_LENGTH + 2], Some(PASSPHRASE)).unwrap_err().contains("truncated"));
+        let cut = payload_start(&archive) + 4;
+        assert!(open::<Value>(&archive[..cut], Some(PASSPHRASE)).unwrap_err().contains("damaged"));
+    }
+
+    #[test]
+    fn oversized_key_derivation_parameters_are_rejected() {
+        let archive = seal(PAYLOAD, &metadata(), Some(PASSPHRASE)).unwrap();
+
+        for (field, value) in [("memory_kib", MAX_MEMORY_KIB + 1), ("iterations", MAX_ITERATIONS + 1), ("parallelism", MAX_PARALLELISM + 1)] {
+            let tampered = with_header(&archive, |header| header["encryption"][field] = json!(value));
+            assert!(open::<Value>(&tampered, Some(PASSPHRASE)).unwrap_err().contains("out of range"), "{}", field);
+        }
+    }
+
+    #[test]
+    fn unknown_format_version_is_rejected() {
+        let mut archive = seal(PAYLOAD, &metadata(), None).unwrap();
+        archive[MAGIC.len()] = FORMAT_VERSION + 1;
+        assert!(open::<Value>(&archive, None).unwrap_err().contains("Unsupported backup format"));
+    }
+}
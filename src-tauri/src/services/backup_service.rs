@@ -1,30 +1,60 @@
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::env;
-use reqwest;
-use base64::{Engine as _, engine::general_purpose};
-use chrono::{Utc, Local};
+use std::sync::Arc;
+use chrono::{Datelike, Utc, Local};
+use rusqlite::Connection;
 use serde_json;
+use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
 use flate2::Compression;
-use std::io::Write;
-use crate::config::db::get_database_path;
-use crate::config::api_keys::RESEND_API_KEY;
+use std::io::{Read, Write};
+use crate::config::db::{get_database_path, run_migrations, DbPool};
+use crate::models::settings::{BackupConfig, BackupTargetConfig};
+use crate::services::backup_archive::{self, ARCHIVE_EXTENSION};
+use crate::services::settings_service::SettingsService;
+
+const BACKUP_FILE_PREFIX: &str = "gym_backup_";
+// Plain gzip backups written before the archive format; still restorable
+const LEGACY_BACKUP_EXTENSION: &str = ".db.gz";
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
 
 pub struct BackupService {
     status_path: String,
     db_path: String,
+    data_dir: PathBuf,
+    pool: Option<DbPool>,
+    settings: Arc<SettingsService>,
 }
 
 impl BackupService {
-    pub fn new() -> Self {
+    /// `pool` is the live database pool; restores are refused without it
+    pub fn new(pool: Option<DbPool>, settings: Arc<SettingsService>) -> Self {
         // Use the same path logic as the database
-        let db_path = get_database_path();
+        Self::at(get_database_path(), pool, settings)
+    }
+
+    fn at(db_path: PathBuf, pool: Option<DbPool>, settings: Arc<SettingsService>) -> Self {
         let data_dir = db_path.parent().unwrap_or(&db_path).to_path_buf();
-        
+
         Self {
             status_path: data_dir.join("backup_status.json").to_string_lossy().to_string(),
             db_path: db_path.to_string_lossy().to_string(),
+            data_dir,
+            pool,
+            settings,
+        }
+    }
+
+    fn get_config(&self) -> BackupConfig {
+        self.settings.get().backup
+    }
+
+    fn backup_dir(&self, config: &BackupConfig) -> PathBuf {
+        match &config.directory {
+            Some(directory) => PathBuf::from(directory),
+            None => self.data_dir.join("backups"),
         }
     }
 
@@ -66,27 +96,6 @@ impl BackupService {
         }
     }
 
-    // Check internet connectivity
-    async fn check_internet_connectivity(&self) -> bool {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .build();
-        
-        if let Ok(client) = client {
-            // Try to reach Google's DNS
-            if let Ok(response) = client.get("https://8.8.8.8").send().await {
-                return response.status().is_success();
-            }
-            
-            // Fallback: try to reach a reliable service
-            if let Ok(response) = client.get("https://httpbin.org/status/200").send().await {
-                return response.status().is_success();
-            }
-        }
-        
-        false
-    }
-
     // Create backup file
     fn create_backup(&self) -> Result<(Vec<u8>, BackupMetadata), String> {
         // Check if database exists
@@ -94,164 +103,282 @@ impl BackupService {
             return Err("Database file not found".to_string());
         }
 
-        // Read database file
-        let db_data = fs::read(&self.db_path)
-            .map_err(|e| format!("Failed to read database: {}", e))?;
+        // Consistent copy of the live database, WAL included, taken while the app keeps writing
+        let snapshot_path = format!("{}.snapshot-{}", self.db_path, Utc::now().format("%Y%m%d%H%M%S%f"));
+        let snapshot = self
+            .write_snapshot(&snapshot_path)
+            .and_then(|_| inspect_snapshot(&snapshot_path))
+            .and_then(|table_counts| {
+                fs::read(&snapshot_path)
+                    .map(|db_data| (db_data, table_counts))
+                    .map_err(|e| format!("Failed to read database snapshot: {}", e))
+            });
+        let _ = fs::remove_file(&snapshot_path);
+        let (db_data, table_counts) = snapshot?;
 
         // Get database size
         let db_size = db_data.len() as u64;
 
+        let passphrase = self.get_config().passphrase;
+
         // Create metadata
         let metadata = BackupMetadata {
             app_version: env!("CARGO_PKG_VERSION").to_string(),
             backup_date: Utc::now(),
             database_size_bytes: db_size,
-            total_records: 0,
+            total_records: table_counts.values().sum(),
             checksum: format!("{:x}", md5::compute(&db_data)),
+            table_counts,
+            encrypted: passphrase.is_some(),
         };
 
         // Always compress to reduce size
         let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
         encoder.write_all(&db_data)
             .map_err(|e| format!("Failed to compress data: {}", e))?;
-        let backup_data = encoder.finish()
+        let compressed = encoder.finish()
             .map_err(|e| format!("Failed to finish compression: {}", e))?;
+        let backup_data = backup_archive::seal(&compressed, &metadata, passphrase.as_deref())?;
 
-        println!("Backup created: {} bytes -> {} bytes (compressed)", 
-            db_size, backup_data.len());
+        println!("Backup created: {} bytes -> {} bytes (compressed{})", 
+            db_size, backup_data.len(), if metadata.encrypted { ", encrypted" } else { "" });
 
         Ok((backup_data, metadata))
     }
 
-    // Send backup via email
-    async fn send_backup_email(&self, backup_data: Vec<u8>, metadata: BackupMetadata) -> Result<(), String> {
-        // Use the embedded API key
-        let api_key = RESEND_API_KEY;
-        println!("🔑 Using API key: {}", if api_key.len() > 10 { &api_key[0..10] } else { "SHORT_KEY" });
-        
-        let recipient = "rodriguezjosee8@gmail.com";
-        // Use a development-friendly sender email
-        let from_email = "onboarding@resend.dev";
+    // VACUUM INTO reads one transaction, so the copy is never torn by concurrent writes
+    fn write_snapshot(&self, snapshot_path: &str) -> Result<(), String> {
+        let vacuum = |conn: &Connection| {
+            conn.execute("VACUUM INTO ?1", [snapshot_path])
+                .map(|_| ())
+                .map_err(|e| format!("Failed to snapshot database: {}", e))
+        };
 
-        
-        let client = reqwest::Client::new();
-        
-        // Check if backup data is too large
-        let data_size_mb = backup_data.len() as f64 / 1024.0 / 1024.0;
-        println!("📊 Backup size: {:.2} MB", data_size_mb);
-        
-        if data_size_mb > 100.0 {
-            // If backup is too large, send without attachment
-            let email_data = serde_json::json!({
-                "from": from_email,
-                "to": [recipient],
-                "subject": format!("Quality GYM - Backup automático del {}", metadata.backup_date.format("%Y-%m-%d %H:%M UTC")),
-                "text": format!("Backup de Quality GYM del {}\n\nTamaño: {:.2} MB\nRegistros: {}\nChecksum: {}\n\nEste es un backup automático de la base de datos de Quality GYM.\n\nNOTA: El archivo de backup ({:.2} MB) es demasiado grande para enviarse por email.", 
-                    metadata.backup_date.format("%Y-%m-%d %H:%M UTC"),
-                    metadata.database_size_bytes as f64 / 1024.0 / 1024.0,
-                    metadata.total_records,
-                    metadata.checksum,
-                    data_size_mb
-                )
-            });
-            
-            println!("📧 Sending email without attachment...");
-            let response = client
-                .post("https://api.resend.com/emails")
-                .header("Authorization", format!("Bearer {}", api_key))
-                .header("Content-Type", "application/json")
-                .json(&email_data)
-                .send()
-                .await
-                .map_err(|e| format!("Failed to send email: {}", e))?;
-
-            let status = response.status();
-            println!("📨 Response status: {}", status);
-            let response_text = response.text().await.unwrap_or_else(|_| "No response body".to_string());
-            println!("📨 Response body: {}", response_text);
-
-            if status.is_success() {
-                println!("✅ Email sent successfully via Resend (without attachment due to size)!");
-                Ok(())
-            } else {
-                Err(format!("Resend returned error: {}, {}", status, response_text))
+        match &self.pool {
+            Some(pool) => {
+                let conn = pool.get().map_err(|e| format!("Failed to get database connection: {}", e))?;
+                vacuum(&conn)
             }
+            None => {
+                let conn = Connection::open(&self.db_path)
+                    .map_err(|e| format!("Failed to open database: {}", e))?;
+                vacuum(&conn)
+            }
+        }
+    }
+
+    // Write the backup and its metadata to the backup folder
+    fn save_local_backup(&self, backup_data: &[u8], metadata: &BackupMetadata) -> Result<BackupInfo, String> {
+        let backup_dir = self.backup_dir(&self.get_config());
+        fs::create_dir_all(&backup_dir)
+            .map_err(|e| format!("Failed to create backup folder: {}", e))?;
+
+        // Backups taken in the same millisecond get a numbered suffix; existing files are never replaced
+        let stamp = metadata.backup_date.format("%Y%m%d_%H%M%S_%3f").to_string();
+        let (file_name, backup_path) = (0..100)
+            .map(|n| match n {
+                0 => format!("{}{}{}", BACKUP_FILE_PREFIX, stamp, ARCHIVE_EXTENSION),
+                n => format!("{}{}_{}{}", BACKUP_FILE_PREFIX, stamp, n, ARCHIVE_EXTENSION),
+            })
+            .map(|file_name| {
+                let backup_path = backup_dir.join(&file_name);
+                (file_name, backup_path)
+            })
+            .find(|(_, backup_path)| {
+                !backup_path.exists() && !backup_path.with_extension("partial").exists() && !metadata_path(backup_path).exists()
+            })
+            .ok_or_else(|| "Failed to write backup file: no free file name".to_string())?;
+
+        // Written under a temporary name so a half-written file is never listed
+        let partial_path = backup_path.with_extension("partial");
+        fs::write(&partial_path, backup_data)
+            .map_err(|e| format!("Failed to write backup file: {}", e))?;
+        if backup_path.exists() {
+            let _ = fs::remove_file(&partial_path);
+            return Err(format!("Failed to finalize backup file: {} already exists", file_name));
+        }
+        fs::rename(&partial_path, &backup_path)
+            .map_err(|e| format!("Failed to finalize backup file: {}", e))?;
+
+        let metadata_json = serde_json::to_string_pretty(metadata)
+            .map_err(|e| format!("Failed to serialize backup metadata: {}", e))?;
+        fs::write(metadata_path(&backup_path), metadata_json)
+            .map_err(|e| format!("Failed to write backup metadata: {}", e))?;
+
+        println!("💾 Local backup saved: {}", backup_path.display());
+        Ok(BackupInfo::new(file_name, backup_data.len() as u64, metadata))
+    }
+
+    // Create a local backup right now, regardless of the daily schedule
+    pub fn create_local_backup(&self) -> Result<BackupInfo, String> {
+        let (backup_data, metadata) = self.create_backup()?;
+        let info = self.save_local_backup(&backup_data, &metadata)?;
+        self.apply_retention()?;
+        Ok(info)
+    }
+
+    // Local backups, newest first. Files without readable metadata are skipped.
+    pub fn list_backups(&self) -> Result<Vec<BackupInfo>, String> {
+        let backup_dir = self.backup_dir(&self.get_config());
+        if !backup_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let entries = fs::read_dir(&backup_dir)
+            .map_err(|e| format!("Failed to read backup folder: {}", e))?;
+
+        let mut backups = Vec::new();
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !is_backup_file_name(&file_name) {
+                continue;
+            }
+
+            let size_bytes = match entry.metadata() {
+                Ok(file_metadata) => file_metadata.len(),
+                Err(_) => continue,
+            };
+            match read_metadata(&entry.path()) {
+                Ok(metadata) => backups.push(BackupInfo::new(file_name, size_bytes, &metadata)),
+                Err(e) => println!("⚠️ Skipping {}: {}", file_name, e),
+            }
+        }
+
+        backups.sort_by_key(|backup| std::cmp::Reverse(backup.backup_date));
+        Ok(backups)
+    }
+
+    // Keep the newest backup of each of the last `keep_daily` days and of each of
+    // the last `keep_weekly` weeks; delete everything else
+    fn apply_retention(&self) -> Result<(), String> {
+        let config = self.get_config();
+        let backup_dir = self.backup_dir(&config);
+        let backups = self.list_backups()?;
+
+        let mut keep = HashSet::new();
+        let mut days = HashSet::new();
+        let mut weeks = HashSet::new();
+        for backup in &backups {
+            let local_date = backup.backup_date.with_timezone(&Local).date_naive();
+
+            if days.len() < config.keep_daily as usize && days.insert(local_date) {
+                keep.insert(backup.file_name.clone());
+            }
+
+            let week = local_date.iso_week();
+            if weeks.len() < config.keep_weekly as usize && weeks.insert((week.year(), week.week())) {
+                keep.insert(backup.file_name.clone());
+            }
+        }
+
+        for backup in backups.iter().filter(|backup| !keep.contains(&backup.file_name)) {
+            let backup_path = backup_dir.join(&backup.file_name);
+            fs::remove_file(&backup_path)
+                .map_err(|e| format!("Failed to delete old backup {}: {}", backup.file_name, e))?;
+            let _ = fs::remove_file(metadata_path(&backup_path));
+            println!("🗑️ Old backup deleted: {}", backup.file_name);
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the live database with a local backup. The backup is decrypted
+    /// with `passphrase` (default: the configured one), checked against its md5,
+    /// migrated to the current schema and integrity-checked before the swap;
+    /// the current database is backed up first.
+    pub fn restore_backup(&self, file_name: &str, passphrase: Option<&str>) -> Result<(), String> {
+        let pool = self.pool.as_ref().ok_or_else(|| "Database unavailable".to_string())?;
+
+        if !is_backup_file_name(file_name) {
+            return Err("Invalid backup file name".to_string());
+        }
+        let config = self.get_config();
+        let backup_path = self.backup_dir(&config).join(file_name);
+        if !backup_path.exists() {
+            return Err("Backup file not found".to_string());
+        }
+
+        let backup_data = fs::read(&backup_path)
+            .map_err(|e| format!("Failed to read backup: {}", e))?;
+        let (metadata, compressed) = if file_name.ends_with(ARCHIVE_EXTENSION) {
+            backup_archive::open::<BackupMetadata>(&backup_data, passphrase.or(config.passphrase.as_deref()))?
         } else {
-            // Try with attachment for smaller files
-            let filename = format!("gym_backup_{}.db.gz", metadata.backup_date.format("%Y%m%d_%H%M%S"));
-            
-            let email_data = serde_json::json!({
-                "from": from_email,
-                "to": [recipient],
-                "subject": format!("Quality GYM - Backup automático del {}", metadata.backup_date.format("%Y-%m-%d %H:%M UTC")),
-                "text": format!("Backup de Quality GYM del {}\n\nTamaño original: {:.2} MB\nTamaño comprimido: {:.2} MB\nRegistros: {}\nChecksum: {}\n\nEste es un backup automático de la base de datos de Quality GYM.", 
-                    metadata.backup_date.format("%Y-%m-%d %H:%M UTC"),
-                    metadata.database_size_bytes as f64 / 1024.0 / 1024.0,
-                    data_size_mb,
-                    metadata.total_records,
-                    metadata.checksum
-                ),
-                "attachments": [{
-                    "content": general_purpose::STANDARD.encode(&backup_data),
-                    "filename": filename,
-                    "content_type": "application/gzip"
-                }]
-            });
+            (read_metadata(&backup_path)?, backup_data)
+        };
+        let mut db_data = Vec::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut db_data)
+            .map_err(|e| format!("Failed to decompress backup: {}", e))?;
 
-            let response = client
-                .post("https://api.resend.com/emails")
-                .header("Authorization", format!("Bearer {}", api_key))
-                .header("Content-Type", "application/json")
-                .json(&email_data)
-                .send()
-                .await
-                .map_err(|e| format!("Failed to send email: {}", e))?;
-
-            let status = response.status();
-            let response_text = response.text().await.unwrap_or_else(|_| "No response body".to_string());
-            println!("📨 Response status: {}", status);
-            println!("📨 Response body: {}", response_text);
-
-            if status.is_success() {
-                println!("✅ Email sent successfully via Resend with attachment!");
-                Ok(())
-            } else {
-                // If attachment fails, try without it
-                let email_data_no_attachment = serde_json::json!({
-                    "from": from_email,
-                    "to": [recipient],
-                    "subject": format!("Quality GYM - Backup automático del {}", metadata.backup_date.format("%Y-%m-%d %H:%M UTC")),
-                    "text": format!("Backup de Quality GYM del {}\n\nTamaño original: {:.2} MB\nTamaño comprimido: {:.2} MB\nRegistros: {}\nChecksum: {}\n\nEste es un backup automático de la base de datos de Quality GYM.\n\nNOTA: El archivo de backup no se pudo adjuntar debido a restricciones del servicio de email.", 
-                        metadata.backup_date.format("%Y-%m-%d %H:%M UTC"),
-                        metadata.database_size_bytes as f64 / 1024.0 / 1024.0,
-                        data_size_mb,
-                        metadata.total_records,
-                        metadata.checksum
-                    )
-                });
-
-                let response_no_attachment = client
-                    .post("https://api.resend.com/emails")
-                    .header("Authorization", format!("Bearer {}", api_key))
-                    .header("Content-Type", "application/json")
-                    .json(&email_data_no_attachment)
-                    .send()
-                    .await
-                    .map_err(|e| format!("Failed to send email: {}", e))?;
-
-                let status_no_attachment = response_no_attachment.status();
-                let response_text_no_attachment = response_no_attachment.text().await.unwrap_or_else(|_| "No response body".to_string());
-                println!("📨 No-attachment response status: {}", status_no_attachment);
-                println!("📨 No-attachment response body: {}", response_text_no_attachment);
-
-                if status_no_attachment.is_success() {
-                    println!("✅ Email sent successfully via Resend (without attachment)!");
-                    Ok(())
-                } else {
-                    Err(format!("Resend returned error: {}, {}", status_no_attachment, response_text_no_attachment))
+        if format!("{:x}", md5::compute(&db_data)) != metadata.checksum {
+            return Err("Backup checksum does not match, the file is corrupted".to_string());
+        }
+        if !db_data.starts_with(SQLITE_HEADER) {
+            return Err("Backup does not contain a SQLite database".to_string());
+        }
+
+        // Staged next to the live database so the final rename stays on the same disk
+        let staged_path = format!("{}.restore", self.db_path);
+        fs::write(&staged_path, &db_data)
+            .map_err(|e| format!("Failed to stage restored database: {}", e))?;
+        if let Err(e) = prepare_restored_database(&staged_path) {
+            let _ = fs::remove_file(&staged_path);
+            return Err(e);
+        }
+
+        // Safety copy of the current data; skips retention so the backup being restored is kept
+        println!("📦 Backing up current database before restoring...");
+        let safety_backup = self
+            .create_backup()
+            .and_then(|(backup_data, metadata)| self.save_local_backup(&backup_data, &metadata));
+        if let Err(e) = safety_backup {
+            let _ = fs::remove_file(&staged_path);
+            return Err(format!("Failed to back up current database: {}", e));
+        }
+
+        let db_path = self.db_path.clone();
+        pool.swap_database(&self.db_path, || {
+            // A leftover WAL would be replayed on top of the restored file
+            for suffix in ["-wal", "-shm"] {
+                let sidecar = format!("{}{}", db_path, suffix);
+                if Path::new(&sidecar).exists() {
+                    fs::remove_file(&sidecar).map_err(|e| format!("Failed to remove {}: {}", sidecar, e))?;
+                }
+            }
+            fs::rename(&staged_path, &db_path).map_err(|e| format!("Failed to replace database: {}", e))
+        })?;
+
+        println!("✅ Backup {} restored", file_name);
+        Ok(())
+    }
+
+    // Uploads to every configured target and records how each one went; one failing
+    // target does not stop the others
+    async fn upload_to_targets(&self, info: &BackupInfo, backup_data: &[u8], status: &mut BackupStatus) -> Result<(), String> {
+        let mut errors = Vec::new();
+        for target in self.get_config().targets.iter().map(BackupTargetConfig::build) {
+            println!("📤 Sending backup to {}...", target.name());
+            let target_status = status.targets.entry(target.name()).or_default();
+            target_status.last_attempt = Some(Utc::now());
+            match target.upload(info, backup_data).await {
+                Ok(()) => {
+                    println!("✅ Backup sent to {}", target.name());
+                    target_status.last_success = target_status.last_attempt;
+                    target_status.last_error = None;
+                }
+                Err(e) => {
+                    println!("❌ {}: {}", target.name(), e);
+                    target_status.last_error = Some(e.clone());
+                    errors.push(format!("{}: {}", target.name(), e));
                 }
             }
         }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("; "))
+        }
     }
 
     // Main backup execution function - runs automatically on startup
@@ -268,43 +395,43 @@ impl BackupService {
 
         println!("🚀 Starting automatic backup...");
 
-        // Check internet connectivity
-        println!("🌐 Checking internet connectivity...");
-        if !self.check_internet_connectivity().await {
-            let error_msg = "No internet connection available for backup".to_string();
-            println!("❌ {}", error_msg);
-            status.last_error = Some(error_msg.clone());
-            status.last_backup_success = false;
-            self.save_status(&status)?;
-            return Err(error_msg);
-        }
-        println!("✅ Internet connectivity OK");
-
         // Create backup
         println!("📦 Creating backup...");
         let (backup_data, metadata) = self.create_backup()?;
         println!("✅ Backup created successfully");
 
-        // Send backup via email
-        println!("📧 Sending backup via email...");
-        match self.send_backup_email(backup_data, metadata).await {
-            Ok(()) => {
-                println!("✅ Backup completed successfully!");
-                status.last_backup_date = Some(Utc::now());
-                status.last_backup_success = true;
-                status.last_error = None;
-                status.backup_count += 1;
-                
+        // The local copy does not depend on the network
+        let local_result = self
+            .save_local_backup(&backup_data, &metadata)
+            .and_then(|info| self.apply_retention().map(|_| info));
+        let info = match local_result {
+            Ok(info) => info,
+            Err(e) => {
+                println!("❌ Local backup failed: {}", e);
+                status.last_error = Some(e.clone());
+                status.last_backup_success = false;
                 self.save_status(&status)?;
+                return Err(e);
+            }
+        };
+
+        // Today's backup is done once it is on disk; remote copies are tracked per target
+        status.last_backup_date = Some(Utc::now());
+        status.last_backup_success = true;
+        status.last_error = None;
+        status.backup_count += 1;
+        self.save_status(&status)?;
+        println!("✅ Local backup completed successfully!");
+
+        // Copy to every configured destination
+        let upload_result = self.upload_to_targets(&info, &backup_data, &mut status).await;
+        self.save_status(&status)?;
+        match upload_result {
+            Ok(()) => {
                 println!("✅ Automatic backup completed successfully!");
                 Ok(())
             },
             Err(e) => {
-                println!("❌ Backup failed: {}", e);
-                status.last_backup_success = false;
-                status.last_error = Some(e.clone());
-                
-                self.save_status(&status)?;
                 println!("❌ Automatic backup failed: {}", e);
                 Err(e)
             }
@@ -312,32 +439,297 @@ impl BackupService {
     }
 }
 
-// Simple status struct
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+// Simple status struct. The last_backup_* fields describe the local backup.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 struct BackupStatus {
     last_backup_date: Option<chrono::DateTime<chrono::Utc>>,
     last_backup_success: bool,
     last_error: Option<String>,
     backup_count: u32,
+    #[serde(default)]
+    targets: BTreeMap<String, TargetStatus>, // By target name
 }
 
-impl Default for BackupStatus {
-    fn default() -> Self {
-        Self {
-            last_backup_date: None,
-            last_backup_success: false,
-            last_error: None,
-            backup_count: 0,
-        }
-    }
+// Outcome of the uploads to one backup target
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+struct TargetStatus {
+    last_attempt: Option<chrono::DateTime<chrono::Utc>>,
+    last_success: Option<chrono::DateTime<chrono::Utc>>,
+    last_error: Option<String>,
 }
 
-// Simple metadata struct
-#[derive(Debug, Clone)]
+// Simple metadata struct, stored next to each local backup as JSON
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 struct BackupMetadata {
     app_version: String,
     backup_date: chrono::DateTime<chrono::Utc>,
     database_size_bytes: u64,
     total_records: u32,
     checksum: String,
-} 
\ No newline at end of file
+    #[serde(default)]
+    table_counts: BTreeMap<String, u32>, // Rows per table at backup time
+    #[serde(default)]
+    encrypted: bool,
+}
+
+/// A local backup as shown by `list_backups`
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub backup_date: chrono::DateTime<chrono::Utc>,
+    pub size_bytes: u64,          // Archive on disk
+    pub database_size_bytes: u64,
+    pub total_records: u32,
+    pub table_counts: BTreeMap<String, u32>,
+    pub checksum: String,         // md5 of the uncompressed database
+    pub app_version: String,
+    pub encrypted: bool,
+}
+
+impl BackupInfo {
+    fn new(file_name: String, size_bytes: u64, metadata: &BackupMetadata) -> Self {
+        Self {
+            file_name,
+            backup_date: metadata.backup_date,
+            size_bytes,
+            database_size_bytes: metadata.database_size_bytes,
+            total_records: metadata.total_records,
+            table_counts: metadata.table_counts.clone(),
+            checksum: metadata.checksum.clone(),
+            app_version: metadata.app_version.clone(),
+            encrypted: metadata.encrypted,
+        }
+    }
+}
+
+// gym_backup_20240501_101500_123.gymbak (or .db.gz); never a path
+fn is_backup_file_name(file_name: &str) -> bool {
+    file_name.starts_with(BACKUP_FILE_PREFIX)
+        && (file_name.ends_with(ARCHIVE_EXTENSION) || file_name.ends_with(LEGACY_BACKUP_EXTENSION))
+        && !file_name.contains(['/', '\\'])
+        && !file_name.contains("..")
+}
+
+// gym_backup_X.gymbak -> gym_backup_X.json
+pub(crate) fn metadata_file_name(file_name: &str) -> String {
+    let stem = file_name
+        .strip_suffix(ARCHIVE_EXTENSION)
+        .or_else(|| file_name.strip_suffix(LEGACY_BACKUP_EXTENSION))
+        .unwrap_or(file_name);
+    format!("{}.json", stem)
+}
+
+fn metadata_path(backup_path: &Path) -> PathBuf {
+    let file_name = backup_path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    backup_path.with_file_name(metadata_file_name(&file_name))
+}
+
+// Archives copied in without their .json (e.g. from an email) are read from their own header
+fn read_metadata(backup_path: &Path) -> Result<BackupMetadata, String> {
+    let sidecar_path = metadata_path(backup_path);
+    if !sidecar_path.exists() && backup_path.to_string_lossy().ends_with(ARCHIVE_EXTENSION) {
+        let backup_data = fs::read(backup_path)
+            .map_err(|e| format!("Failed to read backup: {}", e))?;
+        let (mut metadata, encrypted) = backup_archive::read_header::<BackupMetadata>(&backup_data)?;
+        metadata.encrypted = encrypted;
+        return Ok(metadata);
+    }
+
+    let metadata_data = fs::read_to_string(sidecar_path)
+        .map_err(|e| format!("Failed to read backup metadata: {}", e))?;
+    serde_json::from_str(&metadata_data).map_err(|e| format!("Invalid backup metadata: {}", e))
+}
+
+// Integrity-checks a snapshot and counts the rows of every user table
+fn inspect_snapshot(path: &str) -> Result<BTreeMap<String, u32>, String> {
+    let conn = Connection::open(path).map_err(|e| format!("Failed to open database snapshot: {}", e))?;
+
+    let integrity: String = conn
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to check database snapshot: {}", e))?;
+    if integrity != "ok" {
+        return Err(format!("Database snapshot failed the integrity check: {}", integrity));
+    }
+
+    let tables = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+        .and_then(|mut stmt| stmt.query_map([], |row| row.get::<_, String>(0))?.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Failed to list tables: {}", e))?;
+
+    let mut table_counts = BTreeMap::new();
+    for table in tables {
+        let count: u32 = conn
+            .query_row(&format!("SELECT COUNT(*) FROM \"{}\"", table.replace('"', "\"\"")), [], |row| row.get(0))
+            .map_err(|e| format!("Failed to count rows of {}: {}", table, e))?;
+        table_counts.insert(table, count);
+    }
+    Ok(table_counts)
+}
+
+// Checks the staged file and brings it to the current schema before it goes live
+fn prepare_restored_database(path: &str) -> Result<(), String> {
+    let mut conn = Connection::open(path).map_err(|e| format!("Failed to open restored database: {}", e))?;
+
+    let integrity: String = conn
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to check restored database: {}", e))?;
+    if integrity != "ok" {
+        return Err(format!("Restored database failed the integrity check: {}", integrity));
+    }
+
+    run_migrations(&mut conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::config::db::test_pool;
+    use crate::models::person::Person;
+    use crate::repository::person_repository::PersonRepository;
+    use crate::repository::sqlite_person_repository::SqlitePersonRepository;
+
+    fn service(dir: &Path, pool: DbPool) -> (BackupService, Arc<SettingsService>) {
+        let settings = Arc::new(SettingsService::new(dir));
+        (BackupService::at(dir.join("test.db"), Some(pool), settings.clone()), settings)
+    }
+
+    fn add_person(pool: &DbPool, phone: &str) {
+        let person: Person =
+            serde_json::from_value(serde_json::json!({ "id": null, "name": "Ana", "last_name": "Ruiz", "phone": phone })).unwrap();
+        SqlitePersonRepository::new(pool.clone()).create(person).unwrap();
+    }
+
+    fn people(pool: &DbPool) -> usize {
+        SqlitePersonRepository::new(pool.clone()).list_all().unwrap().len()
+    }
+
+    fn metadata_at(backup_date: chrono::DateTime<Utc>, checksum: &str) -> BackupMetadata {
+        BackupMetadata {
+            app_version: "0.1.0".to_string(),
+            backup_date,
+            database_size_bytes: 0,
+            total_records: 0,
+            checksum: checksum.to_string(),
+            table_counts: BTreeMap::new(),
+            encrypted: false,
+        }
+    }
+
+    #[test]
+    fn restore_brings_back_the_backed_up_data() {
+        let (dir, pool) = test_pool();
+        let (backups, _) = service(dir.path(), pool.clone());
+        add_person(&pool, "600000001");
+        let backup = backups.create_local_backup().unwrap();
+        assert_eq!(backup.table_counts["people"], 1);
+
+        add_person(&pool, "600000002");
+        backups.restore_backup(&backup.file_name, None).unwrap();
+        assert_eq!(people(&pool), 1);
+
+        // The data replaced by the restore was backed up first
+        let listed = backups.list_backups().unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].table_counts["people"], 2);
+        assert!(backups.restore_backup("../test.db", None).is_err());
+    }
+
+    #[test]
+    fn backups_taken_at_once_never_overwrite_each_other() {
+        let (dir, pool) = test_pool();
+        let (backups, _) = service(dir.path(), pool);
+        let (backup_data, metadata) = backups.create_backup().unwrap();
+
+        let first = backups.save_local_backup(&backup_data, &metadata).unwrap();
+        let second = backups.save_local_backup(&backup_data, &metadata).unwrap();
+        assert_ne!(first.file_name, second.file_name);
+        assert_eq!(backups.list_backups().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn corrupted_backups_are_rejected_before_the_swap() {
+        let (dir, pool) = test_pool();
+        let (backups, _) = service(dir.path(), pool.clone());
+        add_person(&pool, "600000001");
+        let backup_dir = dir.path().join("backups");
+        fs::create_dir_all(&backup_dir).unwrap();
+
+        // A well-formed archive whose database does not match the recorded checksum
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(SQLITE_HEADER).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let metadata = metadata_at(Utc::now(), &"0".repeat(32));
+        let file_name = "gym_backup_20240506_120000_000.gymbak";
+        fs::write(backup_dir.join(file_name), backup_archive::seal(&compressed, &metadata, None).unwrap()).unwrap();
+
+        let error = backups.restore_backup(file_name, None).unwrap_err();
+        assert!(error.contains("checksum does not match"), "{}", error);
+        assert_eq!(people(&pool), 1);
+        // Nothing was staged or backed up on the way
+        assert!(!dir.path().join("test.db.restore").exists());
+        assert_eq!(fs::read_dir(&backup_dir).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn retention_keeps_the_newest_backup_of_recent_days_and_weeks() {
+        let (dir, pool) = test_pool();
+        let (backups, settings) = service(dir.path(), pool);
+        let mut current = settings.get();
+        current.backup.keep_daily = 2;
+        current.backup.keep_weekly = 3;
+        settings.update(current).unwrap();
+
+        let backup_dir = dir.path().join("backups");
+        fs::create_dir_all(&backup_dir).unwrap();
+        let dates = [
+            ("20240506_110000_000", (2024, 5, 6, 11)), // Monday, older of the day
+            ("20240506_130000_000", (2024, 5, 6, 13)),
+            ("20240505_120000_000", (2024, 5, 5, 12)), // Sunday, newest of the week before
+            ("20240501_120000_000", (2024, 5, 1, 12)),
+            ("20240420_120000_000", (2024, 4, 20, 12)),
+            ("20240301_120000_000", (2024, 3, 1, 12)), // Beyond three weeks
+        ];
+        for (stamp, (year, month, day, hour)) in dates {
+            let backup_path = backup_dir.join(format!("{}{}{}", BACKUP_FILE_PREFIX, stamp, ARCHIVE_EXTENSION));
+            fs::write(&backup_path, b"archive").unwrap();
+            let metadata = metadata_at(Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap(), "");
+            fs::write(metadata_path(&backup_path), serde_json::to_string(&metadata).unwrap()).unwrap();
+        }
+
+        backups.apply_retention().unwrap();
+
+        let kept: Vec<String> = backups.list_backups().unwrap().into_iter().map(|backup| backup.file_name).collect();
+        assert_eq!(
+            kept,
+            ["gym_backup_20240506_130000_000.gymbak", "gym_backup_20240505_120000_000.gymbak", "gym_backup_20240420_120000_000.gymbak"]
+        );
+        assert!(!backup_dir.join("gym_backup_20240501_120000_000.json").exists());
+    }
+
+    #[tokio::test]
+    async fn failing_target_does_not_undo_the_local_backup() {
+        let (dir, pool) = test_pool();
+        let (backups, settings) = service(dir.path(), pool);
+        let blocked = dir.path().join("not-a-folder");
+        fs::write(&blocked, b"").unwrap();
+        let mut current = settings.get();
+        current.backup.targets = vec![
+            BackupTargetConfig::Directory { path: blocked.to_string_lossy().to_string() },
+            BackupTargetConfig::Directory { path: dir.path().join("nas").to_string_lossy().to_string() },
+        ];
+        settings.update(current).unwrap();
+
+        assert!(backups.execute_backup().await.is_err());
+
+        let status = backups.load_status();
+        assert!(status.last_backup_date.is_some() && status.last_backup_success);
+        assert_eq!(status.backup_count, 1);
+        let failed = &status.targets[&format!("folder {}", blocked.display())];
+        assert!(failed.last_error.is_some() && failed.last_success.is_none());
+        let copied = &status.targets[&format!("folder {}", dir.path().join("nas").display())];
+        assert!(copied.last_error.is_none() && copied.last_success.is_some());
+        // Already done today, whatever happened to the copies
+        assert!(!backups.should_run_backup());
+    }
+}
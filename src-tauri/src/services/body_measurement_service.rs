@@ -0,0 +1,111 @@
+use std::sync::Arc;
+use chrono::Local;
+use crate::error::{AppError, AppResult};
+use crate::models::body_measurement::{BodyMeasurement, BodyMetric, MeasurementPoint};
+use crate::repository::body_measurement_repository::BodyMeasurementRepository;
+use crate::repository::person_repository::PersonRepository;
+use crate::services::validation::{format_date, parse_date};
+
+const MAX_NOTES_LENGTH: usize = 500;
+
+pub struct BodyMeasurementService {
+    repository: Arc<dyn BodyMeasurementRepository>,
+    person_repository: Arc<dyn PersonRepository>,
+}
+
+impl BodyMeasurementService {
+    pub fn new(repository: Arc<dyn BodyMeasurementRepository>, person_repository: Arc<dyn PersonRepository>) -> Self {
+        Self { repository, person_repository }
+    }
+
+    pub fn create_measurement(&self, measurement: BodyMeasurement) -> AppResult<i32> {
+        let measurement = self.validate_measurement(measurement)?;
+        self.repository.create(&measurement)
+    }
+
+    /// Replaces every value of the session; metrics left out are removed
+    pub fn update_measurement(&self, measurement: BodyMeasurement) -> AppResult<()> {
+        let id = match measurement.id {
+            Some(id) if id > 0 => id,
+            _ => return Err(AppError::validation("id", "Invalid measurement ID")),
+        };
+        let existing = self.repository.get_by_id(id)?
+            .ok_or_else(|| AppError::not_found("Body measurement not found"))?;
+        if existing.person_id != measurement.person_id {
+            return Err(AppError::validation("person_id", "A measurement cannot be moved to another person"));
+        }
+
+        let measurement = self.validate_measurement(measurement)?;
+        self.repository.update(&measurement)
+    }
+
+    pub fn delete_measurement(&self, id: i32) -> AppResult<()> {
+        if id <= 0 {
+            return Err(AppError::validation("id", "Invalid measurement ID"));
+        }
+        self.repository.delete(id)
+    }
+
+    pub fn get_person_measurements(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<Vec<BodyMeasurement>> {
+        let (start_date, end_date) = validate_range(person_id, start_date, end_date)?;
+        self.repository.list_by_person(person_id, &start_date, &end_date)
+    }
+
+    /// One metric over time, oldest first, for charts
+    pub fn get_measurement_series(&self, person_id: i32, metric: BodyMetric, start_date: &str, end_date: &str) -> AppResult<Vec<MeasurementPoint>> {
+        let (start_date, end_date) = validate_range(person_id, start_date, end_date)?;
+        self.repository.series(person_id, metric, &start_date, &end_date)
+    }
+
+    fn validate_measurement(&self, mut measurement: BodyMeasurement) -> AppResult<BodyMeasurement> {
+        if measurement.person_id <= 0 {
+            return Err(AppError::validation("person_id", "Invalid person ID"));
+        }
+        if self.person_repository.get_by_id(measurement.person_id)?.is_none() {
+            return Err(AppError::not_found("Person not found"));
+        }
+
+        let date = parse_date("date", &measurement.date)?;
+        if date > Local::now().date_naive() {
+            return Err(AppError::validation("date", "Measurement date cannot be in the future"));
+        }
+        measurement.date = format_date(date);
+
+        measurement.notes = measurement.notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        if let Some(notes) = &measurement.notes {
+            if notes.chars().count() > MAX_NOTES_LENGTH {
+                return Err(AppError::validation("notes", format!("Notes cannot exceed {} characters", MAX_NOTES_LENGTH)));
+            }
+        }
+
+        if measurement.values.is_empty() {
+            return Err(AppError::validation("values", "Record at least one measurement"));
+        }
+        for (index, value) in measurement.values.iter().enumerate() {
+            if measurement.values[..index].iter().any(|v| v.metric == value.metric) {
+                return Err(AppError::validation("values", format!("'{}' is recorded twice", value.metric.as_str())));
+            }
+            let (min, max) = value.metric.valid_range();
+            if !value.value.is_finite() || value.value < min || value.value > max {
+                return Err(AppError::validation(
+                    "values",
+                    format!("'{}' must be between {} and {}", value.metric.as_str(), min, max),
+                ));
+            }
+        }
+
+        Ok(measurement)
+    }
+}
+
+fn validate_range(person_id: i32, start_date: &str, end_date: &str) -> AppResult<(String, String)> {
+    if person_id <= 0 {
+        return Err(AppError::validation("person_id", "Invalid person ID"));
+    }
+    let start = parse_date("start_date", start_date)?;
+    let end = parse_date("end_date", end_date)?;
+    if end < start {
+        return Err(AppError::validation("end_date", "End date cannot be before start date"));
+    }
+    Ok((format_date(start), format_date(end)))
+}
@@ -0,0 +1,270 @@
+use std::sync::Arc;
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use crate::error::{AppError, AppResult};
+use crate::models::check_in::{CheckIn, DailyOccupancy, HourlyOccupancy, VisitStreaks};
+use crate::repository::check_in_repository::CheckInRepository;
+use crate::repository::person_repository::PersonRepository;
+use crate::services::person_service::phone_digits;
+use crate::services::validation::{format_date, parse_date};
+
+const MAX_REPORT_RANGE_DAYS: i64 = 366;
+// Visits left open for longer were never checked out and no longer count as in the gym
+const MAX_VISIT_HOURS: i64 = 12;
+
+pub struct CheckInService {
+    repository: Arc<dyn CheckInRepository>,
+    person_repository: Arc<dyn PersonRepository>,
+}
+
+impl CheckInService {
+    pub fn new(repository: Arc<dyn CheckInRepository>, person_repository: Arc<dyn PersonRepository>) -> Self {
+        Self { repository, person_repository }
+    }
+
+    pub fn check_in(&self, person_id: i32) -> AppResult<CheckIn> {
+        if person_id <= 0 {
+            return Err(AppError::validation("person_id", "Invalid person ID"));
+        }
+
+        let now = Local::now().naive_local();
+        if self.repository.find_open(person_id, &open_since(now))?.is_some() {
+            return Err(AppError::duplicate("person_id", "The person is already checked in"));
+        }
+
+        let id = self.repository.create(person_id, &format_timestamp(now))?;
+        self.get_check_in(id)
+    }
+
+    /// Front desk shortcut: the phone may be typed with any spacing or punctuation
+    pub fn check_in_by_phone(&self, phone: &str) -> AppResult<CheckIn> {
+        let digits = phone_digits(phone);
+        if digits.is_empty() {
+            return Err(AppError::validation("phone", "Phone cannot be empty"));
+        }
+
+        let people = self.person_repository.find_by_phone(&digits)?;
+        match people.as_slice() {
+            [] => Err(AppError::not_found("No person with this phone")),
+            [person] => match person.id {
+                Some(id) => self.check_in(id),
+                None => Err(AppError::not_found("No person with this phone")),
+            },
+            _ => Err(AppError::validation("phone", "Several people share this phone, check in by name instead")),
+        }
+    }
+
+    /// Closes the person's open visit, also when it started before midnight
+    pub fn check_out(&self, person_id: i32) -> AppResult<CheckIn> {
+        if person_id <= 0 {
+            return Err(AppError::validation("person_id", "Invalid person ID"));
+        }
+
+        let now = Local::now().naive_local();
+        let open = self.repository.find_open(person_id, &open_since(now))?
+            .ok_or_else(|| AppError::not_found("The person is not checked in"))?;
+
+        self.repository.check_out(open.id, &format_timestamp(now))?;
+        self.get_check_in(open.id)
+    }
+
+    pub fn delete_check_in(&self, id: i32) -> AppResult<()> {
+        if id <= 0 {
+            return Err(AppError::validation("id", "Invalid check-in ID"));
+        }
+        self.repository.delete(id)
+    }
+
+    /// People in the gym right now, earliest arrival first
+    pub fn get_open_check_ins(&self) -> AppResult<Vec<CheckIn>> {
+        self.repository.list_open(&open_since(Local::now().naive_local()))
+    }
+
+    pub fn get_person_check_ins(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<Vec<CheckIn>> {
+        if person_id <= 0 {
+            return Err(AppError::validation("person_id", "Invalid person ID"));
+        }
+        let (start_date, end_date) = validate_range(start_date, end_date, None)?;
+        self.repository.list_by_person(person_id, &start_date, &end_date)
+    }
+
+    pub fn get_daily_occupancy(&self, start_date: &str, end_date: &str) -> AppResult<Vec<DailyOccupancy>> {
+        let (start_date, end_date) = validate_range(start_date, end_date, Some(MAX_REPORT_RANGE_DAYS))?;
+        self.repository.daily_occupancy(&start_date, &end_date)
+    }
+
+    pub fn get_hourly_occupancy(&self, start_date: &str, end_date: &str) -> AppResult<Vec<HourlyOccupancy>> {
+        let (start_date, end_date) = validate_range(start_date, end_date, Some(MAX_REPORT_RANGE_DAYS))?;
+        self.repository.hourly_occupancy(&start_date, &end_date)
+    }
+
+    pub fn get_visit_streaks(&self, person_id: i32) -> AppResult<VisitStreaks> {
+        if person_id <= 0 {
+            return Err(AppError::validation("person_id", "Invalid person ID"));
+        }
+        if self.person_repository.get_by_id(person_id)?.is_none() {
+            return Err(AppError::not_found("Person not found"));
+        }
+
+        let dates = self.repository.visit_dates(person_id)?
+            .iter()
+            .filter_map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+            .collect::<Vec<_>>();
+
+        let today = Local::now().date_naive();
+        let this_week = week_start(today);
+        let weeks = {
+            let mut weeks = dates.iter().map(|d| week_start(*d)).collect::<Vec<_>>();
+            weeks.dedup();
+            weeks
+        };
+
+        let (current_daily_streak, longest_daily_streak) = streaks(&dates, Duration::days(1), today);
+        let (current_weekly_streak, longest_weekly_streak) = streaks(&weeks, Duration::weeks(1), this_week);
+
+        Ok(VisitStreaks {
+            person_id,
+            visit_days: dates.len() as i32,
+            last_visit_date: dates.last().map(|d| format_date(*d)),
+            current_daily_streak,
+            longest_daily_streak,
+            current_weekly_streak,
+            longest_weekly_streak,
+        })
+    }
+
+    fn get_check_in(&self, id: i32) -> AppResult<CheckIn> {
+        self.repository.get_by_id(id)?.ok_or_else(|| AppError::not_found("Check-in not found"))
+    }
+}
+
+fn format_timestamp(at: chrono::NaiveDateTime) -> String {
+    at.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+// Earliest check-in still counted as an open visit at `now`
+fn open_since(now: chrono::NaiveDateTime) -> String {
+    format_timestamp(now - Duration::hours(MAX_VISIT_HOURS))
+}
+
+fn validate_range(start_date: &str, end_date: &str, max_days: Option<i64>) -> AppResult<(String, String)> {
+    let start = parse_date("start_date", start_date)?;
+    let end = parse_date("end_date", end_date)?;
+    if end < start {
+        return Err(AppError::validation("end_date", "End date cannot be before start date"));
+    }
+    if let Some(max_days) = max_days {
+        if (end - start).num_days() >= max_days {
+            return Err(AppError::validation("end_date", format!("Range cannot exceed {} days", max_days)));
+        }
+    }
+    Ok((format_date(start), format_date(end)))
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+// `periods` are sorted and distinct, `step` apart when consecutive. The current
+// streak is the one reaching `current` or the period just before it, so a
+// streak is not lost before the day (or week) is over.
+fn streaks(periods: &[NaiveDate], step: Duration, current: NaiveDate) -> (i32, i32) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+
+    for period in periods {
+        run = match previous {
+            Some(previous) if *period - previous == step => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(*period);
+    }
+
+    let current_streak = match previous {
+        Some(last) if last == current || last == current - step => run,
+        _ => 0,
+    };
+    (current_streak, longest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::db::{test_pool, DbPool};
+    use crate::models::person::Person;
+    use crate::repository::sqlite_check_in_repository::SqliteCheckInRepository;
+    use crate::repository::sqlite_person_repository::SqlitePersonRepository;
+
+    fn service(pool: &DbPool) -> CheckInService {
+        let people = Arc::new(SqlitePersonRepository::new(pool.clone()));
+        let person: Person =
+            serde_json::from_value(serde_json::json!({ "id": null, "name": "Ana", "last_name": "Ruiz", "phone": "600000001" })).unwrap();
+        people.create(person).unwrap();
+        CheckInService::new(Arc::new(SqliteCheckInRepository::new(pool.clone())), people)
+    }
+
+    // Open visit of person 1 that started `hours_ago`
+    fn open_visit(pool: &DbPool, hours_ago: i64) {
+        let checked_in_at = format_timestamp(Local::now().naive_local() - Duration::hours(hours_ago));
+        pool.get()
+            .unwrap()
+            .execute("INSERT INTO check_ins (person_id, checked_in_at) VALUES (1, ?1)", [checked_in_at])
+            .unwrap();
+    }
+
+    #[test]
+    fn check_in_then_check_out() {
+        let (_dir, pool) = test_pool();
+        let service = service(&pool);
+
+        let visit = service.check_in(1).unwrap();
+        assert!(visit.checked_out_at.is_none());
+        assert_eq!(service.get_open_check_ins().unwrap().len(), 1);
+
+        let closed = service.check_out(1).unwrap();
+        assert_eq!(closed.id, visit.id);
+        assert!(closed.checked_out_at.is_some());
+        assert_eq!(closed.duration_minutes, Some(0));
+        assert!(service.get_open_check_ins().unwrap().is_empty());
+        assert_eq!(service.check_out(1).unwrap_err().code(), "NOT_FOUND");
+        assert_eq!(service.check_in(99).unwrap_err().code(), "NOT_FOUND");
+    }
+
+    #[test]
+    fn second_check_in_is_refused_while_the_visit_is_open() {
+        let (_dir, pool) = test_pool();
+        let service = service(&pool);
+
+        service.check_in(1).unwrap();
+        let error = service.check_in(1).unwrap_err();
+        assert_eq!((error.code(), error.field()), ("DUPLICATE", Some("person_id")));
+
+        service.check_out(1).unwrap();
+        service.check_in(1).unwrap();
+    }
+
+    #[test]
+    fn visits_are_closed_across_midnight() {
+        let (_dir, pool) = test_pool();
+        let service = service(&pool);
+        // Started two hours ago, possibly yesterday
+        open_visit(&pool, 2);
+
+        assert_eq!(service.check_in(1).unwrap_err().code(), "DUPLICATE");
+        let closed = service.check_out(1).unwrap();
+        assert_eq!(closed.duration_minutes, Some(120));
+    }
+
+    #[test]
+    fn visits_left_open_too_long_are_ignored() {
+        let (_dir, pool) = test_pool();
+        let service = service(&pool);
+        open_visit(&pool, MAX_VISIT_HOURS + 1);
+
+        assert!(service.get_open_check_ins().unwrap().is_empty());
+        assert_eq!(service.check_out(1).unwrap_err().code(), "NOT_FOUND");
+        let visit = service.check_in(1).unwrap();
+        assert_eq!(service.check_out(1).unwrap().id, visit.id);
+    }
+}
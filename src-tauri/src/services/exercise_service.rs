@@ -1,79 +1,169 @@
+use crate::error::{AppError, AppResult};
 use crate::models::exercise::{Exercise, PaginatedExerciseResponse};
+use crate::models::taxonomy::{ExerciseFilter, ExerciseTaxonomy, ExerciseTaxonomyUpdate, TaxonomyItem, TaxonomyKind};
 use crate::repository::exercise_repository::ExerciseRepository;
+use crate::repository::taxonomy_repository::TaxonomyRepository;
 use std::sync::Arc;
 
+const MAX_TAXONOMY_NAME_LENGTH: usize = 50;
+
 pub struct ExerciseService {
     repository: Arc<dyn ExerciseRepository>,
+    taxonomy_repository: Arc<dyn TaxonomyRepository>,
 }
 
 impl ExerciseService {
-    pub fn new(repository: Arc<dyn ExerciseRepository>) -> Self {
-        Self { repository }
+    pub fn new(repository: Arc<dyn ExerciseRepository>, taxonomy_repository: Arc<dyn TaxonomyRepository>) -> Self {
+        Self { repository, taxonomy_repository }
     }
 
-    pub fn create_exercise(&self, exercise: Exercise) -> Result<(), String> {
+    pub fn create_exercise(&self, exercise: Exercise) -> AppResult<()> {
         self.repository.create(exercise)
     }
 
-    pub fn list_exercises(&self) -> Vec<Exercise> {
+    pub fn list_exercises(&self) -> AppResult<Vec<Exercise>> {
         self.repository.list()
     }
 
-    pub fn list_exercises_paginated(&self, page: i32, page_size: i32) -> PaginatedExerciseResponse {
-        let exercises = self.repository.list_paginated(page, page_size);
-        let total = self.repository.count();
+    pub fn list_exercises_paginated(&self, page: i32, page_size: i32) -> AppResult<PaginatedExerciseResponse> {
+        let exercises = self.repository.list_paginated(page, page_size)?;
+        let total = self.repository.count()?;
         let total_pages = if page_size > 0 { (total + page_size - 1) / page_size } else { 0 };
 
-        PaginatedExerciseResponse {
+        Ok(PaginatedExerciseResponse {
             exercises,
             total,
             page,
             page_size,
             total_pages,
-        }
+        })
     }
 
-    pub fn delete_exercise(&self, id: i32) -> Result<(), String> {
+    pub fn delete_exercise(&self, id: i32) -> AppResult<()> {
         self.repository.delete(id)
     }
 
-    pub fn restore_exercise(&self, id: i32) -> Result<(), String> {
+    pub fn restore_exercise(&self, id: i32) -> AppResult<()> {
         self.repository.restore(id)
     }
 
-    pub fn update_exercise(&self, exercise: Exercise) -> Result<(), String> {
+    pub fn update_exercise(&self, exercise: Exercise) -> AppResult<()> {
         self.repository.update(exercise)
     }
 
-    pub fn list_deleted_exercises(&self) -> Vec<Exercise> {
+    pub fn list_deleted_exercises(&self) -> AppResult<Vec<Exercise>> {
         self.repository.list_deleted()
     }
 
-    pub fn count_deleted_exercises(&self) -> i32 {
+    pub fn count_deleted_exercises(&self) -> AppResult<i32> {
         self.repository.count_deleted()
     }
 
-    pub fn search_exercises_paginated(&self, query: &str, page: i32, page_size: i32) -> PaginatedExerciseResponse {
-        if query.trim().is_empty() {
-            return PaginatedExerciseResponse {
+    pub fn search_exercises_paginated(
+        &self,
+        query: &str,
+        filter: &ExerciseFilter,
+        page: i32,
+        page_size: i32,
+    ) -> AppResult<PaginatedExerciseResponse> {
+        if query.trim().is_empty() && filter.is_empty() {
+            return Ok(PaginatedExerciseResponse {
                 exercises: Vec::new(),
                 total: 0,
                 page,
                 page_size,
                 total_pages: 0,
-            };
+            });
         }
         
-        let exercises = self.repository.search_paginated(query, page, page_size);
-        let total = self.repository.search_count(query);
+        let exercises = self.repository.search_paginated(query, filter, page, page_size)?;
+        let total = self.repository.search_count(query, filter)?;
         let total_pages = if page_size > 0 { (total + page_size - 1) / page_size } else { 0 };
 
-        PaginatedExerciseResponse {
+        Ok(PaginatedExerciseResponse {
             exercises,
             total,
             page,
             page_size,
             total_pages,
+        })
+    }
+
+    // Taxonomy lists
+    pub fn list_taxonomy(&self, kind: TaxonomyKind) -> AppResult<Vec<TaxonomyItem>> {
+        self.taxonomy_repository.list(kind)
+    }
+
+    pub fn create_taxonomy_item(&self, kind: TaxonomyKind, name: &str) -> AppResult<i32> {
+        let name = Self::validate_taxonomy_name(name)?;
+        self.taxonomy_repository.create(kind, name)
+    }
+
+    pub fn rename_taxonomy_item(&self, kind: TaxonomyKind, id: i32, name: &str) -> AppResult<()> {
+        if id <= 0 {
+            return Err(AppError::validation("id", "Invalid taxonomy item ID"));
         }
+        let name = Self::validate_taxonomy_name(name)?;
+        self.taxonomy_repository.rename(kind, id, name)
     }
-} 
\ No newline at end of file
+
+    pub fn delete_taxonomy_item(&self, kind: TaxonomyKind, id: i32) -> AppResult<()> {
+        if id <= 0 {
+            return Err(AppError::validation("id", "Invalid taxonomy item ID"));
+        }
+        self.taxonomy_repository.delete(kind, id)
+    }
+
+    pub fn get_exercise_taxonomy(&self, exercise_id: i32) -> AppResult<ExerciseTaxonomy> {
+        self.ensure_exercise_exists(exercise_id)?;
+        self.taxonomy_repository.get_exercise_taxonomy(exercise_id)
+    }
+
+    pub fn set_exercise_taxonomy(&self, exercise_id: i32, mut taxonomy: ExerciseTaxonomyUpdate) -> AppResult<()> {
+        self.ensure_exercise_exists(exercise_id)?;
+
+        for ids in [
+            &mut taxonomy.primary_muscle_group_ids,
+            &mut taxonomy.secondary_muscle_group_ids,
+            &mut taxonomy.equipment_ids,
+            &mut taxonomy.movement_pattern_ids,
+        ] {
+            ids.sort_unstable();
+            ids.dedup();
+        }
+
+        if taxonomy
+            .secondary_muscle_group_ids
+            .iter()
+            .any(|id| taxonomy.primary_muscle_group_ids.contains(id))
+        {
+            return Err(AppError::validation(
+                "secondary_muscle_group_ids",
+                "A muscle group cannot be both primary and secondary",
+            ));
+        }
+
+        self.taxonomy_repository.set_exercise_taxonomy(exercise_id, &taxonomy)
+    }
+
+    fn ensure_exercise_exists(&self, exercise_id: i32) -> AppResult<()> {
+        if exercise_id <= 0 {
+            return Err(AppError::validation("exercise_id", "Invalid exercise ID"));
+        }
+        if self.repository.get_by_id(exercise_id)?.is_none() {
+            return Err(AppError::not_found("Exercise not found"));
+        }
+        Ok(())
+    }
+
+    fn validate_taxonomy_name(name: &str) -> AppResult<&str> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::validation("name", "Name is required"));
+        }
+        if name.chars().count() > MAX_TAXONOMY_NAME_LENGTH {
+            return Err(AppError::validation("name", format!("Name cannot exceed {} characters", MAX_TAXONOMY_NAME_LENGTH)));
+        }
+        Ok(name)
+    }
+}
@@ -0,0 +1,177 @@
+use std::sync::Arc;
+use chrono::{Duration, Local};
+use crate::error::{AppError, AppResult};
+use crate::models::membership::{MembershipPlan, Payment, Subscription, SubscriptionStatus, SubscriptionWithDetails};
+use crate::repository::membership_repository::MembershipRepository;
+use crate::repository::person_repository::PersonRepository;
+use crate::services::validation::{format_date, parse_date};
+
+const MAX_PLAN_NAME_LENGTH: usize = 60;
+const MAX_PLAN_DURATION_DAYS: i32 = 3660;
+const MAX_EXPIRING_WINDOW_DAYS: i32 = 365;
+
+pub struct MembershipService {
+    repository: Arc<dyn MembershipRepository>,
+    person_repository: Arc<dyn PersonRepository>,
+}
+
+impl MembershipService {
+    pub fn new(repository: Arc<dyn MembershipRepository>, person_repository: Arc<dyn PersonRepository>) -> Self {
+        Self { repository, person_repository }
+    }
+
+    pub fn list_plans(&self, include_inactive: bool) -> AppResult<Vec<MembershipPlan>> {
+        self.repository.list_plans(include_inactive)
+    }
+
+    pub fn create_plan(&self, plan: MembershipPlan) -> AppResult<i32> {
+        let plan = Self::validate_plan(plan)?;
+        self.repository.create_plan(&plan)
+    }
+
+    /// Price and duration changes only apply to new subscriptions
+    pub fn update_plan(&self, plan: MembershipPlan) -> AppResult<()> {
+        match plan.id {
+            Some(id) if id > 0 => {}
+            _ => return Err(AppError::validation("id", "Invalid membership plan ID")),
+        }
+        let plan = Self::validate_plan(plan)?;
+        self.repository.update_plan(&plan)
+    }
+
+    pub fn create_subscription(&self, mut subscription: Subscription) -> AppResult<i32> {
+        if subscription.person_id <= 0 {
+            return Err(AppError::validation("person_id", "Invalid person ID"));
+        }
+        if self.person_repository.get_by_id(subscription.person_id)?.is_none() {
+            return Err(AppError::not_found("Person not found"));
+        }
+
+        let plan = self.repository.get_plan(subscription.plan_id)?
+            .ok_or_else(|| AppError::not_found("Membership plan not found"))?;
+        if !plan.is_active {
+            return Err(AppError::validation("plan_id", "This membership plan is no longer offered"));
+        }
+
+        let start_date = parse_date("start_date", &subscription.start_date)?;
+        let end_date = match &subscription.end_date {
+            Some(end_date) => parse_date("end_date", end_date)?,
+            // The start day counts, so a 30 day plan from the 1st covers up to the 30th
+            None => start_date + Duration::days(plan.duration_days as i64 - 1),
+        };
+        if end_date < start_date {
+            return Err(AppError::validation("end_date", "End date cannot be before the start date"));
+        }
+
+        let price = subscription.price.unwrap_or(plan.price);
+        if price < 0.0 || !price.is_finite() {
+            return Err(AppError::validation("price", "Price cannot be negative"));
+        }
+
+        subscription.start_date = format_date(start_date);
+        subscription.end_date = Some(format_date(end_date));
+        subscription.price = Some(price);
+        subscription.notes = normalize_notes(subscription.notes);
+
+        if self.repository.has_overlapping_subscription(subscription.person_id, &subscription.start_date, &format_date(end_date))? {
+            return Err(AppError::validation("start_date", "The person already has a membership for these dates"));
+        }
+
+        self.repository.create_subscription(&subscription)
+    }
+
+    pub fn get_person_subscriptions(&self, person_id: i32) -> AppResult<Vec<SubscriptionWithDetails>> {
+        if person_id <= 0 {
+            return Err(AppError::validation("person_id", "Invalid person ID"));
+        }
+        self.repository.list_person_subscriptions(person_id, &today())
+    }
+
+    /// Keeps the subscription and its payments for history
+    pub fn cancel_subscription(&self, id: i32) -> AppResult<()> {
+        if id <= 0 {
+            return Err(AppError::validation("id", "Invalid subscription ID"));
+        }
+        self.repository.cancel_subscription(id)
+    }
+
+    pub fn record_payment(&self, mut payment: Payment) -> AppResult<i32> {
+        if payment.amount <= 0.0 || !payment.amount.is_finite() {
+            return Err(AppError::validation("amount", "Amount must be greater than 0"));
+        }
+        payment.paid_on = format_date(parse_date("paid_on", &payment.paid_on)?);
+        payment.notes = normalize_notes(payment.notes);
+
+        let subscription = self.repository.get_subscription(payment.subscription_id, &today())?
+            .ok_or_else(|| AppError::not_found("Subscription not found"))?;
+        if subscription.status == SubscriptionStatus::Cancelled {
+            return Err(AppError::validation("subscription_id", "Cannot record a payment for a cancelled subscription"));
+        }
+        if payment.amount - subscription.balance >= 0.01 {
+            return Err(AppError::validation(
+                "amount",
+                format!("Amount exceeds the outstanding balance of {:.2}", subscription.balance),
+            ));
+        }
+
+        self.repository.create_payment(&payment)
+    }
+
+    pub fn get_subscription_payments(&self, subscription_id: i32) -> AppResult<Vec<Payment>> {
+        if subscription_id <= 0 {
+            return Err(AppError::validation("subscription_id", "Invalid subscription ID"));
+        }
+        self.repository.list_payments(subscription_id)
+    }
+
+    pub fn delete_payment(&self, id: i32) -> AppResult<()> {
+        if id <= 0 {
+            return Err(AppError::validation("id", "Invalid payment ID"));
+        }
+        self.repository.delete_payment(id)
+    }
+
+    /// Memberships ending within `days` days (0 = today) that have not been renewed
+    pub fn get_expiring_memberships(&self, days: i32) -> AppResult<Vec<SubscriptionWithDetails>> {
+        if !(0..=MAX_EXPIRING_WINDOW_DAYS).contains(&days) {
+            return Err(AppError::validation("days", format!("Days must be between 0 and {}", MAX_EXPIRING_WINDOW_DAYS)));
+        }
+
+        let today = Local::now().date_naive();
+        self.repository.list_expiring(&format_date(today), &format_date(today + Duration::days(days as i64)))
+    }
+
+    /// People with a started membership that is not fully paid or that expired without
+    /// a renewal, oldest debt first
+    pub fn get_overdue_clients(&self) -> AppResult<Vec<SubscriptionWithDetails>> {
+        self.repository.list_overdue(&today())
+    }
+
+    fn validate_plan(mut plan: MembershipPlan) -> AppResult<MembershipPlan> {
+        plan.name = plan.name.trim().to_string();
+        if plan.name.is_empty() {
+            return Err(AppError::validation("name", "Name is required"));
+        }
+        if plan.name.chars().count() > MAX_PLAN_NAME_LENGTH {
+            return Err(AppError::validation("name", format!("Name cannot exceed {} characters", MAX_PLAN_NAME_LENGTH)));
+        }
+        if plan.price < 0.0 || !plan.price.is_finite() {
+            return Err(AppError::validation("price", "Price cannot be negative"));
+        }
+        if !(1..=MAX_PLAN_DURATION_DAYS).contains(&plan.duration_days) {
+            return Err(AppError::validation(
+                "duration_days",
+                format!("Duration must be between 1 and {} days", MAX_PLAN_DURATION_DAYS),
+            ));
+        }
+        Ok(plan)
+    }
+}
+
+fn today() -> String {
+    format_date(Local::now().date_naive())
+}
+
+fn normalize_notes(notes: Option<String>) -> Option<String> {
+    notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty())
+}
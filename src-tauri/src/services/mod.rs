@@ -1,6 +1,18 @@
 pub mod person_service;
+pub mod membership_service;
+pub mod check_in_service;
+pub mod body_measurement_service;
 pub mod exercise_service;
 pub mod workout_entry_service;
 pub mod routine_service;
+pub mod program_service;
+pub mod assignment_service;
+pub mod progression_service;
+pub mod analytics_service;
+pub mod report_service;
 pub mod backup_service;
-pub mod updater_service; 
\ No newline at end of file
+pub mod backup_archive;
+pub mod backup_target;
+pub mod settings_service;
+pub mod updater_service;
+pub mod validation; 
\ No newline at end of file
@@ -1,7 +1,23 @@
-use crate::models::person::{Person, PaginatedPersonResponse};
+use crate::error::{AppError, AppResult};
+use crate::models::person::{DuplicateCandidate, Person, PaginatedPersonResponse, PersonMergeSummary};
 use crate::repository::person_repository::PersonRepository;
+use crate::services::validation::{format_date, parse_date};
+use chrono::{Datelike, Local};
 use std::sync::Arc;
 
+const MAX_PHONE_DIGITS: usize = 15; // E.164
+const MAX_EMAIL_LENGTH: usize = 254;
+const MIN_HEIGHT_CM: f64 = 50.0;
+const MAX_HEIGHT_CM: f64 = 250.0;
+const MAX_AGE_YEARS: i32 = 120;
+const MAX_GOAL_LENGTH: usize = 200;
+const MAX_MEDICAL_NOTES_LENGTH: usize = 2000;
+const MAX_CONTACT_NAME_LENGTH: usize = 100;
+// Shorter phones are placeholders like "0" and say nothing about identity
+const MIN_MATCHING_PHONE_DIGITS: usize = 6;
+const DUPLICATE_NAME_SIMILARITY: f64 = 0.85;
+const DUPLICATE_NAME_SIMILARITY_SAME_PHONE: f64 = 0.7;
+
 pub struct PersonService {
     repository: Arc<dyn PersonRepository>,
 }
@@ -11,98 +27,422 @@ impl PersonService {
         Self { repository }
     }
 
-    pub fn create_person(&self, person: Person) -> Result<(), String> {
-        // Validate person data
-        if person.name.trim().is_empty() {
-            return Err("Name cannot be empty".to_string());
-        }
-        if person.last_name.trim().is_empty() {
-            return Err("Last name cannot be empty".to_string());
-        }
-        if person.phone.trim().is_empty() {
-            return Err("Phone cannot be empty".to_string());
-        }
-
+    pub fn create_person(&self, person: Person) -> AppResult<()> {
+        let person = Self::validate_person(person)?;
         self.repository.create(person)
     }
 
-    pub fn list_people(&self) -> Vec<Person> {
+    pub fn list_people(&self) -> AppResult<Vec<Person>> {
         self.repository.list_all()
     }
 
-    pub fn list_people_paginated(&self, page: i32, page_size: i32) -> Vec<Person> {
+    pub fn list_people_paginated(&self, page: i32, page_size: i32) -> AppResult<Vec<Person>> {
         self.repository.list_paginated(page, page_size)
     }
 
-    pub fn list_people_paginated_response(&self, page: i32, page_size: i32) -> PaginatedPersonResponse {
-        let persons = self.repository.list_paginated(page, page_size);
-        let total = self.repository.count_all();
+    pub fn list_people_paginated_response(&self, page: i32, page_size: i32) -> AppResult<PaginatedPersonResponse> {
+        let persons = self.repository.list_paginated(page, page_size)?;
+        let total = self.repository.count_all()?;
         let total_pages = if page_size > 0 { (total + page_size - 1) / page_size } else { 0 };
 
-        PaginatedPersonResponse {
+        Ok(PaginatedPersonResponse {
             persons,
             total,
             page,
             page_size,
             total_pages,
-        }
+        })
     }
 
-    pub fn search_people(&self, query: &str) -> Vec<Person> {
+    pub fn search_people(&self, query: &str) -> AppResult<Vec<Person>> {
         if query.trim().is_empty() {
             return self.repository.list_all();
         }
         self.repository.search(query)
     }
 
-    pub fn search_people_paginated(&self, query: &str, page: i32, page_size: i32) -> Vec<Person> {
+    /// Active people with this phone number, however it is formatted
+    pub fn find_people_by_phone(&self, phone: &str) -> AppResult<Vec<Person>> {
+        let digits = phone_digits(phone);
+        if digits.is_empty() {
+            return Err(AppError::validation("phone", "Phone cannot be empty"));
+        }
+        self.repository.find_by_phone(&digits)
+    }
+
+    pub fn search_people_paginated(&self, query: &str, page: i32, page_size: i32) -> AppResult<Vec<Person>> {
         if query.trim().is_empty() {
-            return Vec::new();
+            return Ok(Vec::new());
         }
         self.repository.search_paginated(query, page, page_size)
     }
 
-    pub fn search_people_paginated_response(&self, query: &str, page: i32, page_size: i32) -> PaginatedPersonResponse {
+    pub fn search_people_paginated_response(&self, query: &str, page: i32, page_size: i32) -> AppResult<PaginatedPersonResponse> {
         if query.trim().is_empty() {
-            return PaginatedPersonResponse {
+            return Ok(PaginatedPersonResponse {
                 persons: Vec::new(),
                 total: 0,
                 page,
                 page_size,
                 total_pages: 0,
-            };
+            });
         }
         
-        let persons = self.repository.search_paginated(query, page, page_size);
-        let total = self.repository.search_count(query);
+        let persons = self.repository.search_paginated(query, page, page_size)?;
+        let total = self.repository.search_count(query)?;
         let total_pages = if page_size > 0 { (total + page_size - 1) / page_size } else { 0 };
 
-        PaginatedPersonResponse {
+        Ok(PaginatedPersonResponse {
             persons,
             total,
             page,
             page_size,
             total_pages,
-        }
+        })
     }
 
-    pub fn update_person(&self, person: Person) -> Result<(), String> {
+    /// Replaces the whole profile; fields left out are cleared
+    pub fn update_person(&self, person: Person) -> AppResult<()> {
+        match person.id {
+            Some(id) if id > 0 => {}
+            _ => return Err(AppError::validation("id", "Invalid person ID")),
+        }
+        let person = Self::validate_person(person)?;
         self.repository.update(person)
     }
 
-    pub fn delete_person(&self, id: i32) -> Result<(), String> {
+    pub fn delete_person(&self, id: i32) -> AppResult<()> {
         self.repository.delete(id)
     }
 
-    pub fn restore_person(&self, id: i32) -> Result<(), String> {
+    pub fn restore_person(&self, id: i32) -> AppResult<()> {
         self.repository.restore(id)
     }
 
-    pub fn list_deleted_people(&self) -> Vec<Person> {
+    pub fn list_deleted_people(&self) -> AppResult<Vec<Person>> {
         self.repository.list_deleted()
     }
 
-    pub fn count_deleted_people(&self) -> i32 {
+    pub fn count_deleted_people(&self) -> AppResult<i32> {
         self.repository.count_deleted()
     }
+
+    /// Pairs of active people that look like the same client, most likely first.
+    /// Names are compared without accents or case ("Juan Perez" / "Juan Pérez");
+    /// a shared phone lowers the bar, but people sharing a phone with clearly
+    /// different first names (families) are not reported.
+    pub fn find_duplicate_people(&self) -> AppResult<Vec<DuplicateCandidate>> {
+        let people = self.repository.list_all()?;
+        let keys: Vec<(String, String, String)> = people
+            .iter()
+            .map(|p| (fold_name(&p.name), fold_name(&p.last_name), phone_digits(&p.phone)))
+            .collect();
+
+        let mut candidates = Vec::new();
+        for i in 0..people.len() {
+            for j in (i + 1)..people.len() {
+                let (first_a, last_a, phone_a) = &keys[i];
+                let (first_b, last_b, phone_b) = &keys[j];
+
+                let same_phone = phone_a.len() >= MIN_MATCHING_PHONE_DIGITS && phone_a == phone_b;
+                let similarity = name_similarity(first_a, last_a, first_b, last_b);
+                let threshold = if same_phone { DUPLICATE_NAME_SIMILARITY_SAME_PHONE } else { DUPLICATE_NAME_SIMILARITY };
+                if similarity < threshold {
+                    continue;
+                }
+
+                let (person, duplicate) = if people[i].id <= people[j].id { (i, j) } else { (j, i) };
+                candidates.push(DuplicateCandidate {
+                    person: people[person].clone(),
+                    duplicate: people[duplicate].clone(),
+                    similarity: (similarity * 100.0).round() / 100.0,
+                    same_phone,
+                });
+            }
+        }
+
+        candidates.sort_by(|a, b| {
+            b.same_phone
+                .cmp(&a.same_phone)
+                .then(b.similarity.total_cmp(&a.similarity))
+                .then(a.person.id.cmp(&b.person.id))
+        });
+        Ok(candidates)
+    }
+
+    /// Keeps `survivor_id` and soft-deletes `duplicate_id` after moving its workouts,
+    /// memberships, visits and measurements over; the merged record can still be restored
+    pub fn merge_people(&self, survivor_id: i32, duplicate_id: i32) -> AppResult<PersonMergeSummary> {
+        if survivor_id <= 0 {
+            return Err(AppError::validation("survivor_id", "Invalid person ID"));
+        }
+        if duplicate_id <= 0 {
+            return Err(AppError::validation("duplicate_id", "Invalid person ID"));
+        }
+        if survivor_id == duplicate_id {
+            return Err(AppError::validation("duplicate_id", "A person cannot be merged into itself"));
+        }
+        self.repository.merge(survivor_id, duplicate_id)
+    }
+
+    /// Trims every field, clears blank optional ones and checks the profile is plausible
+    fn validate_person(mut person: Person) -> AppResult<Person> {
+        person.name = person.name.trim().to_string();
+        if person.name.is_empty() {
+            return Err(AppError::validation("name", "Name cannot be empty"));
+        }
+        person.last_name = person.last_name.trim().to_string();
+        if person.last_name.is_empty() {
+            return Err(AppError::validation("last_name", "Last name cannot be empty"));
+        }
+        person.phone = normalize_phone("phone", &person.phone)?;
+
+        if let Some(birth_date) = normalize_text(person.birth_date) {
+            let date = parse_date("birth_date", &birth_date)?;
+            let today = Local::now().date_naive();
+            if date > today {
+                return Err(AppError::validation("birth_date", "Birth date cannot be in the future"));
+            }
+            if today.year() - date.year() > MAX_AGE_YEARS {
+                return Err(AppError::validation("birth_date", "Birth date is not plausible"));
+            }
+            person.birth_date = Some(format_date(date));
+        } else {
+            person.birth_date = None;
+        }
+
+        person.email = normalize_text(person.email).map(|e| e.to_lowercase());
+        if let Some(email) = &person.email {
+            if email.len() > MAX_EMAIL_LENGTH || !is_valid_email(email) {
+                return Err(AppError::validation("email", "Invalid email address"));
+            }
+        }
+
+        if let Some(height_cm) = person.height_cm {
+            if !(MIN_HEIGHT_CM..=MAX_HEIGHT_CM).contains(&height_cm) {
+                return Err(AppError::validation(
+                    "height_cm",
+                    format!("Height must be between {} and {} cm", MIN_HEIGHT_CM, MAX_HEIGHT_CM),
+                ));
+            }
+        }
+
+        person.training_goal = normalize_text(person.training_goal);
+        check_length("training_goal", "Training goal", &person.training_goal, MAX_GOAL_LENGTH)?;
+        person.medical_notes = normalize_text(person.medical_notes);
+        check_length("medical_notes", "Medical notes", &person.medical_notes, MAX_MEDICAL_NOTES_LENGTH)?;
+        person.emergency_contact_name = normalize_text(person.emergency_contact_name);
+        check_length("emergency_contact_name", "Emergency contact name", &person.emergency_contact_name, MAX_CONTACT_NAME_LENGTH)?;
+
+        person.emergency_contact_phone = match normalize_text(person.emergency_contact_phone) {
+            Some(phone) => Some(normalize_phone("emergency_contact_phone", &phone)?),
+            None => None,
+        };
+        if person.emergency_contact_name.is_some() && person.emergency_contact_phone.is_none() {
+            return Err(AppError::validation("emergency_contact_phone", "Emergency contact needs a phone"));
+        }
+
+        Ok(person)
+    }
+}
+
+/// Keeps only the digits of a phone number, the form `find_by_phone` compares
+pub fn phone_digits(phone: &str) -> String {
+    phone.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+// Keeps the number as typed, minus stray whitespace, so "+54 11  5555-1234 " becomes
+// "+54 11 5555-1234". Only digits, spaces and + - ( ) . are accepted.
+fn normalize_phone(field: &str, phone: &str) -> AppResult<String> {
+    let phone = phone.split_whitespace().collect::<Vec<_>>().join(" ");
+    if phone.is_empty() {
+        return Err(AppError::validation(field, "Phone cannot be empty"));
+    }
+    if !phone.chars().all(|c| c.is_ascii_digit() || " +-().".contains(c)) {
+        return Err(AppError::validation(field, "Phone can only contain digits, spaces and + - ( ) ."));
+    }
+    let digits = phone_digits(&phone).len();
+    if digits == 0 || digits > MAX_PHONE_DIGITS {
+        return Err(AppError::validation(field, format!("Phone must have between 1 and {} digits", MAX_PHONE_DIGITS)));
+    }
+    Ok(phone)
+}
+
+fn normalize_text(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn check_length(field: &str, label: &str, value: &Option<String>, max: usize) -> AppResult<()> {
+    match value {
+        Some(value) if value.chars().count() > max => {
+            Err(AppError::validation(field, format!("{} cannot exceed {} characters", label, max)))
+        }
+        _ => Ok(()),
+    }
+}
+
+// Deliberately loose: one @, something before it and a dotted domain after it
+fn is_valid_email(email: &str) -> bool {
+    let (local, domain) = match email.split_once('@') {
+        Some(parts) => parts,
+        None => return false,
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && !email.chars().any(|c| c.is_whitespace())
+        && domain.split('.').count() >= 2
+        && domain.split('.').all(|label| !label.is_empty())
+}
+
+// Lowercase, without accents or punctuation, single spaced: "  José-Luis " -> "jose luis"
+fn fold_name(name: &str) -> String {
+    let folded: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'á' | 'à' | 'ä' | 'â' | 'ã' | 'å' => 'a',
+            'é' | 'è' | 'ë' | 'ê' => 'e',
+            'í' | 'ì' | 'ï' | 'î' => 'i',
+            'ó' | 'ò' | 'ö' | 'ô' | 'õ' => 'o',
+            'ú' | 'ù' | 'ü' | 'û' => 'u',
+            'ñ' => 'n',
+            'ç' => 'c',
+            c if c.is_alphanumeric() => c,
+            _ => ' ',
+        })
+        .collect();
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// The weaker of the first and last name similarities, also trying the names swapped
+fn name_similarity(first_a: &str, last_a: &str, first_b: &str, last_b: &str) -> f64 {
+    let straight = part_similarity(first_a, first_b).min(part_similarity(last_a, last_b));
+    let swapped = part_similarity(first_a, last_b).min(part_similarity(last_a, first_b));
+    straight.max(swapped)
+}
+
+fn part_similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    // An initial or two ("Juan P") matches the full name it abbreviates
+    let (short, long) = if a.chars().count() <= b.chars().count() { (a, b) } else { (b, a) };
+    if short.chars().count() <= 2 && long.starts_with(short) {
+        return 0.9;
+    }
+
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    1.0 - levenshtein(&a, &b) as f64 / a.len().max(b.len()) as f64
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::db::test_pool;
+    use crate::repository::sqlite_person_repository::SqlitePersonRepository;
+
+    fn person(name: &str, last_name: &str, phone: &str) -> Person {
+        serde_json::from_value(serde_json::json!({ "id": null, "name": name, "last_name": last_name, "phone": phone })).unwrap()
+    }
+
+    // Duplicate pairs as (person id, duplicate id, same phone) among people created in order
+    fn duplicates(people: &[(&str, &str, &str)]) -> Vec<(i32, i32, bool)> {
+        let (_dir, pool) = test_pool();
+        let repository = Arc::new(SqlitePersonRepository::new(pool));
+        for (name, last_name, phone) in people {
+            repository.create(person(name, last_name, phone)).unwrap();
+        }
+        PersonService::new(repository)
+            .find_duplicate_people()
+            .unwrap()
+            .iter()
+            .map(|candidate| (candidate.person.id.unwrap(), candidate.duplicate.id.unwrap(), candidate.same_phone))
+            .collect()
+    }
+
+    #[test]
+    fn names_are_folded_without_accents_case_or_punctuation() {
+        assert_eq!(fold_name("José-Luis"), "jose luis");
+        assert_eq!(fold_name("  MARÍA  del   Carmen "), "maria del carmen");
+        assert_eq!(fold_name("Núñez"), "nunez");
+        assert_eq!(fold_name("O'Connor"), "o connor");
+        assert_eq!(fold_name("François Müller"), "francois muller");
+    }
+
+    #[test]
+    fn name_similarity_tries_the_names_swapped() {
+        assert_eq!(name_similarity("ana", "gomez", "gomez", "ana"), 1.0);
+        assert_eq!(name_similarity("juan", "perez", "juan", "p"), 0.9);
+        // Both names have to match: a shared surname alone is not enough
+        assert!(name_similarity("maria", "perez", "juan", "perez") < DUPLICATE_NAME_SIMILARITY_SAME_PHONE);
+        assert_eq!(levenshtein(&['g', 'o', 'm', 'e', 'z'], &['g', 'o', 'm', 'e', 's']), 1);
+    }
+
+    #[test]
+    fn accents_case_and_phone_format_do_not_hide_a_duplicate() {
+        let pairs = duplicates(&[("Juan", "Perez", "11 5555-1234"), ("juan", "Pérez ", "+1155551234")]);
+        assert_eq!(pairs, vec![(1, 2, true)]);
+    }
+
+    #[test]
+    fn swapped_first_and_last_names_are_duplicates() {
+        let pairs = duplicates(&[("José Luis", "García", "600000001"), ("Garcia", "Jose-Luis", "600000002")]);
+        assert_eq!(pairs, vec![(1, 2, false)]);
+    }
+
+    #[test]
+    fn family_members_sharing_a_phone_are_not_duplicates() {
+        let pairs = duplicates(&[
+            ("Juan", "Perez", "11 5555-1234"),
+            ("Maria", "Perez", "11 5555 1234"),
+            ("Lucas", "Perez", "1155551234"),
+        ]);
+        assert!(pairs.is_empty(), "{:?}", pairs);
+
+        // The shared phone still lowers the bar for an abbreviated surname
+        let pairs = duplicates(&[("Juan", "Perez", "11 5555-1234"), ("Maria", "Perez", "11 5555 1234"), ("Juan", "P", "1155551234")]);
+        assert_eq!(pairs, vec![(1, 3, true)]);
+    }
+
+    #[test]
+    fn placeholder_phones_do_not_count_as_shared() {
+        // "Gomes" is 0.8 similar: enough with a shared phone, not without one
+        let pairs = duplicates(&[("Ana", "Gomez", "0"), ("Ana", "Gómes", "0")]);
+        assert!(pairs.is_empty(), "{:?}", pairs);
+        let pairs = duplicates(&[("Ana", "Gomez", "600000001"), ("Ana", "Gómes", "600 000 001")]);
+        assert_eq!(pairs, vec![(1, 2, true)]);
+    }
+
+    #[test]
+    fn shared_phone_pairs_come_first() {
+        let pairs = duplicates(&[("Ana", "Gomez", "600000001"), ("Ana", "Gomez", "600000002"), ("Juan", "Perez", "611111111"), ("Juan", "Peres", "611111111")]);
+        assert_eq!(pairs, vec![(3, 4, true), (1, 2, false)]);
+    }
+
+    #[test]
+    fn merge_refuses_the_same_person() {
+        let (_dir, pool) = test_pool();
+        let service = PersonService::new(Arc::new(SqlitePersonRepository::new(pool)));
+        assert_eq!(service.merge_people(1, 1).unwrap_err().field(), Some("duplicate_id"));
+        assert_eq!(service.merge_people(0, 1).unwrap_err().field(), Some("survivor_id"));
+        assert_eq!(service.merge_people(1, 2).unwrap_err().code(), "NOT_FOUND");
+    }
 }
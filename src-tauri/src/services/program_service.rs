@@ -0,0 +1,303 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use crate::error::{AppError, AppResult};
+use crate::models::program::{Program, ProgramDay, ProgramExerciseWithDetails, ProgramWithDays};
+use crate::models::routine_exercise::RoutineExerciseWithDetails;
+use crate::repository::program_repository::ProgramRepository;
+use crate::repository::routine_repository::RoutineRepository;
+use crate::services::validation::{normalize_tempo, validate_duration_distance, validate_intensity};
+
+const MAX_PROGRAM_WEEKS: i32 = 52;
+const DAYS_PER_WEEK: i32 = 7;
+const MAX_LABEL_LENGTH: usize = 30;
+
+pub struct ProgramService {
+    repository: Arc<dyn ProgramRepository>,
+    routine_repository: Arc<dyn RoutineRepository>,
+}
+
+impl ProgramService {
+    pub fn new(repository: Arc<dyn ProgramRepository>, routine_repository: Arc<dyn RoutineRepository>) -> Self {
+        Self { repository, routine_repository }
+    }
+
+    pub fn create_program(&self, program: Program, days: Vec<ProgramDay>) -> AppResult<i32> {
+        let program = Self::validate_program(program)?;
+        let days = self.validate_days(&program, days)?;
+        self.repository.create(&program, &days)
+    }
+
+    /// Replaces the program and all of its days
+    pub fn update_program(&self, program: Program, days: Vec<ProgramDay>) -> AppResult<()> {
+        match program.id {
+            Some(id) if id > 0 => {}
+            _ => return Err(AppError::validation("id", "Invalid program ID")),
+        }
+        let program = Self::validate_program(program)?;
+        let days = self.validate_days(&program, days)?;
+        self.repository.update(&program, &days)
+    }
+
+    /// Copies a program, e.g. to adapt last block's mesocycle for a new one
+    pub fn clone_program(&self, id: i32, name: String, code: String) -> AppResult<i32> {
+        if id <= 0 {
+            return Err(AppError::validation("id", "Invalid program ID"));
+        }
+        let (name, code) = validate_name_code(&name, &code)?;
+        self.repository.clone_program(id, &name, &code)
+    }
+
+    /// The program with every day resolved to its exercises, routine days included
+    pub fn get_program(&self, id: i32) -> AppResult<Option<ProgramWithDays>> {
+        let program = match self.repository.get_by_id(id)? {
+            Some(program) => program,
+            None => return Ok(None),
+        };
+
+        let mut days = self.repository.get_days(id)?;
+        let mut routines: HashMap<i32, Vec<ProgramExerciseWithDetails>> = HashMap::new();
+        for day in days.iter_mut() {
+            let routine_id = match day.routine_id {
+                Some(routine_id) => routine_id,
+                None => continue,
+            };
+            // Several days often share a routine; load each one once
+            day.exercises = match routines.get(&routine_id) {
+                Some(exercises) => exercises.clone(),
+                None => {
+                    let exercises: Vec<_> = self
+                        .routine_repository
+                        .get_routine_exercises(routine_id)?
+                        .into_iter()
+                        .map(from_routine_exercise)
+                        .collect();
+                    routines.insert(routine_id, exercises.clone());
+                    exercises
+                }
+            };
+        }
+
+        Ok(Some(ProgramWithDays {
+            id: program.id,
+            name: program.name,
+            code: program.code,
+            description: program.description,
+            weeks: program.weeks,
+            created_at: program.created_at,
+            updated_at: program.updated_at,
+            days,
+        }))
+    }
+
+    pub fn list_programs(&self) -> AppResult<Vec<Program>> {
+        self.repository.list_all()
+    }
+
+    pub fn delete_program(&self, id: i32) -> AppResult<()> {
+        self.repository.delete(id)
+    }
+
+    pub fn restore_program(&self, id: i32) -> AppResult<()> {
+        self.repository.restore(id)
+    }
+
+    fn validate_program(mut program: Program) -> AppResult<Program> {
+        let (name, code) = validate_name_code(&program.name, &program.code)?;
+        program.name = name;
+        program.code = code;
+        program.description = program.description.map(|d| d.trim().to_string()).filter(|d| !d.is_empty());
+
+        if !(1..=MAX_PROGRAM_WEEKS).contains(&program.weeks) {
+            return Err(AppError::validation("weeks", format!("Weeks must be between 1 and {}", MAX_PROGRAM_WEEKS)));
+        }
+        Ok(program)
+    }
+
+    fn validate_days(&self, program: &Program, mut days: Vec<ProgramDay>) -> AppResult<Vec<ProgramDay>> {
+        if days.is_empty() {
+            return Err(AppError::validation("days", "A program needs at least one training day"));
+        }
+
+        days.sort_by_key(|day| (day.week, day.day_index));
+        for (index, day) in days.iter().enumerate() {
+            if index > 0 && days[index - 1].week == day.week && days[index - 1].day_index == day.day_index {
+                return Err(AppError::validation(
+                    "days",
+                    format!("Week {} has two days at position {}", day.week, day.day_index),
+                ));
+            }
+        }
+
+        for day in days.iter_mut() {
+            if !(1..=program.weeks).contains(&day.week) {
+                return Err(AppError::validation("week", format!("Week must be between 1 and {}", program.weeks)));
+            }
+            if !(1..=DAYS_PER_WEEK).contains(&day.day_index) {
+                return Err(AppError::validation("day_index", format!("Day must be between 1 and {}", DAYS_PER_WEEK)));
+            }
+
+            day.label = day.label.take().map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+            if let Some(label) = &day.label {
+                if label.chars().count() > MAX_LABEL_LENGTH {
+                    return Err(AppError::validation("label", format!("Label cannot exceed {} characters", MAX_LABEL_LENGTH)));
+                }
+            }
+            day.notes = day.notes.take().map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+
+            match day.routine_id {
+                Some(routine_id) => {
+                    if !day.exercises.is_empty() {
+                        return Err(AppError::validation(
+                            "exercises",
+                            format!("Week {} day {} uses a routine and cannot also list exercises", day.week, day.day_index),
+                        ));
+                    }
+                    if self.routine_repository.get_by_id(routine_id)?.is_none() {
+                        return Err(AppError::not_found("Routine not found"));
+                    }
+                }
+                None => {
+                    if day.exercises.is_empty() {
+                        return Err(AppError::validation(
+                            "exercises",
+                            format!("Week {} day {} needs a routine or at least one exercise", day.week, day.day_index),
+                        ));
+                    }
+                }
+            }
+
+            let mut seen = HashSet::new();
+            for (order_index, exercise) in day.exercises.iter_mut().enumerate() {
+                if !seen.insert(exercise.exercise_id) {
+                    return Err(AppError::validation("exercises", "An exercise appears twice in the same day"));
+                }
+                exercise.tempo = normalize_tempo(exercise.tempo.take());
+                validate_intensity(exercise.rpe, exercise.rir, exercise.tempo.as_deref(), exercise.rest_seconds)?;
+                validate_duration_distance(exercise.duration_seconds, exercise.distance_meters)?;
+                exercise.order_index = order_index as i32;
+                exercise.group_number = Some(exercise.group_number.unwrap_or(1));
+            }
+        }
+
+        Ok(days)
+    }
+}
+
+fn validate_name_code(name: &str, code: &str) -> AppResult<(String, String)> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::validation("name", "Name cannot be empty"));
+    }
+    let code = code.trim();
+    if code.is_empty() {
+        return Err(AppError::validation("code", "Code cannot be empty"));
+    }
+    Ok((name.to_string(), code.to_uppercase()))
+}
+
+/// A routine exercise as it is prescribed on a program day
+pub fn from_routine_exercise(exercise: RoutineExerciseWithDetails) -> ProgramExerciseWithDetails {
+    ProgramExerciseWithDetails {
+        program_day_exercise_id: None,
+        routine_exercise_id: exercise.id,
+        exercise_id: exercise.exercise_id,
+        order_index: exercise.order_index,
+        sets: exercise.sets,
+        reps: exercise.reps,
+        weight: exercise.weight,
+        notes: exercise.notes,
+        group_number: exercise.group_number,
+        rpe: exercise.rpe,
+        rir: exercise.rir,
+        tempo: exercise.tempo,
+        rest_seconds: exercise.rest_seconds,
+        duration_seconds: exercise.duration_seconds,
+        distance_meters: exercise.distance_meters,
+        exercise_name: exercise.exercise_name,
+        exercise_code: exercise.exercise_code,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::db::test_pool;
+    use crate::models::exercise::Exercise;
+    use crate::models::program::ProgramDayExercise;
+    use crate::models::routine::Routine;
+    use crate::repository::exercise_repository::ExerciseRepository;
+    use crate::repository::sqlite_exercise_repository::SqliteExerciseRepository;
+    use crate::repository::sqlite_program_repository::SqliteProgramRepository;
+    use crate::repository::sqlite_routine_repository::SqliteRoutineRepository;
+
+    fn program(weeks: i32) -> Program {
+        Program { id: None, name: "Block".into(), code: "BLK".into(), description: None, weeks, created_at: None, updated_at: None }
+    }
+
+    fn day(week: i32, day_index: i32, routine_id: Option<i32>, exercise_ids: &[i32]) -> ProgramDay {
+        let exercises = exercise_ids
+            .iter()
+            .map(|&exercise_id| {
+                serde_json::from_value::<ProgramDayExercise>(serde_json::json!({ "id": null, "exercise_id": exercise_id, "sets": 3, "reps": 5 }))
+                    .unwrap()
+            })
+            .collect();
+        ProgramDay { id: None, program_id: 0, week, day_index, label: None, routine_id, notes: None, exercises }
+    }
+
+    // A service with exercises 1 and 2 and routine 1
+    fn setup() -> (tempfile::TempDir, ProgramService) {
+        let (dir, pool) = test_pool();
+        let exercise_repository = SqliteExerciseRepository::new(pool.clone());
+        for (name, code) in [("Squat", "SQ"), ("Bench", "BP")] {
+            exercise_repository
+                .create(Exercise { id: None, name: name.into(), code: code.into(), measurement_type: Default::default() })
+                .unwrap();
+        }
+        let routine_repository = Arc::new(SqliteRoutineRepository::new(pool.clone()));
+        routine_repository.create(Routine::new("Full body".into(), "FB".into())).unwrap();
+        (dir, ProgramService::new(Arc::new(SqliteProgramRepository::new(pool)), routine_repository))
+    }
+
+    #[test]
+    fn the_same_week_and_day_cannot_appear_twice() {
+        let (_dir, service) = setup();
+
+        let error = service.create_program(program(4), vec![day(2, 3, None, &[1]), day(1, 1, Some(1), &[]), day(2, 3, Some(1), &[])]).unwrap_err();
+        assert_eq!((error.code(), error.field()), ("VALIDATION_FAILED", Some("days")));
+
+        service.create_program(program(4), vec![day(2, 3, None, &[1]), day(1, 3, Some(1), &[])]).unwrap();
+    }
+
+    #[test]
+    fn a_day_has_either_a_routine_or_exercises() {
+        let (_dir, service) = setup();
+
+        for invalid in [day(1, 1, Some(1), &[1]), day(1, 1, None, &[])] {
+            let error = service.create_program(program(1), vec![invalid]).unwrap_err();
+            assert_eq!((error.code(), error.field()), ("VALIDATION_FAILED", Some("exercises")));
+        }
+        let error = service.create_program(program(1), vec![day(1, 1, Some(99), &[])]).unwrap_err();
+        assert_eq!(error.code(), "NOT_FOUND");
+
+        let id = service.create_program(program(1), vec![day(1, 1, Some(1), &[]), day(1, 2, None, &[1, 2])]).unwrap();
+        let days = service.get_program(id).unwrap().unwrap().days;
+        assert_eq!(days[0].routine_name.as_deref(), Some("Full body"));
+        let order: Vec<_> = days[1].exercises.iter().map(|e| (e.exercise_id, e.order_index)).collect();
+        assert_eq!(order, [(1, 0), (2, 1)]);
+    }
+
+    #[test]
+    fn weeks_and_days_stay_within_the_program() {
+        let (_dir, service) = setup();
+
+        for (week, day_index, field) in [(0, 1, "week"), (5, 1, "week"), (1, 0, "day_index"), (1, 8, "day_index")] {
+            let error = service.create_program(program(4), vec![day(week, day_index, Some(1), &[])]).unwrap_err();
+            assert_eq!((error.code(), error.field()), ("VALIDATION_FAILED", Some(field)), "week {} day {}", week, day_index);
+        }
+        let error = service.create_program(program(MAX_PROGRAM_WEEKS + 1), vec![day(1, 1, Some(1), &[])]).unwrap_err();
+        assert_eq!(error.field(), Some("weeks"));
+
+        service.create_program(program(4), vec![day(4, 7, Some(1), &[])]).unwrap();
+    }
+}
@@ -0,0 +1,484 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use chrono::Local;
+use crate::error::{AppError, AppResult};
+use crate::models::personal_record::OneRepMaxFormula;
+use crate::models::progression::{ProgressionKind, ProgressionOutcome, ProgressionProposal, ProgressionRule};
+use crate::models::routine_exercise::RoutineExerciseWithDetails;
+use crate::models::workout_entry::{WorkoutEntryStatus, WorkoutEntryWithDetails};
+use crate::repository::progression_repository::ProgressionRepository;
+use crate::repository::routine_repository::RoutineRepository;
+use crate::repository::workout_entry_repository::WorkoutEntryRepository;
+use crate::services::analytics_service::performed_sets;
+use crate::services::validation::format_date;
+
+// Smallest jump most gyms can load; used when a percentage rule has no increment
+const DEFAULT_ROUNDING_KG: f64 = 2.5;
+const DEFAULT_DELOAD_PERCENTAGE: f64 = 10.0;
+const MAX_DELOAD_PERCENTAGE: f64 = 50.0;
+const MAX_FAILURES_BEFORE_DELOAD: i32 = 10;
+
+pub struct ProgressionService {
+    repository: Arc<dyn ProgressionRepository>,
+    routine_repository: Arc<dyn RoutineRepository>,
+    workout_entry_repository: Arc<dyn WorkoutEntryRepository + Send + Sync>,
+}
+
+// One training day of an exercise, from the sets actually performed
+struct Session {
+    date: String,
+    top_weight: f64,
+    top_sets: i32, // Sets done at the top weight
+    top_reps: i32, // Fewest reps over those sets
+    estimated_max: Option<f64>,
+}
+
+impl Session {
+    fn completed(&self, sets: Option<i32>, reps: Option<i32>) -> bool {
+        self.top_sets >= sets.unwrap_or(1) && self.top_reps >= reps.unwrap_or(1)
+    }
+}
+
+impl ProgressionService {
+    pub fn new(
+        repository: Arc<dyn ProgressionRepository>,
+        routine_repository: Arc<dyn RoutineRepository>,
+        workout_entry_repository: Arc<dyn WorkoutEntryRepository + Send + Sync>,
+    ) -> Self {
+        Self { repository, routine_repository, workout_entry_repository }
+    }
+
+    pub fn set_progression_rule(&self, rule: ProgressionRule) -> AppResult<i32> {
+        let rule = self.validate_rule(rule)?;
+        self.repository.save(&rule)
+    }
+
+    pub fn delete_progression_rule(&self, routine_id: i32, exercise_id: i32) -> AppResult<()> {
+        self.repository.delete(routine_id, exercise_id)
+    }
+
+    pub fn get_progression_rules(&self, routine_id: i32) -> AppResult<Vec<ProgressionRule>> {
+        self.repository.list_by_routine(routine_id)
+    }
+
+    /// Targets for the person's next session of the routine, exercise by exercise,
+    /// from their last session of each exercise up to today
+    pub fn propose_next_session(
+        &self,
+        person_id: i32,
+        routine_id: i32,
+        formula: OneRepMaxFormula,
+    ) -> AppResult<Vec<ProgressionProposal>> {
+        if person_id <= 0 {
+            return Err(AppError::validation("person_id", "Invalid person ID"));
+        }
+        if self.routine_repository.get_by_id(routine_id)?.is_none() {
+            return Err(AppError::not_found("Routine not found"));
+        }
+
+        let rules: HashMap<i32, ProgressionRule> = self
+            .repository
+            .list_by_routine(routine_id)?
+            .into_iter()
+            .map(|rule| (rule.exercise_id, rule))
+            .collect();
+        let today = format_date(Local::now().date_naive());
+
+        let mut proposals = Vec::new();
+        for exercise in self.routine_repository.get_routine_exercises(routine_id)? {
+            let sessions = self.sessions(person_id, exercise.exercise_id, &today, formula)?;
+            proposals.push(propose(&exercise, rules.get(&exercise.exercise_id), &sessions));
+        }
+        Ok(proposals)
+    }
+
+    fn sessions(&self, person_id: i32, exercise_id: i32, today: &str, formula: OneRepMaxFormula) -> AppResult<Vec<Session>> {
+        let entries = self.workout_entry_repository.get_by_person_and_exercise(person_id, exercise_id)?;
+        Ok(group_sessions(entries, today, formula))
+    }
+
+    fn validate_rule(&self, mut rule: ProgressionRule) -> AppResult<ProgressionRule> {
+        if rule.routine_id <= 0 {
+            return Err(AppError::validation("routine_id", "Invalid routine ID"));
+        }
+        let exercises = self.routine_repository.get_routine_exercises(rule.routine_id)?;
+        if !exercises.iter().any(|e| e.exercise_id == rule.exercise_id) {
+            return Err(AppError::not_found("The exercise is not part of the routine"));
+        }
+
+        if let Some(increment) = rule.increment_kg {
+            if increment <= 0.0 {
+                return Err(AppError::validation("increment_kg", "Increment must be greater than 0"));
+            }
+        }
+
+        match rule.kind {
+            ProgressionKind::Linear | ProgressionKind::DoubleProgression if rule.increment_kg.is_none() => {
+                return Err(AppError::validation("increment_kg", "Increment is required"));
+            }
+            ProgressionKind::DoubleProgression => match (rule.min_reps, rule.max_reps) {
+                (Some(min), Some(max)) if min >= 1 && max > min => {}
+                _ => {
+                    return Err(AppError::validation("max_reps", "Rep range needs a minimum of at least 1 and a larger maximum"));
+                }
+            },
+            ProgressionKind::PercentageOfE1rm => match rule.percentage {
+                Some(percentage) if percentage > 0.0 && percentage <= 100.0 => {}
+                _ => return Err(AppError::validation("percentage", "Percentage must be between 1 and 100")),
+            },
+            ProgressionKind::Linear => {}
+        }
+        if rule.kind != ProgressionKind::DoubleProgression {
+            rule.min_reps = None;
+            rule.max_reps = None;
+        }
+        if rule.kind != ProgressionKind::PercentageOfE1rm {
+            rule.percentage = None;
+        }
+
+        if let Some(failures) = rule.deload_after_failures {
+            if !(1..=MAX_FAILURES_BEFORE_DELOAD).contains(&failures) {
+                return Err(AppError::validation(
+                    "deload_after_failures",
+                    format!("Failures before a deload must be between 1 and {}", MAX_FAILURES_BEFORE_DELOAD),
+                ));
+            }
+        }
+        if let Some(percentage) = rule.deload_percentage {
+            if percentage <= 0.0 || percentage > MAX_DELOAD_PERCENTAGE {
+                return Err(AppError::validation(
+                    "deload_percentage",
+                    format!("Deload must be between 1 and {}%", MAX_DELOAD_PERCENTAGE),
+                ));
+            }
+        }
+
+        Ok(rule)
+    }
+}
+
+// One session per training day, newest first, from entries sorted newest first. Planned
+// entries, days after today and days without a performed set are not sessions.
+fn group_sessions(entries: Vec<WorkoutEntryWithDetails>, today: &str, formula: OneRepMaxFormula) -> Vec<Session> {
+    let mut sets_by_date: Vec<(String, Vec<_>)> = Vec::new();
+    for entry in entries {
+        if entry.status == WorkoutEntryStatus::Planned || entry.date.as_str() > today {
+            continue;
+        }
+        let sets = performed_sets(&entry);
+        match sets_by_date.last_mut() {
+            Some((date, date_sets)) if *date == entry.date => date_sets.extend(sets),
+            _ => sets_by_date.push((entry.date, sets)),
+        }
+    }
+
+    sets_by_date
+        .into_iter()
+        .filter(|(_, sets)| !sets.is_empty())
+        .map(|(date, sets)| {
+            let top_weight = sets.iter().map(|(weight, _, _)| *weight).fold(0.0, f64::max);
+            let top = sets.iter().filter(|(weight, _, _)| *weight == top_weight);
+            Session {
+                date,
+                top_weight,
+                top_sets: top.clone().map(|(_, _, count)| count).sum(),
+                top_reps: top.map(|(_, reps, _)| *reps).min().unwrap_or(0),
+                estimated_max: sets
+                    .iter()
+                    .filter_map(|(weight, reps, _)| formula.estimate(*weight, *reps))
+                    .fold(None, |best: Option<f64>, e| Some(best.map_or(e, |b| b.max(e)))),
+            }
+        })
+        .collect()
+}
+
+fn propose(exercise: &RoutineExerciseWithDetails, rule: Option<&ProgressionRule>, sessions: &[Session]) -> ProgressionProposal {
+    // Double progression counts a session as done once every set reaches the bottom of the range
+    let target_reps = match rule {
+        Some(rule) if rule.kind == ProgressionKind::DoubleProgression => rule.min_reps,
+        _ => exercise.reps,
+    };
+    let consecutive_failures = sessions
+        .iter()
+        .take_while(|session| !session.completed(exercise.sets, target_reps))
+        .count() as i32;
+
+    let mut proposal = ProgressionProposal {
+        routine_id: exercise.routine_id,
+        exercise_id: exercise.exercise_id,
+        exercise_name: exercise.exercise_name.clone(),
+        outcome: ProgressionOutcome::NoRule,
+        last_date: None,
+        last_weight: None,
+        last_reps: None,
+        last_completed: false,
+        consecutive_failures,
+        sets: exercise.sets,
+        reps: target_reps,
+        weight: exercise.weight,
+    };
+
+    let last = match sessions.first() {
+        Some(last) => last,
+        None => {
+            if rule.is_some() {
+                proposal.outcome = ProgressionOutcome::NoHistory;
+            }
+            return proposal;
+        }
+    };
+    proposal.last_date = Some(last.date.clone());
+    proposal.last_weight = Some(last.top_weight);
+    proposal.last_reps = Some(last.top_reps);
+    proposal.last_completed = consecutive_failures == 0;
+
+    let rule = match rule {
+        Some(rule) => rule,
+        None => return proposal,
+    };
+    let increment = rule.increment_kg.unwrap_or(DEFAULT_ROUNDING_KG);
+
+    if let Some(failures) = rule.deload_after_failures {
+        if consecutive_failures >= failures {
+            let deload = rule.deload_percentage.unwrap_or(DEFAULT_DELOAD_PERCENTAGE);
+            proposal.outcome = ProgressionOutcome::Deload;
+            proposal.weight = Some(round_to(last.top_weight * (1.0 - deload / 100.0), increment));
+            return proposal;
+        }
+    }
+
+    match rule.kind {
+        ProgressionKind::Linear => {
+            if proposal.last_completed {
+                proposal.outcome = ProgressionOutcome::Increase;
+                proposal.weight = Some(round_to(last.top_weight + increment, 0.01));
+            } else {
+                proposal.outcome = ProgressionOutcome::Hold;
+                proposal.weight = Some(last.top_weight);
+            }
+        }
+        ProgressionKind::DoubleProgression => {
+            let max_reps = rule.max_reps.unwrap_or(last.top_reps);
+            if last.completed(exercise.sets, Some(max_reps)) {
+                proposal.outcome = ProgressionOutcome::Increase;
+                proposal.weight = Some(round_to(last.top_weight + increment, 0.01));
+            } else if proposal.last_completed {
+                proposal.outcome = ProgressionOutcome::AddReps;
+                proposal.reps = Some((last.top_reps + 1).min(max_reps));
+                proposal.weight = Some(last.top_weight);
+            } else {
+                proposal.outcome = ProgressionOutcome::Hold;
+                proposal.weight = Some(last.top_weight);
+            }
+        }
+        ProgressionKind::PercentageOfE1rm => match (last.estimated_max, rule.percentage) {
+            (Some(estimated_max), Some(percentage)) => {
+                proposal.outcome = ProgressionOutcome::Percentage;
+                proposal.weight = Some(round_to(estimated_max * percentage / 100.0, increment));
+            }
+            _ => proposal.outcome = ProgressionOutcome::Hold,
+        },
+    }
+    proposal
+}
+
+fn round_to(weight: f64, step: f64) -> f64 {
+    let rounded = (weight / step).round() * step;
+    (rounded * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::workout_set::{SetType, WorkoutSet};
+
+    // Squat prescribed at 3×5 @ 50 kg
+    fn exercise() -> RoutineExerciseWithDetails {
+        RoutineExerciseWithDetails {
+            id: Some(1),
+            routine_id: 1,
+            exercise_id: 1,
+            order_index: 0,
+            sets: Some(3),
+            reps: Some(5),
+            weight: Some(50.0),
+            notes: None,
+            group_number: Some(1),
+            rpe: None,
+            rir: None,
+            tempo: None,
+            rest_seconds: None,
+            duration_seconds: None,
+            distance_meters: None,
+            created_at: None,
+            updated_at: None,
+            exercise_name: "Squat".into(),
+            exercise_code: "SQ".into(),
+        }
+    }
+
+    fn rule(kind: ProgressionKind) -> ProgressionRule {
+        ProgressionRule {
+            id: None,
+            routine_id: 1,
+            exercise_id: 1,
+            kind,
+            increment_kg: Some(2.5),
+            min_reps: Some(8),
+            max_reps: Some(12),
+            percentage: Some(80.0),
+            deload_after_failures: None,
+            deload_percentage: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    fn session(date: &str, top_weight: f64, top_sets: i32, top_reps: i32) -> Session {
+        Session {
+            date: date.into(),
+            top_weight,
+            top_sets,
+            top_reps,
+            estimated_max: OneRepMaxFormula::Epley.estimate(top_weight, top_reps),
+        }
+    }
+
+    fn entry(date: &str, status: WorkoutEntryStatus, summary: (i32, i32, f64), workout_sets: Vec<WorkoutSet>) -> WorkoutEntryWithDetails {
+        let (sets, reps, weight) = summary;
+        WorkoutEntryWithDetails {
+            id: Some(1),
+            person_id: 1,
+            exercise_id: 1,
+            date: date.into(),
+            sets: Some(sets),
+            reps: Some(reps),
+            weight: Some(weight),
+            notes: None,
+            order_index: Some(0),
+            group_number: Some(1),
+            rpe: None,
+            rir: None,
+            tempo: None,
+            rest_seconds: None,
+            duration_seconds: None,
+            distance_meters: None,
+            routine_id: None,
+            routine_exercise_id: None,
+            status,
+            created_at: None,
+            updated_at: None,
+            person_name: "Ana".into(),
+            person_last_name: "Ruiz".into(),
+            exercise_name: "Squat".into(),
+            exercise_code: "SQ".into(),
+            workout_sets,
+        }
+    }
+
+    #[test]
+    fn linear_adds_the_increment_after_a_completed_session() {
+        let proposal = propose(&exercise(), Some(&rule(ProgressionKind::Linear)), &[session("2024-01-01", 100.0, 3, 5)]);
+        assert_eq!(proposal.outcome, ProgressionOutcome::Increase);
+        assert_eq!(proposal.weight, Some(102.5));
+        assert!(proposal.last_completed);
+
+        // Only two sets at the top weight: same weight again
+        let proposal = propose(&exercise(), Some(&rule(ProgressionKind::Linear)), &[session("2024-01-01", 100.0, 2, 5)]);
+        assert_eq!(proposal.outcome, ProgressionOutcome::Hold);
+        assert_eq!(proposal.weight, Some(100.0));
+        assert_eq!(proposal.consecutive_failures, 1);
+    }
+
+    #[test]
+    fn double_progression_adds_reps_then_weight() {
+        let rule = rule(ProgressionKind::DoubleProgression);
+
+        let proposal = propose(&exercise(), Some(&rule), &[session("2024-01-01", 60.0, 3, 9)]);
+        assert_eq!(proposal.outcome, ProgressionOutcome::AddReps);
+        assert_eq!(proposal.reps, Some(10));
+        assert_eq!(proposal.weight, Some(60.0));
+
+        let proposal = propose(&exercise(), Some(&rule), &[session("2024-01-01", 60.0, 3, 12)]);
+        assert_eq!(proposal.outcome, ProgressionOutcome::Increase);
+        assert_eq!(proposal.reps, Some(8));
+        assert_eq!(proposal.weight, Some(62.5));
+
+        // Below the bottom of the range
+        let proposal = propose(&exercise(), Some(&rule), &[session("2024-01-01", 60.0, 3, 7)]);
+        assert_eq!(proposal.outcome, ProgressionOutcome::Hold);
+        assert_eq!(proposal.reps, Some(8));
+    }
+
+    #[test]
+    fn percentage_of_e1rm_rounds_to_the_increment() {
+        // 100 × 5 is an Epley 1RM of 116.7; 80% is 93.4, rounded to 92.5
+        let proposal = propose(&exercise(), Some(&rule(ProgressionKind::PercentageOfE1rm)), &[session("2024-01-01", 100.0, 3, 5)]);
+        assert_eq!(proposal.outcome, ProgressionOutcome::Percentage);
+        assert_eq!(proposal.weight, Some(92.5));
+
+        let without_estimate = Session { estimated_max: None, ..session("2024-01-01", 100.0, 3, 5) };
+        let proposal = propose(&exercise(), Some(&rule(ProgressionKind::PercentageOfE1rm)), &[without_estimate]);
+        assert_eq!(proposal.outcome, ProgressionOutcome::Hold);
+    }
+
+    #[test]
+    fn deload_after_consecutive_failures() {
+        let rule = ProgressionRule {
+            deload_after_failures: Some(2),
+            deload_percentage: Some(10.0),
+            ..rule(ProgressionKind::Linear)
+        };
+
+        let one_miss = [session("2024-01-03", 102.5, 3, 4), session("2024-01-01", 100.0, 3, 5)];
+        let proposal = propose(&exercise(), Some(&rule), &one_miss);
+        assert_eq!(proposal.outcome, ProgressionOutcome::Hold);
+        assert_eq!(proposal.consecutive_failures, 1);
+
+        // 102.5 less 10% is 92.25, rounded to 92.5
+        let two_misses = [session("2024-01-05", 102.5, 2, 5), session("2024-01-03", 102.5, 3, 4), session("2024-01-01", 100.0, 3, 5)];
+        let proposal = propose(&exercise(), Some(&rule), &two_misses);
+        assert_eq!(proposal.outcome, ProgressionOutcome::Deload);
+        assert_eq!(proposal.consecutive_failures, 2);
+        assert_eq!(proposal.weight, Some(92.5));
+    }
+
+    #[test]
+    fn no_history_keeps_the_prescription() {
+        let proposal = propose(&exercise(), Some(&rule(ProgressionKind::Linear)), &[]);
+        assert_eq!(proposal.outcome, ProgressionOutcome::NoHistory);
+        assert_eq!((proposal.sets, proposal.reps, proposal.weight), (Some(3), Some(5), Some(50.0)));
+        assert!(proposal.last_date.is_none());
+
+        let proposal = propose(&exercise(), None, &[]);
+        assert_eq!(proposal.outcome, ProgressionOutcome::NoRule);
+    }
+
+    #[test]
+    fn sessions_leave_out_planned_future_and_untrained_entries() {
+        let set = |reps: i32, weight: f64, completed: bool| WorkoutSet {
+            id: None,
+            workout_entry_id: 1,
+            order_index: 0,
+            reps: Some(reps),
+            weight: Some(weight),
+            completed,
+            set_type: SetType::Working,
+            created_at: None,
+            updated_at: None,
+        };
+        let entries = vec![
+            entry("2999-01-01", WorkoutEntryStatus::Performed, (3, 5, 300.0), Vec::new()),
+            entry("2024-01-05", WorkoutEntryStatus::Planned, (3, 5, 200.0), Vec::new()),
+            entry("2024-01-03", WorkoutEntryStatus::Performed, (3, 5, 150.0), vec![set(5, 150.0, false), set(5, 150.0, false)]),
+            entry("2024-01-01", WorkoutEntryStatus::Performed, (3, 5, 100.0), Vec::new()),
+            entry("2024-01-01", WorkoutEntryStatus::Performed, (1, 3, 110.0), Vec::new()),
+        ];
+
+        let sessions = group_sessions(entries, "2024-06-01", OneRepMaxFormula::Epley);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].date, "2024-01-01");
+        assert_eq!((sessions[0].top_weight, sessions[0].top_sets, sessions[0].top_reps), (110.0, 1, 3));
+        assert_eq!(sessions[0].estimated_max, Some(121.0));
+    }
+}
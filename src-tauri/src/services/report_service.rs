@@ -0,0 +1,263 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use chrono::Local;
+use crate::error::{AppError, AppResult};
+use crate::models::report::{AdherenceReport, ExerciseSetsPoint, FrequencyPoint, IntensityPoint, ReportBucket, TonnagePoint};
+use crate::repository::report_repository::ReportRepository;
+use crate::services::validation::{format_date, is_valid_date_format};
+
+pub struct ReportService {
+    repository: Arc<dyn ReportRepository + Send + Sync>,
+}
+
+impl ReportService {
+    pub fn new(repository: Arc<dyn ReportRepository + Send + Sync>) -> Self {
+        Self { repository }
+    }
+
+    pub fn get_tonnage_report(&self, person_id: i32, start_date: &str, end_date: &str, bucket: ReportBucket) -> AppResult<Vec<TonnagePoint>> {
+        self.validate_range(person_id, start_date, end_date)?;
+        self.repository.tonnage(person_id, start_date, end_date, bucket)
+    }
+
+    pub fn get_frequency_report(&self, person_id: i32, start_date: &str, end_date: &str, bucket: ReportBucket) -> AppResult<Vec<FrequencyPoint>> {
+        self.validate_range(person_id, start_date, end_date)?;
+        self.repository.frequency(person_id, start_date, end_date, bucket)
+    }
+
+    pub fn get_sets_per_exercise_report(
+        &self,
+        person_id: i32,
+        start_date: &str,
+        end_date: &str,
+        bucket: ReportBucket,
+    ) -> AppResult<Vec<ExerciseSetsPoint>> {
+        self.validate_range(person_id, start_date, end_date)?;
+        self.repository.sets_per_exercise(person_id, start_date, end_date, bucket)
+    }
+
+    pub fn get_intensity_report(&self, person_id: i32, start_date: &str, end_date: &str, bucket: ReportBucket) -> AppResult<Vec<IntensityPoint>> {
+        self.validate_range(person_id, start_date, end_date)?;
+        self.repository.intensity(person_id, start_date, end_date, bucket)
+    }
+
+    /// Completed vs prescribed sets and reps of the entries planned from routines.
+    /// Days after today are left out: sessions scheduled ahead are not missed yet.
+    pub fn get_adherence_report(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<AdherenceReport> {
+        self.validate_range(person_id, start_date, end_date)?;
+        let today = format_date(Local::now().date_naive());
+        let until = if end_date > today.as_str() { today.as_str() } else { end_date };
+
+        let entries = self.repository.adherence(person_id, start_date, until)?;
+        let mut report = AdherenceReport {
+            person_id,
+            start_date: start_date.to_string(),
+            end_date: end_date.to_string(),
+            sessions: entries.iter().map(|e| e.date.as_str()).collect::<HashSet<_>>().len() as i32,
+            prescribed_sets: 0,
+            completed_sets: 0,
+            prescribed_reps: 0,
+            performed_reps: 0,
+            completed_entries: 0,
+            adherence: None,
+            entries: Vec::new(),
+        };
+
+        for entry in &entries {
+            // Entries whose exercise left the routine have nothing to compare with
+            let prescribed_sets = match entry.prescribed_sets {
+                Some(sets) => sets,
+                None => continue,
+            };
+            report.prescribed_sets += prescribed_sets;
+            report.completed_sets += entry.completed_sets;
+            report.prescribed_reps += prescribed_sets * entry.prescribed_reps.unwrap_or(0);
+            report.performed_reps += entry.performed_reps;
+            if entry.completed_sets >= prescribed_sets {
+                report.completed_entries += 1;
+            }
+        }
+        if report.prescribed_sets > 0 {
+            let adherence = report.completed_sets as f64 / report.prescribed_sets as f64;
+            report.adherence = Some((adherence * 1000.0).round() / 1000.0);
+        }
+        report.entries = entries;
+        Ok(report)
+    }
+
+    fn validate_range(&self, person_id: i32, start_date: &str, end_date: &str) -> AppResult<()> {
+        if person_id <= 0 {
+            return Err(AppError::validation("person_id", "Invalid person ID"));
+        }
+
+        if !is_valid_date_format(start_date) {
+            return Err(AppError::validation("start_date", "Invalid date format. Use YYYY-MM-DD"));
+        }
+
+        if !is_valid_date_format(end_date) {
+            return Err(AppError::validation("end_date", "Invalid date format. Use YYYY-MM-DD"));
+        }
+
+        // YYYY-MM-DD compares chronologically as text
+        if start_date > end_date {
+            return Err(AppError::validation("end_date", "End date cannot be before start date"));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::db::test_pool;
+    use crate::models::exercise::Exercise;
+    use crate::models::person::Person;
+    use crate::models::routine::Routine;
+    use crate::models::routine_exercise::RoutineExercise;
+    use crate::models::workout_entry::{WorkoutEntry, WorkoutEntryStatus};
+    use crate::models::workout_set::{SetType, WorkoutSet};
+    use crate::repository::exercise_repository::ExerciseRepository;
+    use crate::repository::person_repository::PersonRepository;
+    use crate::repository::routine_repository::RoutineRepository;
+    use crate::repository::sqlite_exercise_repository::SqliteExerciseRepository;
+    use crate::repository::sqlite_person_repository::SqlitePersonRepository;
+    use crate::repository::sqlite_report_repository::SqliteReportRepository;
+    use crate::repository::sqlite_routine_repository::SqliteRoutineRepository;
+    use crate::repository::sqlite_workout_entry_repository::SqliteWorkoutEntryRepository;
+    use crate::repository::workout_entry_repository::WorkoutEntryRepository;
+
+    const DATE: &str = "2024-01-01";
+
+    fn set(order_index: i32, reps: i32, weight: f64, completed: bool) -> WorkoutSet {
+        WorkoutSet {
+            id: None,
+            workout_entry_id: 0,
+            order_index,
+            reps: Some(reps),
+            weight: Some(weight),
+            completed,
+            set_type: SetType::Working,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    // Saves one entry against a 3×5 @ 100 kg squat prescription and reports on its day
+    fn report_for(status: WorkoutEntryStatus, summary: (i32, i32, f64), workout_sets: Vec<WorkoutSet>) -> AdherenceReport {
+        let (_dir, pool) = test_pool();
+        SqliteExerciseRepository::new(pool.clone())
+            .create(Exercise { id: None, name: "Squat".into(), code: "SQ".into(), measurement_type: Default::default() })
+            .unwrap();
+        let person: Person =
+            serde_json::from_value(serde_json::json!({ "id": null, "name": "Ana", "last_name": "Ruiz", "phone": "600000001" })).unwrap();
+        SqlitePersonRepository::new(pool.clone()).create(person).unwrap();
+        let routines = SqliteRoutineRepository::new(pool.clone());
+        let routine_id = routines.create(Routine::new("Legs".into(), "LEGS".into())).unwrap();
+        routines
+            .add_exercise_to_routine(RoutineExercise::new(routine_id, 1, 0, Some(3), Some(5), Some(100.0), None, Some(1)))
+            .unwrap();
+
+        let (sets, reps, weight) = summary;
+        let mut entry = WorkoutEntry::new(1, 1, DATE.to_string(), Some(sets), Some(reps), Some(weight), None);
+        entry.group_number = Some(1);
+        entry.routine_id = Some(routine_id);
+        entry.routine_exercise_id = routines.get_routine_exercises(routine_id).unwrap()[0].id;
+        entry.status = status;
+        entry.workout_sets = Some(workout_sets);
+        SqliteWorkoutEntryRepository::new(pool.clone()).create(entry).unwrap();
+
+        ReportService::new(Arc::new(SqliteReportRepository::new(pool)))
+            .get_adherence_report(1, DATE, DATE)
+            .unwrap()
+    }
+
+    #[test]
+    fn skipped_session_completes_nothing() {
+        // Never logged, although it still carries the prescription
+        let report = report_for(WorkoutEntryStatus::Planned, (3, 5, 100.0), Vec::new());
+        assert_eq!(report.completed_sets, 0);
+        assert_eq!(report.entries[0].performed_sets, 0);
+        assert_eq!(report.adherence, Some(0.0));
+
+        // Logged with per-set detail, but no set ticked off
+        let sets = vec![set(0, 5, 100.0, false), set(1, 5, 100.0, false), set(2, 5, 100.0, false)];
+        let report = report_for(WorkoutEntryStatus::Performed, (3, 5, 100.0), sets);
+        assert_eq!(report.completed_sets, 0);
+        assert_eq!(report.entries[0].performed_sets, 0);
+        assert_eq!(report.entries[0].performed_reps, 0);
+        assert_eq!(report.entries[0].top_weight, None);
+        assert_eq!(report.completed_entries, 0);
+    }
+
+    #[test]
+    fn partial_session_counts_only_sets_done_as_prescribed() {
+        let sets = vec![set(0, 5, 100.0, true), set(1, 3, 100.0, true), set(2, 5, 100.0, false)];
+        let report = report_for(WorkoutEntryStatus::Performed, (3, 5, 100.0), sets);
+        assert_eq!(report.entries[0].performed_sets, 2);
+        assert_eq!(report.entries[0].performed_reps, 8);
+        assert_eq!(report.completed_sets, 1);
+        assert_eq!(report.completed_entries, 0);
+        assert_eq!(report.adherence, Some(0.333));
+
+        // Summary only: one set short of the prescription
+        let report = report_for(WorkoutEntryStatus::Performed, (2, 5, 100.0), Vec::new());
+        assert_eq!(report.completed_sets, 2);
+        assert_eq!(report.completed_entries, 0);
+    }
+
+    #[test]
+    fn over_performed_session_is_capped_at_the_prescription() {
+        let sets = (0..4).map(|i| set(i, 6, 105.0, true)).collect();
+        let report = report_for(WorkoutEntryStatus::Performed, (4, 6, 105.0), sets);
+        assert_eq!(report.entries[0].performed_sets, 4);
+        assert_eq!(report.entries[0].performed_reps, 24);
+        assert_eq!(report.entries[0].top_weight, Some(105.0));
+        assert_eq!(report.completed_sets, 3);
+        assert_eq!(report.completed_entries, 1);
+        assert_eq!(report.adherence, Some(1.0));
+    }
+
+    #[test]
+    fn routine_edits_leave_past_prescriptions_alone() {
+        let (_dir, pool) = test_pool();
+        SqliteExerciseRepository::new(pool.clone())
+            .create(Exercise { id: None, name: "Squat".into(), code: "SQ".into(), measurement_type: Default::default() })
+            .unwrap();
+        let person: Person =
+            serde_json::from_value(serde_json::json!({ "id": null, "name": "Ana", "last_name": "Ruiz", "phone": "600000001" })).unwrap();
+        SqlitePersonRepository::new(pool.clone()).create(person).unwrap();
+        let routines = SqliteRoutineRepository::new(pool.clone());
+        let routine_id = routines.create(Routine::new("Legs".into(), "LEGS".into())).unwrap();
+        routines
+            .add_exercise_to_routine(RoutineExercise::new(routine_id, 1, 0, Some(3), Some(5), Some(100.0), None, Some(1)))
+            .unwrap();
+
+        let workouts = SqliteWorkoutEntryRepository::new(pool.clone());
+        let mut entry = WorkoutEntry::new(1, 1, DATE.to_string(), Some(3), Some(5), Some(100.0), None);
+        entry.group_number = Some(1);
+        entry.routine_id = Some(routine_id);
+        entry.routine_exercise_id = routines.get_routine_exercises(routine_id).unwrap()[0].id;
+        workouts.create(entry).unwrap();
+
+        // The coach raises the load, then replaces the whole list
+        let mut heavier = RoutineExercise::new(routine_id, 1, 0, Some(5), Some(5), Some(120.0), None, Some(1));
+        heavier.id = routines.get_routine_exercises(routine_id).unwrap()[0].id;
+        routines.update_routine_exercise(heavier.clone()).unwrap();
+        heavier.id = None;
+        routines.replace_routine_exercises(routine_id, vec![heavier], None).unwrap();
+
+        // Editing the logged session keeps what was prescribed at the time
+        let mut logged = workouts.get_by_id(1).unwrap().unwrap();
+        logged.notes = Some("felt good".into());
+        workouts.replace_session(1, DATE, vec![logged]).unwrap();
+
+        let report = ReportService::new(Arc::new(SqliteReportRepository::new(pool)))
+            .get_adherence_report(1, DATE, DATE)
+            .unwrap();
+        let entry = &report.entries[0];
+        assert_eq!((entry.prescribed_sets, entry.prescribed_reps, entry.prescribed_weight), (Some(3), Some(5), Some(100.0)));
+        assert_eq!(report.completed_sets, 3);
+        assert_eq!(report.completed_entries, 1);
+    }
+}
@@ -1,7 +1,15 @@
 use std::sync::Arc;
+use crate::error::{AppError, AppResult};
 use crate::models::routine::{Routine, RoutineWithExercises};
 use crate::models::routine_exercise::{RoutineExercise, RoutineExerciseWithDetails};
+use crate::models::routine_revision::{
+    RoutineExerciseChange, RoutineRevision, RoutineRevisionDiff, RoutineRevisionExercise, RoutineRevisionWithExercises,
+};
+use crate::models::workout_entry::WorkoutEntry;
 use crate::repository::routine_repository::RoutineRepository;
+use crate::services::validation::{normalize_tempo, validate_duration_distance, validate_intensity};
+
+const MAX_REVISION_COMMENT_LENGTH: usize = 200;
 
 pub struct RoutineService {
     repository: Arc<dyn RoutineRepository + Send + Sync>,
@@ -13,10 +21,10 @@ impl RoutineService {
     }
 
     // Validate that routine groups are consecutive
-    fn validate_routine_groups_consecutive(&self, routine_id: i32) -> Result<(), String> {
+    fn validate_routine_groups_consecutive(&self, routine_id: i32) -> AppResult<()> {
         println!("DEBUG: validate_routine_groups_consecutive called for routine_id: {}", routine_id);
         
-        let exercises = self.repository.get_routine_exercises(routine_id);
+        let exercises = self.repository.get_routine_exercises(routine_id)?;
         println!("DEBUG: Found {} exercises in routine", exercises.len());
         
         let groups: std::collections::HashSet<i32> = exercises
@@ -43,7 +51,7 @@ impl RoutineService {
                 min_group
             );
             println!("DEBUG: Validation failed: {}", error_msg);
-            return Err(error_msg);
+            return Err(AppError::validation("group_number", error_msg));
         }
         
         // Check that all groups from min to max exist
@@ -54,7 +62,7 @@ impl RoutineService {
                     group_num
                 );
                 println!("DEBUG: Validation failed: {}", error_msg);
-                return Err(error_msg);
+                return Err(AppError::validation("group_number", error_msg));
             }
         }
         
@@ -63,10 +71,10 @@ impl RoutineService {
     }
 
     // Validate that routine groups are consecutive WITH a new group (before adding)
-    fn validate_routine_groups_consecutive_with_new_group(&self, routine_id: i32, new_group_number: Option<i32>) -> Result<(), String> {
+    fn validate_routine_groups_consecutive_with_new_group(&self, routine_id: i32, new_group_number: Option<i32>) -> AppResult<()> {
         println!("DEBUG: validate_routine_groups_consecutive_with_new_group called for routine_id: {} with new_group: {:?}", routine_id, new_group_number);
         
-        let exercises = self.repository.get_routine_exercises(routine_id);
+        let exercises = self.repository.get_routine_exercises(routine_id)?;
         println!("DEBUG: Found {} exercises in routine", exercises.len());
         
         let mut groups: std::collections::HashSet<i32> = exercises
@@ -98,7 +106,7 @@ impl RoutineService {
                 min_group
             );
             println!("DEBUG: Validation failed: {}", error_msg);
-            return Err(error_msg);
+            return Err(AppError::validation("group_number", error_msg));
         }
         
         // Check that all groups from min to max exist
@@ -109,7 +117,7 @@ impl RoutineService {
                     group_num
                 );
                 println!("DEBUG: Validation failed: {}", error_msg);
-                return Err(error_msg);
+                return Err(AppError::validation("group_number", error_msg));
             }
         }
         
@@ -117,10 +125,10 @@ impl RoutineService {
         Ok(())
     }
 
-    pub fn get_available_groups(&self, routine_id: i32) -> Result<Vec<i32>, String> {
+    pub fn get_available_groups(&self, routine_id: i32) -> AppResult<Vec<i32>> {
         // Validate that routine exists
-        if self.repository.get_by_id(routine_id).is_none() {
-            return Err("La rutina especificada no existe".to_string());
+        if self.repository.get_by_id(routine_id)?.is_none() {
+            return Err(AppError::not_found("La rutina especificada no existe"));
         }
 
         // Always return all groups from 1 to 5 for selection
@@ -128,76 +136,80 @@ impl RoutineService {
     }
 
     // Routine operations
-    pub fn create_routine(&self, name: String, code: String) -> Result<i32, String> {
+    pub fn create_routine(&self, name: String, code: String) -> AppResult<i32> {
+        let routine = Self::new_routine(name, code)?;
+        self.repository.create(routine)
+    }
+
+    fn new_routine(name: String, code: String) -> AppResult<Routine> {
         // Validate input
         if name.trim().is_empty() {
-            return Err("El nombre de la rutina no puede estar vacío".to_string());
+            return Err(AppError::validation("name", "El nombre de la rutina no puede estar vacío"));
         }
         if code.trim().is_empty() {
-            return Err("El código de la rutina no puede estar vacío".to_string());
+            return Err(AppError::validation("code", "El código de la rutina no puede estar vacío"));
         }
 
-        let routine = Routine::new(name.trim().to_string(), code.trim().to_uppercase());
-        self.repository.create(routine)
+        Ok(Routine::new(name.trim().to_string(), code.trim().to_uppercase()))
     }
 
-    pub fn get_routine_by_id(&self, id: i32) -> Option<Routine> {
+    pub fn get_routine_by_id(&self, id: i32) -> AppResult<Option<Routine>> {
         self.repository.get_by_id(id)
     }
 
-    pub fn get_routine_with_exercises(&self, id: i32) -> Option<RoutineWithExercises> {
+    pub fn get_routine_with_exercises(&self, id: i32) -> AppResult<Option<RoutineWithExercises>> {
         self.repository.get_with_exercises(id)
     }
 
-    pub fn update_routine(&self, id: i32, name: String, code: String) -> Result<(), String> {
+    pub fn update_routine(&self, id: i32, name: String, code: String) -> AppResult<()> {
         // Validate input
         if name.trim().is_empty() {
-            return Err("El nombre de la rutina no puede estar vacío".to_string());
+            return Err(AppError::validation("name", "El nombre de la rutina no puede estar vacío"));
         }
         if code.trim().is_empty() {
-            return Err("El código de la rutina no puede estar vacío".to_string());
+            return Err(AppError::validation("code", "El código de la rutina no puede estar vacío"));
         }
 
         self.repository.update(id, name.trim().to_string(), code.trim().to_uppercase())
     }
 
-    pub fn delete_routine(&self, id: i32) -> Result<(), String> {
+    pub fn delete_routine(&self, id: i32) -> AppResult<()> {
         self.repository.delete(id)
     }
 
-    pub fn restore_routine(&self, id: i32) -> Result<(), String> {
+    pub fn restore_routine(&self, id: i32) -> AppResult<()> {
         self.repository.restore(id)
     }
 
-    pub fn list_routines(&self) -> Vec<Routine> {
+    pub fn list_routines(&self) -> AppResult<Vec<Routine>> {
         self.repository.list_all()
     }
 
-    pub fn list_deleted_routines(&self) -> Vec<Routine> {
+    pub fn list_deleted_routines(&self) -> AppResult<Vec<Routine>> {
         self.repository.list_deleted()
     }
 
-    pub fn count_deleted_routines(&self) -> i32 {
+    pub fn count_deleted_routines(&self) -> AppResult<i32> {
         self.repository.count_deleted()
     }
 
-    pub fn list_routines_paginated(&self, page: i32, page_size: i32) -> Vec<Routine> {
+    pub fn list_routines_paginated(&self, page: i32, page_size: i32) -> AppResult<Vec<Routine>> {
         if page < 1 || page_size < 1 {
-            return Vec::new();
+            return Ok(Vec::new());
         }
         self.repository.list_routines_paginated(page, page_size)
     }
 
-    pub fn search_routines(&self, query: String) -> Vec<Routine> {
+    pub fn search_routines(&self, query: String) -> AppResult<Vec<Routine>> {
         if query.trim().is_empty() {
             return self.list_routines();
         }
         self.repository.search_routines(&query.trim())
     }
 
-    pub fn search_routines_paginated(&self, query: String, page: i32, page_size: i32) -> Vec<Routine> {
+    pub fn search_routines_paginated(&self, query: String, page: i32, page_size: i32) -> AppResult<Vec<Routine>> {
         if page < 1 || page_size < 1 {
-            return Vec::new();
+            return Ok(Vec::new());
         }
         if query.trim().is_empty() {
             return self.list_routines_paginated(page, page_size);
@@ -216,37 +228,55 @@ impl RoutineService {
         weight: Option<f64>,
         notes: Option<String>,
         group_number: Option<i32>,
-    ) -> Result<(), String> {
+        rpe: Option<f64>,
+        rir: Option<i32>,
+        tempo: Option<String>,
+        rest_seconds: Option<i32>,
+        duration_seconds: Option<i32>,
+        distance_meters: Option<f64>,
+    ) -> AppResult<()> {
         println!("DEBUG: add_exercise_to_routine called with group_number: {:?}", group_number);
         
         // Validate that routine exists
-        if self.repository.get_by_id(routine_id).is_none() {
-            return Err("La rutina especificada no existe".to_string());
+        if self.repository.get_by_id(routine_id)?.is_none() {
+            return Err(AppError::not_found("La rutina especificada no existe"));
         }
 
         // Basic validation for group number
         if let Some(group_num) = group_number {
             if group_num <= 0 || group_num > 5 {
-                return Err("El número de grupo debe estar entre 1 y 5".to_string());
+                return Err(AppError::validation("group_number", "El número de grupo debe estar entre 1 y 5"));
             }
         }
 
+        let tempo = normalize_tempo(tempo);
+        validate_intensity(rpe, rir, tempo.as_deref(), rest_seconds)?;
+        validate_duration_distance(duration_seconds, distance_meters)?;
+
         // Validate consecutiveness BEFORE adding
         println!("DEBUG: About to validate consecutive groups BEFORE adding");
         let validation_result = self.validate_routine_groups_consecutive_with_new_group(routine_id, group_number);
         println!("DEBUG: Validation result: {:?}", validation_result);
         validation_result?;
 
-        let routine_exercise = RoutineExercise::new(
-            routine_id,
-            exercise_id,
-            order_index,
-            sets,
-            reps,
-            weight,
-            notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
-            group_number,
-        );
+        let routine_exercise = RoutineExercise {
+            rpe,
+            rir,
+            tempo,
+            rest_seconds,
+            duration_seconds,
+            distance_meters,
+            ..RoutineExercise::new(
+                routine_id,
+                exercise_id,
+                order_index,
+                sets,
+                reps,
+                weight,
+                notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+                group_number,
+            )
+        };
 
         println!("DEBUG: About to add exercise to repository");
         self.repository.add_exercise_to_routine(routine_exercise)?;
@@ -266,14 +296,24 @@ impl RoutineService {
         weight: Option<f64>,
         notes: Option<String>,
         group_number: Option<i32>,
-    ) -> Result<(), String> {
+        rpe: Option<f64>,
+        rir: Option<i32>,
+        tempo: Option<String>,
+        rest_seconds: Option<i32>,
+        duration_seconds: Option<i32>,
+        distance_meters: Option<f64>,
+    ) -> AppResult<()> {
         // Basic validation for group number
         if let Some(group_num) = group_number {
             if group_num <= 0 || group_num > 5 {
-                return Err("El número de grupo debe estar entre 1 y 5".to_string());
+                return Err(AppError::validation("group_number", "El número de grupo debe estar entre 1 y 5"));
             }
         }
 
+        let tempo = normalize_tempo(tempo);
+        validate_intensity(rpe, rir, tempo.as_deref(), rest_seconds)?;
+        validate_duration_distance(duration_seconds, distance_meters)?;
+
         // Validate consecutiveness BEFORE updating
         let validation_result = self.validate_routine_groups_consecutive_with_new_group(routine_id, group_number);
         validation_result?;
@@ -288,6 +328,12 @@ impl RoutineService {
             weight,
             notes: notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
             group_number,
+            rpe,
+            rir,
+            tempo,
+            rest_seconds,
+            duration_seconds,
+            distance_meters,
             created_at: None,
             updated_at: None,
         };
@@ -297,15 +343,15 @@ impl RoutineService {
         Ok(())
     }
 
-    pub fn remove_exercise_from_routine(&self, routine_id: i32, exercise_id: i32) -> Result<(), String> {
+    pub fn remove_exercise_from_routine(&self, routine_id: i32, exercise_id: i32) -> AppResult<()> {
         self.repository.remove_exercise_from_routine(routine_id, exercise_id)
     }
 
-    pub fn get_routine_exercises(&self, routine_id: i32) -> Vec<RoutineExerciseWithDetails> {
+    pub fn get_routine_exercises(&self, routine_id: i32) -> AppResult<Vec<RoutineExerciseWithDetails>> {
         self.repository.get_routine_exercises(routine_id)
     }
 
-    pub fn reorder_routine_exercises(&self, routine_id: i32, exercise_orders: Vec<(i32, i32)>) -> Result<(), String> {
+    pub fn reorder_routine_exercises(&self, routine_id: i32, exercise_orders: Vec<(i32, i32)>) -> AppResult<()> {
         if exercise_orders.is_empty() {
             return Ok(());
         }
@@ -313,23 +359,29 @@ impl RoutineService {
         self.repository.reorder_routine_exercises(routine_id, exercise_orders)
     }
 
-    pub fn replace_routine_exercises(&self, routine_id: i32, exercises: Vec<RoutineExercise>) -> Result<(), String> {
+    pub fn replace_routine_exercises(
+        &self,
+        routine_id: i32,
+        exercises: Vec<RoutineExercise>,
+        comment: Option<String>, // Kept on the revision this change creates
+    ) -> AppResult<()> {
         // Validate that routine exists
-        if self.repository.get_by_id(routine_id).is_none() {
-            return Err("La rutina especificada no existe".to_string());
+        if self.repository.get_by_id(routine_id)?.is_none() {
+            return Err(AppError::not_found("La rutina especificada no existe"));
         }
 
-        self.repository.replace_routine_exercises(routine_id, exercises)
+        let comment = validate_revision_comment(comment)?;
+        self.repository.replace_routine_exercises(routine_id, exercises, comment)
     }
 
-    pub fn renumber_routine_groups(&self, routine_id: i32) -> Result<(), String> {
+    pub fn renumber_routine_groups(&self, routine_id: i32) -> AppResult<()> {
         if routine_id <= 0 {
-            return Err("Invalid routine ID".to_string());
+            return Err(AppError::validation("routine_id", "Invalid routine ID"));
         }
 
         // Validate that routine exists
-        if self.repository.get_by_id(routine_id).is_none() {
-            return Err("La rutina especificada no existe".to_string());
+        if self.repository.get_by_id(routine_id)?.is_none() {
+            return Err(AppError::not_found("La rutina especificada no existe"));
         }
 
         self.repository.renumber_routine_groups(routine_id)
@@ -340,33 +392,301 @@ impl RoutineService {
         &self,
         name: String,
         code: String,
-        workout_exercises: Vec<(i32, Option<i32>, Option<i32>, Option<f64>, Option<String>, Option<i32>)>, // (exercise_id, sets, reps, weight, notes, group_number)
-    ) -> Result<i32, String> {
-        // Create the routine first
-        let routine_id = self.create_routine(name, code)?;
+        workout_exercises: Vec<WorkoutEntry>, // Entries of the session, in display order
+    ) -> AppResult<i32> {
+        for entry in &workout_exercises {
+            validate_intensity(entry.rpe, entry.rir, entry.tempo.as_deref(), entry.rest_seconds)?;
+            validate_duration_distance(entry.duration_seconds, entry.distance_meters)?;
+        }
 
-        // Add exercises to the routine
+        let routine = Self::new_routine(name, code)?;
+
+        // Keep the prescription of each entry; the routine and its exercises are saved together
         let routine_exercises: Vec<RoutineExercise> = workout_exercises
             .into_iter()
             .enumerate()
-            .map(|(index, (exercise_id, sets, reps, weight, notes, group_number))| {
-                RoutineExercise::new(
-                    routine_id,
-                    exercise_id,
+            .map(|(index, entry)| RoutineExercise {
+                rpe: entry.rpe,
+                rir: entry.rir,
+                tempo: entry.tempo,
+                rest_seconds: entry.rest_seconds,
+                duration_seconds: entry.duration_seconds,
+                distance_meters: entry.distance_meters,
+                ..RoutineExercise::new(
+                    0,
+                    entry.exercise_id,
                     index as i32,
-                    sets,
-                    reps,
-                    weight,
-                    notes,
-                    group_number,
+                    entry.sets,
+                    entry.reps,
+                    entry.weight,
+                    entry.notes,
+                    entry.group_number,
                 )
             })
             .collect();
 
-        if !routine_exercises.is_empty() {
-            self.repository.replace_routine_exercises(routine_id, routine_exercises)?;
+        self.repository.create_with_exercises(routine, routine_exercises)
+    }
+
+    // Revision operations
+    /// Newest revision first
+    pub fn list_routine_revisions(&self, routine_id: i32) -> AppResult<Vec<RoutineRevision>> {
+        if self.repository.get_by_id(routine_id)?.is_none() {
+            return Err(AppError::not_found("La rutina especificada no existe"));
+        }
+        self.repository.list_revisions(routine_id)
+    }
+
+    pub fn get_routine_revision(&self, routine_id: i32, revision: i32) -> AppResult<Option<RoutineRevisionWithExercises>> {
+        self.repository.get_revision(routine_id, revision)
+    }
+
+    /// Exercises added, removed and changed going from `from_revision` to `to_revision`.
+    /// Exercises are matched by exercise, so moving one around shows up as a change of order or group.
+    pub fn diff_routine_revisions(&self, routine_id: i32, from_revision: i32, to_revision: i32) -> AppResult<RoutineRevisionDiff> {
+        let from = self.require_revision(routine_id, from_revision)?;
+        let to = self.require_revision(routine_id, to_revision)?;
+
+        let mut removed = from.exercises;
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        for after in to.exercises {
+            let before = match removed.iter().position(|e| e.exercise_id == after.exercise_id) {
+                Some(index) => removed.remove(index),
+                None => {
+                    added.push(after);
+                    continue;
+                }
+            };
+            let fields = changed_fields(&before, &after);
+            if !fields.is_empty() {
+                changed.push(RoutineExerciseChange {
+                    exercise_id: after.exercise_id,
+                    exercise_name: after.exercise_name.clone(),
+                    fields,
+                    before,
+                    after,
+                });
+            }
+        }
+
+        Ok(RoutineRevisionDiff {
+            routine_id,
+            from_revision,
+            to_revision,
+            name_changed: from.revision.name != to.revision.name,
+            code_changed: from.revision.code != to.revision.code,
+            added,
+            removed,
+            changed,
+        })
+    }
+
+    /// Brings back an older revision; the restore itself becomes the newest revision
+    pub fn restore_routine_revision(&self, routine_id: i32, revision: i32, comment: Option<String>) -> AppResult<i32> {
+        if self.repository.get_by_id(routine_id)?.is_none() {
+            return Err(AppError::not_found("La rutina especificada no existe"));
+        }
+        let comment = validate_revision_comment(comment)?;
+        self.repository.restore_revision(routine_id, revision, comment)
+    }
+
+    fn require_revision(&self, routine_id: i32, revision: i32) -> AppResult<RoutineRevisionWithExercises> {
+        match self.repository.get_revision(routine_id, revision)? {
+            Some(revision) => Ok(revision),
+            None => Err(AppError::not_found(format!("Routine revision {} not found", revision))),
+        }
+    }
+}
+
+fn validate_revision_comment(comment: Option<String>) -> AppResult<Option<String>> {
+    let comment = comment.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+    if let Some(comment) = &comment {
+        if comment.chars().count() > MAX_REVISION_COMMENT_LENGTH {
+            return Err(AppError::validation(
+                "comment",
+                format!("Comment cannot exceed {} characters", MAX_REVISION_COMMENT_LENGTH),
+            ));
+        }
+    }
+    Ok(comment)
+}
+
+/// Names of the prescription fields that differ between two versions of an exercise
+fn changed_fields(before: &RoutineRevisionExercise, after: &RoutineRevisionExercise) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut check = |name: &str, differs: bool| {
+        if differs {
+            fields.push(name.to_string());
+        }
+    };
+    check("order_index", before.order_index != after.order_index);
+    check("group_number", before.group_number != after.group_number);
+    check("sets", before.sets != after.sets);
+    check("reps", before.reps != after.reps);
+    check("weight", before.weight != after.weight);
+    check("rpe", before.rpe != after.rpe);
+    check("rir", before.rir != after.rir);
+    check("tempo", before.tempo != after.tempo);
+    check("rest_seconds", before.rest_seconds != after.rest_seconds);
+    check("duration_seconds", before.duration_seconds != after.duration_seconds);
+    check("distance_meters", before.distance_meters != after.distance_meters);
+    check("notes", before.notes != after.notes);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::db::{test_pool, DbPool};
+    use crate::models::exercise::Exercise;
+    use crate::models::person::Person;
+    use crate::repository::exercise_repository::ExerciseRepository;
+    use crate::repository::person_repository::PersonRepository;
+    use crate::repository::sqlite_exercise_repository::SqliteExerciseRepository;
+    use crate::repository::sqlite_person_repository::SqlitePersonRepository;
+    use crate::repository::sqlite_routine_repository::SqliteRoutineRepository;
+    use crate::repository::sqlite_workout_entry_repository::SqliteWorkoutEntryRepository;
+    use crate::repository::workout_entry_repository::WorkoutEntryRepository;
+
+    const SQUAT: i32 = 1;
+    const BENCH: i32 = 2;
+    const ROW: i32 = 3;
+
+    fn entry(exercise_id: i32, sets: i32, reps: i32, weight: f64) -> WorkoutEntry {
+        let mut entry = WorkoutEntry::new(1, exercise_id, "2024-01-01".into(), Some(sets), Some(reps), Some(weight), None);
+        entry.group_number = Some(1);
+        entry
+    }
+
+    // "Full body": squat 3×5 @ 100 and bench 3×8 @ 60, created from a workout
+    fn setup(pool: &DbPool) -> (RoutineService, i32) {
+        let exercises = SqliteExerciseRepository::new(pool.clone());
+        for (name, code) in [("Squat", "SQ"), ("Bench", "BP"), ("Row", "RW")] {
+            exercises
+                .create(Exercise { id: None, name: name.into(), code: code.into(), measurement_type: Default::default() })
+                .unwrap();
         }
+        let person: Person =
+            serde_json::from_value(serde_json::json!({ "id": null, "name": "Ana", "last_name": "Ruiz", "phone": "600000001" })).unwrap();
+        SqlitePersonRepository::new(pool.clone()).create(person).unwrap();
+
+        let service = RoutineService::new(Arc::new(SqliteRoutineRepository::new(pool.clone())));
+        let routine_id = service
+            .create_routine_from_workout("Full body".into(), "fb".into(), vec![entry(SQUAT, 3, 5, 100.0), entry(BENCH, 3, 8, 60.0)])
+            .unwrap();
+        (service, routine_id)
+    }
+
+    fn routine_exercise(service: &RoutineService, routine_id: i32, exercise_id: i32) -> Option<RoutineExerciseWithDetails> {
+        service.get_routine_exercises(routine_id).unwrap().into_iter().find(|e| e.exercise_id == exercise_id)
+    }
+
+    fn revision_numbers(service: &RoutineService, routine_id: i32) -> Vec<i32> {
+        service.list_routine_revisions(routine_id).unwrap().iter().map(|r| r.revision).collect()
+    }
+
+    #[test]
+    fn routine_from_workout_starts_with_one_revision() {
+        let (_dir, pool) = test_pool();
+        let (service, routine_id) = setup(&pool);
 
-        Ok(routine_id)
+        let revisions = service.list_routine_revisions(routine_id).unwrap();
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0].revision, 1);
+        assert_eq!(revisions[0].exercise_count, 2);
+        assert_eq!(revisions[0].code, "FB");
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn delete_and_restore_are_revisions() {
+        let (_dir, pool) = test_pool();
+        let (service, routine_id) = setup(&pool);
+
+        service.delete_routine(routine_id).unwrap();
+        service.restore_routine(routine_id).unwrap();
+        let revisions = service.list_routine_revisions(routine_id).unwrap();
+        assert_eq!(revisions.iter().map(|r| r.revision).collect::<Vec<_>>(), vec![3, 2, 1]);
+        assert_eq!(revisions[0].comment.as_deref(), Some("Routine restored"));
+        assert_eq!(revisions[1].comment.as_deref(), Some("Routine deleted"));
+        assert_eq!(revisions[1].exercise_count, 2);
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_exercises() {
+        let (_dir, pool) = test_pool();
+        let (service, routine_id) = setup(&pool);
+
+        service
+            .replace_routine_exercises(
+                routine_id,
+                vec![
+                    RoutineExercise::new(routine_id, BENCH, 0, Some(4), Some(8), Some(60.0), Some("slow".into()), Some(1)),
+                    RoutineExercise::new(routine_id, ROW, 1, Some(3), Some(10), Some(50.0), None, Some(2)),
+                ],
+                Some(" Swap squat for row ".into()),
+            )
+            .unwrap();
+        service.update_routine(routine_id, "Upper".into(), "fb".into()).unwrap();
+        assert_eq!(revision_numbers(&service, routine_id), vec![3, 2, 1]);
+        assert_eq!(service.list_routine_revisions(routine_id).unwrap()[1].comment.as_deref(), Some("Swap squat for row"));
+
+        let diff = service.diff_routine_revisions(routine_id, 1, 3).unwrap();
+        assert!(diff.name_changed);
+        assert!(!diff.code_changed);
+        assert_eq!(diff.added.iter().map(|e| e.exercise_id).collect::<Vec<_>>(), vec![ROW]);
+        assert_eq!(diff.removed.iter().map(|e| e.exercise_id).collect::<Vec<_>>(), vec![SQUAT]);
+        assert_eq!(diff.removed[0].weight, Some(100.0));
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].exercise_id, BENCH);
+        assert_eq!(diff.changed[0].fields, vec!["order_index", "sets", "notes"]);
+
+        let unchanged = service.diff_routine_revisions(routine_id, 2, 2).unwrap();
+        assert!(unchanged.added.is_empty() && unchanged.removed.is_empty() && unchanged.changed.is_empty());
+        assert_eq!(service.diff_routine_revisions(routine_id, 1, 9).unwrap_err().code(), "NOT_FOUND");
+    }
+
+    #[test]
+    fn restore_updates_kept_exercises_in_place() {
+        let (_dir, pool) = test_pool();
+        let (service, routine_id) = setup(&pool);
+        let bench_id = routine_exercise(&service, routine_id, BENCH).unwrap().id;
+
+        // A workout planned from the bench prescription
+        let workout_entries = SqliteWorkoutEntryRepository::new(pool.clone());
+        let mut planned = entry(BENCH, 3, 8, 60.0);
+        planned.routine_id = Some(routine_id);
+        planned.routine_exercise_id = bench_id;
+        workout_entries.create(planned).unwrap();
+
+        service
+            .replace_routine_exercises(
+                routine_id,
+                vec![RoutineExercise::new(routine_id, BENCH, 0, Some(5), Some(5), Some(70.0), None, Some(1))],
+                None,
+            )
+            .unwrap();
+        assert_eq!(routine_exercise(&service, routine_id, BENCH).unwrap().id, bench_id);
+        assert!(routine_exercise(&service, routine_id, SQUAT).is_none());
+
+        assert_eq!(service.restore_routine_revision(routine_id, 1, None).unwrap(), 3);
+        let bench = routine_exercise(&service, routine_id, BENCH).unwrap();
+        assert_eq!(bench.id, bench_id);
+        assert_eq!((bench.order_index, bench.sets, bench.reps, bench.weight), (1, Some(3), Some(8), Some(60.0)));
+        assert_eq!(routine_exercise(&service, routine_id, SQUAT).unwrap().weight, Some(100.0));
+        assert_eq!(workout_entries.get_by_person(1).unwrap()[0].routine_exercise_id, bench_id);
+
+        let revisions = service.list_routine_revisions(routine_id).unwrap();
+        assert_eq!(revisions[0].comment.as_deref(), Some("Restored revision 1"));
+        let diff = service.diff_routine_revisions(routine_id, 1, 3).unwrap();
+        assert!(diff.added.is_empty() && diff.removed.is_empty() && diff.changed.is_empty());
+
+        assert_eq!(service.restore_routine_revision(routine_id, 9, None).unwrap_err().code(), "NOT_FOUND");
+        assert_eq!(service.restore_routine_revision(99, 1, None).unwrap_err().code(), "NOT_FOUND");
+        assert_eq!(
+            service.restore_routine_revision(routine_id, 1, Some("x".repeat(201))).unwrap_err().code(),
+            "VALIDATION_FAILED"
+        );
+        assert_eq!(revision_numbers(&service, routine_id), vec![3, 2, 1]);
+    }
+}
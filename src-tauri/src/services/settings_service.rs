@@ -0,0 +1,371 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use reqwest::Url;
+use serde_json::Value;
+use crate::models::settings::{AppSettings, BackupConfig, UpdaterSettings, SETTINGS_VERSION};
+use crate::services::backup_archive::MIN_PASSPHRASE_LENGTH;
+use crate::services::updater_service::decode_public_key;
+
+// Written by every version that sent the email backup with the key built into the app
+const BACKUP_STATUS_FILE: &str = "backup_status.json";
+const EMAIL_BACKUP_NOTICE: &str = "Automatic email backups are no longer sent with the key built into the app. \
+Add a Resend or SMTP destination under backup targets to keep receiving them.";
+
+// The backup passphrase lives in its own file, readable only by the user, so it is
+// neither part of settings.json nor anywhere near the backups it decrypts
+const PASSPHRASE_FILE: &str = "backup_passphrase";
+
+/// Typed, versioned settings persisted as `settings.json` in the data directory
+pub struct SettingsService {
+    path: PathBuf,
+    passphrase_path: PathBuf,
+    settings: RwLock<AppSettings>,
+    // Why the file on disk must not be overwritten, when it could not be loaded
+    write_error: Option<String>,
+}
+
+impl SettingsService {
+    /// Loads the settings, migrating older files. An unreadable file, e.g. one
+    /// written by a newer version, is left untouched: defaults are used and
+    /// `update` refuses to overwrite it.
+    pub fn new(data_dir: &Path) -> Self {
+        let path = data_dir.join("settings.json");
+        let passphrase_path = data_dir.join(PASSPHRASE_FILE);
+        let (settings, write_error) = match load(&path, &passphrase_path, data_dir) {
+            Ok(settings) => (settings, None),
+            Err(e) => {
+                eprintln!("Warning: {}, using default settings", e);
+                (AppSettings::default(), Some(e))
+            }
+        };
+
+        Self {
+            path,
+            passphrase_path,
+            settings: RwLock::new(settings),
+            write_error,
+        }
+    }
+
+    pub fn get(&self) -> AppSettings {
+        self.settings.read().map(|settings| settings.clone()).unwrap_or_default()
+    }
+
+    /// Validates and stores `settings`; returns them as saved, after normalization.
+    /// Secrets are write-only: an omitted passphrase or credential keeps the stored
+    /// one, an empty passphrase or password clears it.
+    pub fn update(&self, mut settings: AppSettings) -> Result<AppSettings, String> {
+        if let Some(e) = &self.write_error {
+            return Err(format!("Settings cannot be saved: {}", e));
+        }
+
+        let mut current = self.settings.write().map_err(|_| "Settings lock poisoned".to_string())?;
+        if settings.backup.passphrase.is_none() {
+            settings.backup.passphrase = current.backup.passphrase.clone();
+        }
+        for target in &mut settings.backup.targets {
+            target.keep_secret(&current.backup.targets);
+        }
+        let settings = normalize(settings)?;
+
+        save_passphrase(&self.passphrase_path, settings.backup.passphrase.as_deref())?;
+        save(&self.path, &settings)?;
+        *current = settings.clone();
+        Ok(settings)
+    }
+}
+
+fn load(path: &Path, passphrase_path: &Path, data_dir: &Path) -> Result<AppSettings, String> {
+    let mut settings = load_settings(path, data_dir)?;
+
+    // Files written before the passphrase had its own file still carry it in clear text
+    if let Some(passphrase) = settings.backup.passphrase.take() {
+        save_passphrase(passphrase_path, Some(&passphrase))?;
+        save(path, &settings)?;
+    }
+
+    settings.backup.passphrase = load_passphrase(passphrase_path)?;
+    Ok(settings)
+}
+
+fn load_settings(path: &Path, data_dir: &Path) -> Result<AppSettings, String> {
+    if !path.exists() {
+        // Earlier versions mailed a backup every day with no settings at all; say
+        // so instead of silently stopping
+        let mut settings = AppSettings::default();
+        if data_dir.join(BACKUP_STATUS_FILE).exists() {
+            settings.notices.push(EMAIL_BACKUP_NOTICE.to_string());
+        }
+        save(path, &settings)?;
+        return Ok(settings);
+    }
+
+    let settings_data = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read settings: {}", e))?;
+    let value: Value = serde_json::from_str(&settings_data)
+        .map_err(|e| format!("Invalid settings file: {}", e))?;
+    serde_json::from_value(migrate(value)?).map_err(|e| format!("Invalid settings file: {}", e))
+}
+
+// Upgrades the raw JSON one version at a time, so each step only knows its predecessor
+fn migrate(mut value: Value) -> Result<Value, String> {
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+    if version > SETTINGS_VERSION {
+        return Err(format!(
+            "Settings version {} is newer than this app supports ({})",
+            version, SETTINGS_VERSION
+        ));
+    }
+
+    // 0 -> 1: unversioned files already have the version 1 layout
+
+    if version < 2 {
+        // 1 -> 2: the updater reads an update manifest instead of the GitHub
+        // releases API, so the old releases URL cannot be carried over
+        if let Some(updater) = value.get_mut("updater").and_then(Value::as_object_mut) {
+            updater.remove("releases_url");
+        }
+    }
+
+    value["version"] = Value::from(SETTINGS_VERSION);
+    Ok(value)
+}
+
+fn save(path: &Path, settings: &AppSettings) -> Result<(), String> {
+    let mut settings = settings.clone();
+    settings.backup.passphrase = None;
+    let settings_json = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    // Written under a temporary name so a crash never leaves a truncated file
+    let partial_path = path.with_extension("json.partial");
+    fs::write(&partial_path, settings_json)
+        .map_err(|e| format!("Failed to write settings: {}", e))?;
+    fs::rename(&partial_path, path)
+        .map_err(|e| format!("Failed to write settings: {}", e))
+}
+
+fn load_passphrase(path: &Path) -> Result<Option<String>, String> {
+    match fs::read_to_string(path) {
+        Ok(passphrase) => Ok(Some(passphrase).filter(|p| !p.is_empty())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Failed to read backup passphrase: {}", e)),
+    }
+}
+
+fn save_passphrase(path: &Path, passphrase: Option<&str>) -> Result<(), String> {
+    let passphrase = match passphrase {
+        Some(passphrase) => passphrase,
+        None => {
+            return match fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to remove backup passphrase: {}", e)),
+                _ => Ok(()),
+            };
+        }
+    };
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(passphrase.as_bytes()))
+        .map_err(|e| format!("Failed to write backup passphrase: {}", e))
+}
+
+fn normalize(mut settings: AppSettings) -> Result<AppSettings, String> {
+    settings.version = SETTINGS_VERSION;
+    settings.notices.clear();
+    settings.backup = normalize_backup(settings.backup)?;
+    settings.updater = normalize_updater(settings.updater)?;
+    Ok(settings)
+}
+
+fn normalize_backup(mut config: BackupConfig) -> Result<BackupConfig, String> {
+    if config.keep_daily == 0 {
+        return Err("At least one daily backup must be kept".to_string());
+    }
+
+    config.directory = config.directory.map(|d| d.trim().to_string()).filter(|d| !d.is_empty());
+    if let Some(directory) = &config.directory {
+        if !Path::new(directory).is_absolute() {
+            return Err("Backup folder must be an absolute path".to_string());
+        }
+        fs::create_dir_all(directory)
+            .map_err(|e| format!("Failed to create backup folder: {}", e))?;
+    }
+
+    for target in &config.targets {
+        target.validate()?;
+    }
+
+    config.passphrase = config.passphrase.filter(|p| !p.is_empty());
+    if config.passphrase.as_ref().is_some_and(|p| p.chars().count() < MIN_PASSPHRASE_LENGTH) {
+        return Err(format!("Backup passphrase must be at least {} characters", MIN_PASSPHRASE_LENGTH));
+    }
+    Ok(config)
+}
+
+fn normalize_updater(mut updater: UpdaterSettings) -> Result<UpdaterSettings, String> {
+    updater.manifest_url = updater.manifest_url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty());
+    if let Some(url) = &updater.manifest_url {
+        match Url::parse(url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+            _ => return Err(format!("Invalid update URL: {}", url)),
+        }
+    }
+
+    updater.public_key = updater.public_key.map(|key| key.trim().to_string()).filter(|key| !key.is_empty());
+    if let Some(public_key) = &updater.public_key {
+        decode_public_key(public_key)?;
+    }
+    Ok(updater)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::settings::{BackupTargetConfig, SettingsView};
+
+    const PASSPHRASE: &str = "correct horse";
+
+    fn targets() -> Vec<BackupTargetConfig> {
+        serde_json::from_value(serde_json::json!([
+            { "type": "resend", "api_key": "re_123", "from": "gym@example.com", "to": ["owner@example.com"] },
+            { "type": "webdav", "url": "https://nas.local/dav", "username": "gym", "password": "dav-secret" },
+            { "type": "s3", "endpoint": "https://s3.example.com", "region": "us-east-1", "bucket": "gym",
+              "access_key_id": "AKIA", "secret_access_key": "s3-secret" }
+        ]))
+        .unwrap()
+    }
+
+    fn with_passphrase(service: &SettingsService, passphrase: Option<&str>) -> AppSettings {
+        let mut settings = service.get();
+        settings.backup.passphrase = passphrase.map(str::to_string);
+        service.update(settings).unwrap()
+    }
+
+    #[test]
+    fn passphrase_is_kept_out_of_settings_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = SettingsService::new(dir.path());
+        with_passphrase(&service, Some(PASSPHRASE));
+
+        let settings_json = fs::read_to_string(dir.path().join("settings.json")).unwrap();
+        assert!(!settings_json.contains(PASSPHRASE));
+        assert_eq!(fs::read_to_string(dir.path().join(PASSPHRASE_FILE)).unwrap(), PASSPHRASE);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dir.path().join(PASSPHRASE_FILE)).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let reloaded = SettingsService::new(dir.path());
+        assert_eq!(reloaded.get().backup.passphrase.as_deref(), Some(PASSPHRASE));
+
+        with_passphrase(&reloaded, None);
+        assert_eq!(reloaded.get().backup.passphrase.as_deref(), Some(PASSPHRASE));
+        with_passphrase(&reloaded, Some(""));
+        assert!(!dir.path().join(PASSPHRASE_FILE).exists());
+        assert_eq!(SettingsService::new(dir.path()).get().backup.passphrase, None);
+    }
+
+    #[test]
+    fn passphrase_in_an_older_settings_file_is_moved_out() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("settings.json"),
+            format!(r#"{{"version": 2, "backup": {{"keep_daily": 3, "passphrase": "{}"}}}}"#, PASSPHRASE),
+        )
+        .unwrap();
+
+        let service = SettingsService::new(dir.path());
+        assert_eq!(service.get().backup.keep_daily, 3);
+        assert_eq!(service.get().backup.passphrase.as_deref(), Some(PASSPHRASE));
+        assert!(!fs::read_to_string(dir.path().join("settings.json")).unwrap().contains(PASSPHRASE));
+        assert_eq!(fs::read_to_string(dir.path().join(PASSPHRASE_FILE)).unwrap(), PASSPHRASE);
+    }
+
+    #[test]
+    fn view_never_contains_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = SettingsService::new(dir.path());
+        let mut settings = service.get();
+        settings.backup.passphrase = Some(PASSPHRASE.to_string());
+        settings.backup.targets = targets();
+        let saved = service.update(settings).unwrap();
+
+        let view = serde_json::to_string(&SettingsView::from(&saved)).unwrap();
+        for secret in [PASSPHRASE, "re_123", "dav-secret", "s3-secret"] {
+            assert!(!view.contains(secret), "{}", secret);
+        }
+        let view = SettingsView::from(&saved);
+        assert!(view.backup.passphrase_set);
+        assert!(view.backup.targets.iter().all(|target| target.secret_set));
+        assert!(view.backup.targets.iter().any(|target| matches!(&target.target, BackupTargetConfig::S3 { access_key_id, .. } if access_key_id == "AKIA")));
+    }
+
+    #[test]
+    fn omitted_secrets_keep_the_stored_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = SettingsService::new(dir.path());
+        let mut settings = service.get();
+        settings.backup.targets = targets();
+        service.update(settings).unwrap();
+
+        // What the webview sends back after editing the retention only
+        let mut settings: AppSettings = serde_json::from_value(serde_json::json!({
+            "backup": { "keep_daily": 3, "targets": serde_json::to_value(SettingsView::from(&service.get()).backup.targets).unwrap() }
+        }))
+        .unwrap();
+        let saved = service.update(settings.clone()).unwrap();
+        assert_eq!(saved.backup.keep_daily, 3);
+        assert_eq!(serde_json::to_value(&saved.backup.targets).unwrap(), serde_json::to_value(targets()).unwrap());
+
+        // A new account does not inherit the old credential; an empty password clears it
+        if let BackupTargetConfig::S3 { access_key_id, .. } = &mut settings.backup.targets[2] {
+            *access_key_id = "OTHER".to_string();
+        }
+        assert!(service.update(settings.clone()).unwrap_err().contains("S3"));
+        settings.backup.targets.truncate(2);
+        if let BackupTargetConfig::WebDav { password, .. } = &mut settings.backup.targets[1] {
+            *password = Some(String::new());
+        }
+        let saved = service.update(settings).unwrap();
+        assert!(matches!(&saved.backup.targets[1], BackupTargetConfig::WebDav { password: None, .. }));
+    }
+
+    #[test]
+    fn newer_settings_file_is_never_overwritten() {
+        let dir = tempfile::tempdir().unwrap();
+        let newer = format!(r#"{{"version": {}, "backup": {{"keep_daily": 2}}}}"#, SETTINGS_VERSION + 1);
+        fs::write(dir.path().join("settings.json"), &newer).unwrap();
+
+        let service = SettingsService::new(dir.path());
+        assert_eq!(service.get().backup.keep_daily, AppSettings::default().backup.keep_daily);
+        assert!(service.update(service.get()).unwrap_err().contains("newer"));
+        assert_eq!(fs::read_to_string(dir.path().join("settings.json")).unwrap(), newer);
+    }
+
+    #[test]
+    fn upgraded_install_gets_a_notice_about_email_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(SettingsService::new(dir.path()).get().notices.is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(BACKUP_STATUS_FILE), "{}").unwrap();
+        let service = SettingsService::new(dir.path());
+        assert_eq!(service.get().notices, vec![EMAIL_BACKUP_NOTICE.to_string()]);
+        assert_eq!(SettingsService::new(dir.path()).get().notices.len(), 1);
+
+        service.update(service.get()).unwrap();
+        assert!(SettingsService::new(dir.path()).get().notices.is_empty());
+    }
+}
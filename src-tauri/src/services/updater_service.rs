@@ -1,6 +1,19 @@
-use tauri::{AppHandle, Emitter};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use base64::{Engine as _, engine::general_purpose};
+use minisign_verify::{PublicKey, Signature};
+use reqwest::Url;
+use semver::Version;
 use serde::{Deserialize, Serialize};
-use reqwest;
+use sha2::{Digest, Sha256};
+use crate::models::settings::UpdateChannel;
+use crate::services::settings_service::SettingsService;
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateInfo {
@@ -10,97 +23,484 @@ pub struct UpdateInfo {
     pub pub_date: String,
 }
 
+// Manifest format of update-server.json: the top-level release is the stable
+// channel, other channels are listed under "channels" with the same fields
+#[derive(Debug, Deserialize)]
+struct UpdateManifest {
+    #[serde(flatten)]
+    stable: ManifestRelease,
+    #[serde(default)]
+    channels: HashMap<String, ManifestRelease>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ManifestRelease {
+    version: String,
+    #[serde(default)]
+    notes: String,
+    #[serde(default)]
+    pub_date: String,
+    #[serde(default)]
+    platforms: HashMap<String, PlatformArtifact>,
+}
+
+/// Installer for one platform; it needs a signature, or a `sha256` when both the manifest
+/// and the installer are served over https
+#[derive(Debug, Clone, Deserialize)]
+struct PlatformArtifact {
+    url: String,
+    #[serde(default)]
+    signature: Option<String>, // base64 minisign signature, as written by `tauri signer sign`
+    #[serde(default)]
+    sha256: Option<String>,    // hex
+}
+
 pub struct UpdaterService {
-    app_handle: AppHandle,
+    settings: Arc<SettingsService>,
+    download_dir: PathBuf,
+    current_version: Version,
+    // Artifact of the last update found; downloads are only accepted for it
+    pending: Mutex<Option<PendingUpdate>>,
+}
+
+struct PendingUpdate {
+    manifest_url: String,
+    artifact: PlatformArtifact,
 }
 
 impl UpdaterService {
-    pub fn new(app_handle: AppHandle) -> Self {
+    /// `download_dir` receives verified installers
+    pub fn new(settings: Arc<SettingsService>, download_dir: PathBuf) -> Self {
         Self {
-            app_handle,
+            settings,
+            download_dir,
+            current_version: Version::parse(env!("CARGO_PKG_VERSION")).unwrap_or_else(|_| Version::new(0, 0, 0)),
+            pending: Mutex::new(None),
         }
     }
 
+    /// Newest release for this platform on the configured channel, if newer than the running app
     pub async fn check_for_updates(&self) -> Result<Option<UpdateInfo>, String> {
-        // URL del servidor de actualizaciones
-        let update_url = "https://api.github.com/repos/tu-usuario/quality-gym-releases/releases/latest";
-        
-        let client = reqwest::Client::new();
-        let response = client.get(update_url)
+        let updater = self.settings.get().updater;
+        // Sin URL configurada no se buscan actualizaciones
+        let manifest_url = match updater.manifest_url {
+            Some(url) => url,
+            None => return Ok(None),
+        };
+
+        let client = reqwest::Client::builder()
+            .timeout(CHECK_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let response = client.get(&manifest_url)
             .header("User-Agent", "Quality-GYM-Updater")
             .send()
-            .await;
-
-        // Si no hay respuesta o hay error, no mostrar actualización
-        let response = match response {
-            Ok(resp) => resp,
-            Err(_) => return Ok(None),
-        };
+            .await
+            .map_err(|e| format!("Failed to check for updates: {}", e))?;
 
         if !response.status().is_success() {
-            // Si el repositorio no existe o hay error 404, no mostrar actualización
+            // Si el manifiesto todavía no existe, no hay actualización
             if response.status().as_u16() == 404 {
                 return Ok(None);
             }
             return Err(format!("Server error: {}", response.status()));
         }
 
-        let github_release: serde_json::Value = match response.json().await {
-            Ok(release) => release,
-            Err(_) => return Ok(None),
+        let manifest: UpdateManifest = response.json().await
+            .map_err(|e| format!("Invalid update manifest: {}", e))?;
+
+        let (version, release, artifact) = match select_release(manifest, updater.channel, &self.current_version)? {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+
+        let update_info = UpdateInfo {
+            version: version.to_string(),
+            notes: release.notes,
+            download_url: artifact.url.clone(),
+            pub_date: release.pub_date,
         };
+        *self.pending.lock().map_err(|_| "Updater lock poisoned".to_string())? = Some(PendingUpdate { manifest_url, artifact });
+        Ok(Some(update_info))
+    }
+
+    /// Downloads the update found by the last check, verifies it and returns where it was saved
+    pub async fn download_update(&self, download_url: &str) -> Result<PathBuf, String> {
+        let (manifest_url, artifact) = self.pending.lock()
+            .map_err(|_| "Updater lock poisoned".to_string())?
+            .as_ref()
+            .filter(|pending| pending.artifact.url == download_url)
+            .map(|pending| (pending.manifest_url.clone(), pending.artifact.clone()))
+            .ok_or_else(|| "Unknown update, check for updates first".to_string())?;
+
+        let client = reqwest::Client::builder()
+            .timeout(DOWNLOAD_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let response = client.get(&artifact.url)
+            .header("User-Agent", "Quality-GYM-Updater")
+            .send()
+            .await
+            .map_err(|e| format!("Failed to download update: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to download update: {}", response.status()));
+        }
+        let data = response.bytes().await
+            .map_err(|e| format!("Failed to download update: {}", e))?;
+
+        verify_artifact(&data, &artifact, &manifest_url, self.settings.get().updater.public_key.as_deref())?;
+
+        fs::create_dir_all(&self.download_dir)
+            .map_err(|e| format!("Failed to create update folder: {}", e))?;
+        let installer_path = self.download_dir.join(installer_file_name(&artifact.url)?);
+        let partial_path = installer_path.with_extension("partial");
+        fs::write(&partial_path, &data)
+            .map_err(|e| format!("Failed to save update: {}", e))?;
+        fs::rename(&partial_path, &installer_path)
+            .map_err(|e| format!("Failed to save update: {}", e))?;
+        Ok(installer_path)
+    }
+}
+
+// Tauri platform key, e.g. "darwin-aarch64" or "windows-x86_64"
+fn platform_key() -> String {
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        os => os,
+    };
+    format!("{}-{}", os, std::env::consts::ARCH)
+}
+
+fn select_release(
+    mut manifest: UpdateManifest,
+    channel: UpdateChannel,
+    current_version: &Version,
+) -> Result<Option<(Version, ManifestRelease, PlatformArtifact)>, String> {
+    let mut releases = vec![manifest.stable];
+    if channel == UpdateChannel::Beta {
+        releases.extend(manifest.channels.remove("beta"));
+    }
+
+    let platform = platform_key();
+    let mut newest: Option<(Version, ManifestRelease, PlatformArtifact)> = None;
+    for mut release in releases {
+        let version = Version::parse(release.version.trim().trim_start_matches('v'))
+            .map_err(|e| format!("Invalid version '{}' in update manifest: {}", release.version, e))?;
+
+        // Pre-releases compare lower than their release, so 1.2.0-beta.1 < 1.2.0;
+        // build metadata is ignored, 1.2.0+build.5 is not newer than 1.2.0
+        if (channel == UpdateChannel::Stable && !version.pre.is_empty()) || version.cmp_precedence(current_version).is_le() {
+            continue;
+        }
+        if newest.as_ref().is_some_and(|(newest_version, _, _)| version.cmp_precedence(newest_version).is_le()) {
+            continue;
+        }
+        if let Some(artifact) = release.platforms.remove(&platform) {
+            newest = Some((version, release, artifact));
+        }
+    }
+    Ok(newest)
+}
+
+fn verify_artifact(data: &[u8], artifact: &PlatformArtifact, manifest_url: &str, public_key: Option<&str>) -> Result<(), String> {
+    let mut verified = false;
+
+    if let Some(expected) = &artifact.sha256 {
+        let actual = format!("{:x}", Sha256::digest(data));
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            return Err("Update checksum does not match, download discarded".to_string());
+        }
+        // Over plain http the checksum can be swapped together with the installer
+        verified = is_https(manifest_url) && is_https(&artifact.url);
+    }
+
+    // With a public key configured only signed updates are installed
+    if let Some(public_key) = public_key {
+        let signature = artifact.signature.as_deref()
+            .ok_or_else(|| "Update is not signed, download discarded".to_string())?;
+        let signature = Signature::decode(&decode_base64_text(signature)?)
+            .map_err(|e| format!("Invalid update signature: {}", e))?;
+        decode_public_key(public_key)?
+            .verify(data, &signature, true)
+            .map_err(|_| "Update signature is not valid, download discarded".to_string())?;
+        verified = true;
+    }
+
+    if !verified {
+        return Err(
+            "Update cannot be verified: without an update public key the manifest and installer must be served over https with a checksum"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+fn is_https(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|url| url.scheme() == "https")
+}
+
+/// Parses a base64 minisign public key, as stored in the updater settings
+pub fn decode_public_key(public_key: &str) -> Result<PublicKey, String> {
+    PublicKey::decode(&decode_base64_text(public_key)?)
+        .map_err(|e| format!("Invalid update public key: {}", e))
+}
+
+// Tauri stores minisign keys and signatures as base64 of the whole text file
+fn decode_base64_text(value: &str) -> Result<String, String> {
+    general_purpose::STANDARD.decode(value.trim())
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(|| "Invalid base64 in update key or signature".to_string())
+}
+
+fn installer_file_name(url: &str) -> Result<String, String> {
+    let url = Url::parse(url).map_err(|e| format!("Invalid update URL: {}", e))?;
+    url.path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|name| !name.is_empty() && *name != "." && *name != "..")
+        .map(|name| name.to_string())
+        .ok_or_else(|| "Update URL does not name a file".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    // Key pair made with `tauri signer generate`; SIGNATURE signs INSTALLER
+    const PUBLIC_KEY: &str = "dW50cnVzdGVkIGNvbW1lbnQ6IG1pbmlzaWduIHB1YmxpYyBrZXk6IDA4MDcwNjA1MDQwMzAyMDEKUldRQkFnTUVCUVlIQ0FPaEI3L3p6aEMrSFhEZEdPZEx3SmxuNU5Zd202VU5YeDNjaG1RU1ZURzQK";
+    const SIGNATURE: &str = "dW50cnVzdGVkIGNvbW1lbnQ6IHNpZ25hdHVyZSBmcm9tIHRhdXJpIHNlY3JldCBrZXkKUlVRQkFnTUVCUVlIQ09SVlJZUDJrbzA4RUc2OWdkMG1zT25EVm5QVWZNeXFua1VPbzE2dlBkYStaNFZsT0FVYjNXYzZlNkhlbkdrdjVFY2I3THJYb1VPc1ltcGY2ZHJRN2dvPQp0cnVzdGVkIGNvbW1lbnQ6IHRpbWVzdGFtcDowCWZpbGU6UXVhbGl0eV9HWU0uQXBwSW1hZ2UKYkdNLy9seFNrMkF5MWVXcHdXMFROY3Z3S0tEN0pyQ0JBZm9neXlZSWdQeVF1K3kxTmhxTFA2a2JMVEU5Z29pNE96YXVOZEZBR2dXaVNraHNLdjlZRFE9PQo=";
+    const INSTALLER: &[u8] = b"installer-bytes";
+    const INSTALLER_SHA256: &str = "204676736cea68d6411da9d3aa3fab0a5e70b023ba30cd560cfa9c8e7250f4df";
+    const MANIFEST_URL: &str = "https://updates.example.com/update-server.json";
 
-        // Extraer información de la release de GitHub
-        let version = github_release["tag_name"]
-            .as_str()
-            .unwrap_or("")
-            .trim_start_matches('v')
-            .to_string();
-
-        let notes = github_release["body"]
-            .as_str()
-            .unwrap_or("")
-            .to_string();
-
-        let download_url = github_release["html_url"]
-            .as_str()
-            .unwrap_or("")
-            .to_string();
-
-        let pub_date = github_release["published_at"]
-            .as_str()
-            .unwrap_or("")
-            .to_string();
-
-        // Verificar si hay una nueva versión
-        let current_version = env!("CARGO_PKG_VERSION");
-        
-        if version != current_version && !version.is_empty() {
-            let update_info = UpdateInfo {
-                version,
-                notes,
-                download_url,
-                pub_date,
-            };
-
-            // Emitir evento al frontend
-            self.app_handle.emit("update-available", &update_info)
-                .map_err(|e| format!("Failed to emit event: {}", e))?;
-
-            Ok(Some(update_info))
-        } else {
-            Ok(None)
+    fn version(version: &str) -> Version {
+        Version::parse(version).unwrap()
+    }
+
+    fn release(version: &str) -> serde_json::Value {
+        serde_json::json!({
+            "version": version,
+            "platforms": { platform_key(): { "url": format!("https://updates.example.com/{}/Quality_GYM.AppImage", version), "sha256": INSTALLER_SHA256 } },
+        })
+    }
+
+    fn manifest(stable: &str, beta: Option<&str>) -> UpdateManifest {
+        let mut manifest = release(stable);
+        if let Some(beta) = beta {
+            manifest["channels"] = serde_json::json!({ "beta": release(beta) });
         }
+        serde_json::from_value(manifest).unwrap()
+    }
+
+    fn selected(manifest: UpdateManifest, channel: UpdateChannel, current: &str) -> Option<String> {
+        select_release(manifest, channel, &version(current))
+            .unwrap()
+            .map(|(version, _, _)| version.to_string())
+    }
+
+    fn artifact(sha256: Option<&str>, signature: Option<&str>) -> PlatformArtifact {
+        PlatformArtifact {
+            url: "https://updates.example.com/Quality_GYM.AppImage".to_string(),
+            signature: signature.map(str::to_string),
+            sha256: sha256.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn stable_channel_skips_pre_releases() {
+        assert_eq!(selected(manifest("1.1.0", Some("1.2.0-beta.1")), UpdateChannel::Stable, "1.0.0").as_deref(), Some("1.1.0"));
+        assert_eq!(selected(manifest("1.1.0-rc.1", None), UpdateChannel::Stable, "1.0.0"), None);
+    }
+
+    #[test]
+    fn beta_channel_takes_the_newest_of_both() {
+        assert_eq!(selected(manifest("1.1.0", Some("1.2.0-beta.1")), UpdateChannel::Beta, "1.0.0").as_deref(), Some("1.2.0-beta.1"));
+        // The release outranks the pre-releases that led to it
+        assert_eq!(selected(manifest("1.2.0", Some("1.2.0-beta.3")), UpdateChannel::Beta, "1.0.0").as_deref(), Some("1.2.0"));
+        // A pre-release on the stable line is still offered to beta users
+        assert_eq!(selected(manifest("1.3.0-rc.1", None), UpdateChannel::Beta, "1.2.0").as_deref(), Some("1.3.0-rc.1"));
+    }
+
+    #[test]
+    fn versions_are_compared_by_semver_precedence() {
+        // Numeric identifiers compare as numbers, not text
+        assert_eq!(selected(manifest("1.0.0", Some("1.2.0-beta.10")), UpdateChannel::Beta, "1.2.0-beta.9").as_deref(), Some("1.2.0-beta.10"));
+        assert_eq!(selected(manifest("1.10.0", None), UpdateChannel::Stable, "1.9.0").as_deref(), Some("1.10.0"));
+        // Leading `v` and whitespace are tolerated
+        assert_eq!(selected(manifest(" v1.1.0 ", None), UpdateChannel::Stable, "1.0.0").as_deref(), Some("1.1.0"));
+        // Build metadata does not make a release newer
+        assert_eq!(selected(manifest("1.0.0+build.5", None), UpdateChannel::Stable, "1.0.0"), None);
+        assert_eq!(selected(manifest("1.0.0+build.5", Some("1.0.0+build.9")), UpdateChannel::Beta, "0.9.0").as_deref(), Some("1.0.0+build.5"));
+
+        assert!(select_release(manifest("1.1", None), UpdateChannel::Stable, &version("1.0.0")).is_err());
+        assert!(select_release(manifest("latest", None), UpdateChannel::Stable, &version("1.0.0")).is_err());
+    }
+
+    #[test]
+    fn same_or_older_versions_are_refused() {
+        assert_eq!(selected(manifest("1.0.0", None), UpdateChannel::Stable, "1.0.0"), None);
+        assert_eq!(selected(manifest("0.9.9", None), UpdateChannel::Stable, "1.0.0"), None);
+        // Leaving the beta channel does not roll back to the last stable release
+        assert_eq!(selected(manifest("1.1.0", Some("1.2.0-beta.1")), UpdateChannel::Stable, "1.2.0-beta.1"), None);
+        assert_eq!(selected(manifest("1.1.0", Some("1.2.0-beta.1")), UpdateChannel::Beta, "1.2.0-beta.2"), None);
     }
 
-    pub async fn download_update(&self, download_url: String) -> Result<(), String> {
-        // Abrir el navegador para descargar la actualización
-        let _ = webbrowser::open(&download_url);
-        
-        // Emitir evento de descarga iniciada
-        self.app_handle.emit("update-download-started", download_url)
-            .map_err(|e| format!("Failed to emit event: {}", e))?;
+    #[test]
+    fn releases_without_this_platform_are_skipped() {
+        let mut beta = release("1.2.0-beta.1");
+        beta["platforms"] = serde_json::json!({ "plan9-mips": { "url": "https://updates.example.com/x", "sha256": INSTALLER_SHA256 } });
+        let mut stable = release("1.1.0");
+        stable["channels"] = serde_json::json!({ "beta": beta });
+        let manifest: UpdateManifest = serde_json::from_value(stable).unwrap();
 
-        Ok(())
+        assert_eq!(selected(manifest, UpdateChannel::Beta, "1.0.0").as_deref(), Some("1.1.0"));
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn checksum_must_match() {
+        assert!(verify_artifact(INSTALLER, &artifact(Some(INSTALLER_SHA256), None), MANIFEST_URL, None).is_ok());
+        assert!(verify_artifact(INSTALLER, &artifact(Some(&INSTALLER_SHA256.to_uppercase()), None), MANIFEST_URL, None).is_ok());
+
+        let error = verify_artifact(b"installer-bytez", &artifact(Some(INSTALLER_SHA256), None), MANIFEST_URL, None).unwrap_err();
+        assert!(error.contains("checksum"), "{}", error);
+        // A valid signature does not make up for a wrong checksum
+        let error = verify_artifact(INSTALLER, &artifact(Some(&"0".repeat(64)), Some(SIGNATURE)), MANIFEST_URL, Some(PUBLIC_KEY)).unwrap_err();
+        assert!(error.contains("checksum"), "{}", error);
+    }
+
+    #[test]
+    fn signature_is_required_once_a_key_is_configured() {
+        assert!(verify_artifact(INSTALLER, &artifact(None, Some(SIGNATURE)), MANIFEST_URL, Some(PUBLIC_KEY)).is_ok());
+        assert!(verify_artifact(INSTALLER, &artifact(Some(INSTALLER_SHA256), Some(SIGNATURE)), MANIFEST_URL, Some(PUBLIC_KEY)).is_ok());
+
+        let error = verify_artifact(INSTALLER, &artifact(Some(INSTALLER_SHA256), None), MANIFEST_URL, Some(PUBLIC_KEY)).unwrap_err();
+        assert!(error.contains("not signed"), "{}", error);
+        let error = verify_artifact(b"installer-bytez", &artifact(None, Some(SIGNATURE)), MANIFEST_URL, Some(PUBLIC_KEY)).unwrap_err();
+        assert!(error.contains("signature is not valid"), "{}", error);
+        let error = verify_artifact(INSTALLER, &artifact(None, Some("not base64!")), MANIFEST_URL, Some(PUBLIC_KEY)).unwrap_err();
+        assert!(error.contains("Invalid base64"), "{}", error);
+        let garbage = general_purpose::STANDARD.encode("untrusted comment: nothing here\n");
+        let error = verify_artifact(INSTALLER, &artifact(None, Some(&garbage)), MANIFEST_URL, Some(PUBLIC_KEY)).unwrap_err();
+        assert!(error.contains("Invalid update signature"), "{}", error);
+    }
+
+    #[test]
+    fn unverifiable_artifacts_are_refused() {
+        // A signature alone proves nothing without the key to check it
+        let error = verify_artifact(INSTALLER, &artifact(None, Some(SIGNATURE)), MANIFEST_URL, None).unwrap_err();
+        assert!(error.contains("cannot be verified"), "{}", error);
+        assert!(decode_public_key("bad").is_err());
+    }
+
+    #[test]
+    fn checksums_over_http_need_a_signature() {
+        let error = verify_artifact(INSTALLER, &artifact(Some(INSTALLER_SHA256), None), "http://updates.example.com/update-server.json", None)
+            .unwrap_err();
+        assert!(error.contains("cannot be verified"), "{}", error);
+        let mut over_http = artifact(Some(INSTALLER_SHA256), None);
+        over_http.url = "http://updates.example.com/Quality_GYM.AppImage".to_string();
+        let error = verify_artifact(INSTALLER, &over_http, MANIFEST_URL, None).unwrap_err();
+        assert!(error.contains("cannot be verified"), "{}", error);
+
+        over_http.signature = Some(SIGNATURE.to_string());
+        assert!(verify_artifact(INSTALLER, &over_http, "http://updates.example.com/update-server.json", Some(PUBLIC_KEY)).is_ok());
+    }
+
+    // Serves `manifest.json` and the installer under `/<version>/`; anything else is a 404
+    fn update_server(files: Arc<Mutex<HashMap<String, Vec<u8>>>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let path = line.split_whitespace().nth(1).unwrap_or_default().to_string();
+                loop {
+                    let mut header = String::new();
+                    if reader.read_line(&mut header).unwrap() == 0 || header == "\r\n" {
+                        break;
+                    }
+                }
+
+                match files.lock().unwrap().get(&path) {
+                    Some(body) => {
+                        write!(stream, "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n", body.len()).unwrap();
+                        stream.write_all(body).unwrap();
+                    }
+                    None => write!(stream, "HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\nconnection: close\r\n\r\n").unwrap(),
+                }
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn checks_downloads_and_verifies_through_the_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = Arc::new(Mutex::new(HashMap::new()));
+        let url = update_server(files.clone());
+        let settings = Arc::new(SettingsService::new(dir.path()));
+        let mut updater = UpdaterService::new(settings.clone(), dir.path().join("updates"));
+        updater.current_version = version("0.1.0");
+
+        // No manifest configured, then a manifest not published yet
+        assert!(updater.check_for_updates().await.unwrap().is_none());
+        let mut current = settings.get();
+        let unreachable = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        current.updater.manifest_url = Some(format!("http://{}/manifest.json", unreachable));
+        settings.update(current.clone()).unwrap();
+        let error = updater.check_for_updates().await.unwrap_err();
+        assert!(error.contains("Failed to check for updates"), "{}", error);
+
+        current.updater.manifest_url = Some(format!("{}/manifest.json", url));
+        current.updater.channel = UpdateChannel::Beta;
+        settings.update(current).unwrap();
+        assert!(updater.check_for_updates().await.unwrap().is_none());
+        files.lock().unwrap().insert("/manifest.json".to_string(), b"<html>maintenance</html>".to_vec());
+        let error = updater.check_for_updates().await.unwrap_err();
+        assert!(error.contains("Invalid update manifest"), "{}", error);
+
+        let manifest = serde_json::json!({
+            "version": "0.1.0",
+            "platforms": { platform_key(): { "url": format!("{}/0.1.0/Quality_GYM.AppImage", url), "sha256": INSTALLER_SHA256 } },
+            "channels": { "beta": {
+                "version": "0.2.0-beta.1",
+                "notes": "Beta",
+                "platforms": { platform_key(): { "url": format!("{}/0.2.0-beta.1/Quality_GYM.AppImage", url), "signature": SIGNATURE } },
+            } },
+        });
+        files.lock().unwrap().insert("/manifest.json".to_string(), serde_json::to_vec(&manifest).unwrap());
+        files.lock().unwrap().insert("/0.2.0-beta.1/Quality_GYM.AppImage".to_string(), INSTALLER.to_vec());
+
+        let update = updater.check_for_updates().await.unwrap().unwrap();
+        assert_eq!(update.version, "0.2.0-beta.1");
+        assert_eq!(update.notes, "Beta");
+
+        // Signed only, and no key to check it with yet
+        let error = updater.download_update(&update.download_url).await.unwrap_err();
+        assert!(error.contains("cannot be verified"), "{}", error);
+        let error = updater.download_update("http://evil.example.com/Quality_GYM.AppImage").await.unwrap_err();
+        assert!(error.contains("Unknown update"), "{}", error);
+
+        let mut current = settings.get();
+        current.updater.public_key = Some("bad".to_string());
+        assert!(settings.update(current.clone()).is_err());
+        current.updater.public_key = Some(PUBLIC_KEY.to_string());
+        settings.update(current).unwrap();
+        let installer = updater.download_update(&update.download_url).await.unwrap();
+        assert_eq!(installer, dir.path().join("updates").join("Quality_GYM.AppImage"));
+        assert_eq!(fs::read(&installer).unwrap(), INSTALLER);
+
+        // A tampered installer is discarded before it reaches the update folder
+        fs::remove_file(&installer).unwrap();
+        files.lock().unwrap().insert("/0.2.0-beta.1/Quality_GYM.AppImage".to_string(), b"installer-bytez".to_vec());
+        let error = updater.download_update(&update.download_url).await.unwrap_err();
+        assert!(error.contains("signature"), "{}", error);
+        assert!(!installer.exists());
+    }
+}
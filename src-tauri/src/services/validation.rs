@@ -0,0 +1,210 @@
+use chrono::NaiveDate;
+use crate::error::{AppError, AppResult};
+use crate::models::exercise::MeasurementType;
+
+const MIN_RPE: f64 = 1.0;
+const MAX_RPE: f64 = 10.0;
+const MAX_RIR: i32 = 10;
+const MAX_REST_SECONDS: i32 = 3600;
+
+/// Simple validation for YYYY-MM-DD dates
+pub fn is_valid_date_format(date: &str) -> bool {
+    if date.len() != 10 {
+        return false;
+    }
+
+    let parts: Vec<&str> = date.split('-').collect();
+    if parts.len() != 3 {
+        return false;
+    }
+
+    // Check if year, month, day are valid numbers
+    if let (Ok(year), Ok(month), Ok(day)) = (
+        parts[0].parse::<i32>(),
+        parts[1].parse::<i32>(),
+        parts[2].parse::<i32>(),
+    ) {
+        year >= 1900 && year <= 2100 && month >= 1 && month <= 12 && day >= 1 && day <= 31
+    } else {
+        false
+    }
+}
+
+/// Parses a YYYY-MM-DD date, also rejecting days the format check lets through (2024-02-31)
+pub fn parse_date(field: &str, date: &str) -> AppResult<NaiveDate> {
+    if !is_valid_date_format(date) {
+        return Err(AppError::validation(field, "Invalid date format. Use YYYY-MM-DD"));
+    }
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| AppError::validation(field, "Invalid date"))
+}
+
+pub fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Validates the optional intensity prescription shared by workout entries and
+/// routine exercises (RPE, reps in reserve, tempo and rest interval).
+pub fn validate_intensity(rpe: Option<f64>, rir: Option<i32>, tempo: Option<&str>, rest_seconds: Option<i32>) -> AppResult<()> {
+    if let Some(rpe) = rpe {
+        if !(MIN_RPE..=MAX_RPE).contains(&rpe) {
+            return Err(AppError::validation("rpe", format!("RPE must be between {} and {}", MIN_RPE, MAX_RPE)));
+        }
+    }
+
+    if let Some(rir) = rir {
+        if !(0..=MAX_RIR).contains(&rir) {
+            return Err(AppError::validation("rir", format!("Reps in reserve must be between 0 and {}", MAX_RIR)));
+        }
+    }
+
+    if let Some(tempo) = tempo {
+        if !is_valid_tempo(tempo) {
+            return Err(AppError::validation("tempo", "Invalid tempo. Use four phases like 3-1-1-0 (X for explosive)"));
+        }
+    }
+
+    if let Some(rest_seconds) = rest_seconds {
+        if !(0..=MAX_REST_SECONDS).contains(&rest_seconds) {
+            return Err(AppError::validation("rest_seconds", format!("Rest must be between 0 and {} seconds", MAX_REST_SECONDS)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Duration and distance are optional everywhere but must be positive when given
+pub fn validate_duration_distance(duration_seconds: Option<i32>, distance_meters: Option<f64>) -> AppResult<()> {
+    if let Some(duration_seconds) = duration_seconds {
+        if duration_seconds <= 0 {
+            return Err(AppError::validation("duration_seconds", "Duration must be greater than 0"));
+        }
+    }
+
+    if let Some(distance_meters) = distance_meters {
+        if distance_meters <= 0.0 || !distance_meters.is_finite() {
+            return Err(AppError::validation("distance_meters", "Distance must be greater than 0"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that an entry records what its exercise measures. Weight is accepted
+/// for every type (added load on planks, dips, carries...).
+pub fn validate_measurement(
+    measurement_type: MeasurementType,
+    reps: Option<i32>,
+    duration_seconds: Option<i32>,
+    distance_meters: Option<f64>,
+) -> AppResult<()> {
+    validate_duration_distance(duration_seconds, distance_meters)?;
+
+    let (needs_duration, needs_distance) = match measurement_type {
+        MeasurementType::RepsWeight | MeasurementType::BodyweightReps => (false, false),
+        MeasurementType::Time => (true, false),
+        MeasurementType::Distance => (false, true),
+        MeasurementType::DistanceTime => (true, true),
+    };
+    let counts_reps = !needs_duration && !needs_distance;
+
+    if needs_duration && duration_seconds.is_none() {
+        return Err(AppError::validation("duration_seconds", "Duration is required for this exercise"));
+    }
+    if needs_distance && distance_meters.is_none() {
+        return Err(AppError::validation("distance_meters", "Distance is required for this exercise"));
+    }
+    if !needs_duration && duration_seconds.is_some() {
+        return Err(AppError::validation("duration_seconds", "This exercise does not record a duration"));
+    }
+    if !needs_distance && distance_meters.is_some() {
+        return Err(AppError::validation("distance_meters", "This exercise does not record a distance"));
+    }
+    if !counts_reps && reps.is_some() {
+        return Err(AppError::validation("reps", "This exercise does not record reps"));
+    }
+
+    Ok(())
+}
+
+/// Trims a free-text tempo; blank input is stored as `None`
+pub fn normalize_tempo(tempo: Option<String>) -> Option<String> {
+    tempo.map(|t| t.trim().to_uppercase()).filter(|t| !t.is_empty())
+}
+
+// Eccentric-pause-concentric-pause, each phase in seconds or X for explosive: "3-1-1-0", "2-0-X-1"
+fn is_valid_tempo(tempo: &str) -> bool {
+    let phases: Vec<&str> = tempo.split('-').collect();
+    phases.len() == 4
+        && phases.iter().all(|phase| {
+            phase.eq_ignore_ascii_case("x")
+                || (!phase.is_empty() && phase.len() <= 2 && phase.chars().all(|c| c.is_ascii_digit()))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed_field(result: AppResult<()>) -> Option<String> {
+        result.unwrap_err().field().map(str::to_string)
+    }
+
+    #[test]
+    fn reps_exercises_reject_duration_and_distance() {
+        assert!(validate_measurement(MeasurementType::RepsWeight, Some(8), None, None).is_ok());
+        assert!(validate_measurement(MeasurementType::BodyweightReps, None, None, None).is_ok());
+        assert_eq!(
+            failed_field(validate_measurement(MeasurementType::RepsWeight, Some(8), Some(30), None)).as_deref(),
+            Some("duration_seconds")
+        );
+        assert_eq!(
+            failed_field(validate_measurement(MeasurementType::BodyweightReps, Some(8), None, Some(100.0))).as_deref(),
+            Some("distance_meters")
+        );
+    }
+
+    #[test]
+    fn timed_exercises_require_duration_and_reject_reps() {
+        assert!(validate_measurement(MeasurementType::Time, None, Some(60), None).is_ok());
+        assert_eq!(failed_field(validate_measurement(MeasurementType::Time, None, None, None)).as_deref(), Some("duration_seconds"));
+        assert_eq!(failed_field(validate_measurement(MeasurementType::Time, Some(10), Some(60), None)).as_deref(), Some("reps"));
+        assert_eq!(
+            failed_field(validate_measurement(MeasurementType::Time, None, Some(60), Some(100.0))).as_deref(),
+            Some("distance_meters")
+        );
+    }
+
+    #[test]
+    fn distance_exercises_require_their_fields() {
+        assert!(validate_measurement(MeasurementType::Distance, None, None, Some(400.0)).is_ok());
+        assert!(validate_measurement(MeasurementType::DistanceTime, None, Some(90), Some(400.0)).is_ok());
+        assert_eq!(failed_field(validate_measurement(MeasurementType::Distance, None, None, None)).as_deref(), Some("distance_meters"));
+        assert_eq!(
+            failed_field(validate_measurement(MeasurementType::DistanceTime, None, None, Some(400.0))).as_deref(),
+            Some("duration_seconds")
+        );
+    }
+
+    #[test]
+    fn duration_and_distance_must_be_positive() {
+        assert_eq!(failed_field(validate_measurement(MeasurementType::Time, None, Some(0), None)).as_deref(), Some("duration_seconds"));
+        assert_eq!(
+            failed_field(validate_measurement(MeasurementType::Distance, None, None, Some(-5.0))).as_deref(),
+            Some("distance_meters")
+        );
+        assert_eq!(
+            failed_field(validate_measurement(MeasurementType::Distance, None, None, Some(f64::NAN))).as_deref(),
+            Some("distance_meters")
+        );
+    }
+
+    #[test]
+    fn tempo_has_four_numeric_or_explosive_phases() {
+        for tempo in ["3-1-1-0", "2-0-X-1", "10-0-x-0"] {
+            assert!(is_valid_tempo(tempo), "{}", tempo);
+        }
+        for tempo in ["", "3-1-1", "3-1-1-0-0", "3--1-0", "3-1-A-0", "100-0-1-0", "3 1 1 0"] {
+            assert!(!is_valid_tempo(tempo), "{}", tempo);
+        }
+    }
+}
@@ -1,60 +1,80 @@
 use std::sync::Arc;
-use crate::models::workout_entry::{WorkoutEntry, WorkoutEntryWithDetails};
+use crate::error::{AppError, AppResult};
+use crate::models::personal_record::{OneRepMaxFormula, PersonalRecord};
+use crate::models::workout_entry::{WorkoutEntry, WorkoutEntryStatus, WorkoutEntryWithDetails};
+use crate::models::workout_set::WorkoutSet;
+use crate::repository::exercise_repository::ExerciseRepository;
 use crate::repository::workout_entry_repository::WorkoutEntryRepository;
+use crate::services::analytics_service::detect_session_records;
+use crate::services::validation::{is_valid_date_format, validate_intensity, validate_measurement};
+
+// A summary entry stands for this many identical sets at most
+const MAX_SETS: i32 = 100;
 
 pub struct WorkoutEntryService {
     repository: Arc<dyn WorkoutEntryRepository + Send + Sync>,
+    exercise_repository: Arc<dyn ExerciseRepository + Send + Sync>,
 }
 
 impl WorkoutEntryService {
-    pub fn new(repository: Arc<dyn WorkoutEntryRepository + Send + Sync>) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: Arc<dyn WorkoutEntryRepository + Send + Sync>,
+        exercise_repository: Arc<dyn ExerciseRepository + Send + Sync>,
+    ) -> Self {
+        Self { repository, exercise_repository }
     }
 
-    pub fn create_workout_entry(&self, workout_entry: WorkoutEntry) -> Result<(), String> {
+    pub fn create_workout_entry(&self, workout_entry: WorkoutEntry) -> AppResult<()> {
         // Validate the workout entry
         if workout_entry.person_id <= 0 {
-            return Err("Invalid person ID".to_string());
+            return Err(AppError::validation("person_id", "Invalid person ID"));
         }
 
         if workout_entry.exercise_id <= 0 {
-            return Err("Invalid exercise ID".to_string());
+            return Err(AppError::validation("exercise_id", "Invalid exercise ID"));
         }
 
         if workout_entry.date.is_empty() {
-            return Err("Date is required".to_string());
+            return Err(AppError::validation("date", "Date is required"));
         }
 
         // Validate date format (YYYY-MM-DD)
-        if !self.is_valid_date_format(&workout_entry.date) {
-            return Err("Invalid date format. Use YYYY-MM-DD".to_string());
+        if !is_valid_date_format(&workout_entry.date) {
+            return Err(AppError::validation("date", "Invalid date format. Use YYYY-MM-DD"));
         }
 
         // Validate optional numeric fields
         if let Some(sets) = workout_entry.sets {
-            if sets <= 0 {
-                return Err("Sets must be greater than 0".to_string());
+            if !(1..=MAX_SETS).contains(&sets) {
+                return Err(AppError::validation("sets", format!("Sets must be between 1 and {}", MAX_SETS)));
             }
         }
 
         if let Some(reps) = workout_entry.reps {
             if reps <= 0 {
-                return Err("Reps must be greater than 0".to_string());
+                return Err(AppError::validation("reps", "Reps must be greater than 0"));
             }
         }
 
         if let Some(weight) = workout_entry.weight {
             if weight < 0.0 {
-                return Err("Weight cannot be negative".to_string());
+                return Err(AppError::validation("weight", "Weight cannot be negative"));
             }
         }
 
+        validate_intensity(workout_entry.rpe, workout_entry.rir, workout_entry.tempo.as_deref(), workout_entry.rest_seconds)?;
+        self.validate_measurement(&workout_entry)?;
+
+        for workout_set in workout_entry.workout_sets.iter().flatten() {
+            self.validate_workout_set(workout_set)?;
+        }
+
         self.repository.create(workout_entry)
     }
 
-    pub fn create_batch(&self, workout_entries: Vec<WorkoutEntry>) -> Result<(), String> {
+    pub fn create_batch(&self, workout_entries: Vec<WorkoutEntry>) -> AppResult<()> {
         if workout_entries.is_empty() {
-            return Err("Workout entries list cannot be empty".to_string());
+            return Err(AppError::validation("workout_entries", "Workout entries list cannot be empty"));
         }
 
         // Validate all entries using helper method
@@ -71,9 +91,10 @@ impl WorkoutEntryService {
         self.repository.create_batch(workout_entries)
     }
 
-    pub fn create_workout_session(&self, workout_entries: Vec<WorkoutEntry>) -> Result<(), String> {
+    /// Saves the session and returns the personal records it sets
+    pub fn create_workout_session(&self, workout_entries: Vec<WorkoutEntry>, formula: OneRepMaxFormula) -> AppResult<Vec<PersonalRecord>> {
         if workout_entries.is_empty() {
-            return Err("Workout session cannot be empty".to_string());
+            return Err(AppError::validation("workout_entries", "Workout session cannot be empty"));
         }
 
         // Validate all entries using helper method
@@ -89,29 +110,72 @@ impl WorkoutEntryService {
         
         for (index, entry) in workout_entries.iter().enumerate() {
             if entry.person_id != person_id {
-                return Err(format!("All exercises must be for the same person. Exercise {} has different person_id", index + 1));
+                return Err(AppError::validation("person_id", format!("All exercises must be for the same person. Exercise {} has different person_id", index + 1)));
             }
             if entry.date != date {
-                return Err(format!("All exercises must be for the same date. Exercise {} has different date", index + 1));
+                return Err(AppError::validation("date", format!("All exercises must be for the same date. Exercise {} has different date", index + 1)));
             }
         }
 
         // Use replace_session to handle both new and existing sessions
-        self.repository.replace_session(person_id, &date, workout_entries)
+        self.repository.replace_session(person_id, &date, workout_entries)?;
+
+        self.session_records(person_id, &date, formula)
+    }
+
+    /// Writes scheduled sessions, one list of entries per date, as planned entries in a
+    /// single transaction: either every session is written or none is. Exercises the
+    /// person already has on that date are left out; returns the entries written.
+    pub fn create_planned_sessions(&self, sessions: Vec<Vec<WorkoutEntry>>) -> AppResult<Vec<WorkoutEntry>> {
+        let mut workout_entries = Vec::new();
+        for mut session in sessions {
+            self.validate_workout_entries(&session)?;
+            self.validate_workout_groups_consecutive(&session)?;
+            for entry in session.iter_mut() {
+                entry.status = WorkoutEntryStatus::Planned;
+            }
+            workout_entries.append(&mut session);
+        }
+
+        self.repository.create_planned(workout_entries)
     }
 
-    pub fn replace_workout_session(&self, person_id: i32, date: &str, workout_entries: Vec<WorkoutEntry>) -> Result<(), String> {
+    /// Marks the planned entries of the person's session as performed and returns the
+    /// personal records the session sets
+    pub fn log_planned_session(&self, person_id: i32, date: &str, formula: OneRepMaxFormula) -> AppResult<Vec<PersonalRecord>> {
         if person_id <= 0 {
-            return Err("Invalid person ID".to_string());
+            return Err(AppError::validation("person_id", "Invalid person ID"));
+        }
+        if !is_valid_date_format(date) {
+            return Err(AppError::validation("date", "Invalid date format. Use YYYY-MM-DD"));
         }
 
-        if !self.is_valid_date_format(date) {
-            return Err("Invalid date format. Use YYYY-MM-DD".to_string());
+        if self.repository.mark_performed(person_id, date)? == 0 {
+            return Err(AppError::not_found("No planned session on this date"));
+        }
+        self.session_records(person_id, date, formula)
+    }
+
+    /// Replaces the session and returns the personal records it sets
+    pub fn replace_workout_session(
+        &self,
+        person_id: i32,
+        date: &str,
+        workout_entries: Vec<WorkoutEntry>,
+        formula: OneRepMaxFormula,
+    ) -> AppResult<Vec<PersonalRecord>> {
+        if person_id <= 0 {
+            return Err(AppError::validation("person_id", "Invalid person ID"));
+        }
+
+        if !is_valid_date_format(date) {
+            return Err(AppError::validation("date", "Invalid date format. Use YYYY-MM-DD"));
         }
 
         // If workout_entries is empty, just delete existing entries
         if workout_entries.is_empty() {
-            return self.repository.replace_session(person_id, date, workout_entries);
+            self.repository.replace_session(person_id, date, workout_entries)?;
+            return Ok(Vec::new());
         }
 
         // Validate all entries using helper method
@@ -123,22 +187,24 @@ impl WorkoutEntryService {
         // Additional validation for replace session
         for (index, workout_entry) in workout_entries.iter().enumerate() {
             if workout_entry.person_id != person_id {
-                return Err(format!("Person ID mismatch in exercise {}", index + 1));
+                return Err(AppError::validation("person_id", format!("Person ID mismatch in exercise {}", index + 1)));
             }
 
             if workout_entry.date != date {
-                return Err(format!("Date mismatch in exercise {}", index + 1));
+                return Err(AppError::validation("date", format!("Date mismatch in exercise {}", index + 1)));
             }
         }
 
-        self.repository.replace_session(person_id, date, workout_entries)
+        self.repository.replace_session(person_id, date, workout_entries)?;
+
+        self.session_records(person_id, date, formula)
     }
 
-    pub fn replace_workout_session_granular(&self, ids_to_delete: Vec<i32>, workout_entries_to_insert: Vec<WorkoutEntry>) -> Result<(), String> {
+    pub fn replace_workout_session_granular(&self, ids_to_delete: Vec<i32>, workout_entries_to_insert: Vec<WorkoutEntry>) -> AppResult<()> {
         // Validate IDs to delete
         for id in &ids_to_delete {
             if *id <= 0 {
-                return Err("Invalid workout entry ID for deletion".to_string());
+                return Err(AppError::validation("id", "Invalid workout entry ID for deletion"));
             }
         }
 
@@ -150,9 +216,9 @@ impl WorkoutEntryService {
         self.repository.replace_session_granular(ids_to_delete, workout_entries_to_insert)
     }
 
-    pub fn get_workout_entry(&self, id: i32) -> Option<WorkoutEntry> {
+    pub fn get_workout_entry(&self, id: i32) -> AppResult<Option<WorkoutEntry>> {
         if id <= 0 {
-            return None;
+            return Err(AppError::validation("id", "Invalid workout entry ID"));
         }
         self.repository.get_by_id(id)
     }
@@ -162,182 +228,266 @@ impl WorkoutEntryService {
         person_id: i32, 
         start_date: &str, 
         end_date: &str
-    ) -> Vec<WorkoutEntryWithDetails> {
+    ) -> AppResult<Vec<WorkoutEntryWithDetails>> {
         if person_id <= 0 {
-            return Vec::new();
+            return Err(AppError::validation("person_id", "Invalid person ID"));
         }
 
-        if !self.is_valid_date_format(start_date) || !self.is_valid_date_format(end_date) {
-            return Vec::new();
+        if !is_valid_date_format(start_date) {
+            return Err(AppError::validation("start_date", "Invalid date format. Use YYYY-MM-DD"));
+        }
+
+        if !is_valid_date_format(end_date) {
+            return Err(AppError::validation("end_date", "Invalid date format. Use YYYY-MM-DD"));
         }
 
         self.repository.get_by_person_and_date_range(person_id, start_date, end_date)
     }
 
-    pub fn get_workout_entries_by_person(&self, person_id: i32) -> Vec<WorkoutEntryWithDetails> {
+    pub fn get_workout_entries_by_person(&self, person_id: i32) -> AppResult<Vec<WorkoutEntryWithDetails>> {
         if person_id <= 0 {
-            return Vec::new();
+            return Err(AppError::validation("person_id", "Invalid person ID"));
         }
         self.repository.get_by_person(person_id)
     }
 
-    pub fn update_workout_entry(&self, workout_entry: WorkoutEntry) -> Result<(), String> {
+    pub fn update_workout_entry(&self, workout_entry: WorkoutEntry) -> AppResult<()> {
         if workout_entry.id.is_none() {
-            return Err("Workout entry ID is required for update".to_string());
+            return Err(AppError::validation("id", "Workout entry ID is required for update"));
         }
 
         // Same validations as create
         if workout_entry.person_id <= 0 {
-            return Err("Invalid person ID".to_string());
+            return Err(AppError::validation("person_id", "Invalid person ID"));
         }
 
         if workout_entry.exercise_id <= 0 {
-            return Err("Invalid exercise ID".to_string());
+            return Err(AppError::validation("exercise_id", "Invalid exercise ID"));
         }
 
         if workout_entry.date.is_empty() {
-            return Err("Date is required".to_string());
+            return Err(AppError::validation("date", "Date is required"));
         }
 
-        if !self.is_valid_date_format(&workout_entry.date) {
-            return Err("Invalid date format. Use YYYY-MM-DD".to_string());
+        if !is_valid_date_format(&workout_entry.date) {
+            return Err(AppError::validation("date", "Invalid date format. Use YYYY-MM-DD"));
         }
 
         if let Some(sets) = workout_entry.sets {
-            if sets <= 0 {
-                return Err("Sets must be greater than 0".to_string());
+            if !(1..=MAX_SETS).contains(&sets) {
+                return Err(AppError::validation("sets", format!("Sets must be between 1 and {}", MAX_SETS)));
             }
         }
 
         if let Some(reps) = workout_entry.reps {
             if reps <= 0 {
-                return Err("Reps must be greater than 0".to_string());
+                return Err(AppError::validation("reps", "Reps must be greater than 0"));
             }
         }
 
         if let Some(weight) = workout_entry.weight {
             if weight < 0.0 {
-                return Err("Weight cannot be negative".to_string());
+                return Err(AppError::validation("weight", "Weight cannot be negative"));
             }
         }
 
+        validate_intensity(workout_entry.rpe, workout_entry.rir, workout_entry.tempo.as_deref(), workout_entry.rest_seconds)?;
+        self.validate_measurement(&workout_entry)?;
+
         // Validate consecutive groups when updating
         self.validate_workout_entry_update_with_existing(&workout_entry)?;
 
         self.repository.update(workout_entry)
     }
 
-    pub fn delete_workout_entry(&self, id: i32) -> Result<(), String> {
+    pub fn delete_workout_entry(&self, id: i32) -> AppResult<()> {
         if id <= 0 {
-            return Err("Invalid workout entry ID".to_string());
-        }
-
-        // Check if the workout entry exists
-        if self.repository.get_by_id(id).is_none() {
-            return Err("Workout entry not found".to_string());
+            return Err(AppError::validation("id", "Invalid workout entry ID"));
         }
 
+        // Fails with NOT_FOUND when the entry does not exist
         self.repository.delete(id)
     }
 
-    pub fn list_all_workout_entries(&self) -> Vec<WorkoutEntryWithDetails> {
+    pub fn list_all_workout_entries(&self) -> AppResult<Vec<WorkoutEntryWithDetails>> {
         self.repository.list_all()
     }
 
-    pub fn update_exercise_order(&self, exercise_orders: Vec<(i32, i32)>) -> Result<(), String> {
+    pub fn update_exercise_order(&self, exercise_orders: Vec<(i32, i32)>) -> AppResult<()> {
         if exercise_orders.is_empty() {
-            return Err("Exercise orders list cannot be empty".to_string());
+            return Err(AppError::validation("exercise_orders", "Exercise orders list cannot be empty"));
         }
 
         // Validate that all IDs are positive and orders are non-negative
         for (id, order) in &exercise_orders {
             if *id <= 0 {
-                return Err("Invalid exercise ID".to_string());
+                return Err(AppError::validation("exercise_id", "Invalid exercise ID"));
             }
             if *order < 0 {
-                return Err("Order cannot be negative".to_string());
+                return Err(AppError::validation("order_index", "Order cannot be negative"));
             }
         }
 
         self.repository.update_exercise_order(exercise_orders)
     }
 
-    pub fn renumber_groups(&self, person_id: i32, date: &str) -> Result<(), String> {
-        if person_id <= 0 {
-            return Err("Invalid person ID".to_string());
+    // Set operations
+    pub fn get_workout_sets(&self, workout_entry_id: i32) -> AppResult<Vec<WorkoutSet>> {
+        if workout_entry_id <= 0 {
+            return Err(AppError::validation("workout_entry_id", "Invalid workout entry ID"));
         }
+        self.repository.get_sets(workout_entry_id)
+    }
 
-        if !self.is_valid_date_format(date) {
-            return Err("Invalid date format. Use YYYY-MM-DD".to_string());
+    pub fn create_workout_set(&self, workout_set: WorkoutSet) -> AppResult<i32> {
+        if workout_set.workout_entry_id <= 0 {
+            return Err(AppError::validation("workout_entry_id", "Invalid workout entry ID"));
         }
 
-        self.repository.renumber_groups(person_id, date)
+        self.validate_workout_set(&workout_set)?;
+
+        if self.repository.get_by_id(workout_set.workout_entry_id)?.is_none() {
+            return Err(AppError::not_found("Workout entry not found"));
+        }
+
+        self.repository.create_set(workout_set)
+    }
+
+    pub fn update_workout_set(&self, workout_set: WorkoutSet) -> AppResult<()> {
+        if workout_set.id.is_none() {
+            return Err(AppError::validation("id", "Workout set ID is required for update"));
+        }
+
+        self.validate_workout_set(&workout_set)?;
+
+        self.repository.update_set(workout_set)
+    }
+
+    pub fn delete_workout_set(&self, id: i32) -> AppResult<()> {
+        if id <= 0 {
+            return Err(AppError::validation("id", "Invalid workout set ID"));
+        }
+        self.repository.delete_set(id)
     }
 
-    fn is_valid_date_format(&self, date: &str) -> bool {
-        // Simple validation for YYYY-MM-DD format
-        if date.len() != 10 {
-            return false;
+    pub fn reorder_workout_sets(&self, set_orders: Vec<(i32, i32)>) -> AppResult<()> {
+        if set_orders.is_empty() {
+            return Ok(());
+        }
+
+        for (id, order) in &set_orders {
+            if *id <= 0 {
+                return Err(AppError::validation("id", "Invalid workout set ID"));
+            }
+            if *order < 0 {
+                return Err(AppError::validation("order_index", "Set order cannot be negative"));
+            }
         }
 
-        let parts: Vec<&str> = date.split('-').collect();
-        if parts.len() != 3 {
-            return false;
+        self.repository.update_set_order(set_orders)
+    }
+
+    pub fn renumber_groups(&self, person_id: i32, date: &str) -> AppResult<()> {
+        if person_id <= 0 {
+            return Err(AppError::validation("person_id", "Invalid person ID"));
         }
 
-        // Check if year, month, day are valid numbers
-        if let (Ok(year), Ok(month), Ok(day)) = (
-            parts[0].parse::<i32>(),
-            parts[1].parse::<i32>(),
-            parts[2].parse::<i32>(),
-        ) {
-            year >= 1900 && year <= 2100 && month >= 1 && month <= 12 && day >= 1 && day <= 31
-        } else {
-            false
+        if !is_valid_date_format(date) {
+            return Err(AppError::validation("date", "Invalid date format. Use YYYY-MM-DD"));
         }
+
+        self.repository.renumber_groups(person_id, date)
     }
 
-    fn validate_workout_entries(&self, workout_entries: &Vec<WorkoutEntry>) -> Result<(), String> {
+    // Reads back the saved session so records carry entry ids and exercise names
+    fn session_records(&self, person_id: i32, date: &str, formula: OneRepMaxFormula) -> AppResult<Vec<PersonalRecord>> {
+        let history = self.repository.get_by_person(person_id)?;
+        Ok(detect_session_records(date, history, formula))
+    }
+
+    fn validate_workout_entries(&self, workout_entries: &Vec<WorkoutEntry>) -> AppResult<()> {
         for (index, entry) in workout_entries.iter().enumerate() {
             if entry.person_id <= 0 {
-                return Err(format!("Invalid person ID in exercise {}", index + 1));
+                return Err(AppError::validation("person_id", format!("Invalid person ID in exercise {}", index + 1)));
             }
 
             if entry.exercise_id <= 0 {
-                return Err(format!("Invalid exercise ID in exercise {}", index + 1));
+                return Err(AppError::validation("exercise_id", format!("Invalid exercise ID in exercise {}", index + 1)));
             }
 
             if entry.date.is_empty() {
-                return Err(format!("Date is required in exercise {}", index + 1));
+                return Err(AppError::validation("date", format!("Date is required in exercise {}", index + 1)));
             }
 
-            if !self.is_valid_date_format(&entry.date) {
-                return Err(format!("Invalid date format in exercise {}. Use YYYY-MM-DD", index + 1));
+            if !is_valid_date_format(&entry.date) {
+                return Err(AppError::validation("date", format!("Invalid date format in exercise {}. Use YYYY-MM-DD", index + 1)));
             }
 
             if let Some(sets) = entry.sets {
-                if sets <= 0 {
-                    return Err(format!("Sets must be greater than 0 in exercise {}", index + 1));
+                if !(1..=MAX_SETS).contains(&sets) {
+                    return Err(AppError::validation("sets", format!("Sets must be between 1 and {} in exercise {}", MAX_SETS, index + 1)));
                 }
             }
 
             if let Some(reps) = entry.reps {
                 if reps <= 0 {
-                    return Err(format!("Reps must be greater than 0 in exercise {}", index + 1));
+                    return Err(AppError::validation("reps", format!("Reps must be greater than 0 in exercise {}", index + 1)));
                 }
             }
 
             if let Some(weight) = entry.weight {
                 if weight < 0.0 {
-                    return Err(format!("Weight cannot be negative in exercise {}", index + 1));
+                    return Err(AppError::validation("weight", format!("Weight cannot be negative in exercise {}", index + 1)));
                 }
             }
+
+            validate_intensity(entry.rpe, entry.rir, entry.tempo.as_deref(), entry.rest_seconds)?;
+            self.validate_measurement(entry)?;
+
+            for workout_set in entry.workout_sets.iter().flatten() {
+                self.validate_workout_set(workout_set)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Reps, duration and distance depend on what the exercise measures
+    fn validate_measurement(&self, workout_entry: &WorkoutEntry) -> AppResult<()> {
+        let exercise = self
+            .exercise_repository
+            .get_by_id(workout_entry.exercise_id)?
+            .ok_or_else(|| AppError::not_found("Exercise not found"))?;
+
+        validate_measurement(
+            exercise.measurement_type.unwrap_or_default(),
+            workout_entry.reps,
+            workout_entry.duration_seconds,
+            workout_entry.distance_meters,
+        )
+    }
+
+    fn validate_workout_set(&self, workout_set: &WorkoutSet) -> AppResult<()> {
+        if workout_set.order_index < 0 {
+            return Err(AppError::validation("order_index", "Set order cannot be negative"));
+        }
+
+        if let Some(reps) = workout_set.reps {
+            if reps <= 0 {
+                return Err(AppError::validation("reps", "Set reps must be greater than 0"));
+            }
         }
+
+        if let Some(weight) = workout_set.weight {
+            if weight < 0.0 {
+                return Err(AppError::validation("weight", "Set weight cannot be negative"));
+            }
+        }
+
         Ok(())
     }
 
     // Validate that workout entry groups are consecutive
-    fn validate_workout_groups_consecutive(&self, workout_entries: &Vec<WorkoutEntry>) -> Result<(), String> {
+    fn validate_workout_groups_consecutive(&self, workout_entries: &Vec<WorkoutEntry>) -> AppResult<()> {
         let groups: std::collections::HashSet<i32> = workout_entries
             .iter()
             .map(|e| e.group_number.unwrap_or(1))
@@ -352,19 +502,19 @@ impl WorkoutEntryService {
         
         // First exercise must always be in group 1
         if *min_group != 1 {
-            return Err(format!(
+            return Err(AppError::validation("group_number", format!(
                 "⚠️ El primer ejercicio debe estar en el grupo 1. No puedes empezar en el grupo {}.",
                 min_group
-            ));
+            )));
         }
         
         // Check that all groups from min to max exist
         for group_num in *min_group..=*max_group {
             if !groups.contains(&group_num) {
-                return Err(format!(
+                return Err(AppError::validation("group_number", format!(
                     "⚠️ No puedes saltar grupos. Agrega primero un ejercicio al grupo {}.",
                     group_num
-                ));
+                )));
             }
         }
         
@@ -372,9 +522,9 @@ impl WorkoutEntryService {
     }
 
     // Validate that new workout entries can be added to existing session
-    fn validate_new_workout_entries_with_existing(&self, new_entries: &Vec<WorkoutEntry>, person_id: i32, date: &str) -> Result<(), String> {
+    fn validate_new_workout_entries_with_existing(&self, new_entries: &Vec<WorkoutEntry>, person_id: i32, date: &str) -> AppResult<()> {
         // Get existing exercises for this person and date
-        let existing_entries = self.repository.get_by_person_and_date_range(person_id, date, date);
+        let existing_entries = self.repository.get_by_person_and_date_range(person_id, date, date)?;
         
         // If no existing exercises, validate that the first new exercise is in group 1
         if existing_entries.is_empty() {
@@ -386,10 +536,10 @@ impl WorkoutEntryService {
             if !new_groups.is_empty() {
                 let min_new_group = new_groups.iter().min().unwrap();
                 if *min_new_group != 1 {
-                    return Err(format!(
+                    return Err(AppError::validation("group_number", format!(
                         "⚠️ El primer ejercicio debe estar en el grupo 1. No puedes empezar en el grupo {}.",
                         min_new_group
-                    ));
+                    )));
                 }
             }
         }
@@ -415,10 +565,10 @@ impl WorkoutEntryService {
         // Check that all groups from min to max exist
         for group_num in *min_group..=*max_group {
             if !all_groups.contains(&group_num) {
-                return Err(format!(
+                return Err(AppError::validation("group_number", format!(
                     "⚠️ No puedes saltar grupos. Agrega primero un ejercicio al grupo {}.",
                     group_num
-                ));
+                )));
             }
         }
         
@@ -426,13 +576,13 @@ impl WorkoutEntryService {
     }
 
     // Validate that workout entry update maintains consecutive groups
-    fn validate_workout_entry_update_with_existing(&self, updated_entry: &WorkoutEntry) -> Result<(), String> {
+    fn validate_workout_entry_update_with_existing(&self, updated_entry: &WorkoutEntry) -> AppResult<()> {
         // Get existing exercises for this person and date (excluding the one being updated)
         let existing_entries = self.repository.get_by_person_and_date_range(
             updated_entry.person_id, 
             &updated_entry.date, 
             &updated_entry.date
-        );
+        )?;
         
         // Filter out the entry being updated
         let other_entries: Vec<_> = existing_entries
@@ -444,10 +594,10 @@ impl WorkoutEntryService {
         if other_entries.is_empty() {
             let new_group = updated_entry.group_number.unwrap_or(1);
             if new_group != 1 {
-                return Err(format!(
+                return Err(AppError::validation("group_number", format!(
                     "⚠️ El primer ejercicio debe estar en el grupo 1. No puedes empezar en el grupo {}.",
                     new_group
-                ));
+                )));
             }
             return Ok(());
         }
@@ -472,13 +622,44 @@ impl WorkoutEntryService {
         // Check that all groups from min to max exist
         for group_num in *min_group..=*max_group {
             if !all_groups.contains(&group_num) {
-                return Err(format!(
+                return Err(AppError::validation("group_number", format!(
                     "⚠️ No puedes saltar grupos. Agrega primero un ejercicio al grupo {}.",
                     group_num
-                ));
+                )));
             }
         }
         
         Ok(())
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::db::test_pool;
+    use crate::models::exercise::Exercise;
+    use crate::models::person::Person;
+    use crate::repository::person_repository::PersonRepository;
+    use crate::repository::sqlite_exercise_repository::SqliteExerciseRepository;
+    use crate::repository::sqlite_person_repository::SqlitePersonRepository;
+    use crate::repository::sqlite_workout_entry_repository::SqliteWorkoutEntryRepository;
+
+    #[test]
+    fn set_count_is_capped() {
+        let (_dir, pool) = test_pool();
+        let exercises = Arc::new(SqliteExerciseRepository::new(pool.clone()));
+        exercises
+            .create(Exercise { id: None, name: "Squat".into(), code: "SQ".into(), measurement_type: Default::default() })
+            .unwrap();
+        let person: Person =
+            serde_json::from_value(serde_json::json!({ "id": null, "name": "Ana", "last_name": "Ruiz", "phone": "600000001" })).unwrap();
+        SqlitePersonRepository::new(pool.clone()).create(person).unwrap();
+        let service = WorkoutEntryService::new(Arc::new(SqliteWorkoutEntryRepository::new(pool)), exercises);
+        let entry = |sets| WorkoutEntry::new(1, 1, "2024-01-01".into(), Some(sets), Some(5), Some(100.0), None);
+
+        let error = service.create_workout_entry(entry(MAX_SETS + 1)).unwrap_err();
+        assert_eq!(error.field(), Some("sets"));
+        let error = service.create_batch(vec![entry(i32::MAX)]).unwrap_err();
+        assert_eq!(error.field(), Some("sets"));
+        service.create_workout_entry(entry(MAX_SETS)).unwrap();
+    }
+}
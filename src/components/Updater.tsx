@@ -16,7 +16,7 @@ const Updater: React.FC = () => {
 
   useEffect(() => {
     let unlistenUpdateAvailable: (() => void) | undefined;
-    let unlistenUpdateDownloadStarted: (() => void) | undefined;
+    let unlistenUpdateDownloaded: (() => void) | undefined;
     let isMounted = true;
 
     (async () => {
@@ -26,9 +26,9 @@ const Updater: React.FC = () => {
         setUpdateError(null);
       });
 
-      unlistenUpdateDownloadStarted = await listen('update-download-started', (event) => {
+      unlistenUpdateDownloaded = await listen('update-downloaded', (event) => {
         if (!isMounted) return;
-        console.log('Download started:', event.payload);
+        console.log('Update downloaded:', event.payload);
       });
     })();
 
@@ -37,7 +37,7 @@ const Updater: React.FC = () => {
     return () => {
       isMounted = false;
       if (unlistenUpdateAvailable) unlistenUpdateAvailable();
-      if (unlistenUpdateDownloadStarted) unlistenUpdateDownloadStarted();
+      if (unlistenUpdateDownloaded) unlistenUpdateDownloaded();
     };
   }, []);
 
@@ -40,7 +40,9 @@ export const useDashboardRoutineOperations = ({
         weight: routineExercise.weight || 0,
         notes: routineExercise.notes || "",
         order: routineExercise.order_index || index,
-        group_number: routineExercise.group_number || 1
+        group_number: routineExercise.group_number || 1,
+        routine_id: routineId,
+        routine_exercise_id: routineExercise.id
       }));
 
       showToast(`Rutina "${routine.name}" cargada exitosamente (${exerciseForms.length} ejercicios)`, 'success');
@@ -79,7 +81,9 @@ export const useDashboardRoutineOperations = ({
         weight: routineExercise.weight || 0,
         notes: routineExercise.notes || "",
         order: routineExercise.order_index || index,
-        group_number: groupNumber || routineExercise.group_number || 1
+        group_number: groupNumber || routineExercise.group_number || 1,
+        routine_id: routineId,
+        routine_exercise_id: routineExercise.id
       }));
 
       // Here you would typically save the workout entries to the database
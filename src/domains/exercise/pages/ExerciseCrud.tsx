@@ -4,6 +4,7 @@ import { Button, Input, Title, Card, Modal } from "../../../shared/components/ba
 import ToastContainer from "../../../shared/components/notifications/ToastContainer";
 import "../../../styles/ExerciseCrud.css";
 import { useToast } from '../../../shared/contexts/ToastContext';
+import { getErrorCode, getErrorMessage } from '../../../shared/utils/errorUtils';
 
 const ITEMS_PER_PAGE = 10;
 
@@ -178,9 +179,9 @@ export default function ExerciseCrud() {
       setLoading(true);
       
       if (editingExercise) {
-        // Actualizar ejercicio existente
+        // Actualizar ejercicio existente, conservando su tipo de medición
         const updatedExercise: Exercise = {
-          id: editingExercise.id,
+          ...editingExercise,
           name: form.name.trim(),
           code: form.code.trim()
         };
@@ -223,19 +224,9 @@ export default function ExerciseCrud() {
     } catch (error) {
       console.error("Error saving exercise:", error);
       
-      // Extract error message
-      let errorMessage: string;
-      if (error instanceof Error) {
-        errorMessage = error.message;
-      } else if (typeof error === 'string') {
-        errorMessage = error;
-      } else {
-        errorMessage = 'Error al guardar el ejercicio';
-      }
-      
       // Check if it's a unique constraint error for code
-      if (errorMessage.includes('⚠️ Ya existe un ejercicio con ese código')) {
-        addNotification(errorMessage, 'error', 10000);
+      if (getErrorCode(error) === 'DUPLICATE') {
+        addNotification(getErrorMessage(error), 'error', 10000);
       } else {
         addNotification('Error al guardar el ejercicio. Inténtalo de nuevo.', 'error', 5000);
       }
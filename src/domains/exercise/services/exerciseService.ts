@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { Exercise, PaginatedExerciseResponse } from "../../../shared/types/dashboard";
+import { ServiceError, getErrorCode, serviceError } from "../../../shared/utils/errorUtils";
 
 const requestNames = {
   createExercise: "create_exercise",
@@ -24,11 +25,11 @@ export class ExerciseService {
       console.error("Error creating exercise:", error);
       
       // Check if it's a unique constraint error for code
-      if (typeof error === 'string' && error.includes('UNIQUE constraint failed: exercise.code')) {
-        throw new Error('⚠️ Ya existe un ejercicio con ese código. Por favor, usa un código diferente.');
+      if (getErrorCode(error) === 'DUPLICATE') {
+        throw new ServiceError('⚠️ Ya existe un ejercicio con ese código. Por favor, usa un código diferente.', error);
       }
       
-      throw new Error(`Error al crear el ejercicio: ${error}`);
+      throw serviceError("Error al crear el ejercicio", error);
     }
   }
 
@@ -40,7 +41,7 @@ export class ExerciseService {
       return await invoke(requestNames.getExercises) as Exercise[];
     } catch (error) {
       console.error("Error getting exercises:", error);
-      throw new Error(`Error al obtener los ejercicios: ${error}`);
+      throw serviceError("Error al obtener los ejercicios", error);
     }
   }
 
@@ -52,7 +53,7 @@ export class ExerciseService {
       return await invoke(requestNames.getExercisesPaginated, { page, pageSize }) as PaginatedExerciseResponse;
     } catch (error) {
       console.error("Error getting paginated exercises:", error);
-      throw new Error(`Error al obtener los ejercicios paginados: ${error}`);
+      throw serviceError("Error al obtener los ejercicios paginados", error);
     }
   }
 
@@ -64,7 +65,7 @@ export class ExerciseService {
       return await invoke(requestNames.searchExercisesPaginated, { query, page, pageSize }) as PaginatedExerciseResponse;
     } catch (error) {
       console.error("Error searching paginated exercises:", error);
-      throw new Error(`Error al buscar ejercicios paginados: ${error}`);
+      throw serviceError("Error al buscar ejercicios paginados", error);
     }
   }
 
@@ -76,7 +77,13 @@ export class ExerciseService {
       await invoke(requestNames.updateExercise, { exercise });
     } catch (error) {
       console.error("Error updating exercise:", error);
-      throw new Error(`Error al actualizar el ejercicio: ${error}`);
+      
+      // Check if it's a unique constraint error for code
+      if (getErrorCode(error) === 'DUPLICATE') {
+        throw new ServiceError('⚠️ Ya existe un ejercicio con ese código. Por favor, usa un código diferente.', error);
+      }
+      
+      throw serviceError("Error al actualizar el ejercicio", error);
     }
   }
 
@@ -88,7 +95,7 @@ export class ExerciseService {
       await invoke(requestNames.deleteExercise, { id });
     } catch (error) {
       console.error("Error deleting exercise:", error);
-      throw new Error(`Error al eliminar el ejercicio: ${error}`);
+      throw serviceError("Error al eliminar el ejercicio", error);
     }
   }
 
@@ -100,7 +107,7 @@ export class ExerciseService {
       await invoke(requestNames.restoreExercise, { id });
     } catch (error) {
       console.error("Error restoring exercise:", error);
-      throw new Error(`Error al restaurar el ejercicio: ${error}`);
+      throw serviceError("Error al restaurar el ejercicio", error);
     }
   }
 
@@ -112,7 +119,7 @@ export class ExerciseService {
       return await invoke(requestNames.getDeletedExercises);
     } catch (error) {
       console.error("Error getting deleted exercises:", error);
-      throw new Error(`Error al obtener ejercicios eliminados: ${error}`);
+      throw serviceError("Error al obtener ejercicios eliminados", error);
     }
   }
 
@@ -124,7 +131,7 @@ export class ExerciseService {
       return await invoke(requestNames.countDeletedExercises);
     } catch (error) {
       console.error("Error counting deleted exercises:", error);
-      throw new Error(`Error al contar ejercicios eliminados: ${error}`);
+      throw serviceError("Error al contar ejercicios eliminados", error);
     }
   }
 } 
\ No newline at end of file
@@ -172,7 +172,9 @@ export default function PersonCrud() {
       
       if (editingPerson) {
         // Actualizar persona existente
+        // Keep the profile fields this form does not edit
         const updatedPerson: Person = {
+          ...editingPerson,
           id: editingPerson.id,
           name: form.name.trim(),
           last_name: form.last_name.trim(),
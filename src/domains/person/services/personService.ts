@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { Person, PaginatedPersonResponse } from "../../../shared/types/dashboard";
+import { serviceError } from "../../../shared/utils/errorUtils";
 
 const requestNames = {
   createPerson: "create_person",
@@ -25,7 +26,7 @@ export class PersonService {
       await invoke(requestNames.createPerson, { person });
     } catch (error) {
       console.error("Error creating person:", error);
-      throw new Error(`Error al crear la persona: ${error}`);
+      throw serviceError("Error al crear la persona", error);
     }
   }
 
@@ -37,7 +38,7 @@ export class PersonService {
       return await invoke(requestNames.getPersons) as Person[];
     } catch (error) {
       console.error("Error getting persons:", error);
-      throw new Error(`Error al obtener las personas: ${error}`);
+      throw serviceError("Error al obtener las personas", error);
     }
   }
 
@@ -49,7 +50,7 @@ export class PersonService {
       return await invoke(requestNames.getPersonsPaginated, { page, pageSize }) as Person[];
     } catch (error) {
       console.error("Error getting paginated persons:", error);
-      throw new Error(`Error al obtener las personas paginadas: ${error}`);
+      throw serviceError("Error al obtener las personas paginadas", error);
     }
   }
 
@@ -61,7 +62,7 @@ export class PersonService {
       return await invoke(requestNames.getPersonsPaginatedResponse, { page, pageSize }) as PaginatedPersonResponse;
     } catch (error) {
       console.error("Error getting paginated persons response:", error);
-      throw new Error(`Error al obtener las personas paginadas: ${error}`);
+      throw serviceError("Error al obtener las personas paginadas", error);
     }
   }
 
@@ -73,7 +74,7 @@ export class PersonService {
       return await invoke(requestNames.searchPersons, { query }) as Person[];
     } catch (error) {
       console.error("Error searching persons:", error);
-      throw new Error(`Error al buscar personas: ${error}`);
+      throw serviceError("Error al buscar personas", error);
     }
   }
 
@@ -85,7 +86,7 @@ export class PersonService {
       return await invoke(requestNames.searchPersonsPaginated, { query, page, pageSize }) as Person[];
     } catch (error) {
       console.error("Error searching paginated persons:", error);
-      throw new Error(`Error al buscar personas paginadas: ${error}`);
+      throw serviceError("Error al buscar personas paginadas", error);
     }
   }
 
@@ -97,7 +98,7 @@ export class PersonService {
       return await invoke(requestNames.searchPersonsPaginatedResponse, { query, page, pageSize }) as PaginatedPersonResponse;
     } catch (error) {
       console.error("Error searching paginated persons response:", error);
-      throw new Error(`Error al buscar personas paginadas: ${error}`);
+      throw serviceError("Error al buscar personas paginadas", error);
     }
   }
 
@@ -109,7 +110,7 @@ export class PersonService {
       await invoke(requestNames.updatePerson, { person });
     } catch (error) {
       console.error("Error updating person:", error);
-      throw new Error(`Error al actualizar la persona: ${error}`);
+      throw serviceError("Error al actualizar la persona", error);
     }
   }
 
@@ -121,7 +122,7 @@ export class PersonService {
       await invoke(requestNames.deletePerson, { id });
     } catch (error) {
       console.error("Error deleting person:", error);
-      throw new Error(`Error al eliminar la persona: ${error}`);
+      throw serviceError("Error al eliminar la persona", error);
     }
   }
 
@@ -133,7 +134,7 @@ export class PersonService {
       await invoke(requestNames.restorePerson, { id });
     } catch (error) {
       console.error("Error restoring person:", error);
-      throw new Error(`Error al restaurar la persona: ${error}`);
+      throw serviceError("Error al restaurar la persona", error);
     }
   }
 
@@ -145,7 +146,7 @@ export class PersonService {
       return await invoke(requestNames.getDeletedPeople);
     } catch (error) {
       console.error("Error getting deleted people:", error);
-      throw new Error(`Error al obtener personas eliminadas: ${error}`);
+      throw serviceError("Error al obtener personas eliminadas", error);
     }
   }
 
@@ -157,7 +158,7 @@ export class PersonService {
       return await invoke(requestNames.countDeletedPeople);
     } catch (error) {
       console.error("Error counting deleted people:", error);
-      throw new Error(`Error al contar personas eliminadas: ${error}`);
+      throw serviceError("Error al contar personas eliminadas", error);
     }
   }
 } 
\ No newline at end of file
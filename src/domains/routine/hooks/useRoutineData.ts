@@ -3,6 +3,7 @@ import { RoutineService } from '../services';
 import { useToast } from '../../../shared/contexts/ToastContext';
 import { Routine, RoutineForm } from '../../../shared/types/dashboard';
 import { ROUTINE_ERROR_MESSAGES } from '../../../shared/constants';
+import { getErrorCode, getErrorMessage } from '../../../shared/utils/errorUtils';
 
 const ROUTINE_SUCCESS_MESSAGES = {
   CREATED: 'Rutina creada exitosamente',
@@ -78,21 +79,11 @@ export const useRoutineData = (): UseRoutineDataReturn => {
     } catch (error) {
       console.error('Error creating routine:', error);
       
-      // Extract error message
-      let errorMessage: string;
-      if (error instanceof Error) {
-        errorMessage = error.message;
-      } else if (typeof error === 'string') {
-        errorMessage = error;
-      } else {
-        errorMessage = ROUTINE_ERROR_MESSAGES.CREATE_ROUTINE_FAILED(String(error));
-      }
-      
       // Check if it's a unique constraint error for code
-      if (errorMessage.includes('⚠️ Ya existe una rutina con ese código')) {
-        addNotification(errorMessage, 'error', 10000);
+      if (getErrorCode(error) === 'DUPLICATE') {
+        addNotification(getErrorMessage(error), 'error', 10000);
       } else {
-        addNotification(ROUTINE_ERROR_MESSAGES.CREATE_ROUTINE_FAILED(String(error)), 'error');
+        addNotification(ROUTINE_ERROR_MESSAGES.CREATE_ROUTINE_FAILED(getErrorMessage(error)), 'error');
       }
       
       return false;
@@ -121,21 +112,11 @@ export const useRoutineData = (): UseRoutineDataReturn => {
     } catch (error) {
       console.error('Error updating routine:', error);
       
-      // Extract error message
-      let errorMessage: string;
-      if (error instanceof Error) {
-        errorMessage = error.message;
-      } else if (typeof error === 'string') {
-        errorMessage = error;
-      } else {
-        errorMessage = ROUTINE_ERROR_MESSAGES.UPDATE_ROUTINE_FAILED(String(error));
-      }
-      
       // Check if it's a unique constraint error for code
-      if (errorMessage.includes('⚠️ Ya existe una rutina con ese código')) {
-        addNotification(errorMessage, 'error', 10000);
+      if (getErrorCode(error) === 'DUPLICATE') {
+        addNotification(getErrorMessage(error), 'error', 10000);
       } else {
-        addNotification(ROUTINE_ERROR_MESSAGES.UPDATE_ROUTINE_FAILED(String(error)), 'error');
+        addNotification(ROUTINE_ERROR_MESSAGES.UPDATE_ROUTINE_FAILED(getErrorMessage(error)), 'error');
       }
       
       return false;
@@ -162,7 +143,7 @@ export const useRoutineData = (): UseRoutineDataReturn => {
       return true;
     } catch (error) {
       console.error('Error deleting routine:', error);
-      addNotification(ROUTINE_ERROR_MESSAGES.DELETE_ROUTINE_FAILED(String(error)), 'error');
+      addNotification(ROUTINE_ERROR_MESSAGES.DELETE_ROUTINE_FAILED(getErrorMessage(error)), 'error');
       return false;
     } finally {
       setLoading(false);
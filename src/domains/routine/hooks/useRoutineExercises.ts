@@ -3,6 +3,7 @@ import { RoutineService } from '../services';
 import { useToast } from '../../../shared/contexts/ToastContext';
 import { RoutineExerciseWithDetails } from '../../../shared/types/dashboard';
 import { ROUTINE_ERROR_MESSAGES, ROUTINE_UI_LABELS } from '../../../shared/constants';
+import { getErrorCode, getErrorDetail, getErrorField, getErrorMessage } from '../../../shared/utils/errorUtils';
 
 interface UseRoutineExercisesProps {
   routineId: number | null;
@@ -69,14 +70,14 @@ export const useRoutineExercises = ({ routineId }: UseRoutineExercisesProps) =>
       console.error('DEBUG: Error in addExercise:', error);
       
       // Check for specific duplicate exercise error
-      const errorMessage = String(error);
-      if (errorMessage.includes('Este ejercicio ya está en la rutina')) {
+      const errorCode = getErrorCode(error);
+      if (errorCode === 'DUPLICATE' && getErrorField(error) === 'exercise_id') {
         addNotification(ROUTINE_ERROR_MESSAGES.EXERCISE_ALREADY_IN_ROUTINE, 'warning');
-      } else if (errorMessage.includes('⚠️ No puedes saltar grupos')) {
-        // Use longer duration for group validation errors so user can read it
-        addNotification(ROUTINE_ERROR_MESSAGES.ADD_EXERCISE_FAILED(errorMessage), 'error', 10000);
+      } else if (errorCode === 'VALIDATION_FAILED' && getErrorField(error) === 'group_number') {
+        // Use the backend message directly and a longer duration so user can read it
+        addNotification(getErrorDetail(error), 'error', 10000);
       } else {
-        addNotification(ROUTINE_ERROR_MESSAGES.ADD_EXERCISE_FAILED(errorMessage), 'error');
+        addNotification(ROUTINE_ERROR_MESSAGES.ADD_EXERCISE_FAILED(getErrorMessage(error)), 'error');
       }
       // NO recargar ejercicios ni mostrar mensaje de éxito cuando hay error
       // Propagar el error para que se maneje en el componente padre
@@ -132,12 +133,11 @@ export const useRoutineExercises = ({ routineId }: UseRoutineExercisesProps) =>
       addNotification(ROUTINE_UI_LABELS.EXERCISE_UPDATED_SUCCESS, 'success');
     } catch (error) {
       console.error('Error updating exercise in routine:', error);
-      const errorMessage = String(error);
-      if (errorMessage.includes('⚠️ No puedes saltar grupos')) {
-        // Use longer duration for group validation errors so user can read it
-        addNotification(ROUTINE_ERROR_MESSAGES.UPDATE_EXERCISE_FAILED(errorMessage), 'error', 10000);
+      if (getErrorCode(error) === 'VALIDATION_FAILED' && getErrorField(error) === 'group_number') {
+        // Use the backend message directly and a longer duration so user can read it
+        addNotification(getErrorDetail(error), 'error', 10000);
       } else {
-        addNotification(ROUTINE_ERROR_MESSAGES.UPDATE_EXERCISE_FAILED(errorMessage), 'error');
+        addNotification(ROUTINE_ERROR_MESSAGES.UPDATE_EXERCISE_FAILED(getErrorMessage(error)), 'error');
       }
       // NO recargar ejercicios ni mostrar mensaje de éxito cuando hay error
       // Propagar el error para que se maneje en el componente padre
@@ -185,7 +185,7 @@ export const useRoutineExercises = ({ routineId }: UseRoutineExercisesProps) =>
       console.error('Error removing exercise from routine:', error);
       // Recargar en caso de error
       await loadExercises();
-      addNotification(ROUTINE_ERROR_MESSAGES.REMOVE_EXERCISE_FAILED(String(error)), 'error');
+      addNotification(ROUTINE_ERROR_MESSAGES.REMOVE_EXERCISE_FAILED(getErrorMessage(error)), 'error');
     } finally {
       setLoading(false);
     }
@@ -3,8 +3,12 @@ import {
   Routine, 
   RoutineWithExercises, 
   RoutineExerciseWithDetails,
-  RoutineOption 
+  RoutineOption,
+  RoutineRevision,
+  RoutineRevisionWithExercises,
+  RoutineRevisionDiff
 } from "../../../shared/types/dashboard";
+import { ServiceError, getErrorCode, serviceError } from "../../../shared/utils/errorUtils";
 
 const requestNames = {
   createRoutine: "create_routine",
@@ -25,7 +29,11 @@ const requestNames = {
   getRoutineExercises: "get_routine_exercises",
   reorderRoutineExercises: "reorder_routine_exercises",
   getRoutineOptions: "get_routine_options",
-  renumberRoutineGroups: "renumber_routine_groups"
+  renumberRoutineGroups: "renumber_routine_groups",
+  listRoutineRevisions: "list_routine_revisions",
+  getRoutineRevision: "get_routine_revision",
+  diffRoutineRevisions: "diff_routine_revisions",
+  restoreRoutineRevision: "restore_routine_revision"
 };
 
 export class RoutineService {
@@ -39,11 +47,11 @@ export class RoutineService {
       console.error("Error creating routine:", error);
       
       // Check if it's a unique constraint error for code
-      if (typeof error === 'string' && error.includes('UNIQUE constraint failed: routines.code')) {
-        throw new Error('⚠️ Ya existe una rutina con ese código. Por favor, usa un código diferente.');
+      if (getErrorCode(error) === 'DUPLICATE') {
+        throw new ServiceError('⚠️ Ya existe una rutina con ese código. Por favor, usa un código diferente.', error);
       }
       
-      throw new Error(`Error al crear la rutina: ${error}`);
+      throw serviceError("Error al crear la rutina", error);
     }
   }
 
@@ -55,7 +63,7 @@ export class RoutineService {
       return await invoke(requestNames.getRoutineById, { id }) as Routine | null;
     } catch (error) {
       console.error("Error getting routine by id:", error);
-      throw new Error(`Error al obtener la rutina: ${error}`);
+      throw serviceError("Error al obtener la rutina", error);
     }
   }
 
@@ -67,7 +75,7 @@ export class RoutineService {
       return await invoke(requestNames.getRoutineWithExercises, { id }) as RoutineWithExercises | null;
     } catch (error) {
       console.error("Error getting routine with exercises:", error);
-      throw new Error(`Error al obtener la rutina con ejercicios: ${error}`);
+      throw serviceError("Error al obtener la rutina con ejercicios", error);
     }
   }
 
@@ -81,11 +89,11 @@ export class RoutineService {
       console.error("Error updating routine:", error);
       
       // Check if it's a unique constraint error for code
-      if (typeof error === 'string' && error.includes('UNIQUE constraint failed: routines.code')) {
-        throw new Error('⚠️ Ya existe una rutina con ese código. Por favor, usa un código diferente.');
+      if (getErrorCode(error) === 'DUPLICATE') {
+        throw new ServiceError('⚠️ Ya existe una rutina con ese código. Por favor, usa un código diferente.', error);
       }
       
-      throw new Error(`Error al actualizar la rutina: ${error}`);
+      throw serviceError("Error al actualizar la rutina", error);
     }
   }
 
@@ -97,7 +105,7 @@ export class RoutineService {
       await invoke(requestNames.deleteRoutine, { id });
     } catch (error) {
       console.error("Error deleting routine:", error);
-      throw new Error(`Error al eliminar la rutina: ${error}`);
+      throw serviceError("Error al eliminar la rutina", error);
     }
   }
 
@@ -109,7 +117,7 @@ export class RoutineService {
       await invoke(requestNames.restoreRoutine, { id });
     } catch (error) {
       console.error("Error restoring routine:", error);
-      throw new Error(`Error al restaurar la rutina: ${error}`);
+      throw serviceError("Error al restaurar la rutina", error);
     }
   }
 
@@ -121,7 +129,7 @@ export class RoutineService {
       return await invoke(requestNames.listRoutines) as Routine[];
     } catch (error) {
       console.error("Error listing routines:", error);
-      throw new Error(`Error al listar las rutinas: ${error}`);
+      throw serviceError("Error al listar las rutinas", error);
     }
   }
 
@@ -133,7 +141,7 @@ export class RoutineService {
       return await invoke(requestNames.listRoutinesPaginated, { page, pageSize }) as Routine[];
     } catch (error) {
       console.error("Error listing paginated routines:", error);
-      throw new Error(`Error al listar las rutinas paginadas: ${error}`);
+      throw serviceError("Error al listar las rutinas paginadas", error);
     }
   }
 
@@ -145,7 +153,7 @@ export class RoutineService {
       return await invoke(requestNames.searchRoutines, { query }) as Routine[];
     } catch (error) {
       console.error("Error searching routines:", error);
-      throw new Error(`Error al buscar rutinas: ${error}`);
+      throw serviceError("Error al buscar rutinas", error);
     }
   }
 
@@ -157,7 +165,7 @@ export class RoutineService {
       return await invoke(requestNames.searchRoutinesPaginated, { query, page, pageSize }) as Routine[];
     } catch (error) {
       console.error("Error searching paginated routines:", error);
-      throw new Error(`Error al buscar rutinas paginadas: ${error}`);
+      throw serviceError("Error al buscar rutinas paginadas", error);
     }
   }
 
@@ -187,7 +195,7 @@ export class RoutineService {
       });
     } catch (error) {
       console.error("Error adding exercise to routine:", error);
-      throw new Error(`Error al agregar ejercicio a la rutina: ${error}`);
+      throw serviceError("Error al agregar ejercicio a la rutina", error);
     }
   }
 
@@ -219,7 +227,7 @@ export class RoutineService {
       });
     } catch (error) {
       console.error("Error updating routine exercise:", error);
-      throw new Error(`Error al actualizar ejercicio de rutina: ${error}`);
+      throw serviceError("Error al actualizar ejercicio de rutina", error);
     }
   }
 
@@ -231,7 +239,7 @@ export class RoutineService {
       await invoke(requestNames.removeExerciseFromRoutine, { routineId, exerciseId });
     } catch (error) {
       console.error("Error removing exercise from routine:", error);
-      throw new Error(`Error al remover ejercicio de la rutina: ${error}`);
+      throw serviceError("Error al remover ejercicio de la rutina", error);
     }
   }
 
@@ -243,7 +251,7 @@ export class RoutineService {
       return await invoke(requestNames.getRoutineExercises, { routineId }) as RoutineExerciseWithDetails[];
     } catch (error) {
       console.error("Error getting routine exercises:", error);
-      throw new Error(`Error al obtener ejercicios de la rutina: ${error}`);
+      throw serviceError("Error al obtener ejercicios de la rutina", error);
     }
   }
 
@@ -258,7 +266,7 @@ export class RoutineService {
       await invoke(requestNames.reorderRoutineExercises, { routineId, exerciseOrders });
     } catch (error) {
       console.error("Error reordering routine exercises:", error);
-      throw new Error(`Error al reordenar ejercicios de la rutina: ${error}`);
+      throw serviceError("Error al reordenar ejercicios de la rutina", error);
     }
   }
 
@@ -285,7 +293,7 @@ export class RoutineService {
       return routineOptions;
     } catch (error) {
       console.error("Error getting routine options:", error);
-      throw new Error(`Error al obtener opciones de rutinas: ${error}`);
+      throw serviceError("Error al obtener opciones de rutinas", error);
     }
   }
 
@@ -298,7 +306,7 @@ export class RoutineService {
       await invoke(requestNames.renumberRoutineGroups, { routineId });
     } catch (error) {
       console.error("Error renumbering routine groups:", error);
-      throw new Error(`Error al renumerar grupos de rutina: ${error}`);
+      throw serviceError("Error al renumerar grupos de rutina", error);
     }
   }
 
@@ -310,7 +318,7 @@ export class RoutineService {
       return await invoke(requestNames.getDeletedRoutines) as Routine[];
     } catch (error) {
       console.error("Error getting deleted routines:", error);
-      throw new Error(`Error al obtener rutinas eliminadas: ${error}`);
+      throw serviceError("Error al obtener rutinas eliminadas", error);
     }
   }
 
@@ -322,7 +330,55 @@ export class RoutineService {
       return await invoke(requestNames.countDeletedRoutines) as number;
     } catch (error) {
       console.error("Error counting deleted routines:", error);
-      throw new Error(`Error al contar rutinas eliminadas: ${error}`);
+      throw serviceError("Error al contar rutinas eliminadas", error);
     }
   }
-} 
\ No newline at end of file
+
+  /**
+   * Lista las revisiones de una rutina, de la más reciente a la más antigua
+   */
+  static async listRoutineRevisions(routineId: number): Promise<RoutineRevision[]> {
+    try {
+      return await invoke(requestNames.listRoutineRevisions, { routineId }) as RoutineRevision[];
+    } catch (error) {
+      console.error("Error listing routine revisions:", error);
+      throw serviceError("Error al obtener el historial de la rutina", error);
+    }
+  }
+
+  /**
+   * Obtiene una revisión de una rutina con sus ejercicios
+   */
+  static async getRoutineRevision(routineId: number, revision: number): Promise<RoutineRevisionWithExercises | null> {
+    try {
+      return await invoke(requestNames.getRoutineRevision, { routineId, revision }) as RoutineRevisionWithExercises | null;
+    } catch (error) {
+      console.error("Error getting routine revision:", error);
+      throw serviceError("Error al obtener la revisión de la rutina", error);
+    }
+  }
+
+  /**
+   * Compara dos revisiones de una rutina
+   */
+  static async diffRoutineRevisions(routineId: number, fromRevision: number, toRevision: number): Promise<RoutineRevisionDiff> {
+    try {
+      return await invoke(requestNames.diffRoutineRevisions, { routineId, fromRevision, toRevision }) as RoutineRevisionDiff;
+    } catch (error) {
+      console.error("Error diffing routine revisions:", error);
+      throw serviceError("Error al comparar revisiones de la rutina", error);
+    }
+  }
+
+  /**
+   * Restaura una revisión anterior; devuelve el número de la nueva revisión
+   */
+  static async restoreRoutineRevision(routineId: number, revision: number, comment?: string): Promise<number> {
+    try {
+      return await invoke(requestNames.restoreRoutineRevision, { routineId, revision, comment }) as number;
+    } catch (error) {
+      console.error("Error restoring routine revision:", error);
+      throw serviceError("Error al restaurar la revisión de la rutina", error);
+    }
+  }
+}
\ No newline at end of file
@@ -2,6 +2,7 @@ import { useState } from 'react';
 import { WorkoutService } from '../services/workoutService';
 import { WorkoutEntry, WorkoutEntryWithDetails, Person } from '../../../shared/types/dashboard';
 import { DASHBOARD_ERROR_MESSAGES, DASHBOARD_SUCCESS_MESSAGES } from '../../../shared/constants/errorMessages';
+import { getErrorCode, getErrorDetail } from '../../../shared/utils/errorUtils';
 
 interface UseWorkoutOperationsProps {
   selectedPerson: Person | null;
@@ -67,19 +68,9 @@ export const useWorkoutOperations = ({
     } catch (error) {
       console.error("Error updating workout entry:", error);
       
-      // Extract the error message - it could be a string or Error object
-      let errorMessage: string;
-      if (error instanceof Error) {
-        errorMessage = error.message;
-      } else if (typeof error === 'string') {
-        errorMessage = error;
-      } else {
-        errorMessage = "Error al actualizar el ejercicio";
-      }
-      
-      // Check if it's a group validation error and use the backend message directly
-      if (errorMessage.includes('⚠️')) {
-        showToast(errorMessage, 'error');
+      // Validation errors carry a message meant for the user, so show it directly
+      if (getErrorCode(error) === 'VALIDATION_FAILED') {
+        showToast(getErrorDetail(error), 'error');
       } else {
         showToast("Error al actualizar el ejercicio", 'error');
       }
@@ -105,7 +96,9 @@ export const useWorkoutOperations = ({
           weight: entry.weight || 0,
           notes: entry.notes || "",
           order_index: entry.order_index,
-          group_number: entry.group_number
+          group_number: entry.group_number,
+          routine_id: entry.routine_id,
+          routine_exercise_id: entry.routine_exercise_id
         }))
       );
       
@@ -115,19 +108,9 @@ export const useWorkoutOperations = ({
     } catch (error) {
       console.error(DASHBOARD_ERROR_MESSAGES.CONSOLE_SAVE_WORKOUT_SESSION, error);
       
-      // Extract the error message - it could be a string or Error object
-      let errorMessage: string;
-      if (error instanceof Error) {
-        errorMessage = error.message;
-      } else if (typeof error === 'string') {
-        errorMessage = error;
-      } else {
-        errorMessage = DASHBOARD_ERROR_MESSAGES.SAVE_SESSION_FAILED;
-      }
-      
-      // Check if it's a group validation error and use the backend message directly
-      if (errorMessage.includes('⚠️')) {
-        showToast(errorMessage, 'error');
+      // Validation errors carry a message meant for the user, so show it directly
+      if (getErrorCode(error) === 'VALIDATION_FAILED') {
+        showToast(getErrorDetail(error), 'error');
       } else {
         showToast(DASHBOARD_ERROR_MESSAGES.SAVE_SESSION_FAILED, 'error');
       }
@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { WorkoutEntry, WorkoutEntryWithDetails } from "../../../shared/types/dashboard";
+import { serviceError } from "../../../shared/utils/errorUtils";
 
 const requestNames = {
   createWorkoutEntry: "create_workout_entry",
@@ -11,6 +12,7 @@ const requestNames = {
   deleteWorkoutEntry: "delete_workout_entry",
   replaceWorkoutSession: "replace_workout_session",
   replaceWorkoutSessionGranular: "replace_workout_session_granular",
+  logPlannedSession: "log_planned_session",
   updateExerciseOrder: "update_exercise_order",
   createBatch: "create_batch",
   renumberWorkoutGroups: "renumber_workout_groups"
@@ -24,7 +26,7 @@ export class WorkoutService {
       await invoke(requestNames.createWorkoutEntry, { workoutEntry: workoutEntry });
     } catch (error) {
       console.error("Error creating workout entry:", error);
-      throw new Error(`Error al crear la entrada de entrenamiento: ${error}`);
+      throw serviceError("Error al crear la entrada de entrenamiento", error);
     }
   }
 
@@ -44,7 +46,7 @@ export class WorkoutService {
       }) as WorkoutEntryWithDetails[];
     } catch (error) {
       console.error("Error getting workout entries by person and date range:", error);
-      throw new Error(`Error al obtener las entradas de entrenamiento: ${error}`);
+      throw serviceError("Error al obtener las entradas de entrenamiento", error);
     }
   }
 
@@ -56,7 +58,7 @@ export class WorkoutService {
       return await invoke(requestNames.getWorkoutEntriesByPerson, { personId: personId }) as WorkoutEntryWithDetails[];
     } catch (error) {
       console.error("Error getting workout entries by person:", error);
-      throw new Error(`Error al obtener las entradas de entrenamiento de la persona: ${error}`);
+      throw serviceError("Error al obtener las entradas de entrenamiento de la persona", error);
     }
   }
 
@@ -68,7 +70,7 @@ export class WorkoutService {
       return await invoke(requestNames.getAllWorkoutEntries) as WorkoutEntryWithDetails[];
     } catch (error) {
       console.error("Error getting all workout entries:", error);
-      throw new Error(`Error al obtener todas las entradas de entrenamiento: ${error}`);
+      throw serviceError("Error al obtener todas las entradas de entrenamiento", error);
     }
   }
 
@@ -93,7 +95,7 @@ export class WorkoutService {
       await invoke(requestNames.deleteWorkoutEntry, { id });
     } catch (error) {
       console.error("Error deleting workout entry:", error);
-      throw new Error(`Error al eliminar la entrada de entrenamiento: ${error}`);
+      throw serviceError("Error al eliminar la entrada de entrenamiento", error);
     }
   }
 
@@ -109,7 +111,7 @@ export class WorkoutService {
       });
     } catch (error) {
       console.error("Error renumbering workout groups:", error);
-      throw new Error(`Error al renumerar grupos: ${error}`);
+      throw serviceError("Error al renumerar grupos", error);
     }
   }
 
@@ -130,6 +132,18 @@ export class WorkoutService {
     }
   }
 
+  /**
+   * Marca como realizada la sesión planificada de una persona en una fecha
+   */
+  static async logPlannedSession(personId: number, date: string): Promise<void> {
+    try {
+      await invoke(requestNames.logPlannedSession, { personId: personId, date });
+    } catch (error) {
+      console.error("Error logging planned session:", error);
+      throw error;
+    }
+  }
+
   /**
    * Reemplaza una sesión de entrenamiento de forma granular
    */
@@ -144,7 +158,7 @@ export class WorkoutService {
       });
     } catch (error) {
       console.error("Error replacing workout session granular:", error);
-      throw new Error(`Error al reemplazar la sesión de entrenamiento: ${error}`);
+      throw serviceError("Error al reemplazar la sesión de entrenamiento", error);
     }
   }
 
@@ -156,7 +170,7 @@ export class WorkoutService {
       await invoke(requestNames.updateExerciseOrder, { exerciseOrders: exerciseOrders });
     } catch (error) {
       console.error("Error updating exercise order:", error);
-      throw new Error(`Error al actualizar el orden de los ejercicios: ${error}`);
+      throw serviceError("Error al actualizar el orden de los ejercicios", error);
     }
   }
 
@@ -170,7 +184,7 @@ export class WorkoutService {
       await this.updateExerciseOrder(formattedOrders);
     } catch (error) {
       console.error("Error reordering exercises:", error);
-      throw new Error(`Error al reordenar los ejercicios: ${error}`);
+      throw serviceError("Error al reordenar los ejercicios", error);
     }
   }
 
@@ -188,6 +202,8 @@ export class WorkoutService {
       notes: string;
       order_index?: number;
       group_number?: number;
+      routine_id?: number;
+      routine_exercise_id?: number;
     }>
   ): Promise<void> {
     try {
@@ -240,7 +256,9 @@ export class WorkoutService {
             weight: exercise.weight,
             notes: exercise.notes,
             order_index: exercise.order_index !== undefined ? exercise.order_index : maxOrderIndex + 1 + index,
-            group_number: exercise.group_number || maxGroupNumber + 1
+            group_number: exercise.group_number || maxGroupNumber + 1,
+            routine_id: exercise.routine_id,
+            routine_exercise_id: exercise.routine_exercise_id
           });
         }
       });
@@ -10,22 +10,9 @@ export const ROUTINE_ERROR_MESSAGES = {
   
   // Routine operations errors
   DELETE_ROUTINE_FAILED: (error: string) => `Error al eliminar la rutina: ${error}`,
-  UPDATE_EXERCISE_FAILED: (error: string) => {
-    // Check if it's a group validation error and make it more user-friendly
-    if (error.includes('⚠️ No puedes saltar grupos')) {
-      return error; // Use the backend message directly as it's already user-friendly
-    }
-    // For other errors, show a simpler message
-    return `Error: ${error}`;
-  },
+  UPDATE_EXERCISE_FAILED: (error: string) => `Error: ${error}`,
   REMOVE_EXERCISE_FAILED: (error: string) => `Error al eliminar ejercicio: ${error}`,
-  ADD_EXERCISE_FAILED: (error: string) => {
-    // Check if it's a group validation error and make it more user-friendly
-    if (error.includes('⚠️ No puedes saltar grupos')) {
-      return error; // Use the backend message directly as it's already user-friendly
-    }
-    return `Error: ${error}`;
-  },
+  ADD_EXERCISE_FAILED: (error: string) => `Error: ${error}`,
   EXERCISE_ALREADY_IN_ROUTINE: "Este ejercicio ya está en la rutina",
   
   // Data loading errors
@@ -42,10 +29,7 @@ export const ROUTINE_ERROR_MESSAGES = {
   SELECT_ROUTINE_PROMPT: "Selecciona una rutina para ver sus detalles",
   
   // Action prompts
-  ADD_EXERCISE_PROMPT: "Haz clic en \"Agregar Ejercicio\" para comenzar.",
-  
-  // Database constraint errors
-  UNIQUE_CONSTRAINT_FAILED: 'UNIQUE constraint failed: routines.code'
+  ADD_EXERCISE_PROMPT: "Haz clic en \"Agregar Ejercicio\" para comenzar."
 } as const;
 
 // UI Labels and text constants
@@ -1,16 +1,207 @@
+export type Sex = 'female' | 'male' | 'other';
+
 export interface Person {
   id?: number;
   name: string;
   last_name: string;
   phone: string;
+  birth_date?: string;
+  sex?: Sex;
+  email?: string;
+  height_cm?: number;
+  training_goal?: string;
+  medical_notes?: string;
+  emergency_contact_name?: string;
+  emergency_contact_phone?: string;
+}
+
+export interface DuplicateCandidate {
+  person: Person;
+  duplicate: Person;
+  similarity: number;
+  same_phone: boolean;
+}
+
+export interface PersonMergeSummary {
+  survivor_id: number;
+  merged_id: number;
+  workout_entries: number;
+  subscriptions: number;
+  check_ins: number;
+  body_measurements: number;
+  assignments: number;
+}
+
+// Memberships
+export interface MembershipPlan {
+  id?: number;
+  name: string;
+  price: number;
+  duration_days: number;
+  is_active?: boolean;
+}
+
+export type SubscriptionStatus = 'pending' | 'active' | 'expired' | 'cancelled';
+
+export interface Subscription {
+  id?: number;
+  person_id: number;
+  plan_id: number;
+  start_date: string;
+  end_date?: string;
+  price?: number;
+  notes?: string;
 }
 
+export interface SubscriptionWithDetails {
+  id: number;
+  person_id: number;
+  plan_id: number;
+  start_date: string;
+  end_date: string;
+  price: number;
+  notes?: string;
+  cancelled_at?: string;
+  status: SubscriptionStatus;
+  days_remaining: number;
+  amount_paid: number;
+  balance: number;
+  person_name: string;
+  person_last_name: string;
+  person_phone: string;
+  plan_name: string;
+}
+
+export type PaymentMethod = 'cash' | 'card' | 'transfer' | 'other';
+
+export interface Payment {
+  id?: number;
+  subscription_id: number;
+  amount: number;
+  paid_on: string;
+  method?: PaymentMethod;
+  notes?: string;
+  created_at?: string;
+}
+
+// Check-ins
+export interface CheckIn {
+  id: number;
+  person_id: number;
+  checked_in_at: string;
+  checked_out_at?: string;
+  duration_minutes?: number;
+  person_name: string;
+  person_last_name: string;
+  person_phone: string;
+}
+
+export interface DailyOccupancy {
+  date: string;
+  visits: number;
+  unique_people: number;
+}
+
+export interface HourlyOccupancy {
+  hour: number;
+  check_ins: number;
+  average_present: number;
+}
+
+export interface VisitStreaks {
+  person_id: number;
+  visit_days: number;
+  last_visit_date?: string;
+  current_daily_streak: number;
+  longest_daily_streak: number;
+  current_weekly_streak: number;
+  longest_weekly_streak: number;
+}
+
+// Body measurements
+export type BodyMetric =
+  | 'weight'
+  | 'body_fat'
+  | 'muscle_mass'
+  | 'chest'
+  | 'waist'
+  | 'hips'
+  | 'arm'
+  | 'thigh'
+  | 'calf'
+  | 'neck';
+
+export interface MeasurementValue {
+  metric: BodyMetric;
+  value: number;
+}
+
+export interface BodyMeasurement {
+  id?: number;
+  person_id: number;
+  date: string;
+  notes?: string;
+  values: MeasurementValue[];
+}
+
+export interface MeasurementPoint {
+  date: string;
+  value: number;
+}
+
+export type MeasurementType = 'reps_weight' | 'time' | 'distance' | 'distance_time' | 'bodyweight_reps';
+
 export interface Exercise {
   id?: number;
   name: string;
   code: string;
+  measurement_type?: MeasurementType;
+}
+
+export type TaxonomyKind = 'muscle_group' | 'equipment' | 'movement_pattern';
+
+export interface TaxonomyItem {
+  id?: number;
+  name: string;
+}
+
+export interface ExerciseTaxonomy {
+  primary_muscle_groups: TaxonomyItem[];
+  secondary_muscle_groups: TaxonomyItem[];
+  equipment: TaxonomyItem[];
+  movement_patterns: TaxonomyItem[];
+}
+
+export interface ExerciseTaxonomyUpdate {
+  primary_muscle_group_ids?: number[];
+  secondary_muscle_group_ids?: number[];
+  equipment_ids?: number[];
+  movement_pattern_ids?: number[];
 }
 
+export interface ExerciseFilter {
+  muscle_group_id?: number;
+  primary_muscle_only?: boolean;
+  equipment_id?: number;
+  movement_pattern_id?: number;
+}
+
+export type SetType = 'warm_up' | 'working' | 'drop' | 'failure';
+
+export interface WorkoutSet {
+  id?: number;
+  workout_entry_id?: number;
+  order_index: number;
+  reps?: number;
+  weight?: number;
+  completed?: boolean;
+  set_type?: SetType;
+  created_at?: string;
+  updated_at?: string;
+}
+
+export type WorkoutEntryStatus = 'planned' | 'performed';
+
 export interface WorkoutEntry {
   id?: number;
   person_id: number;
@@ -22,8 +213,18 @@ export interface WorkoutEntry {
   notes?: string;
   order_index?: number;
   group_number?: number;
+  rpe?: number;
+  rir?: number;
+  tempo?: string;
+  rest_seconds?: number;
+  duration_seconds?: number;
+  distance_meters?: number;
+  routine_id?: number; // Routine the entry was planned from
+  routine_exercise_id?: number;
+  status?: WorkoutEntryStatus; // Scheduled entries stay 'planned' until logged
   created_at?: string;
   updated_at?: string;
+  workout_sets?: WorkoutSet[];
 }
 
 export interface WorkoutEntryWithDetails extends WorkoutEntry {
@@ -55,6 +256,8 @@ export interface WorkoutEntryForm {
   notes: string;
   order_index?: number;
   group_number?: number;
+  routine_id?: number;
+  routine_exercise_id?: number;
 }
 
 export interface EditWorkoutEntryForm extends WorkoutEntryForm {
@@ -86,6 +289,12 @@ export interface RoutineExercise {
   weight?: number;
   notes?: string;
   group_number?: number;
+  rpe?: number;
+  rir?: number;
+  tempo?: string;
+  rest_seconds?: number;
+  duration_seconds?: number;
+  distance_meters?: number;
   created_at?: string;
   updated_at?: string;
 }
@@ -113,6 +322,219 @@ export interface RoutineExerciseForm {
   group_number?: number;
 }
 
+// Routine revisions
+export interface RoutineRevision {
+  id: number;
+  routine_id: number;
+  revision: number;
+  name: string;
+  code: string;
+  comment?: string;
+  exercise_count: number;
+  created_at?: string;
+}
+
+export interface RoutineRevisionExercise {
+  exercise_id: number;
+  exercise_name: string;
+  exercise_code: string;
+  order_index: number;
+  sets?: number;
+  reps?: number;
+  weight?: number;
+  notes?: string;
+  group_number?: number;
+  rpe?: number;
+  rir?: number;
+  tempo?: string;
+  rest_seconds?: number;
+  duration_seconds?: number;
+  distance_meters?: number;
+}
+
+export interface RoutineRevisionWithExercises {
+  revision: RoutineRevision;
+  exercises: RoutineRevisionExercise[];
+}
+
+export interface RoutineExerciseChange {
+  exercise_id: number;
+  exercise_name: string;
+  fields: string[];
+  before: RoutineRevisionExercise;
+  after: RoutineRevisionExercise;
+}
+
+export interface RoutineRevisionDiff {
+  routine_id: number;
+  from_revision: number;
+  to_revision: number;
+  name_changed: boolean;
+  code_changed: boolean;
+  added: RoutineRevisionExercise[];
+  removed: RoutineRevisionExercise[];
+  changed: RoutineExerciseChange[];
+}
+
+// Programs
+export interface Program {
+  id?: number;
+  name: string;
+  code: string;
+  description?: string;
+  weeks: number;
+  created_at?: string;
+  updated_at?: string;
+}
+
+export interface ProgramDayExercise {
+  id?: number;
+  exercise_id: number;
+  order_index?: number;
+  sets?: number;
+  reps?: number;
+  weight?: number;
+  notes?: string;
+  group_number?: number;
+  rpe?: number;
+  rir?: number;
+  tempo?: string;
+  rest_seconds?: number;
+  duration_seconds?: number;
+  distance_meters?: number;
+}
+
+// Either routine_id or exercises
+export interface ProgramDay {
+  id?: number;
+  week: number;
+  day_index: number;
+  label?: string;
+  routine_id?: number;
+  notes?: string;
+  exercises?: ProgramDayExercise[];
+}
+
+export interface ProgramExerciseWithDetails {
+  program_day_exercise_id?: number;
+  routine_exercise_id?: number;
+  exercise_id: number;
+  order_index: number;
+  sets?: number;
+  reps?: number;
+  weight?: number;
+  notes?: string;
+  group_number?: number;
+  rpe?: number;
+  rir?: number;
+  tempo?: string;
+  rest_seconds?: number;
+  duration_seconds?: number;
+  distance_meters?: number;
+  exercise_name: string;
+  exercise_code: string;
+}
+
+export interface ProgramDayWithDetails {
+  id: number;
+  week: number;
+  day_index: number;
+  label?: string;
+  routine_id?: number;
+  routine_name?: string;
+  notes?: string;
+  exercises: ProgramExerciseWithDetails[];
+}
+
+export interface ProgramWithDays extends Program {
+  days: ProgramDayWithDetails[];
+}
+
+// Assignments
+export interface Assignment {
+  id?: number;
+  person_id: number;
+  routine_id?: number; // Either routine_id or program_id
+  program_id?: number;
+  start_date: string;
+  end_date?: string;
+  weekdays: number[]; // 1 = Monday ... 7 = Sunday
+  notes?: string;
+  created_at?: string;
+  updated_at?: string;
+}
+
+export interface AssignmentWithDetails extends Assignment {
+  id: number;
+  routine_name?: string;
+  program_name?: string;
+  program_weeks?: number;
+}
+
+export interface PlannedSession {
+  date: string;
+  assignment_id: number;
+  routine_id?: number;
+  routine_name?: string;
+  program_id?: number;
+  program_name?: string;
+  week?: number;
+  day_index?: number;
+  label?: string;
+  exercises: ProgramExerciseWithDetails[];
+  logged: boolean;
+  scheduled: boolean;
+}
+
+export interface ScheduleSummary {
+  assignment_id: number;
+  created_dates: string[];
+  skipped_dates: string[];
+  workout_entries: number;
+}
+
+// Progression
+export type ProgressionKind = 'linear' | 'double_progression' | 'percentage_of_e1rm';
+
+export interface ProgressionRule {
+  id?: number;
+  routine_id: number;
+  exercise_id: number;
+  kind: ProgressionKind;
+  increment_kg?: number;
+  min_reps?: number;
+  max_reps?: number;
+  percentage?: number; // Of the estimated 1RM, 1-100
+  deload_after_failures?: number;
+  deload_percentage?: number;
+  created_at?: string;
+  updated_at?: string;
+}
+
+export type ProgressionOutcome =
+  | 'no_rule'
+  | 'no_history'
+  | 'increase'
+  | 'add_reps'
+  | 'hold'
+  | 'deload'
+  | 'percentage';
+
+export interface ProgressionProposal {
+  routine_id: number;
+  exercise_id: number;
+  exercise_name: string;
+  outcome: ProgressionOutcome;
+  last_date?: string;
+  last_weight?: number;
+  last_reps?: number;
+  last_completed: boolean;
+  consecutive_failures: number;
+  sets?: number;
+  reps?: number;
+  weight?: number;
+}
+
 // Routine selection for workouts
 export interface RoutineOption {
   id: number;
@@ -135,4 +557,181 @@ export interface PaginatedPersonResponse {
   page: number;
   page_size: number;
   total_pages: number;
-} 
\ No newline at end of file
+} 
+// Analytics
+export type OneRepMaxFormula = 'epley' | 'brzycki';
+
+export type RecordType = 'estimated_one_rep_max' | 'max_weight' | 'max_reps_at_weight' | 'max_volume';
+
+export interface PersonalRecord {
+  record_type: RecordType;
+  person_id: number;
+  exercise_id: number;
+  exercise_name: string;
+  workout_entry_id?: number;
+  date: string;
+  value: number;
+  previous_value?: number;
+  weight?: number;
+  reps?: number;
+}
+
+export interface RepsAtWeight {
+  weight: number;
+  reps: number;
+  date: string;
+}
+
+export interface ExerciseStats {
+  person_id: number;
+  exercise_id: number;
+  formula: OneRepMaxFormula;
+  estimated_one_rep_max?: number;
+  estimated_one_rep_max_date?: string;
+  best_weight?: number;
+  best_weight_date?: string;
+  best_volume?: number;
+  best_volume_date?: string;
+  reps_at_weight: RepsAtWeight[];
+  sessions: number;
+}
+
+// Reports
+export type ReportBucket = 'day' | 'week' | 'month';
+
+export interface TonnagePoint {
+  period_start: string;
+  tonnage: number;
+  reps: number;
+}
+
+export interface FrequencyPoint {
+  period_start: string;
+  sessions: number;
+}
+
+export interface ExerciseSetsPoint {
+  period_start: string;
+  exercise_id: number;
+  exercise_name: string;
+  sets: number;
+}
+
+export interface IntensityPoint {
+  period_start: string;
+  average_weight?: number;
+  average_rpe?: number;
+}
+
+export interface AdherenceEntry {
+  workout_entry_id: number;
+  date: string;
+  routine_id?: number;
+  routine_name?: string;
+  exercise_id: number;
+  exercise_name: string;
+  prescribed_sets?: number;
+  prescribed_reps?: number;
+  prescribed_weight?: number;
+  performed_sets: number;
+  performed_reps: number;
+  top_weight?: number;
+  completed_sets: number;
+}
+
+export interface AdherenceReport {
+  person_id: number;
+  start_date: string;
+  end_date: string;
+  sessions: number;
+  prescribed_sets: number;
+  completed_sets: number;
+  prescribed_reps: number;
+  performed_reps: number;
+  completed_entries: number;
+  adherence?: number; // 0-1
+  entries: AdherenceEntry[];
+}
+
+// Backups
+export interface BackupInfo {
+  file_name: string;
+  backup_date: string;
+  size_bytes: number;
+  database_size_bytes: number;
+  total_records: number;
+  table_counts: Record<string, number>;
+  checksum: string;
+  app_version: string;
+  encrypted: boolean;
+}
+
+export type SmtpSecurity = 'none' | 'start_tls' | 'tls';
+
+// Secrets (api_key, password, secret_access_key) are write-only: leave them out to keep the stored value
+export type BackupTargetConfig =
+  | { type: 'directory'; path: string }
+  | { type: 'resend'; api_key?: string; api_url?: string; from: string; to: string[] }
+  | {
+      type: 'smtp';
+      host: string;
+      port: number;
+      security?: SmtpSecurity;
+      username?: string;
+      password?: string;
+      from: string;
+      to: string[];
+    }
+  | { type: 'webdav'; url: string; username?: string; password?: string }
+  | {
+      type: 's3';
+      endpoint: string;
+      region: string;
+      bucket: string;
+      prefix?: string;
+      access_key_id: string;
+      secret_access_key?: string;
+      path_style?: boolean;
+    };
+
+export interface BackupConfig {
+  directory?: string;
+  keep_daily: number;
+  keep_weekly: number;
+  targets: BackupTargetConfig[];
+  passphrase?: string; // Omitted keeps the stored one, '' removes it
+}
+
+// As returned by get_settings: secrets are blank, only whether they are set is reported
+export type BackupTargetView = BackupTargetConfig & { secret_set: boolean };
+
+export interface BackupConfigView {
+  directory?: string;
+  keep_daily: number;
+  keep_weekly: number;
+  targets: BackupTargetView[];
+  passphrase_set: boolean;
+}
+
+// Settings
+export type UpdateChannel = 'stable' | 'beta';
+
+export interface UpdaterSettings {
+  manifest_url?: string;
+  channel: UpdateChannel;
+  public_key?: string;
+}
+
+export interface AppSettings {
+  version: number;
+  backup: BackupConfig;
+  updater: UpdaterSettings;
+  notices?: string[];
+}
+
+export interface SettingsView {
+  version: number;
+  backup: BackupConfigView;
+  updater: UpdaterSettings;
+  notices: string[];
+}
@@ -0,0 +1,72 @@
+// Utilidades para los errores que devuelven los comandos de Tauri.
+// El backend rechaza con un objeto { code, field, message } (ver AppError en src-tauri/src/error.rs).
+
+export type AppErrorCode =
+  | 'NOT_FOUND'
+  | 'VALIDATION_FAILED'
+  | 'DUPLICATE'
+  | 'DATABASE_UNAVAILABLE'
+  | 'DATABASE_ERROR';
+
+export interface AppError {
+  code: AppErrorCode;
+  field: string | null;
+  message: string;
+}
+
+export const isAppError = (error: unknown): error is AppError =>
+  typeof error === 'object' &&
+  error !== null &&
+  typeof (error as AppError).code === 'string' &&
+  typeof (error as AppError).message === 'string';
+
+/**
+ * Error lanzado por los servicios del frontend. Conserva el código y el campo
+ * del error original para que los hooks puedan decidir sin comparar textos.
+ */
+export class ServiceError extends Error {
+  readonly code?: AppErrorCode;
+  readonly field: string | null;
+  // Mensaje original del backend, sin el contexto del servicio
+  readonly detail: string;
+
+  constructor(message: string, cause: unknown) {
+    super(message);
+    this.name = 'ServiceError';
+    this.code = getErrorCode(cause);
+    this.field = getErrorField(cause);
+    this.detail = getErrorDetail(cause);
+  }
+}
+
+export const getErrorMessage = (error: unknown): string => {
+  if (isAppError(error) || error instanceof Error) {
+    return error.message;
+  }
+  if (typeof error === 'string') {
+    return error;
+  }
+  return String(error);
+};
+
+export const getErrorCode = (error: unknown): AppErrorCode | undefined => {
+  if (isAppError(error) || error instanceof ServiceError) {
+    return error.code;
+  }
+  return undefined;
+};
+
+export const getErrorField = (error: unknown): string | null => {
+  if (isAppError(error) || error instanceof ServiceError) {
+    return error.field;
+  }
+  return null;
+};
+
+// Mensaje tal como lo envió el backend, útil cuando ya está pensado para el usuario
+export const getErrorDetail = (error: unknown): string =>
+  error instanceof ServiceError ? error.detail : getErrorMessage(error);
+
+// Construye el error de un servicio anteponiendo el contexto al mensaje del backend
+export const serviceError = (context: string, cause: unknown): ServiceError =>
+  new ServiceError(`${context}: ${getErrorMessage(cause)}`, cause);
@@ -0,0 +1,48 @@
+import { describe, it, expect } from 'vitest'
+import {
+  ServiceError,
+  getErrorCode,
+  getErrorDetail,
+  getErrorField,
+  getErrorMessage,
+  isAppError,
+  serviceError
+} from '../shared/utils/errorUtils'
+
+const duplicate = {
+  code: 'DUPLICATE',
+  field: 'exercise_id',
+  message: 'Este ejercicio ya está en la rutina'
+}
+
+describe('errorUtils', () => {
+  it('reconoce el objeto de error del backend', () => {
+    expect(isAppError(duplicate)).toBe(true)
+    expect(isAppError('texto')).toBe(false)
+    expect(isAppError(null)).toBe(false)
+  })
+
+  it('lee el mensaje de objetos, errores y textos', () => {
+    expect(getErrorMessage(duplicate)).toBe('Este ejercicio ya está en la rutina')
+    expect(getErrorMessage(new Error('fallo'))).toBe('fallo')
+    expect(getErrorMessage('fallo')).toBe('fallo')
+    expect(getErrorMessage(duplicate)).not.toContain('[object Object]')
+  })
+
+  it('expone el código y el campo del backend', () => {
+    expect(getErrorCode(duplicate)).toBe('DUPLICATE')
+    expect(getErrorField(duplicate)).toBe('exercise_id')
+    expect(getErrorCode('fallo')).toBeUndefined()
+    expect(getErrorField(new Error('fallo'))).toBeNull()
+  })
+
+  it('conserva código, campo y mensaje original al envolver el error', () => {
+    const error = serviceError('Error al agregar ejercicio a la rutina', duplicate)
+
+    expect(error).toBeInstanceOf(ServiceError)
+    expect(error.message).toBe('Error al agregar ejercicio a la rutina: Este ejercicio ya está en la rutina')
+    expect(getErrorCode(error)).toBe('DUPLICATE')
+    expect(getErrorField(error)).toBe('exercise_id')
+    expect(getErrorDetail(error)).toBe('Este ejercicio ya está en la rutina')
+  })
+})